@@ -0,0 +1,204 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// a generational index of the arena, a removed node's id never matches a new node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId {
+	index: u32,
+	generation: u32,
+}
+
+impl NodeId {
+	/// pub fn `index`
+	/// the slot index in the arena
+	pub fn index(&self) -> usize {
+		self.index as usize
+	}
+	/// pub fn `generation`
+	/// the times the slot has been reused
+	pub fn generation(&self) -> u32 {
+		self.generation
+	}
+}
+
+// used as the node's uuid, unique in the arena
+impl fmt::Display for NodeId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}v{}", self.index, self.generation)
+	}
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+	Occupied {
+		generation: u32,
+		value: T,
+	},
+	Free {
+		generation: u32,
+		next_free: Option<u32>,
+	},
+}
+
+/// a generational arena, the nodes are stored in one vec and linked by ids
+#[derive(Clone)]
+pub struct Arena<T> {
+	entries: Vec<Entry<T>>,
+	free_head: Option<u32>,
+	len: usize,
+}
+
+impl<T> Default for Arena<T> {
+	fn default() -> Self {
+		Arena::new()
+	}
+}
+
+impl<T> Arena<T> {
+	/// pub fn `new`
+	pub fn new() -> Self {
+		Arena::with_capacity(0)
+	}
+	/// pub fn `with_capacity`
+	pub fn with_capacity(cap: usize) -> Self {
+		Arena {
+			entries: Vec::with_capacity(cap),
+			free_head: None,
+			len: 0,
+		}
+	}
+	/// pub fn `len`
+	/// the count of the nodes in the arena
+	pub fn len(&self) -> usize {
+		self.len
+	}
+	/// pub fn `is_empty`
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+	/// pub fn `insert`
+	/// insert a node, reuse the free slot if there is one
+	pub fn insert(&mut self, value: T) -> NodeId {
+		self.len += 1;
+		if let Some(index) = self.free_head {
+			let entry = &mut self.entries[index as usize];
+			if let Entry::Free {
+				generation,
+				next_free,
+			} = *entry
+			{
+				let generation = generation.wrapping_add(1);
+				self.free_head = next_free;
+				*entry = Entry::Occupied { generation, value };
+				return NodeId { index, generation };
+			}
+			unreachable!("The free list of the arena is broken.");
+		}
+		let index = self.entries.len() as u32;
+		self.entries.push(Entry::Occupied {
+			generation: 0,
+			value,
+		});
+		NodeId {
+			index,
+			generation: 0,
+		}
+	}
+	/// pub fn `remove`
+	/// remove the node, the id and its copies will be invalid
+	pub fn remove(&mut self, id: NodeId) -> Option<T> {
+		match self.entries.get(id.index()) {
+			Some(Entry::Occupied { generation, .. }) if *generation == id.generation => {
+				let entry = std::mem::replace(
+					&mut self.entries[id.index()],
+					Entry::Free {
+						generation: id.generation,
+						next_free: self.free_head,
+					},
+				);
+				self.free_head = Some(id.index);
+				self.len -= 1;
+				match entry {
+					Entry::Occupied { value, .. } => Some(value),
+					Entry::Free { .. } => None,
+				}
+			}
+			_ => None,
+		}
+	}
+	/// pub fn `contains`
+	/// check if the id is still valid
+	pub fn contains(&self, id: NodeId) -> bool {
+		self.get(id).is_some()
+	}
+	/// pub fn `get`
+	pub fn get(&self, id: NodeId) -> Option<&T> {
+		match self.entries.get(id.index()) {
+			Some(Entry::Occupied { generation, value }) if *generation == id.generation => Some(value),
+			_ => None,
+		}
+	}
+	/// pub fn `get_mut`
+	pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+		match self.entries.get_mut(id.index()) {
+			Some(Entry::Occupied { generation, value }) if *generation == id.generation => Some(value),
+			_ => None,
+		}
+	}
+	/// pub fn `iter`
+	/// iterate the nodes in slot order
+	pub fn iter(&self) -> impl Iterator<Item = (NodeId, &T)> {
+		self
+			.entries
+			.iter()
+			.enumerate()
+			.filter_map(|(index, entry)| match entry {
+				Entry::Occupied { generation, value } => Some((
+					NodeId {
+						index: index as u32,
+						generation: *generation,
+					},
+					value,
+				)),
+				Entry::Free { .. } => None,
+			})
+	}
+}
+
+impl<T> Index<NodeId> for Arena<T> {
+	type Output = T;
+	fn index(&self, id: NodeId) -> &T {
+		self.get(id).expect("The node id is invalid.")
+	}
+}
+
+impl<T> IndexMut<NodeId> for Arena<T> {
+	fn index_mut(&mut self, id: NodeId) -> &mut T {
+		self.get_mut(id).expect("The node id is invalid.")
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::Arena;
+	#[test]
+	fn test_arena_generation() {
+		let mut arena = Arena::new();
+		let a = arena.insert("a");
+		let b = arena.insert("b");
+		assert_eq!(arena.len(), 2);
+		assert_eq!(arena.remove(a), Some("a"));
+		assert_eq!(arena.remove(a), None);
+		// the slot is reused with a new generation
+		let c = arena.insert("c");
+		assert_eq!(c.index(), a.index());
+		assert_ne!(c, a);
+		assert!(arena.get(a).is_none());
+		assert_eq!(arena[c], "c");
+		assert_eq!(arena[b], "b");
+		assert_eq!(
+			arena.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+			vec!["c", "b"]
+		);
+	}
+}
@@ -0,0 +1,116 @@
+use crate::interface::IAttrValue;
+use crate::serialize::escape_attr_value;
+use std::iter::FromIterator;
+
+/// an owned attribute map keeps the source order, the names are matched ascii case-insensitive
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AttrMap {
+	attrs: Vec<(String, Option<String>)>,
+}
+
+impl AttrMap {
+	/// pub fn `new`
+	pub fn new() -> Self {
+		Default::default()
+	}
+	/// pub fn `len`
+	pub fn len(&self) -> usize {
+		self.attrs.len()
+	}
+	/// pub fn `is_empty`
+	pub fn is_empty(&self) -> bool {
+		self.attrs.is_empty()
+	}
+	/// pub fn `position`
+	/// the index of the attribute in source order
+	pub fn position(&self, name: &str) -> Option<usize> {
+		self
+			.attrs
+			.iter()
+			.position(|(key, _)| key.eq_ignore_ascii_case(name))
+	}
+	/// pub fn `get`
+	/// get the attribute as `IAttrValue`, the attribute without value is `IAttrValue::True`
+	pub fn get(&self, name: &str) -> Option<IAttrValue> {
+		self.position(name).map(|index| match &self.attrs[index].1 {
+			Some(value) => IAttrValue::Value(value.clone(), Some('"')),
+			None => IAttrValue::True,
+		})
+	}
+	/// pub fn `get_str`
+	/// get the attribute value, the attribute without value is an empty string
+	pub fn get_str(&self, name: &str) -> Option<&str> {
+		self
+			.position(name)
+			.map(|index| self.attrs[index].1.as_deref().unwrap_or(""))
+	}
+	/// pub fn `contains`
+	pub fn contains(&self, name: &str) -> bool {
+		self.position(name).is_some()
+	}
+	/// pub fn `set`
+	/// set the attribute, the new attribute is appended to the end
+	pub fn set(&mut self, name: &str, value: Option<&str>) {
+		let value = value.map(String::from);
+		match self.position(name) {
+			Some(index) => self.attrs[index].1 = value,
+			None => self.attrs.push((String::from(name), value)),
+		}
+	}
+	/// pub fn `remove`
+	/// remove the attribute, return the old value
+	pub fn remove(&mut self, name: &str) -> Option<Option<String>> {
+		self.position(name).map(|index| self.attrs.remove(index).1)
+	}
+	/// pub fn `iter`
+	/// iterate the attributes in source order
+	pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+		self
+			.attrs
+			.iter()
+			.map(|(name, value)| (name.as_str(), value.as_deref()))
+	}
+	/// pub fn `to_attributes`
+	/// the attributes as the result of `IElementTrait::attributes`
+	pub fn to_attributes(&self) -> Vec<(String, IAttrValue)> {
+		self
+			.attrs
+			.iter()
+			.map(|(name, value)| {
+				let value = match value {
+					Some(value) => IAttrValue::Value(value.clone(), Some('"')),
+					None => IAttrValue::True,
+				};
+				(name.clone(), value)
+			})
+			.collect()
+	}
+	/// pub fn `to_html`
+	/// serialize the attributes with a leading space for each, the values are double quoted
+	pub fn to_html(&self) -> String {
+		let mut result = String::with_capacity(self.attrs.len() * 20);
+		for (name, value) in &self.attrs {
+			result.push(' ');
+			result.push_str(name);
+			if let Some(value) = value {
+				result.push_str("=\"");
+				escape_attr_value(value, &mut result);
+				result.push('"');
+			}
+		}
+		result
+	}
+}
+
+impl FromIterator<(String, Option<String>)> for AttrMap {
+	fn from_iter<I: IntoIterator<Item = (String, Option<String>)>>(iter: I) -> Self {
+		let mut attrs = AttrMap::new();
+		for (name, value) in iter {
+			// the first attribute wins, the same as the html parsers
+			if !attrs.contains(&name) {
+				attrs.attrs.push((name, value));
+			}
+		}
+		attrs
+	}
+}
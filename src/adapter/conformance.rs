@@ -0,0 +1,1211 @@
+//! the checks of the conformance test suite, use the `conformance_tests!` macro to run them all
+use crate::interface::{
+	BoxDynElement, BoxDynNode, Elements, IAttrValue, IDocumentTrait, IEnumTyped, INodeType,
+	InsertMode, InsertPosition, NodeVisitor, Range, ScriptSource, SelectorStrategy, VisitAction,
+	HTML_NAMESPACE, MATHML_NAMESPACE, SVG_NAMESPACE,
+};
+use crate::serialize::{serialize, SerializeOptions};
+
+/// the html used by all the checks, no whitespaces between the tags
+pub const FIXTURE: &str = concat!(
+	"<!DOCTYPE html><html><head><title>Conformance</title></head><body>",
+	"<div id=\"main\" class=\"box main\"><p class=\"first\">one</p><p>two <b>bold</b></p>",
+	"<span data-x=\"1\" hidden>three</span></div>",
+	"<ul id=\"list\"><li>a</li><li class=\"x\">b</li><li>c</li></ul>",
+	"</body></html>"
+);
+
+// the root node of the document as `Elements`
+fn root_of<'a>(doc: &dyn IDocumentTrait) -> Elements<'a> {
+	let root = doc
+		.get_root_node()
+		.typed()
+		.into_element()
+		.expect("The root node must be typed as an element.");
+	Elements::with_nodes(vec![root])
+}
+
+// parse the fixture
+fn load<D, F>(parse: F) -> D
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	crate::init();
+	parse(FIXTURE)
+}
+
+// get the first element matched the selector
+fn first<'a>(root: &Elements<'a>, selector: &str) -> BoxDynElement<'a> {
+	root
+		.find(selector)
+		.get(0)
+		.unwrap_or_else(|| panic!("The selector '{}' must match an element.", selector))
+		.cloned()
+}
+
+// the texts of the children
+fn children_texts(ele: &BoxDynElement) -> String {
+	ele
+		.children()
+		.get_ref()
+		.iter()
+		.map(|child| child.text())
+		.collect()
+}
+
+/// check the document methods
+pub fn document<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	assert!(matches!(
+		doc.get_root_node().node_type(),
+		INodeType::Document
+	));
+	let html = doc
+		.document_element()
+		.expect("The document must have a html element.");
+	assert_eq!(html.tag_name(), "HTML");
+	assert_eq!(
+		doc
+			.head()
+			.map(|head| head.tag_name().to_string())
+			.as_deref(),
+		Some("HEAD")
+	);
+	assert_eq!(
+		doc
+			.body()
+			.map(|body| body.tag_name().to_string())
+			.as_deref(),
+		Some("BODY")
+	);
+	assert_eq!(doc.title().as_deref(), Some("Conformance"));
+	let main = doc
+		.get_element_by_id("main")
+		.expect("The element '#main' must be found.");
+	assert_eq!(main.tag_name(), "DIV");
+	assert!(doc.get_element_by_id("none").is_none());
+}
+
+/// check the tag names are uppercase, the name selectors rely on it
+pub fn tag_names<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let paragraphs = root.find("p");
+	assert_eq!(paragraphs.length(), 2);
+	for ele in paragraphs.get_ref() {
+		assert_eq!(ele.tag_name(), "P");
+	}
+	assert_eq!(root.find("P").length(), 2);
+}
+
+/// check the attributes keep the source order, the updated attributes keep their positions
+/// and the new attributes are appended
+pub fn attribute_order<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	crate::init();
+	let doc = parse(concat!(
+		"<html><head></head><body>",
+		"<a id=\"link\" href=\"/a\" class=\"x\" title=\"t\">a</a>",
+		"</body></html>"
+	));
+	let root = root_of(&doc);
+	let mut link = first(&root, "#link");
+	let names = |link: &BoxDynElement| -> Vec<String> {
+		link
+			.attributes()
+			.into_iter()
+			.map(|(name, _)| name.to_ascii_lowercase())
+			.collect()
+	};
+	assert_eq!(names(&link), vec!["id", "href", "class", "title"]);
+	link.set_attribute("href", Some("/b"));
+	link.set_attribute("data-new", Some("1"));
+	assert_eq!(
+		names(&link),
+		vec!["id", "href", "class", "title", "data-new"]
+	);
+	assert_eq!(link.attributes()[1].1.as_str(), "/b");
+	link.remove_attribute("class");
+	link.set_attribute("class", Some("y"));
+	assert_eq!(
+		names(&link),
+		vec!["id", "href", "title", "data-new", "class"]
+	);
+	assert_eq!(link.attribute_names(), names(&link));
+}
+
+/// check getting, setting and removing the attributes
+pub fn attributes<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let main = first(&root, "#main");
+	assert!(main.get_attribute("class").unwrap().is_str("box main"));
+	assert!(main.get_attribute("title").is_none());
+	let mut span = first(&root, "span");
+	// the attribute without value may be `IAttrValue::True` or an empty string
+	match span.get_attribute("hidden") {
+		Some(IAttrValue::True) => {}
+		Some(IAttrValue::Value(value, _)) => assert_eq!(value, ""),
+		None => panic!("The attribute 'hidden' must be found."),
+	}
+	// the order of the attributes is checked by `attribute_order`
+	let mut names: Vec<String> = span
+		.attributes()
+		.into_iter()
+		.map(|(name, _)| name.to_ascii_lowercase())
+		.collect();
+	let attribute_names: Vec<String> = span
+		.attribute_names()
+		.into_iter()
+		.map(|name| name.to_ascii_lowercase())
+		.collect();
+	assert_eq!(attribute_names, names);
+	names.sort();
+	assert_eq!(names, vec!["data-x", "hidden"]);
+	assert!(span.get_attribute("data-x").unwrap().is_str("1"));
+	// the boolean and the enumerated attributes
+	assert!(span.get_bool_attribute("hidden"));
+	assert!(!main.get_bool_attribute("hidden"));
+	let keywords = ["true", "false", ""];
+	assert_eq!(span.get_enum_attribute("hidden", &keywords), Some(""));
+	assert_eq!(span.get_enum_attribute("data-x", &keywords), None);
+	assert_eq!(main.get_enum_attribute("contenteditable", &keywords), None);
+	span.set_attribute("data-x", Some("FALSE"));
+	assert_eq!(span.get_enum_attribute("data-x", &keywords), Some("false"));
+	span.set_attribute("data-x", Some("2"));
+	assert!(span.get_attribute("data-x").unwrap().is_str("2"));
+	span.set_attribute("data-y", Some("3"));
+	assert!(span.get_attribute("data-y").unwrap().is_str("3"));
+	span.set_attribute("data-z", None);
+	assert!(span.has_attribute("data-z"));
+	span.remove_attribute("data-x");
+	assert!(!span.has_attribute("data-x"));
+	// the changes are visible to the selectors
+	assert_eq!(root.find("[data-y='3']").length(), 1);
+	assert_eq!(root.find("[data-x]").length(), 0);
+	// the class list writes back to the attribute
+	let mut class_list = main.class_list();
+	assert_eq!(class_list.to_vec(), vec!["box", "main"]);
+	assert!(class_list.contains("main"));
+	class_list.add("wide box");
+	assert!(main.get_attribute("class").unwrap().is_str("box main wide"));
+	assert!(!class_list.toggle("main"));
+	assert!(class_list.replace("box", "card"));
+	assert!(!class_list.replace("box", "card"));
+	assert_eq!(class_list.value(), "card wide");
+	assert_eq!(root.find(".card.wide").length(), 1);
+	class_list.remove("card wide");
+	assert!(class_list.is_empty());
+	assert!(span.class_list().toggle("new"));
+	assert_eq!(root.find("span.new").length(), 1);
+	// the class methods of the elements
+	let mut items = root.find("li");
+	assert!(items.has_class("y x"));
+	items.toggle_class("x");
+	assert_eq!(root.find("li.x").length(), 2);
+	items.add_class("item x");
+	assert_eq!(root.find("li.item.x").length(), 3);
+	items.remove_class("item x");
+	assert!(!items.has_class("item x"));
+}
+
+/// check the parent, children, siblings and index
+pub fn navigation<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let main = first(&root, "#main");
+	let para = first(&root, "p.first");
+	assert!(para.parent().unwrap().is(&main));
+	let tag_names: Vec<String> = main
+		.children()
+		.get_ref()
+		.iter()
+		.map(|child| child.tag_name().to_string())
+		.collect();
+	assert_eq!(tag_names, vec!["P", "P", "SPAN"]);
+	let second = para
+		.next_element_sibling()
+		.expect("The 'p' must have a next sibling.");
+	assert_eq!(second.child_nodes_length(), 2);
+	let text = second.child_nodes_item(0).unwrap();
+	assert!(matches!(text.node_type(), INodeType::Text));
+	assert!(text.parent().unwrap().is(&second));
+	let span = first(&root, "span");
+	assert_eq!(span.index(), 2);
+	assert!(span.previous_element_sibling().unwrap().is(&second));
+	assert_eq!(span.siblings().length(), 2);
+	let root_element = span
+		.root_element()
+		.expect("The element must have a root element.");
+	assert!(matches!(root_element.node_type(), INodeType::Document));
+}
+
+/// check the same node can be reached by different ways
+pub fn identity<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let main = first(&root, "#main");
+	assert!(main.is(&first(&root, "div")));
+	assert!(main.is(&first(&root, "p").parent().unwrap()));
+	assert!(main.is(&main.cloned()));
+	assert!(!main.is(&first(&root, "p")));
+	if let (Some(main_uuid), Some(para_uuid)) = (main.uuid(), first(&root, "p").uuid()) {
+		assert_ne!(main_uuid, para_uuid);
+	}
+	let owner = main
+		.owner_document()
+		.expect("The element must have an owner document.");
+	assert!(owner.get_element_by_id("main").unwrap().is(&main));
+}
+
+/// check the text content
+pub fn text_content<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let mut main = first(&root, "#main");
+	assert_eq!(main.text(), "onetwo boldthree");
+	let mut span = first(&root, "span");
+	span.set_text("a < b");
+	assert_eq!(span.text(), "a < b");
+	assert_eq!(span.child_nodes_length(), 1);
+	assert_eq!(main.text(), "onetwo bolda < b");
+	main.set_text("");
+	assert_eq!(main.text(), "");
+	assert_eq!(main.child_nodes_length(), 0);
+}
+
+/// check the html serialization and parsing
+pub fn html<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let second = first(&root, "p:nth-child(2)");
+	assert_eq!(second.inner_html().to_ascii_lowercase(), "two <b>bold</b>");
+	assert_eq!(
+		second.outer_html().to_ascii_lowercase(),
+		"<p>two <b>bold</b></p>"
+	);
+	let mut span = first(&root, "span");
+	span.set_html("<i>it</i>x");
+	assert_eq!(span.text(), "itx");
+	assert_eq!(span.child_nodes_length(), 2);
+	assert_eq!(root.find("span > i").length(), 1);
+}
+
+/// check the insertions and removals
+pub fn mutations<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let mut list = first(&root, "#list");
+	let mut items = root.find("li");
+	let (mut a, mut b, c) = (
+		items.get_mut_ref().remove(0),
+		items.get_mut_ref().remove(0),
+		items.get_mut_ref().remove(0),
+	);
+	a.insert_adjacent(&InsertPosition::BeforeBegin, &c);
+	assert_eq!(children_texts(&list), "cab");
+	list.insert_adjacent(&InsertPosition::BeforeEnd, &c);
+	assert_eq!(children_texts(&list), "abc");
+	list.insert_adjacent(&InsertPosition::AfterBegin, &c);
+	assert_eq!(children_texts(&list), "cab");
+	b.insert_adjacent(&InsertPosition::AfterEnd, &c);
+	assert_eq!(children_texts(&list), "abc");
+	assert_eq!(c.index(), 2);
+	list.remove_child(b.cloned());
+	assert_eq!(children_texts(&list), "ac");
+	assert!(b.parent().is_none());
+	assert_eq!(root.find("li").length(), 2);
+	// move the node to another parent
+	let mut main = first(&root, "#main");
+	main.insert_adjacent(&InsertPosition::BeforeEnd, &a);
+	assert!(a.parent().unwrap().is(&main));
+	assert_eq!(children_texts(&list), "c");
+	assert_eq!(root.find("#main > li").length(), 1);
+	a.set_attribute("class", Some("moved"));
+	assert!(root.find("div .moved").get(0).unwrap().is(&a));
+	// insert any node at the index
+	list.insert_child(0, &b.clone_node()).unwrap();
+	assert_eq!(children_texts(&list), "bc");
+	let one = first(&root, "p.first").child_nodes_item(0).unwrap();
+	list.insert_child(10, &one).unwrap();
+	assert_eq!(list.text_content(), "bcone");
+	assert_eq!(first(&root, "p.first").child_nodes_length(), 0);
+}
+
+/// check inserting the html and the texts at the positions
+pub fn adjacent_html<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let mut list = first(&root, "#list");
+	list
+		.insert_adjacent_html(&InsertPosition::AfterBegin, "<li>0</li>start")
+		.unwrap();
+	list
+		.insert_adjacent_html(&InsertPosition::BeforeEnd, "<li>d</li><!--end-->")
+		.unwrap();
+	assert_eq!(children_texts(&list), "0abcd");
+	assert_eq!(list.text_content(), "0startabcd");
+	assert_eq!(list.child_nodes_length(), 7);
+	// the siblings are parsed in the context of the parent
+	let mut b = first(&root, "li.x");
+	b.insert_adjacent_html(&InsertPosition::BeforeBegin, "<li class=\"y\">x</li>")
+		.unwrap();
+	b.insert_adjacent_html(&InsertPosition::AfterEnd, "<li>y</li><li>z</li>")
+		.unwrap();
+	assert_eq!(children_texts(&list), "0axbyzcd");
+	assert_eq!(
+		first(&root, "li.y").next_element_sibling().unwrap().text(),
+		"b"
+	);
+	// the texts are not parsed
+	let mut para = first(&root, "p.first");
+	para
+		.insert_adjacent_text(&InsertPosition::AfterBegin, "<b>")
+		.unwrap();
+	para
+		.insert_adjacent_text(&InsertPosition::AfterEnd, "!")
+		.unwrap();
+	assert_eq!(para.text(), "<b>one");
+	assert_eq!(para.children().length(), 0);
+	assert_eq!(first(&root, "#main").text(), "<b>one!two boldthree");
+	// the root has no parent
+	let html = first(&root, "html");
+	let mut detached = html.clone_deep().unwrap();
+	assert!(detached
+		.insert_adjacent_html(&InsertPosition::AfterEnd, "<p></p>")
+		.is_err());
+}
+
+/// check replacing the element by the parsed source, the other nodes are kept
+pub fn reparse<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let list = first(&root, "#list");
+	let a = first(&root, "#list li");
+	let mut b = first(&root, "li.x");
+	let inserted = b.reparse("<li class=\"y\">b1</li>text<li>b2</li>").unwrap();
+	assert_eq!(inserted.length(), 2);
+	assert!(inserted.get(0).unwrap().is(&first(&root, "li.y")));
+	assert_eq!(children_texts(&list), "ab1b2c");
+	assert_eq!(list.text_content(), "ab1textb2c");
+	assert!(root.find("li.x").is_empty());
+	assert!(b.parent().is_none());
+	// the other nodes are the same nodes
+	assert!(first(&root, "#list").is(&list));
+	assert!(first(&root, "#list li").is(&a));
+	// the root has no parent
+	let mut detached = first(&root, "html").clone_deep().unwrap();
+	assert!(detached.reparse("<p></p>").is_err());
+}
+
+/// check moving and copying the elements already in the tree
+pub fn insert_modes<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let list = first(&root, "#list");
+	let mut main = first(&root, "#main");
+	// moving an element into itself or its descendants is an error
+	let item = first(&root, "li.x");
+	assert!(main
+		.insert_adjacent_with(&InsertPosition::BeforeEnd, &main.cloned(), InsertMode::Move)
+		.is_err());
+	let mut bold = first(&root, "b");
+	assert!(bold
+		.insert_adjacent_with(&InsertPosition::AfterEnd, &main, InsertMode::Move)
+		.is_err());
+	// the copy is inserted and the element is kept
+	let copy = main
+		.insert_adjacent_with(&InsertPosition::AfterBegin, &item, InsertMode::Clone)
+		.unwrap();
+	assert!(!copy.is(&item));
+	assert!(item.parent().unwrap().is(&list));
+	assert_eq!(children_texts(&main), "bonetwo boldthree");
+	// the elements keep their order, the last element gets the moved ones and the others get the copies
+	let mut paras = root.find("#main p");
+	let mut items = root.find("#list li");
+	paras.insert_with(&items, &InsertPosition::BeforeEnd, InsertMode::Move);
+	assert_eq!(children_texts(&list), "");
+	assert_eq!(paras.get(0).unwrap().text(), "oneabc");
+	assert_eq!(paras.get(1).unwrap().text(), "two boldabc");
+	assert!(items
+		.get(0)
+		.unwrap()
+		.parent()
+		.unwrap()
+		.is(paras.get(1).unwrap()));
+	// the copies in the `Clone` mode
+	list.cloned().set_html("<li>1</li>");
+	let mut list_set = root.find("#list");
+	list_set.insert_with(&items, &InsertPosition::AfterBegin, InsertMode::Clone);
+	assert_eq!(children_texts(&list), "abc1");
+	assert_eq!(paras.get(1).unwrap().text(), "two boldabc");
+	items.before(&mut root.find("span"));
+	assert_eq!(root.find("#main > span").length(), 0);
+	assert_eq!(root.find("span").length(), 3);
+	assert_eq!(
+		children_texts(&paras.get(1).unwrap().cloned()),
+		"boldthreeathreebthreec"
+	);
+}
+
+/// check moving the nodes between the documents
+pub fn adoption<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let mut doc = load(&parse);
+	let other = parse("<html><body><p id=\"from\">moved <b>bold</b></p><i>kept</i></body></html>");
+	let root = root_of(&doc);
+	let other_root = root_of(&other);
+	// the nodes of the documents are different
+	let main = first(&root, "#main");
+	let para = first(&other_root, "#from");
+	assert!(!main.is(&para));
+	assert!(!first(&root, "html").is(&first(&other_root, "html")));
+	// adopt then insert the node
+	let adopted = doc.adopt(&para.clone_node()).unwrap();
+	assert_eq!(first(&other_root, "body").children().length(), 1);
+	let adopted = adopted.typed().into_element().unwrap();
+	main
+		.cloned()
+		.insert_adjacent(&InsertPosition::AfterBegin, &adopted);
+	assert_eq!(root.find("#main > #from b").length(), 1);
+	assert!(first(&root, "#from").is(&adopted));
+	assert!(adopted.parent().unwrap().is(&main));
+	// the elements insertion adopts the nodes
+	let mut list = root.find("#list");
+	list.append(&mut other_root.find("i"));
+	assert_eq!(other_root.find("i").length(), 0);
+	assert_eq!(children_texts(&first(&root, "#list")), "abckept");
+	let mut italic = first(&root, "#list > i");
+	italic.set_text("changed");
+	assert_eq!(first(&root, "#list").text(), "abcchanged");
+	assert_eq!(other.get_root_node().text_content(), "");
+}
+
+/// check the deep copies are detached from the tree and independent of the source
+/// the built selectors find the elements again
+pub fn built_selectors<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(&parse);
+	let root = root_of(&doc);
+	for (selector, shortest, path) in [
+		(
+			"#main",
+			"#main",
+			"html:nth-child(1) > body:nth-child(2) > div:nth-child(1)",
+		),
+		(
+			"li.x",
+			"li.x",
+			"html:nth-child(1) > body:nth-child(2) > ul:nth-child(2) > li:nth-child(2)",
+		),
+		(
+			"#main b",
+			"b",
+			"html:nth-child(1) > body:nth-child(2) > div:nth-child(1) > p:nth-child(2) > b:nth-child(1)",
+		),
+		(
+			"title",
+			"title",
+			"html:nth-child(1) > head:nth-child(1) > title:nth-child(1)",
+		),
+	] {
+		let ele = first(&root, selector);
+		assert_eq!(ele.build_selector(SelectorStrategy::Shortest), shortest);
+		assert_eq!(ele.build_selector(SelectorStrategy::Path), path);
+		for built in [shortest, path] {
+			let found = root.find(built);
+			assert_eq!(found.length(), 1, "selector '{}'", built);
+			assert!(found.get(0).unwrap().is(&ele));
+		}
+	}
+	// the nth-child is only added when the tag and the classes are not unique in the siblings
+	let item = first(&root, "#list > li:last-child");
+	assert_eq!(
+		item.build_selector(SelectorStrategy::Shortest),
+		"li:nth-child(3)"
+	);
+}
+
+/// the node paths are resolved again
+pub fn node_paths<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(&parse);
+	let root = root_of(&doc);
+	let bold = first(&root, "#main b");
+	let path = bold.node_path();
+	assert_eq!(path.to_string(), "html[0]/body[1]/div[0]/p[1]/b[0]");
+	assert!(doc.from_path(&path).unwrap().is(&bold));
+	for ele in root.find("*").get_ref() {
+		assert!(doc.from_path(&ele.node_path()).unwrap().is(ele));
+	}
+	// the path is not resolved after the structure is changed
+	root.find("#main > p:first-child").remove();
+	assert!(doc.from_path(&path).is_none());
+}
+
+pub fn clone_deep<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let main = first(&root, "#main");
+	let mut copy = main
+		.clone_deep()
+		.expect("The backend must support `clone_deep`.");
+	assert!(copy.parent().is_none());
+	assert!(!copy.is(&main));
+	assert_eq!(
+		copy.outer_html().to_ascii_lowercase(),
+		main.outer_html().to_ascii_lowercase()
+	);
+	copy.set_attribute("id", Some("copy"));
+	let mut para = copy.children().get(0).unwrap().cloned();
+	para.set_text("changed");
+	assert_eq!(main.text(), "onetwo boldthree");
+	main
+		.cloned()
+		.insert_adjacent(&InsertPosition::AfterEnd, &copy);
+	assert!(copy.parent().unwrap().is(&main.parent().unwrap()));
+	assert_eq!(root.find("#copy p").length(), 2);
+	assert_eq!(root.find("#main p").length(), 2);
+}
+
+/// check the shallow copy has the tag name and the attributes but no child nodes
+pub fn clone_shallow<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let main = first(&root, "#main");
+	let mut copy = main
+		.clone_shallow()
+		.expect("The backend must support `clone_shallow`.");
+	assert!(copy.parent().is_none());
+	assert!(!copy.is(&main));
+	assert_eq!(copy.tag_name(), main.tag_name());
+	assert_eq!(copy.namespace_uri(), main.namespace_uri());
+	assert_eq!(copy.get_attribute("id").unwrap().to_string(), "main");
+	assert_eq!(copy.get_attribute("class").unwrap().to_string(), "box main");
+	assert_eq!(copy.child_nodes_length(), 0);
+	copy.set_html("<p>copy</p>");
+	assert_eq!(copy.text(), "copy");
+	assert_eq!(main.text(), "onetwo boldthree");
+}
+
+/// check the template contents are inert and instantiated by the insert APIs,
+/// the serialized html of the template is not checked, some trees don't serialize the contents
+pub fn template_contents<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	crate::init();
+	let doc = parse(concat!(
+		"<html><head></head><body><ul id=\"list\"></ul>",
+		"<template id=\"row\"><li class=\"item\">x</li></template></body></html>"
+	));
+	let root = root_of(&doc);
+	let template = first(&root, "#row");
+	assert_eq!(template.children().length(), 0);
+	assert!(first(&root, "#list").template_content().is_none());
+	// the contents are not matched by the selectors of the document
+	assert_eq!(root.find(".item").length(), 0);
+	let content = template
+		.template_content()
+		.expect("The template must have the contents.");
+	assert_eq!(content.children().length(), 1);
+	assert_eq!(children_texts(&content), "x");
+	// each insertion instantiates the copies, the contents are kept
+	let mut contents = root.find("#row").template_content();
+	root.find("#list").append(&mut contents);
+	root.find("#list").append(&mut contents);
+	assert_eq!(root.find("#list > li.item").length(), 2);
+	assert_eq!(content.children().length(), 1);
+	// the copy of the template has its own contents
+	let copy = template.clone_deep().unwrap();
+	let copy_content = copy.template_content().unwrap();
+	assert!(!copy_content.is(&content));
+	assert_eq!(children_texts(&copy_content), "x");
+}
+
+/// check the document copy has its own nodes, the changes don't affect each other
+pub fn document_clone<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let copy = doc.deep_clone().unwrap();
+	assert_eq!(copy.source_code(), doc.source_code());
+	let root = root_of(&doc);
+	let copy_root = root_of(&*copy);
+	first(&copy_root, "#main").set_attribute("class", Some("copy"));
+	assert_eq!(copy_root.find(".copy").length(), 1);
+	assert_eq!(root.find(".copy").length(), 0);
+	first(&root, "p.first").set_text("changed");
+	assert_eq!(first(&copy_root, "p.first").text(), "one");
+	assert!(copy.get_element_by_id("list").is_some());
+}
+
+/// check splitting the text nodes and wrapping them in elements
+pub fn text_nodes<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let para = first(&root, "p.first");
+	let mut text = para
+		.child_nodes_item(0)
+		.and_then(|node| node.typed().into_text())
+		.expect("The 'p' must have a text node.");
+	let mut rest = text
+		.split_text(1)
+		.expect("The backend must support `split_text`.");
+	assert_eq!(text.text_content(), "o");
+	assert_eq!(rest.text_content(), "ne");
+	assert_eq!(para.child_nodes_length(), 2);
+	assert_eq!(para.text(), "one");
+	let wrapper = first(&root, "b")
+		.clone_deep()
+		.expect("The backend must support `clone_deep`.");
+	rest
+		.wrap(&wrapper)
+		.expect("The backend must support `wrap`.");
+	assert!(wrapper.parent().unwrap().is(&para));
+	assert_eq!(wrapper.index(), 1);
+	// the text is appended after the wrapper's children
+	assert_eq!(para.inner_html().to_ascii_lowercase(), "o<b>boldne</b>");
+}
+
+/// check the prefixed attributes in svg, such as `xlink:href`
+pub fn namespaced_attributes<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = parse(concat!(
+		"<!DOCTYPE html><html><head></head><body>",
+		"<svg><a xlink:href=\"#a\" href=\"#b\"></a></svg></body></html>"
+	));
+	let root = root_of(&doc);
+	let mut link = first(&root, "a");
+	assert!(link.get_attribute("xlink:href").unwrap().is_str("#a"));
+	assert!(link.get_attribute("href").unwrap().is_str("#b"));
+	assert!(link
+		.get_attribute_ns(Some("xlink"), "href")
+		.unwrap()
+		.is_str("#a"));
+	assert!(link.get_attribute_ns(None, "href").unwrap().is_str("#b"));
+	let names: Vec<String> = link
+		.attribute_names()
+		.into_iter()
+		.map(|name| name.to_ascii_lowercase())
+		.collect();
+	assert!(names.contains(&String::from("xlink:href")));
+	assert_eq!(root.find("[xlink|href='#a']").length(), 1);
+	assert_eq!(root.find("[xlink|href='#b']").length(), 0);
+	assert_eq!(root.find("[*|href]").length(), 1);
+	assert_eq!(root.find("[|href='#b']").length(), 1);
+	// the prefixed attributes round-trip
+	link.set_attribute("xlink:title", Some("t"));
+	assert!(link.get_attribute("xlink:title").unwrap().is_str("t"));
+	assert!(link.outer_html().contains("xlink:title=\"t\""));
+	link.remove_attribute("xlink:href");
+	assert!(link.get_attribute("xlink:href").is_none());
+	assert!(link.get_attribute("href").is_some());
+}
+
+/// check the namespaces and the case-sensitive names of the svg and mathml elements
+pub fn foreign_content<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	crate::init();
+	let doc = parse(concat!(
+		"<!DOCTYPE html><html><head></head><body><div>",
+		"<svg><linearGradient id=\"g\"></linearGradient><text>label</text>",
+		"<foreignObject><p>html</p></foreignObject></svg>",
+		"<math><mi>x</mi></math><p class=\"text\">text</p></div></body></html>"
+	));
+	let root = root_of(&doc);
+	let namespace = |selector: &str| first(&root, selector).namespace_uri().map(String::from);
+	assert_eq!(namespace("div").as_deref(), Some(HTML_NAMESPACE));
+	assert_eq!(namespace("svg").as_deref(), Some(SVG_NAMESPACE));
+	assert_eq!(namespace("svg text").as_deref(), Some(SVG_NAMESPACE));
+	assert_eq!(
+		namespace("foreignObject > p").as_deref(),
+		Some(HTML_NAMESPACE)
+	);
+	assert_eq!(namespace("math").as_deref(), Some(MATHML_NAMESPACE));
+	assert_eq!(namespace("mi").as_deref(), Some(MATHML_NAMESPACE));
+	// the svg names are case-sensitive, the html names are not
+	let gradient = first(&root, "linearGradient");
+	assert_eq!(gradient.local_name(), "linearGradient");
+	assert_eq!(gradient.tag_name().to_ascii_uppercase(), "LINEARGRADIENT");
+	assert_eq!(root.find("lineargradient").length(), 0);
+	assert_eq!(root.find("DIV").length(), 1);
+	assert_eq!(root.find("svg text").length(), 1);
+	assert_eq!(root.find("text").length(), 1);
+	assert_eq!(root.find("svg p").length(), 1);
+	assert_eq!(first(&root, "div").local_name(), "div");
+	assert!(root.find("#g").get(0).unwrap().text_content().is_empty());
+	// the serializer keeps the case of the names
+	let html = serialize(
+		&first(&root, "svg").clone_node(),
+		&SerializeOptions::default(),
+	)
+	.unwrap();
+	assert!(html.contains("<linearGradient id=\"g\"></linearGradient>"));
+	assert!(html.contains("<foreignObject><p>html</p></foreignObject>"));
+}
+
+/// check the charset and the meta helpers, the new elements are inserted into the head
+pub fn head_meta<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let mut doc = load(&parse);
+	assert_eq!(doc.charset(), None);
+	doc.set_charset("utf-8").unwrap();
+	assert_eq!(doc.charset().as_deref(), Some("utf-8"));
+	doc.set_charset("gbk").unwrap();
+	assert_eq!(doc.charset().as_deref(), Some("gbk"));
+	let head = Elements::with_nodes(vec![doc.head().unwrap()]);
+	assert_eq!(head.children("").get(0).unwrap().tag_name(), "META");
+	assert_eq!(doc.meta("viewport"), None);
+	doc.set_meta("viewport", "width=device-width").unwrap();
+	doc.set_meta("Viewport", "width=100").unwrap();
+	doc.set_meta("robots", "noindex").unwrap();
+	assert_eq!(doc.meta("VIEWPORT").as_deref(), Some("width=100"));
+	assert_eq!(doc.meta("robots").as_deref(), Some("noindex"));
+	assert_eq!(head.find("meta").length(), 3);
+	// the existed elements are kept
+	assert_eq!(doc.title().as_deref(), Some("Conformance"));
+	assert_eq!(root_of(&doc).find("#main").length(), 1);
+	// the charset in the content type
+	let mut doc = parse(concat!(
+		"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=ISO-8859-1\">",
+		"</head><body></body></html>"
+	));
+	assert_eq!(doc.charset().as_deref(), Some("ISO-8859-1"));
+	doc.set_charset("utf-8").unwrap();
+	assert_eq!(doc.charset().as_deref(), Some("utf-8"));
+	assert_eq!(root_of(&doc).find("meta").length(), 1);
+	// the head is created if missing
+	let mut doc = parse("<html><body><p>a</p></body></html>");
+	doc.set_meta("robots", "noindex").unwrap();
+	assert_eq!(doc.meta("robots").as_deref(), Some("noindex"));
+	assert_eq!(root_of(&doc).find("html > head + body > p").length(), 1);
+}
+
+/// check the title, stylesheet and script helpers of the head
+pub fn head_management<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let mut doc = load(&parse);
+	doc.set_title("New <title>").unwrap();
+	assert_eq!(doc.title().as_deref(), Some("New <title>"));
+	doc.add_stylesheet("/main.css").unwrap();
+	doc.add_stylesheet("/main.css").unwrap();
+	doc.add_script(ScriptSource::Src("/main.js")).unwrap();
+	doc.add_script(ScriptSource::Src("/main.js")).unwrap();
+	doc
+		.add_script(ScriptSource::Inline("var a = 1 < 2;"))
+		.unwrap();
+	let head = Elements::with_nodes(vec![doc.head().unwrap()]);
+	let children = head.children("");
+	let tag_names: Vec<&str> = children
+		.get_ref()
+		.iter()
+		.map(|ele| ele.tag_name())
+		.collect();
+	assert_eq!(tag_names, vec!["TITLE", "LINK", "SCRIPT", "SCRIPT"]);
+	assert_eq!(
+		head
+			.find("link[rel='stylesheet'][href='/main.css']")
+			.length(),
+		1
+	);
+	assert_eq!(head.find("script[src='/main.js']").length(), 1);
+	assert_eq!(head.find("script:not([src])").text(), "var a = 1 < 2;");
+	// the title is created if missing
+	let mut doc = parse("<html><head></head><body></body></html>");
+	assert_eq!(doc.title(), None);
+	doc.set_title("Created").unwrap();
+	assert_eq!(doc.title().as_deref(), Some("Created"));
+}
+
+/// check the base url detection and the url attributes resolved against it
+pub fn base_url<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	crate::init();
+	let mut doc = parse(concat!(
+		"<html><head><base href=\"https://example.com/docs/\"></head><body>",
+		"<a href=\"../a?x#y\">a</a><a href=\"mailto:a@b.c\">b</a><a>c</a></body></html>"
+	));
+	assert_eq!(doc.base_url().as_deref(), Some("https://example.com/docs/"));
+	let root = root_of(&doc);
+	let links = root.find("a");
+	let absolute_urls: Vec<Option<String>> = links
+		.get_ref()
+		.iter()
+		.map(|link| link.absolute_attr("href"))
+		.collect();
+	assert_eq!(
+		absolute_urls,
+		vec![
+			Some(String::from("https://example.com/a?x#y")),
+			Some(String::from("mailto:a@b.c")),
+			None
+		]
+	);
+	doc.set_base_url("https://example.org/x/y/").unwrap();
+	assert_eq!(root.find("base").length(), 1);
+	assert_eq!(
+		links.get(0).unwrap().absolute_attr("href").as_deref(),
+		Some("https://example.org/x/a?x#y")
+	);
+	// the base is inserted if missing
+	let mut doc =
+		parse("<html><head><title>a</title></head><body><a href=\"/a\">a</a></body></html>");
+	let link = first(&root_of(&doc), "a");
+	if doc.url().is_none() {
+		assert_eq!(doc.base_url(), None);
+		assert_eq!(link.absolute_attr("href"), None);
+	}
+	doc.set_base_url("http://h/p/").unwrap();
+	assert_eq!(link.absolute_attr("href").as_deref(), Some("http://h/a"));
+	let head = Elements::with_nodes(vec![doc.head().unwrap()]);
+	assert_eq!(head.children("").get(0).unwrap().tag_name(), "BASE");
+}
+
+/// check the visitor removes, replaces the nodes and skips the children
+pub fn visitor<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	struct Sanitizer {
+		replacements: Vec<BoxDynElement<'static>>,
+		entered: Vec<String>,
+		left: Vec<String>,
+	}
+	impl NodeVisitor for Sanitizer {
+		fn enter<'b>(&mut self, node: &BoxDynNode<'b>) -> VisitAction<'b> {
+			match node.clone_node().typed() {
+				IEnumTyped::Element(ele) => {
+					let tag_name = ele.tag_name().to_ascii_lowercase();
+					self.entered.push(tag_name.clone());
+					match tag_name.as_str() {
+						"span" => VisitAction::Remove,
+						"ul" => VisitAction::SkipChildren,
+						"b" => VisitAction::Replace(self.replacements.pop().unwrap()),
+						_ => VisitAction::Continue,
+					}
+				}
+				_ if matches!(node.node_type(), INodeType::Comment) => VisitAction::Remove,
+				_ => VisitAction::Continue,
+			}
+		}
+		fn leave<'b>(&mut self, node: &BoxDynNode<'b>) -> VisitAction<'b> {
+			if matches!(node.node_type(), INodeType::Text) {
+				self.left.push(String::from(node.text_content()));
+				if node.text_content() == "one" {
+					return VisitAction::Replace(self.replacements.pop().unwrap());
+				}
+			}
+			VisitAction::Continue
+		}
+	}
+	crate::init();
+	let mut doc = parse(concat!(
+		"<html><head></head><body><div id=\"main\"><!--c--><p class=\"first\">one</p>",
+		"<p>two <b>bold</b></p><span>x</span></div><ul><li>a</li></ul><em>new</em></body></html>"
+	));
+	let root = root_of(&doc);
+	let template = first(&root, "em");
+	let mut sanitizer = Sanitizer {
+		replacements: vec![
+			template.clone_deep().unwrap(),
+			template.clone_deep().unwrap(),
+		],
+		entered: Vec::new(),
+		left: Vec::new(),
+	};
+	doc.visit_mut(&mut sanitizer).unwrap();
+	assert!(sanitizer.replacements.is_empty());
+	assert!(!sanitizer.entered.contains(&String::from("li")));
+	// the removed and replaced nodes are not visited
+	assert_eq!(sanitizer.left, vec!["one", "two ", "new"]);
+	let main = first(&root, "#main");
+	assert_eq!(main.child_nodes_length(), 2);
+	assert_eq!(root.find("span, b").length(), 0);
+	assert_eq!(root.find("p.first > em").length(), 1);
+	assert_eq!(root.find("p > em").length(), 2);
+	assert_eq!(main.text_content(), "newtwo new");
+	assert_eq!(root.find("li").text(), "a");
+}
+
+/// check the range, it relies on the clone_deep, split_text, wrap, insert_child and removing the nodes
+pub fn range<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let text_of = |selector: &str| first(&root, selector).child_nodes_item(0).unwrap();
+	let one = text_of("p.first");
+	let two = text_of("p:nth-child(2)");
+	// the start must not be after the end
+	assert!(Range::new(&two, 0, &one, 0).is_err());
+	assert!(Range::new(&one, 4, &two, 0).is_err());
+	let mut range = Range::new(&one, 1, &two, 2).unwrap();
+	assert!(!range.collapsed());
+	let ancestor = range.common_ancestor_container().unwrap();
+	assert!(ancestor.get_attribute("id").unwrap().is_str("main"));
+	let fragment = range.clone_contents().unwrap();
+	assert_eq!(fragment.inner_html(), "<p class=\"first\">ne</p><p>tw</p>");
+	assert_eq!(first(&root, "p.first").html(), "one");
+	// the element is partially selected
+	let wrapper = first(&root, "b").clone_deep().unwrap();
+	assert!(range.surround_contents(&wrapper).is_err());
+	let fragment = range.extract_contents().unwrap();
+	assert_eq!(fragment.inner_html(), "<p class=\"first\">ne</p><p>tw</p>");
+	assert!(range.collapsed());
+	assert_eq!(first(&root, "p.first").html(), "o");
+	assert_eq!(first(&root, "p:nth-child(2)").html(), "o <b>bold</b>");
+	// everything between the two nodes
+	let items = root.find("li");
+	let mut range = Range::between(
+		&items.get(0).unwrap().clone_node(),
+		&items.get(2).unwrap().clone_node(),
+	)
+	.unwrap();
+	assert_eq!(
+		range.extract_contents().unwrap().inner_html(),
+		"<li class=\"x\">b</li>"
+	);
+	assert_eq!(root.find("li").text(), "ac");
+	// surround the part of the text
+	let bold = text_of("b");
+	let mut range = Range::new(&bold, 1, &bold, 3).unwrap();
+	range.surround_contents(&wrapper).unwrap();
+	assert_eq!(
+		first(&root, "p:nth-child(2)").html(),
+		"o <b>b<b>ol</b>d</b>"
+	);
+	assert_eq!(range.start_offset(), 1);
+	assert_eq!(range.end_offset(), 2);
+	// surround the nodes
+	let main = first(&root, "#main").clone_node();
+	let mut range = Range::new(&main, 0, &main, 2).unwrap();
+	range
+		.surround_contents(&first(&root, "li").clone_deep().unwrap())
+		.unwrap();
+	assert_eq!(root.find("#main > li > p").length(), 2);
+	assert_eq!(first(&root, "#main").children().length(), 2);
+}
+
+/// check the selectors, they rely on the children, siblings, index and attributes
+pub fn selectors<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let cases: [(&str, usize); 20] = [
+		("#main", 1),
+		(".main", 1),
+		(".box.main", 1),
+		("[data-x]", 1),
+		("[data-x='1']", 1),
+		("[class^='bo']", 1),
+		("div > p", 2),
+		("body p", 2),
+		("p + span", 1),
+		("p ~ span", 1),
+		("li:first-child", 1),
+		("li:last-child", 1),
+		("li:nth-child(2)", 1),
+		("li:nth-child(odd)", 2),
+		("li:not(.x)", 2),
+		("p:contains('bold')", 1),
+		(":empty", 0),
+		("span:only-of-type", 1),
+		("p, li", 5),
+		("html *", 12),
+	];
+	for (selector, count) in cases.iter() {
+		assert_eq!(
+			root.find(selector).length(),
+			*count,
+			"The selector '{}' should match {} elements.",
+			selector,
+			count
+		);
+	}
+	assert_eq!(root.find("div").has("b").length(), 1);
+	assert_eq!(root.find("b").closest("div").length(), 1);
+	assert_eq!(root.find("b").parents("div, body, html").length(), 3);
+}
+
+/// check the results are in document order
+pub fn document_order<D, F>(parse: F)
+where
+	D: IDocumentTrait,
+	F: Fn(&str) -> D,
+{
+	let doc = load(parse);
+	let root = root_of(&doc);
+	let tag_names = |eles: &Elements| -> Vec<String> {
+		eles
+			.get_ref()
+			.iter()
+			.map(|ele| ele.tag_name().to_string())
+			.collect()
+	};
+	assert_eq!(
+		tag_names(&root.find("li, span, p")),
+		vec!["P", "P", "SPAN", "LI", "LI", "LI"]
+	);
+	assert_eq!(
+		tag_names(&root.find("li").add(root.find("p"))),
+		vec!["P", "P", "LI", "LI", "LI"]
+	);
+}
+
+/// define the `#[test]` of all the checks for a backend's parse function,
+/// the tests are defined in a new module named `mesdoc_conformance`,
+/// the backend stores the attributes in a sorted map skips the `attribute_order` check by `unordered_attributes`
+///
+/// ```ignore
+/// mesdoc::conformance_tests!(my_backend::parse);
+/// mesdoc::conformance_tests!(my_backend::parse, unordered_attributes);
+/// ```
+#[macro_export]
+macro_rules! conformance_tests {
+	($parse:expr) => {
+		$crate::conformance_tests!(@checks $parse; attribute_order);
+	};
+	($parse:expr, unordered_attributes) => {
+		$crate::conformance_tests!(@checks $parse;);
+	};
+	(@checks $parse:expr; $($extra:ident),*) => {
+		$crate::conformance_tests!(
+			$parse;
+			document,
+			tag_names,
+			attributes,
+			navigation,
+			identity,
+			text_content,
+			html,
+			mutations,
+			adjacent_html,
+			reparse,
+			insert_modes,
+			adoption,
+			built_selectors,
+			node_paths,
+			clone_deep,
+			clone_shallow,
+			template_contents,
+			document_clone,
+			text_nodes,
+			namespaced_attributes,
+			foreign_content,
+			head_meta,
+			head_management,
+			base_url,
+			visitor,
+			range,
+			selectors,
+			document_order,
+			$($extra),*
+		);
+	};
+	($parse:expr; $($check:ident),+ $(,)?) => {
+		#[allow(unused_imports)]
+		mod mesdoc_conformance {
+			use super::*;
+			$(
+				#[test]
+				fn $check() {
+					$crate::adapter::conformance::$check($parse);
+				}
+			)+
+		}
+	};
+}
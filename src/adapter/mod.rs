@@ -0,0 +1,8 @@
+// generational arena, store the nodes by ids
+mod arena;
+pub use arena::{Arena, NodeId};
+// owned attribute map
+mod attrs;
+pub use attrs::AttrMap;
+// conformance test suite for the backends
+pub mod conformance;
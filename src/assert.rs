@@ -0,0 +1,287 @@
+//! the assertions of the parsed documents for testing the rendered html,
+//! the failure messages show the expected and the found values with the outer html of the elements
+use crate::interface::{BoxDynElement, Elements, IAttrValue, IDocumentTrait};
+use crate::serialize::{compare_snapshot, snapshot, SnapshotOptions};
+use std::path::Path;
+
+// the max chars of the outer html in the failure messages
+const MAX_HTML_LEN: usize = 200;
+
+/// the document, the element or the elements checked by the assertions
+pub trait AssertScope {
+	// the elements to check
+	fn elements(&self) -> Elements<'_>;
+}
+
+impl<T: IDocumentTrait> AssertScope for T {
+	fn elements(&self) -> Elements<'_> {
+		Elements::with_nodes(
+			self
+				.get_root_node()
+				.typed()
+				.into_element()
+				.into_iter()
+				.collect(),
+		)
+	}
+}
+
+impl<'a> AssertScope for BoxDynElement<'a> {
+	fn elements(&self) -> Elements<'_> {
+		Elements::with_nodes(vec![self.cloned()])
+	}
+}
+
+impl<'a> AssertScope for Elements<'a> {
+	fn elements(&self) -> Elements<'_> {
+		self.cloned()
+	}
+}
+
+/// how the value is compared with the expected one
+#[derive(Debug, Clone, Copy)]
+pub enum Expected<'e> {
+	Equal(&'e str),
+	Contains(&'e str),
+}
+
+impl<'e> Expected<'e> {
+	fn check(&self, value: &str) -> bool {
+		match *self {
+			Expected::Equal(expected) => value == expected,
+			Expected::Contains(expected) => value.contains(expected),
+		}
+	}
+	fn describe(&self) -> String {
+		match self {
+			Expected::Equal(expected) => format!("{:?}", expected),
+			Expected::Contains(expected) => format!("contains {:?}", expected),
+		}
+	}
+}
+
+// the outer html of the elements, one element per line
+fn outer_htmls(eles: &Elements) -> String {
+	eles
+		.get_ref()
+		.iter()
+		.map(|ele| {
+			let html = ele.outer_html();
+			match html.char_indices().nth(MAX_HTML_LEN) {
+				Some((index, _)) => format!("  {}...", &html[..index]),
+				None => format!("  {}", html),
+			}
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+// the message of a failed assertion
+fn failure(title: String, expected: String, found: String, eles: &Elements) -> String {
+	let mut message = format!("{}\n- {}\n+ {}", title, expected, found);
+	if !eles.is_empty() {
+		message.push('\n');
+		message.push_str(&outer_htmls(eles));
+	}
+	message
+}
+
+/// pub fn `check_selector`
+/// check the scope has the elements matched the selector, exactly `count` elements if it's set
+pub fn check_selector<S: AssertScope + ?Sized>(
+	scope: &S,
+	selector: &str,
+	count: Option<usize>,
+) -> Result<(), String> {
+	let scope = scope.elements();
+	let found = scope.find(selector);
+	let total = found.length();
+	let is_ok = match count {
+		Some(count) => total == count,
+		None => total > 0,
+	};
+	if is_ok {
+		return Ok(());
+	}
+	let expected = match count {
+		Some(count) => format!("{} elements", count),
+		None => String::from("at least 1 element"),
+	};
+	// show the searched elements when nothing is found
+	let shown = if found.is_empty() { &scope } else { &found };
+	Err(failure(
+		format!("The selector '{}' matched {} elements", selector, total),
+		expected,
+		format!("{} elements", total),
+		shown,
+	))
+}
+
+/// pub fn `check_text`
+/// check the text content of the scope's elements
+pub fn check_text<S: AssertScope + ?Sized>(scope: &S, expected: Expected) -> Result<(), String> {
+	let scope = scope.elements();
+	let text = scope.text();
+	if expected.check(text) {
+		return Ok(());
+	}
+	Err(failure(
+		String::from("The text is not matched"),
+		expected.describe(),
+		format!("{:?}", text),
+		&scope,
+	))
+}
+
+/// pub fn `check_attr`
+/// check each element of the scope has the attribute, and the value is matched if `expected` is set
+pub fn check_attr<S: AssertScope + ?Sized>(
+	scope: &S,
+	name: &str,
+	expected: Option<Expected>,
+) -> Result<(), String> {
+	let scope = scope.elements();
+	if scope.is_empty() {
+		return Err(format!("No element to check the attribute '{}'", name));
+	}
+	for ele in scope.get_ref() {
+		let value = ele.get_attribute(name);
+		let found = match &value {
+			None => String::from("no attribute"),
+			Some(IAttrValue::True) => String::from("\"\""),
+			Some(IAttrValue::Value(value, _)) => format!("{:?}", value),
+		};
+		let is_ok = match (&value, expected) {
+			(None, _) => false,
+			(Some(_), None) => true,
+			(Some(value), Some(expected)) => expected.check(&value.to_string()),
+		};
+		if !is_ok {
+			return Err(failure(
+				format!("The attribute '{}' is not matched", name),
+				expected.map_or_else(
+					|| String::from("has the attribute"),
+					|expected| expected.describe(),
+				),
+				found,
+				&Elements::with_nodes(vec![ele.cloned()]),
+			));
+		}
+	}
+	Ok(())
+}
+
+/// pub fn `check_snapshot`
+/// check the normalized html of the scope's elements equals to the snapshot file, one element after another,
+/// the missing snapshot file is written with the found html
+pub fn check_snapshot<S: AssertScope + ?Sized, P: AsRef<Path>>(
+	scope: &S,
+	path: P,
+	options: SnapshotOptions,
+) -> Result<(), String> {
+	let found = scope
+		.elements()
+		.get_ref()
+		.iter()
+		.map(|ele| snapshot(&ele.clone_node(), options.clone()))
+		.collect::<Vec<_>>()
+		.join("\n");
+	compare_snapshot(path, &found)
+}
+
+/// assert the document, the element or the elements has the elements matched the selector
+///
+/// ```ignore
+/// mesdoc::assert_selector!(doc, "ul > li");
+/// mesdoc::assert_selector!(doc, "ul > li", count = 3);
+/// ```
+#[macro_export]
+macro_rules! assert_selector {
+	($scope:expr, $selector:expr) => {
+		if let Err(message) = $crate::assert::check_selector(&$scope, $selector, None) {
+			panic!("{}", message);
+		}
+	};
+	($scope:expr, $selector:expr, count = $count:expr) => {
+		if let Err(message) = $crate::assert::check_selector(&$scope, $selector, Some($count)) {
+			panic!("{}", message);
+		}
+	};
+}
+
+/// assert the text content equals to or contains the text
+///
+/// ```ignore
+/// mesdoc::assert_text!(title, "Welcome");
+/// mesdoc::assert_text!(title, contains "Welcome");
+/// ```
+#[macro_export]
+macro_rules! assert_text {
+	($scope:expr, contains $text:expr) => {
+		if let Err(message) =
+			$crate::assert::check_text(&$scope, $crate::assert::Expected::Contains($text))
+		{
+			panic!("{}", message);
+		}
+	};
+	($scope:expr, $text:expr) => {
+		if let Err(message) =
+			$crate::assert::check_text(&$scope, $crate::assert::Expected::Equal($text))
+		{
+			panic!("{}", message);
+		}
+	};
+}
+
+/// assert each element has the attribute, and the value equals to or contains the value
+///
+/// ```ignore
+/// mesdoc::assert_attr!(link, "href");
+/// mesdoc::assert_attr!(link, "href", "/about");
+/// mesdoc::assert_attr!(link, "class", contains "active");
+/// ```
+#[macro_export]
+macro_rules! assert_attr {
+	($scope:expr, $name:expr) => {
+		if let Err(message) = $crate::assert::check_attr(&$scope, $name, None) {
+			panic!("{}", message);
+		}
+	};
+	($scope:expr, $name:expr, contains $value:expr) => {
+		if let Err(message) = $crate::assert::check_attr(
+			&$scope,
+			$name,
+			Some($crate::assert::Expected::Contains($value)),
+		) {
+			panic!("{}", message);
+		}
+	};
+	($scope:expr, $name:expr, $value:expr) => {
+		if let Err(message) = $crate::assert::check_attr(
+			&$scope,
+			$name,
+			Some($crate::assert::Expected::Equal($value)),
+		) {
+			panic!("{}", message);
+		}
+	};
+}
+
+/// assert the normalized html equals to the snapshot file, the file is written if it doesn't exist
+/// or the environment variable `MESDOC_UPDATE_SNAPSHOTS` is set
+///
+/// ```ignore
+/// mesdoc::assert_snapshot!(nav, "tests/snapshots/nav.html");
+/// mesdoc::assert_snapshot!(nav, "tests/snapshots/nav.html", options);
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+	($scope:expr, $path:expr) => {
+		$crate::assert_snapshot!($scope, $path, Default::default())
+	};
+	($scope:expr, $path:expr, $options:expr) => {
+		if let Err(message) = $crate::assert::check_snapshot(&$scope, $path, $options) {
+			panic!("{}", message);
+		}
+	};
+}
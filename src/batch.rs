@@ -0,0 +1,53 @@
+use crate::error::Error as IError;
+use crate::interface::IDocumentTrait;
+use std::error::Error;
+use std::path::PathBuf;
+
+/// the source of a document in the batch, a file path or the html
+#[derive(Debug, Clone)]
+pub enum Source {
+	Path(PathBuf),
+	Html(String),
+}
+
+impl Source {
+	// read the html of the source
+	fn read(&self) -> Result<String, Box<dyn Error>> {
+		match self {
+			Source::Path(path) => Ok(std::fs::read_to_string(path)?),
+			Source::Html(html) => Ok(html.clone()),
+		}
+	}
+}
+
+/// pub fn `process`
+/// parse the sources and call the handle with each document, the results keep the order of the sources,
+/// a failed source doesn't stop the others; with the `rayon` feature the sources are processed in a thread pool
+pub fn process<D, P, F, R>(sources: &[Source], parse: P, handle: F) -> Vec<Result<R, IError>>
+where
+	D: IDocumentTrait,
+	P: Fn(&str) -> D + Sync,
+	F: Fn(&mut D) -> Result<R, Box<dyn Error>> + Sync,
+	R: Send,
+{
+	// the rules are registered once before the threads use them
+	crate::init();
+	let run = |(index, source): (usize, &Source)| -> Result<R, IError> {
+		source
+			.read()
+			.and_then(|html| handle(&mut parse(&html)))
+			.map_err(|err| IError::BatchFailed {
+				index,
+				message: err.to_string(),
+			})
+	};
+	#[cfg(feature = "rayon")]
+	{
+		use rayon::prelude::*;
+		sources.par_iter().enumerate().map(run).collect()
+	}
+	#[cfg(not(feature = "rayon"))]
+	{
+		sources.iter().enumerate().map(run).collect()
+	}
+}
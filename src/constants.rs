@@ -2,6 +2,10 @@
 pub const ATTR_CLASS: &str = "class";
 // default elements initial node length
 pub const DEF_NODES_LEN: usize = 5;
+// the max levels of the nested selectors, such as `:not(:not(...))`
+pub const MAX_NESTED_DEPTH: usize = 32;
+// the max chars of a selector
+pub const MAX_SELECTOR_LENGTH: usize = 4096;
 // priorities
 /*
 ** different from css selector priority
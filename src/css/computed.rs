@@ -0,0 +1,179 @@
+use super::{parse_declarations, Declaration, Stylesheet};
+use crate::interface::{BoxDynElement, IAttrValue, INodeType};
+
+/// the properties supported by `computed_style`
+pub const COMPUTED_PROPERTIES: [&str; 6] = [
+	"display",
+	"visibility",
+	"color",
+	"background-color",
+	"font-weight",
+	"font-style",
+];
+// the inherited properties
+const INHERITED_PROPERTIES: [&str; 4] = ["visibility", "color", "font-weight", "font-style"];
+// the user agent `display` of the elements, the others are `inline`
+const DISPLAY_NONE_TAGS: [&str; 12] = [
+	"head", "script", "style", "template", "title", "meta", "link", "base", "noscript", "datalist",
+	"param", "area",
+];
+const DISPLAY_BLOCK_TAGS: [&str; 37] = [
+	"html",
+	"body",
+	"address",
+	"article",
+	"aside",
+	"blockquote",
+	"center",
+	"details",
+	"dialog",
+	"dd",
+	"div",
+	"dl",
+	"dt",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"header",
+	"hgroup",
+	"hr",
+	"legend",
+	"main",
+	"menu",
+	"nav",
+	"ol",
+	"p",
+	"pre",
+	"section",
+	"summary",
+	"ul",
+];
+const DISPLAY_TABLE_TAGS: [(&str, &str); 10] = [
+	("table", "table"),
+	("caption", "table-caption"),
+	("colgroup", "table-column-group"),
+	("col", "table-column"),
+	("thead", "table-header-group"),
+	("tbody", "table-row-group"),
+	("tfoot", "table-footer-group"),
+	("tr", "table-row"),
+	("td", "table-cell"),
+	("th", "table-cell"),
+];
+const BOLD_TAGS: [&str; 10] = [
+	"b", "strong", "th", "h1", "h2", "h3", "h4", "h5", "h6", "dt",
+];
+const ITALIC_TAGS: [&str; 7] = ["i", "em", "cite", "var", "dfn", "address", "blockquote"];
+
+// the value of the user agent stylesheet, also used as the `initial` value
+fn initial_value(ele: &BoxDynElement, property: &str) -> String {
+	let tag_name = ele.tag_name().to_ascii_lowercase();
+	let tag_name = tag_name.as_str();
+	let value = match property {
+		"display" => {
+			if ele.has_attribute("hidden") || DISPLAY_NONE_TAGS.contains(&tag_name) {
+				"none"
+			} else if tag_name == "li" {
+				"list-item"
+			} else if let Some((_, display)) = DISPLAY_TABLE_TAGS
+				.iter()
+				.find(|(name, _)| *name == tag_name)
+			{
+				display
+			} else if DISPLAY_BLOCK_TAGS.contains(&tag_name) {
+				"block"
+			} else {
+				"inline"
+			}
+		}
+		"visibility" => "visible",
+		"color" => "canvastext",
+		"background-color" => "transparent",
+		"font-weight" if BOLD_TAGS.contains(&tag_name) => "bold",
+		"font-weight" => "normal",
+		"font-style" if ITALIC_TAGS.contains(&tag_name) => "italic",
+		"font-style" => "normal",
+		_ => "",
+	};
+	String::from(value)
+}
+
+// the declared value after the cascade: the important declarations win, then the inline style, then the rules
+fn cascaded_value(ele: &BoxDynElement, stylesheet: &Stylesheet, property: &str) -> Option<String> {
+	let inline = match ele.get_attribute("style") {
+		Some(IAttrValue::Value(style, _)) => parse_declarations(&style),
+		_ => Vec::new(),
+	};
+	let matched = stylesheet.matched_rules(ele);
+	let rule_declarations = matched
+		.iter()
+		.flat_map(|matched| matched.rule.declarations.iter());
+	// the later declarations win in the same level
+	let mut normal: Option<&Declaration> = None;
+	let mut important: Option<&Declaration> = None;
+	for declaration in rule_declarations.chain(inline.iter()) {
+		if declaration.name != property {
+			continue;
+		}
+		if declaration.important {
+			important = Some(declaration);
+		} else {
+			normal = Some(declaration);
+		}
+	}
+	important
+		.or(normal)
+		.map(|declaration| declaration.value.to_ascii_lowercase())
+}
+
+/// pub fn `computed_style`
+/// resolve the cascade and the inheritance of the property, only the `COMPUTED_PROPERTIES` are supported.
+/// the layout is not computed, so the values are the declared values, such as `red` for the color
+pub fn computed_style(
+	ele: &BoxDynElement,
+	stylesheet: &Stylesheet,
+	property: &str,
+) -> Option<String> {
+	let property = property.trim().to_ascii_lowercase();
+	if !COMPUTED_PROPERTIES.contains(&property.as_str()) {
+		return None;
+	}
+	let is_inherited = INHERITED_PROPERTIES.contains(&property.as_str());
+	let parent_value = || {
+		ele
+			.parent()
+			.filter(|parent| matches!(parent.node_type(), INodeType::Element))
+			.and_then(|parent| computed_style(&parent, stylesheet, &property))
+			.unwrap_or_else(|| initial_value(ele, &property))
+	};
+	let value = match cascaded_value(ele, stylesheet, &property).as_deref() {
+		Some("inherit") => parent_value(),
+		Some("unset") if is_inherited => parent_value(),
+		Some("initial") | Some("unset") => initial_value(ele, &property),
+		Some(value) => String::from(value),
+		None if is_inherited => {
+			// the user agent values of the elements such as `<b>` win the inheritance
+			let initial = initial_value(ele, &property);
+			let is_ua_styled = match property.as_str() {
+				"font-weight" => initial == "bold",
+				"font-style" => initial == "italic",
+				_ => false,
+			};
+			if is_ua_styled {
+				initial
+			} else {
+				parent_value()
+			}
+		}
+		None => initial_value(ele, &property),
+	};
+	Some(value)
+}
@@ -0,0 +1,175 @@
+use super::split_top_level;
+
+// the pixels of `1em` and `1rem`, the default font size
+const FONT_SIZE_PX: f32 = 16.0;
+
+/// the viewport to evaluate the media queries, such as the `media` attributes of `<source>`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+	// the width in css pixels
+	pub width: f32,
+	// the height in css pixels
+	pub height: f32,
+	// the device pixels of a css pixel
+	pub device_pixel_ratio: f32,
+}
+
+impl Default for Viewport {
+	fn default() -> Self {
+		Viewport {
+			width: 1280.0,
+			height: 800.0,
+			device_pixel_ratio: 1.0,
+		}
+	}
+}
+
+// the length in css pixels, such as `600px`, `40em` and `50vw`, the unitless length must be `0`
+pub(crate) fn length_px(value: &str, viewport: &Viewport) -> Option<f32> {
+	let value = value.trim().to_ascii_lowercase();
+	let unit_start = value
+		.find(|ch: char| ch.is_ascii_alphabetic() || ch == '%')
+		.unwrap_or(value.len());
+	let number = value[..unit_start].trim().parse::<f32>().ok()?;
+	let px = match &value[unit_start..] {
+		"" if number == 0.0 => 0.0,
+		"px" => number,
+		"em" | "rem" => number * FONT_SIZE_PX,
+		"vw" => number * viewport.width / 100.0,
+		"vh" => number * viewport.height / 100.0,
+		"vmin" => number * viewport.width.min(viewport.height) / 100.0,
+		"vmax" => number * viewport.width.max(viewport.height) / 100.0,
+		_ => return None,
+	};
+	Some(px)
+}
+
+// the resolution in `dppx`, such as `2dppx`, `2x` and `192dpi`
+fn resolution_dppx(value: &str) -> Option<f32> {
+	let value = value.trim().to_ascii_lowercase();
+	let unit_start = value
+		.find(|ch: char| ch.is_ascii_alphabetic())
+		.unwrap_or(value.len());
+	let number = value[..unit_start].trim().parse::<f32>().ok()?;
+	match &value[unit_start..] {
+		"dppx" | "x" | "" => Some(number),
+		"dpi" => Some(number / 96.0),
+		"dpcm" => Some(number * 2.54 / 96.0),
+		_ => None,
+	}
+}
+
+// compare the actual value with the expected value by the operator
+fn compare(actual: f32, op: &str, expected: f32) -> Option<bool> {
+	// the rounding errors of the unit conversions
+	let diff = actual - expected;
+	let is_equal = diff.abs() < 0.001;
+	let result = match op {
+		"=" => is_equal,
+		">=" => diff > 0.0 || is_equal,
+		"<=" => diff < 0.0 || is_equal,
+		">" => diff > 0.0 && !is_equal,
+		"<" => diff < 0.0 && !is_equal,
+		_ => return None,
+	};
+	Some(result)
+}
+
+// evaluate a media feature in the parentheses, `None` if the feature is unknown or invalid
+fn matches_feature(feature: &str, viewport: &Viewport) -> Option<bool> {
+	let feature = feature.trim();
+	// the range syntax, such as `width >= 600px`
+	for op in [">=", "<=", ">", "<", "="] {
+		if let Some((name, value)) = feature.split_once(op) {
+			return matches_range(name.trim(), op, value.trim(), viewport);
+		}
+	}
+	let (name, value) = match feature.split_once(':') {
+		Some((name, value)) => (name.trim().to_ascii_lowercase(), Some(value.trim())),
+		None => (feature.to_ascii_lowercase(), None),
+	};
+	let (name, op) = if let Some(name) = name.strip_prefix("min-") {
+		(name, ">=")
+	} else if let Some(name) = name.strip_prefix("max-") {
+		(name, "<=")
+	} else if let Some(name) = name.strip_prefix("-webkit-min-") {
+		(name, ">=")
+	} else if let Some(name) = name.strip_prefix("-webkit-max-") {
+		(name, "<=")
+	} else {
+		(name.as_str(), "=")
+	};
+	match (name, value) {
+		("orientation", Some(value)) => {
+			let is_portrait = viewport.height >= viewport.width;
+			match value.to_ascii_lowercase().as_str() {
+				"portrait" => Some(is_portrait),
+				"landscape" => Some(!is_portrait),
+				_ => None,
+			}
+		}
+		// the boolean context, such as `(width)`
+		("width" | "height" | "resolution" | "device-pixel-ratio", None) if op == "=" => Some(true),
+		(_, Some(value)) => matches_range(name, op, value, viewport),
+		_ => None,
+	}
+}
+
+// evaluate the range feature, such as `width >= 600px`
+fn matches_range(name: &str, op: &str, value: &str, viewport: &Viewport) -> Option<bool> {
+	match name.to_ascii_lowercase().as_str() {
+		"width" => compare(viewport.width, op, length_px(value, viewport)?),
+		"height" => compare(viewport.height, op, length_px(value, viewport)?),
+		"resolution" => compare(viewport.device_pixel_ratio, op, resolution_dppx(value)?),
+		"device-pixel-ratio" => compare(
+			viewport.device_pixel_ratio,
+			op,
+			value.trim().parse::<f32>().ok()?,
+		),
+		_ => None,
+	}
+}
+
+// evaluate a media query such as `screen and (min-width: 600px)`, the unknown features never match
+fn matches_query(query: &str, viewport: &Viewport) -> bool {
+	let query = query.trim();
+	let lower = query.to_ascii_lowercase();
+	let (is_not, rest) = if let Some(rest) = lower.strip_prefix("not ") {
+		(true, rest.trim_start())
+	} else if let Some(rest) = lower.strip_prefix("only ") {
+		(false, rest.trim_start())
+	} else {
+		(false, lower.as_str())
+	};
+	let mut is_matched = true;
+	for (index, part) in split_top_level(rest, ' ')
+		.into_iter()
+		.map(str::trim)
+		.filter(|part| !part.is_empty() && *part != "and")
+		.enumerate()
+	{
+		let is_part_matched = match part.strip_prefix('(') {
+			Some(feature) => match feature.strip_suffix(')') {
+				Some(feature) => matches_feature(feature, viewport).unwrap_or(false),
+				None => return false,
+			},
+			// the media type must be the first
+			None if index == 0 => matches!(part, "all" | "screen"),
+			None => return false,
+		};
+		is_matched = is_matched && is_part_matched;
+	}
+	is_matched != is_not
+}
+
+/// pub fn `matches_media`
+/// evaluate the media query list on the viewport, such as `(min-width: 600px), print`, the empty list
+/// matches, the unknown media features never match, the screen is the only matched media type
+pub fn matches_media(media: &str, viewport: &Viewport) -> bool {
+	if media.trim().is_empty() {
+		return true;
+	}
+	split_top_level(media, ',')
+		.into_iter()
+		.any(|query| matches_query(query, viewport))
+}
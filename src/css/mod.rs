@@ -0,0 +1,104 @@
+//! a tolerant css parser for matching the style rules, the at-rules such as `@media` are skipped
+mod computed;
+mod media;
+mod parser;
+mod scope;
+mod specificity;
+pub use computed::{computed_style, COMPUTED_PROPERTIES};
+pub use media::{matches_media, Viewport};
+pub use parser::parse_declarations;
+pub use scope::{scope_attr, scope_css, scope_selector, SCOPE_ATTR_PREFIX};
+pub use specificity::Specificity;
+
+use crate::interface::BoxDynElement;
+use crate::selector::{Selector, SelectorList};
+pub(crate) use media::length_px;
+use parser::parse_rules;
+pub(crate) use parser::split_top_level;
+
+/// a declaration such as `color: red !important`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declaration {
+	// the lowercase property name, the custom properties keep the case
+	pub name: String,
+	pub value: String,
+	pub important: bool,
+}
+
+/// a style rule, the selector list and its declarations
+#[derive(Debug, Clone)]
+pub struct StyleRule {
+	pub selector: String,
+	pub declarations: Vec<Declaration>,
+}
+
+/// a rule matched an element
+#[derive(Debug, Clone, Copy)]
+pub struct MatchedRule<'s> {
+	pub rule: &'s StyleRule,
+	// the index of the rule in the stylesheet
+	pub index: usize,
+	// the specificity of the most specific selector matched the element
+	pub specificity: Specificity,
+}
+
+/// a parsed stylesheet, the selectors are compiled once for matching
+#[derive(Default)]
+pub struct Stylesheet {
+	rules: Vec<StyleRule>,
+	selectors: SelectorList,
+	// the rule index and the specificity of each selector in the list
+	sources: Vec<(usize, Specificity)>,
+}
+
+impl Stylesheet {
+	/// pub fn `parse`
+	/// parse the css, the selectors not supported by the selector engine never match
+	pub fn parse(css: &str) -> Self {
+		let mut rules = Vec::new();
+		let mut selectors: Vec<String> = Vec::new();
+		let mut sources = Vec::new();
+		for (index, (selector, declarations)) in parse_rules(css).into_iter().enumerate() {
+			for complex in split_top_level(&selector, ',') {
+				let complex = complex.trim();
+				if complex.parse::<Selector>().is_ok() {
+					selectors.push(String::from(complex));
+					sources.push((index, Specificity::of(complex)));
+				}
+			}
+			rules.push(StyleRule {
+				selector,
+				declarations,
+			});
+		}
+		let selectors = SelectorList::new(&selectors).expect("The selectors have been checked");
+		Stylesheet {
+			rules,
+			selectors,
+			sources,
+		}
+	}
+	/// pub fn `rules`
+	pub fn rules(&self) -> &[StyleRule] {
+		&self.rules
+	}
+	/// pub fn `matched_rules`
+	/// get the rules matched the element, ordered by the specificity then the source order,
+	/// so the declarations of the later rules win
+	pub fn matched_rules(&self, ele: &BoxDynElement) -> Vec<MatchedRule<'_>> {
+		let mut result: Vec<MatchedRule> = Vec::new();
+		for selector_index in self.selectors.match_element(ele) {
+			let (index, specificity) = self.sources[selector_index];
+			match result.iter_mut().find(|matched| matched.index == index) {
+				Some(matched) => matched.specificity = matched.specificity.max(specificity),
+				None => result.push(MatchedRule {
+					rule: &self.rules[index],
+					index,
+					specificity,
+				}),
+			}
+		}
+		result.sort_by_key(|matched| (matched.specificity, matched.index));
+		result
+	}
+}
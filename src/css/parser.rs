@@ -0,0 +1,192 @@
+use super::Declaration;
+
+// remove the comments, the comments in the strings are kept
+pub(crate) fn strip_comments(css: &str) -> String {
+	let mut result = String::with_capacity(css.len());
+	let mut chars = css.chars().peekable();
+	let mut quote: Option<char> = None;
+	while let Some(ch) = chars.next() {
+		match quote {
+			Some(q) => {
+				result.push(ch);
+				if ch == '\\' {
+					if let Some(next) = chars.next() {
+						result.push(next);
+					}
+				} else if ch == q {
+					quote = None;
+				}
+			}
+			None => {
+				if ch == '/' && chars.peek() == Some(&'*') {
+					chars.next();
+					let mut prev = '\0';
+					for ch in chars.by_ref() {
+						if prev == '*' && ch == '/' {
+							break;
+						}
+						prev = ch;
+					}
+					// the comment works as a whitespace
+					result.push(' ');
+					continue;
+				}
+				if ch == '"' || ch == '\'' {
+					quote = Some(ch);
+				}
+				result.push(ch);
+			}
+		}
+	}
+	result
+}
+
+// split the content by the separator out of the strings, parentheses and brackets
+pub(crate) fn split_top_level(content: &str, separator: char) -> Vec<&str> {
+	let mut result = Vec::new();
+	let mut depth = 0;
+	let mut quote: Option<char> = None;
+	let mut is_escaped = false;
+	let mut start = 0;
+	for (index, ch) in content.char_indices() {
+		if is_escaped {
+			is_escaped = false;
+			continue;
+		}
+		match (quote, ch) {
+			(_, '\\') => is_escaped = true,
+			(Some(q), ch) if ch == q => quote = None,
+			(Some(_), _) => {}
+			(None, '"' | '\'') => quote = Some(ch),
+			(None, '(' | '[' | '{') => depth += 1,
+			(None, ')' | ']' | '}') => depth -= 1,
+			(None, ch) if ch == separator && depth == 0 => {
+				result.push(&content[start..index]);
+				start = index + ch.len_utf8();
+			}
+			_ => {}
+		}
+	}
+	result.push(&content[start..]);
+	result
+}
+
+// find the end of the block started at the `{`, return the index of the matched `}`
+pub(super) fn block_end(content: &str, start: usize) -> Option<usize> {
+	let mut depth = 0;
+	let mut quote: Option<char> = None;
+	let mut is_escaped = false;
+	for (index, ch) in content[start..].char_indices() {
+		if is_escaped {
+			is_escaped = false;
+			continue;
+		}
+		match (quote, ch) {
+			(_, '\\') => is_escaped = true,
+			(Some(q), ch) if ch == q => quote = None,
+			(Some(_), _) => {}
+			(None, '"' | '\'') => quote = Some(ch),
+			(None, '{') => depth += 1,
+			(None, '}') => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(start + index);
+				}
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+// find the first char out of the strings
+pub(super) fn find_top_level(content: &str, targets: &[char]) -> Option<usize> {
+	let mut quote: Option<char> = None;
+	let mut is_escaped = false;
+	for (index, ch) in content.char_indices() {
+		if is_escaped {
+			is_escaped = false;
+			continue;
+		}
+		match (quote, ch) {
+			(_, '\\') => is_escaped = true,
+			(Some(q), ch) if ch == q => quote = None,
+			(Some(_), _) => {}
+			(None, '"' | '\'') => quote = Some(ch),
+			(None, ch) if targets.contains(&ch) => return Some(index),
+			_ => {}
+		}
+	}
+	None
+}
+
+/// pub fn `parse_declarations`
+/// parse the declarations such as the content of a `style` attribute, the invalid declarations are skipped
+pub fn parse_declarations(content: &str) -> Vec<Declaration> {
+	let content = strip_comments(content);
+	split_top_level(&content, ';')
+		.into_iter()
+		.filter_map(|declaration| {
+			let (name, value) = declaration.split_once(':')?;
+			let name = name.trim();
+			if name.is_empty() || name.contains(char::is_whitespace) {
+				return None;
+			}
+			// the custom properties are case-sensitive
+			let name = if name.starts_with("--") {
+				String::from(name)
+			} else {
+				name.to_ascii_lowercase()
+			};
+			let mut value = value.trim();
+			let mut important = false;
+			if let Some(index) = value.rfind('!') {
+				if value[index + 1..].trim().eq_ignore_ascii_case("important") {
+					important = true;
+					value = value[..index].trim_end();
+				}
+			}
+			if value.is_empty() && !name.starts_with("--") {
+				return None;
+			}
+			Some(Declaration {
+				name,
+				value: String::from(value),
+				important,
+			})
+		})
+		.collect()
+}
+
+// parse the style rules as (selector, declarations), the at-rules are skipped
+pub(crate) fn parse_rules(css: &str) -> Vec<(String, Vec<Declaration>)> {
+	let css = strip_comments(css);
+	let mut rules = Vec::new();
+	let mut rest = css.as_str();
+	loop {
+		rest = rest.trim_start();
+		if rest.is_empty() {
+			break;
+		}
+		let is_at_rule = rest.starts_with('@');
+		let open = match find_top_level(rest, if is_at_rule { &['{', ';'] } else { &['{'] }) {
+			Some(open) => open,
+			None => break,
+		};
+		if rest[open..].starts_with(';') {
+			// the statement at-rules, such as `@import`
+			rest = &rest[open + 1..];
+			continue;
+		}
+		let end = block_end(rest, open).unwrap_or(rest.len());
+		if !is_at_rule {
+			let selector = rest[..open].trim();
+			let block = &rest[open + 1..end.max(open + 1)];
+			if !selector.is_empty() {
+				rules.push((String::from(selector), parse_declarations(block)));
+			}
+		}
+		rest = rest.get(end + 1..).unwrap_or("");
+	}
+	rules
+}
@@ -0,0 +1,155 @@
+use super::parser::{block_end, find_top_level, split_top_level, strip_comments};
+
+/// the prefix of the generated scope attributes
+pub const SCOPE_ATTR_PREFIX: &str = "data-s-";
+
+// the offset basis and the prime of the 32 bits FNV-1a hash
+const FNV_OFFSET: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+// the at-rules contain the style rules, the others such as `@keyframes` and `@font-face` are kept
+const GROUPING_AT_RULES: [&str; 5] = ["@media", "@supports", "@layer", "@container", "@document"];
+// the pseudo-elements written with one colon
+const LEGACY_PSEUDO_ELEMENTS: [&str; 4] = ["before", "after", "first-line", "first-letter"];
+
+/// pub fn `scope_attr`
+/// the scope attribute generated from the key such as the component name, the name is stable
+/// across the runs and the platforms, such as `data-s-1b2c3d4e`
+pub fn scope_attr(key: &str) -> String {
+	let mut hash = FNV_OFFSET;
+	for byte in key.bytes() {
+		hash ^= u32::from(byte);
+		hash = hash.wrapping_mul(FNV_PRIME);
+	}
+	format!("{}{:08x}", SCOPE_ATTR_PREFIX, hash)
+}
+
+// the start of the last compound selector, after the last combinator out of the brackets
+fn last_compound_start(selector: &str) -> usize {
+	let mut depth = 0;
+	let mut quote: Option<char> = None;
+	let mut is_escaped = false;
+	let mut start = 0;
+	for (index, ch) in selector.char_indices() {
+		if is_escaped {
+			is_escaped = false;
+			continue;
+		}
+		match (quote, ch) {
+			(_, '\\') => is_escaped = true,
+			(Some(q), ch) if ch == q => quote = None,
+			(Some(_), _) => {}
+			(None, '"' | '\'') => quote = Some(ch),
+			(None, '(' | '[') => depth += 1,
+			(None, ')' | ']') => depth -= 1,
+			(None, ch) if depth == 0 && (ch.is_whitespace() || matches!(ch, '>' | '+' | '~')) => {
+				start = index + ch.len_utf8();
+			}
+			_ => {}
+		}
+	}
+	start
+}
+
+// the start of the pseudo-element in the compound selector, or the end of the compound
+fn pseudo_element_start(compound: &str) -> usize {
+	let mut depth = 0;
+	let mut quote: Option<char> = None;
+	let mut is_escaped = false;
+	for (index, ch) in compound.char_indices() {
+		if is_escaped {
+			is_escaped = false;
+			continue;
+		}
+		match (quote, ch) {
+			(_, '\\') => is_escaped = true,
+			(Some(q), ch) if ch == q => quote = None,
+			(Some(_), _) => {}
+			(None, '"' | '\'') => quote = Some(ch),
+			(None, '(' | '[') => depth += 1,
+			(None, ')' | ']') => depth -= 1,
+			(None, ':') if depth == 0 => {
+				let rest = &compound[index + 1..];
+				let name = rest
+					.split(|ch: char| !(ch.is_alphanumeric() || ch == '-'))
+					.next()
+					.unwrap_or("");
+				if rest.starts_with(':')
+					|| LEGACY_PSEUDO_ELEMENTS
+						.iter()
+						.any(|legacy| legacy.eq_ignore_ascii_case(name))
+				{
+					return index;
+				}
+			}
+			_ => {}
+		}
+	}
+	compound.len()
+}
+
+/// pub fn `scope_selector`
+/// add the attribute selector to the last compound of each complex selector in the list, before the
+/// pseudo-element, such as `.a .b::before` to `.a .b[data-s-1b2c3d4e]::before`
+pub fn scope_selector(selector: &str, attr: &str) -> String {
+	split_top_level(selector, ',')
+		.into_iter()
+		.map(|complex| {
+			let complex = complex.trim();
+			let start = last_compound_start(complex);
+			let index = start + pseudo_element_start(&complex[start..]);
+			format!("{}[{}]{}", &complex[..index], attr, &complex[index..])
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// pub fn `scope_css`
+/// scope the selectors of the style rules by the attribute, the rules in the grouping at-rules such as
+/// `@media` are scoped too, the other at-rules such as `@keyframes` are kept, the comments are removed
+pub fn scope_css(css: &str, attr: &str) -> String {
+	let css = strip_comments(css);
+	let mut result = String::with_capacity(css.len());
+	let mut rest = css.as_str();
+	loop {
+		rest = rest.trim_start();
+		if rest.is_empty() {
+			break;
+		}
+		let is_at_rule = rest.starts_with('@');
+		let open = match find_top_level(rest, if is_at_rule { &['{', ';'] } else { &['{'] }) {
+			Some(open) => open,
+			None => {
+				result.push_str(rest);
+				break;
+			}
+		};
+		if rest[open..].starts_with(';') {
+			// the statement at-rules, such as `@import`
+			result.push_str(&rest[..=open]);
+			result.push('\n');
+			rest = &rest[open + 1..];
+			continue;
+		}
+		let end = block_end(rest, open).unwrap_or(rest.len());
+		let prelude = rest[..open].trim();
+		let block = &rest[open + 1..end.max(open + 1)];
+		if !is_at_rule {
+			result.push_str(&format!(
+				"{} {{{}}}\n",
+				scope_selector(prelude, attr),
+				block
+			));
+		} else if GROUPING_AT_RULES.iter().any(|name| {
+			prelude
+				.split(|ch: char| ch.is_whitespace() || ch == '(')
+				.next()
+				.is_some_and(|at_name| at_name.eq_ignore_ascii_case(name))
+		}) {
+			result.push_str(&format!("{} {{\n{}}}\n", prelude, scope_css(block, attr)));
+		} else {
+			result.push_str(&format!("{} {{{}}}\n", prelude, block));
+		}
+		rest = rest.get(end + 1..).unwrap_or("");
+	}
+	result
+}
@@ -0,0 +1,126 @@
+use super::parser::split_top_level;
+use std::ops::Add;
+
+// the pseudo classes with a selector list, the specificity is the most specific argument
+const SELECTOR_ARGUMENT_PSEUDOS: [&str; 3] = ["not", "is", "has"];
+// the legacy pseudo elements with one colon
+const LEGACY_PSEUDO_ELEMENTS: [&str; 4] = ["before", "after", "first-line", "first-letter"];
+
+/// the specificity of a complex selector: (ids, classes and attributes and pseudo classes, types and pseudo elements)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Specificity(pub u32, pub u32, pub u32);
+
+impl Add for Specificity {
+	type Output = Specificity;
+	fn add(self, other: Specificity) -> Specificity {
+		Specificity(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+	}
+}
+
+// read an identity, return the length in bytes
+fn identity_len(content: &str) -> usize {
+	let mut is_escaped = false;
+	for (index, ch) in content.char_indices() {
+		if is_escaped {
+			is_escaped = false;
+		} else if ch == '\\' {
+			is_escaped = true;
+		} else if !(ch.is_alphanumeric() || ch == '-' || ch == '_' || !ch.is_ascii()) {
+			return index;
+		}
+	}
+	content.len()
+}
+
+// find the end of the group started at the open char, return the index of the close char
+fn group_end(content: &str, open: char, close: char) -> usize {
+	let mut depth = 0;
+	let mut quote: Option<char> = None;
+	for (index, ch) in content.char_indices() {
+		match (quote, ch) {
+			(Some(q), ch) if ch == q => quote = None,
+			(Some(_), _) => {}
+			(None, '"' | '\'') => quote = Some(ch),
+			(None, ch) if ch == open => depth += 1,
+			(None, ch) if ch == close => {
+				depth -= 1;
+				if depth == 0 {
+					return index;
+				}
+			}
+			_ => {}
+		}
+	}
+	content.len()
+}
+
+impl Specificity {
+	/// pub fn `of`
+	/// the specificity of a complex selector without `,`, such as `ul > li.item:first-child`
+	pub fn of(selector: &str) -> Specificity {
+		let mut result = Specificity::default();
+		let mut rest = selector.trim();
+		while let Some(ch) = rest.chars().next() {
+			match ch {
+				'#' => {
+					result.0 += 1;
+					rest = &rest[1 + identity_len(&rest[1..])..];
+				}
+				'.' => {
+					result.1 += 1;
+					rest = &rest[1 + identity_len(&rest[1..])..];
+				}
+				'[' => {
+					result.1 += 1;
+					let end = group_end(rest, '[', ']');
+					rest = rest.get(end + 1..).unwrap_or("");
+				}
+				':' => {
+					let colons = if rest.starts_with("::") { 2 } else { 1 };
+					let name_len = identity_len(&rest[colons..]);
+					let name = rest[colons..colons + name_len].to_ascii_lowercase();
+					rest = &rest[colons + name_len..];
+					let mut argument = None;
+					if rest.starts_with('(') {
+						let end = group_end(rest, '(', ')');
+						argument = Some(&rest[1..end.min(rest.len())]);
+						rest = rest.get(end + 1..).unwrap_or("");
+					}
+					if colons == 2 || LEGACY_PSEUDO_ELEMENTS.contains(&name.as_str()) {
+						result.2 += 1;
+					} else if name == "where" {
+						// `:where()` is always zero
+					} else if SELECTOR_ARGUMENT_PSEUDOS.contains(&name.as_str()) {
+						let most = argument
+							.map(|argument| {
+								split_top_level(argument, ',')
+									.into_iter()
+									.map(Specificity::of)
+									.max()
+									.unwrap_or_default()
+							})
+							.unwrap_or_default();
+						result = result + most;
+					} else {
+						result.1 += 1;
+					}
+				}
+				'*' => rest = &rest[1..],
+				ch if ch.is_whitespace() || ch == '>' || ch == '+' || ch == '~' => {
+					rest = &rest[ch.len_utf8()..];
+				}
+				_ => {
+					let len = identity_len(rest);
+					if len == 0 {
+						// unknown char
+						rest = &rest[ch.len_utf8()..];
+					} else {
+						result.2 += 1;
+						rest = &rest[len..];
+					}
+				}
+			}
+		}
+		result
+	}
+}
@@ -0,0 +1,190 @@
+use super::tree::{NodeKind, Tree};
+use super::Document;
+use crate::adapter::NodeId;
+use crate::interface::DocumentType;
+use html5ever::tendril::{StrTendril, TendrilSink};
+use html5ever::tree_builder::{ElementFlags, NodeOrText, QuirksMode, TreeSink};
+use html5ever::{namespace_url, ns, Attribute, ExpandedName, LocalName, QualName};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+// the attribute name with the prefix, such as `xlink:href`
+fn qualified_name(name: &QualName) -> String {
+	match &name.prefix {
+		Some(prefix) => format!("{}:{}", prefix, name.local),
+		None => name.local.to_string(),
+	}
+}
+
+// build the arena tree by html5ever
+struct Sink {
+	tree: Tree,
+	// the qualified names of the elements, used by the tree builder
+	names: HashMap<NodeId, QualName>,
+}
+
+impl Sink {
+	fn new() -> Self {
+		Sink {
+			tree: Tree::new(parse_fragment_into),
+			names: HashMap::new(),
+		}
+	}
+	// insert the text at the index, merged into the previous text node
+	fn insert_text(&mut self, parent: NodeId, index: usize, text: &str) {
+		if index > 0 {
+			let prev = self.tree.children(parent)[index - 1];
+			if let NodeKind::Text = self.tree.kind(prev) {
+				let content = format!("{}{}", self.tree.content(prev), text);
+				self.tree.nodes[prev].content = content;
+				return;
+			}
+		}
+		let id = self.tree.create(NodeKind::Text, text);
+		self.tree.link(parent, index, id);
+	}
+	fn insert(&mut self, parent: NodeId, index: usize, child: NodeOrText<NodeId>) {
+		match child {
+			NodeOrText::AppendNode(id) => self.tree.link(parent, index, id),
+			NodeOrText::AppendText(text) => self.insert_text(parent, index, &text),
+		}
+	}
+}
+
+impl TreeSink for Sink {
+	type Handle = NodeId;
+	type Output = Tree;
+	fn finish(self) -> Tree {
+		self.tree
+	}
+	fn parse_error(&mut self, _msg: Cow<'static, str>) {}
+	fn get_document(&mut self) -> NodeId {
+		self.tree.root()
+	}
+	fn elem_name<'a>(&'a self, target: &'a NodeId) -> ExpandedName<'a> {
+		self
+			.names
+			.get(target)
+			.expect("The node must be an element.")
+			.expanded()
+	}
+	fn create_element(
+		&mut self,
+		name: QualName,
+		attrs: Vec<Attribute>,
+		_flags: ElementFlags,
+	) -> NodeId {
+		let id = self
+			.tree
+			.create(NodeKind::Element(name.local.to_string()), "");
+		self.tree.set_namespace(id, &name.ns);
+		*self.tree.attrs_mut(id) = attrs
+			.into_iter()
+			.map(|attr| (qualified_name(&attr.name), Some(attr.value.to_string())))
+			.collect();
+		self.names.insert(id, name);
+		id
+	}
+	fn create_comment(&mut self, text: StrTendril) -> NodeId {
+		self.tree.create(NodeKind::Comment, &text)
+	}
+	fn create_pi(&mut self, target: StrTendril, data: StrTendril) -> NodeId {
+		self
+			.tree
+			.create(NodeKind::ProcessingInstruction(target.to_string()), &data)
+	}
+	fn append(&mut self, parent: &NodeId, child: NodeOrText<NodeId>) {
+		let index = self.tree.children(*parent).len();
+		self.insert(*parent, index, child);
+	}
+	fn append_based_on_parent_node(
+		&mut self,
+		element: &NodeId,
+		prev_element: &NodeId,
+		child: NodeOrText<NodeId>,
+	) {
+		if self.tree.parent(*element).is_some() {
+			self.append_before_sibling(element, child);
+		} else {
+			self.append(prev_element, child);
+		}
+	}
+	fn append_doctype_to_document(
+		&mut self,
+		name: StrTendril,
+		public_id: StrTendril,
+		system_id: StrTendril,
+	) {
+		let doctype = DocumentType {
+			name: name.to_string(),
+			public_id: public_id.to_string(),
+			system_id: system_id.to_string(),
+		}
+		.to_string();
+		// the content between `<!` and `>`
+		let id = self
+			.tree
+			.create(NodeKind::Doctype, &doctype[2..doctype.len() - 1]);
+		let root = self.tree.root();
+		self.tree.link(root, usize::MAX, id);
+	}
+	// the template contents are kept in a fragment, not in the child nodes
+	fn get_template_contents(&mut self, target: &NodeId) -> NodeId {
+		self.tree.ensure_template_content(*target)
+	}
+	fn same_node(&self, x: &NodeId, y: &NodeId) -> bool {
+		x == y
+	}
+	fn set_quirks_mode(&mut self, _mode: QuirksMode) {}
+	fn append_before_sibling(&mut self, sibling: &NodeId, new_node: NodeOrText<NodeId>) {
+		if let Some(parent) = self.tree.parent(*sibling) {
+			let index = self.tree.index(*sibling);
+			self.insert(parent, index, new_node);
+		}
+	}
+	fn add_attrs_if_missing(&mut self, target: &NodeId, attrs: Vec<Attribute>) {
+		let map = self.tree.attrs_mut(*target);
+		for attr in attrs {
+			let name = qualified_name(&attr.name);
+			if !map.contains(&name) {
+				map.set(&name, Some(&attr.value));
+			}
+		}
+	}
+	fn remove_from_parent(&mut self, target: &NodeId) {
+		self.tree.detach(*target);
+	}
+	fn reparent_children(&mut self, node: &NodeId, new_parent: &NodeId) {
+		for child in self.tree.children(*node).to_vec() {
+			self.tree.link(*new_parent, usize::MAX, child);
+		}
+	}
+}
+
+/// pub fn `parse`
+/// parse the html document into an arena tree
+pub fn parse(html: &str) -> Document {
+	let tree = html5ever::parse_document(Sink::new(), Default::default()).one(html);
+	Document::from(tree)
+}
+
+/// pub fn `parse_fragment_into`
+/// parse the html fragment in the context of the parent and append the nodes into it,
+/// the default `FragmentParser` of the parsed documents
+pub fn parse_fragment_into(tree: &mut Tree, parent: NodeId, html: &str) {
+	let context = match tree.kind(parent) {
+		NodeKind::Element(name) => name.as_str(),
+		NodeKind::DocumentFragment => "template",
+		_ => "body",
+	};
+	let context = QualName::new(None, ns!(html), LocalName::from(context));
+	let fragment =
+		html5ever::parse_fragment(Sink::new(), Default::default(), context, vec![]).one(html);
+	// the fragment is parsed into a `html` element of a new document
+	if let Some(&wrapper) = fragment.children(fragment.root()).first() {
+		for &child in fragment.children(wrapper) {
+			let child = tree.import(&fragment, child);
+			tree.link(parent, usize::MAX, child);
+		}
+	}
+}
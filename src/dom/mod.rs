@@ -0,0 +1,17 @@
+//! the arena backed tree, the nodes are stored in a generational arena and linked by the ids,
+//! `Node` and `Document` are the facades of the traits over the ids,
+//! the detached subtrees are freed once no `Node` handle refers to them.
+//! the facades share the tree by `Rc<RefCell<Tree>>` and the copy-on-write nodes are shared by `Rc`,
+//! so neither the documents nor the trees are `Send`, parse a document in each thread instead of sending it
+mod tree;
+pub use tree::{FragmentParser, NodeKind, SourceSpan, Tree};
+mod node;
+pub use node::{Document, Node};
+// parse the html with the source spans
+mod source;
+pub use source::{parse_fragment, parse_with_source};
+// parse the html by html5ever
+#[cfg(feature = "arena")]
+mod html;
+#[cfg(feature = "arena")]
+pub use html::{parse, parse_fragment_into};
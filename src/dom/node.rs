@@ -0,0 +1,702 @@
+use super::tree::{Handles, NodeKind, Tree};
+use crate::adapter::NodeId;
+use crate::error::Error as IError;
+use crate::interface::svg_tag_name;
+use crate::interface::{
+	BoxDynElement, BoxDynNode, BoxDynText, DocumentType, Elements, IAttrValue, IDocumentTrait,
+	IElementTrait, IEnumTyped, INodeTrait, INodeType, ITextTrait, IUncareNodeTrait, InsertPosition,
+	MaybeDoc, MaybeElement, MutationCallback, MutationKind, MutationRecord, HTML_NAMESPACE,
+	MATHML_NAMESPACE, SVG_NAMESPACE,
+};
+use crate::utils::{StrCache, StrKind};
+use std::any::Any;
+use std::cell::{OnceCell, RefCell};
+use std::error::Error;
+use std::rc::Rc;
+
+/// a node of the arena tree, a facade over the node id, the detached node is kept until its handles are dropped
+pub struct Node {
+	tree: Rc<RefCell<Tree>>,
+	handles: Rc<Handles>,
+	id: NodeId,
+	uuid: String,
+	// the tag name and the local name shared with the tree
+	names: OnceCell<Option<(Rc<str>, Rc<str>)>>,
+	// the html and the texts returned by reference, freed with the handle or by its `&mut self` methods
+	strings: StrCache,
+}
+
+/// the document of the arena tree
+pub struct Document {
+	tree: Rc<RefCell<Tree>>,
+	strings: StrCache,
+}
+
+impl From<Tree> for Document {
+	fn from(tree: Tree) -> Self {
+		Document::new(Rc::new(RefCell::new(tree)))
+	}
+}
+
+impl Document {
+	fn new(tree: Rc<RefCell<Tree>>) -> Self {
+		Document {
+			tree,
+			strings: StrCache::default(),
+		}
+	}
+	/// pub fn `get_ref`
+	/// get the tree of the document
+	pub fn get_ref(&self) -> &Rc<RefCell<Tree>> {
+		&self.tree
+	}
+	/// pub fn `to_html_preserving_source`
+	/// the html with the unchanged source copied verbatim, only the changed nodes are serialized,
+	/// the document without the source spans is serialized as a whole, see `parse_with_source`
+	pub fn to_html_preserving_source(&self) -> String {
+		let tree = self.tree.borrow();
+		let mut html = String::new();
+		tree.source_html(tree.root, &mut html);
+		html
+	}
+	/// pub fn `set_deny_raw_zones`
+	/// the strict mode for the untrusted documents, the nodes can't be marked as the raw zones, see
+	/// `Tree::set_deny_raw_zones`
+	pub fn set_deny_raw_zones(&self, deny_raw_zones: bool) -> Result<(), IError> {
+		self.tree.borrow_mut().set_deny_raw_zones(deny_raw_zones)
+	}
+	/// pub fn `element`
+	/// get the element of the id in the tree, `None` if the element is freed
+	pub fn element<'b>(&self, id: NodeId) -> Option<BoxDynElement<'b>> {
+		let tree = self.tree.borrow();
+		match tree.is_valid(id).then(|| tree.kind(id)) {
+			Some(NodeKind::Element(_)) => Some(Node::boxed(&self.tree, id)),
+			_ => None,
+		}
+	}
+}
+
+impl Node {
+	/// pub fn `id`
+	/// the id of the node in the tree
+	pub fn id(&self) -> NodeId {
+		self.id
+	}
+	/// pub fn `from_element`
+	/// get the id of an element created by this backend
+	pub fn from_element(ele: &BoxDynElement) -> Option<NodeId> {
+		ele
+			.cloned()
+			.to_node()
+			.downcast::<Node>()
+			.ok()
+			.map(|node| node.id)
+	}
+	/// pub fn `from_node`
+	/// get the id of a node created by this backend
+	pub fn from_node(node: &BoxDynNode) -> Option<NodeId> {
+		node
+			.clone_node()
+			.to_node()
+			.downcast::<Node>()
+			.ok()
+			.map(|node| node.id)
+	}
+	fn new(tree: &Rc<RefCell<Tree>>, id: NodeId) -> Self {
+		let (handles, tree_id) = {
+			let tree = tree.borrow();
+			(tree.handles.clone(), tree.id)
+		};
+		handles.acquire(id);
+		// the ids are only unique in the tree, prefix the tree's id for the nodes of the other documents
+		Node {
+			tree: tree.clone(),
+			handles,
+			id,
+			uuid: format!("{}-{}", tree_id, id),
+			names: OnceCell::new(),
+			strings: StrCache::default(),
+		}
+	}
+	// the tag name and the local name of the element, `None` for the other nodes
+	fn names(&self) -> Option<&(Rc<str>, Rc<str>)> {
+		self
+			.names
+			.get_or_init(|| self.tree.borrow().nodes[self.id].names.clone())
+			.as_ref()
+	}
+	fn boxed<'b>(tree: &Rc<RefCell<Tree>>, id: NodeId) -> BoxDynElement<'b> {
+		Box::new(Node::new(tree, id))
+	}
+	// the id of the node in the same tree
+	fn local_id(&self, node: Box<dyn Any>) -> Option<NodeId> {
+		node
+			.downcast::<Node>()
+			.ok()
+			.filter(|node| Rc::ptr_eq(&node.tree, &self.tree))
+			.map(|node| node.id)
+	}
+	// the id of the node in the same tree, the node of another tree is imported and removed from its tree,
+	// the strict tree refuses the nodes with the raw zones
+	fn adopted_id(&self, node: Box<dyn Any>) -> Result<NodeId, Box<dyn Error>> {
+		let node = node
+			.downcast::<Node>()
+			.map_err(|_| "The node is not an arena node.")?;
+		if Rc::ptr_eq(&node.tree, &self.tree) {
+			return Ok(node.id);
+		}
+		let id = {
+			let mut tree = self.tree.borrow_mut();
+			let from = node.tree.borrow();
+			if tree.deny_raw_zones() {
+				if let Some(raw_zone) = from.raw_zone(node.id) {
+					return Err(Box::new(from.raw_zone_denied(raw_zone)));
+				}
+			}
+			tree.import(&from, node.id)
+		};
+		node.mutate(|tree| tree.detach(node.id));
+		Ok(id)
+	}
+	// change the tree, then notify the records after the tree is released, the unused detached nodes are freed
+	fn mutate<R>(&self, handle: impl FnOnce(&mut Tree) -> R) -> R {
+		let result = handle(&mut self.tree.borrow_mut());
+		let (records, observers) = {
+			let mut tree = self.tree.borrow_mut();
+			match &mut tree.pending {
+				Some(pending) if !pending.is_empty() => (std::mem::take(pending), tree.observers.clone()),
+				_ => {
+					tree.collect();
+					return result;
+				}
+			}
+		};
+		// the records hold the handles, so the nodes are kept while the observers are notified
+		let records = records
+			.into_iter()
+			.map(|(kind, target, node)| MutationRecord {
+				kind,
+				target: Box::new(Node::new(&self.tree, target)),
+				node: node.map(|node| Box::new(Node::new(&self.tree, node)) as BoxDynNode),
+			})
+			.collect::<Vec<_>>();
+		for record in &records {
+			observers.notify(record);
+		}
+		result
+	}
+}
+
+impl Clone for Node {
+	fn clone(&self) -> Self {
+		self.handles.acquire(self.id);
+		Node {
+			tree: self.tree.clone(),
+			handles: self.handles.clone(),
+			id: self.id,
+			uuid: self.uuid.clone(),
+			names: self.names.clone(),
+			strings: self.strings.clone(),
+		}
+	}
+}
+
+// the detached subtree is freed when the last handle is dropped, or later if the tree is borrowed
+impl Drop for Node {
+	fn drop(&mut self) {
+		self.handles.release(self.id);
+		if let Ok(mut tree) = self.tree.try_borrow_mut() {
+			tree.collect();
+		}
+	}
+}
+
+impl IDocumentTrait for Document {
+	fn get_element_by_id<'b>(&self, id: &str) -> Option<BoxDynElement<'b>> {
+		// the first matched element in document order
+		let tree = self.tree.borrow();
+		let mut stack = vec![tree.root];
+		while let Some(node_id) = stack.pop() {
+			let node = &tree.nodes[node_id];
+			if matches!(node.kind, NodeKind::Element(_)) && node.attrs.get_str("id") == Some(id) {
+				return Some(Node::boxed(&self.tree, node_id));
+			}
+			stack.extend(node.children.iter().rev());
+		}
+		None
+	}
+	fn source_code(&self) -> &str {
+		let tree = self.tree.borrow();
+		let mut html = String::new();
+		tree.html(tree.root, &mut html);
+		self.strings.keep(StrKind::Html, html)
+	}
+	fn url(&self) -> Option<&str> {
+		let url = self.tree.borrow().url.clone()?;
+		Some(self.strings.keep(StrKind::Url, url))
+	}
+	fn set_url(&mut self, url: &str) -> Result<(), Box<dyn Error>> {
+		self.strings.clear();
+		self.tree.borrow_mut().url = Some(String::from(url));
+		Ok(())
+	}
+	fn get_root_node<'b>(&self) -> BoxDynNode<'b> {
+		let root = self.tree.borrow().root;
+		Box::new(Node::new(&self.tree, root))
+	}
+	// the clone shares the nodes with the document, a node is copied when it's mutated
+	fn deep_clone<'b>(&self) -> Result<Box<dyn IDocumentTrait + 'b>, Box<dyn Error>> {
+		Ok(Box::new(Document::from(self.tree.borrow().share())))
+	}
+	fn adopt<'b>(&mut self, node: &BoxDynNode) -> Result<BoxDynNode<'b>, Box<dyn Error>> {
+		let root = Node::new(&self.tree, self.tree.borrow().root);
+		let node = node.clone_node().to_node();
+		let is_local =
+			matches!(node.downcast_ref::<Node>(), Some(node) if Rc::ptr_eq(&node.tree, &self.tree));
+		let id = root.adopted_id(node)?;
+		if is_local {
+			root.mutate(|tree| tree.detach(id));
+		}
+		Ok(Box::new(Node::new(&self.tree, id)))
+	}
+	fn on_mutation(&mut self, callback: MutationCallback) -> Result<usize, Box<dyn Error>> {
+		let mut tree = self.tree.borrow_mut();
+		tree.pending.get_or_insert_with(Vec::new);
+		Ok(tree.observers.add(callback))
+	}
+	fn off_mutation(&mut self, id: usize) -> Result<(), Box<dyn Error>> {
+		let mut tree = self.tree.borrow_mut();
+		tree.observers.remove(id);
+		if tree.observers.is_empty() {
+			tree.pending = None;
+		}
+		Ok(())
+	}
+}
+
+impl INodeTrait for Node {
+	fn to_node(self: Box<Self>) -> Box<dyn Any> {
+		self
+	}
+	fn clone_node<'b>(&self) -> BoxDynNode<'b> {
+		Box::new(self.clone())
+	}
+	fn typed<'b>(self: Box<Self>) -> IEnumTyped<'b> {
+		let kind = self.tree.borrow().nodes[self.id].kind.clone();
+		match kind {
+			NodeKind::Element(_) | NodeKind::Document | NodeKind::DocumentFragment => {
+				IEnumTyped::Element(self)
+			}
+			NodeKind::Text => IEnumTyped::Text(self),
+			_ => IEnumTyped::UncareNode(self),
+		}
+	}
+	fn node_type(&self) -> INodeType {
+		match self.tree.borrow().nodes[self.id].kind {
+			NodeKind::Element(_) => INodeType::Element,
+			NodeKind::Document => INodeType::Document,
+			NodeKind::Text => INodeType::Text,
+			NodeKind::Comment => INodeType::Comment,
+			NodeKind::Doctype => INodeType::HTMLDOCTYPE,
+			NodeKind::CData => INodeType::XMLCDATA,
+			NodeKind::ProcessingInstruction(_) => INodeType::ProcessingInstruction,
+			NodeKind::DocumentFragment => INodeType::DocumentFragement,
+		}
+	}
+	fn parent<'b>(&self) -> MaybeElement<'b> {
+		let parent = self.tree.borrow().nodes[self.id].parent;
+		parent.map(|parent| Node::boxed(&self.tree, parent))
+	}
+	fn uuid(&self) -> Option<&str> {
+		Some(&self.uuid)
+	}
+	fn owner_document(&self) -> MaybeDoc<'_> {
+		Some(Box::new(Document::new(self.tree.clone())))
+	}
+	fn doctype(&self) -> Option<DocumentType> {
+		let tree = self.tree.borrow();
+		match tree.nodes[self.id].kind {
+			NodeKind::Doctype => DocumentType::parse(&tree.nodes[self.id].content),
+			_ => None,
+		}
+	}
+	fn pi_target(&self) -> Option<String> {
+		match &self.tree.borrow().nodes[self.id].kind {
+			NodeKind::ProcessingInstruction(target) => Some(target.clone()),
+			_ => None,
+		}
+	}
+	fn text_content(&self) -> &str {
+		let tree = self.tree.borrow();
+		let mut text = String::new();
+		match tree.nodes[self.id].kind {
+			NodeKind::Comment | NodeKind::CData | NodeKind::ProcessingInstruction(_) => {
+				text.push_str(&tree.nodes[self.id].content)
+			}
+			_ => tree.text(self.id, &mut text),
+		}
+		self.strings.keep(StrKind::Text, text)
+	}
+	fn set_text(&mut self, content: &str) {
+		self.strings.clear();
+		self.mutate(|tree| {
+			if let NodeKind::Text = tree.nodes[self.id].kind {
+				tree.set_content(self.id, String::from(content));
+				return;
+			}
+			tree.clear(self.id);
+			if !content.is_empty() {
+				let text = tree.create(NodeKind::Text, content);
+				tree.link(self.id, usize::MAX, text);
+			}
+		});
+	}
+	fn set_html(&mut self, content: &str) {
+		self.strings.clear();
+		self.mutate(|tree| {
+			// the html of the template is parsed into its contents
+			let target = match &tree.nodes[self.id].kind {
+				NodeKind::Element(name) if name == "template" => tree.ensure_template_content(self.id),
+				_ => self.id,
+			};
+			tree.clear(target);
+			tree.parse_into(target, content);
+		});
+	}
+	fn index(&self) -> usize {
+		self.tree.borrow().index(self.id)
+	}
+	fn is_raw_zone(&self) -> bool {
+		self.tree.borrow().is_raw_zone(self.id)
+	}
+	fn set_raw_zone(&mut self, is_raw_zone: bool) -> Result<(), Box<dyn Error>> {
+		self.strings.clear();
+		self.mutate(|tree| tree.set_raw_zone(self.id, is_raw_zone))?;
+		Ok(())
+	}
+}
+
+impl IElementTrait for Node {
+	fn tag_name(&self) -> &str {
+		self.names().map_or("", |(tag_name, _)| tag_name)
+	}
+	fn local_name(&self) -> &str {
+		let local_name = match self.names() {
+			Some((_, local_name)) => local_name,
+			None => return "",
+		};
+		// the names of the svg elements from the lenient parsers may be lowercase
+		match self.tree.borrow().namespace(self.id) {
+			Some(SVG_NAMESPACE) => svg_tag_name(local_name).unwrap_or(local_name),
+			_ => local_name,
+		}
+	}
+	fn namespace_uri(&self) -> Option<&str> {
+		let tree = self.tree.borrow();
+		let namespace = tree.namespace(self.id)?;
+		Some(
+			[HTML_NAMESPACE, SVG_NAMESPACE, MATHML_NAMESPACE]
+				.iter()
+				.find(|known| **known == namespace)
+				.copied()
+				.unwrap_or_else(|| {
+					self
+						.strings
+						.keep(StrKind::Namespace, String::from(namespace))
+				}),
+		)
+	}
+	fn child_nodes_length(&self) -> usize {
+		self.tree.borrow().nodes[self.id].children.len()
+	}
+	fn child_nodes_item<'b>(&self, index: usize) -> Option<BoxDynNode<'b>> {
+		let child = self.tree.borrow().nodes[self.id]
+			.children
+			.get(index)
+			.copied();
+		child.map(|child| Box::new(Node::new(&self.tree, child)) as BoxDynNode)
+	}
+	fn get_attribute(&self, name: &str) -> Option<IAttrValue> {
+		self.tree.borrow().nodes[self.id].attrs.get(name)
+	}
+	fn attributes(&self) -> Vec<(String, IAttrValue)> {
+		self.tree.borrow().nodes[self.id].attrs.to_attributes()
+	}
+	fn set_attribute(&mut self, name: &str, value: Option<&str>) {
+		self.strings.clear();
+		self.mutate(|tree| {
+			let attrs = &mut tree.nodes[self.id].attrs;
+			let old_value = attrs.get(name);
+			let index = attrs.position(name).unwrap_or_else(|| attrs.len());
+			attrs.set(name, value);
+			let name = name.to_ascii_lowercase();
+			let kind = MutationKind::Attribute {
+				name,
+				old_value,
+				index,
+			};
+			tree.record(kind, self.id, None);
+		});
+	}
+	fn remove_attribute(&mut self, name: &str) {
+		self.strings.clear();
+		self.mutate(|tree| {
+			let attrs = &mut tree.nodes[self.id].attrs;
+			if let (Some(old_value), Some(index)) = (attrs.get(name), attrs.position(name)) {
+				attrs.remove(name);
+				let name = name.to_ascii_lowercase();
+				let old_value = Some(old_value);
+				let kind = MutationKind::Attribute {
+					name,
+					old_value,
+					index,
+				};
+				tree.record(kind, self.id, None);
+			}
+		});
+	}
+	fn inner_html(&self) -> &str {
+		let mut html = String::new();
+		self.tree.borrow().inner_html(self.id, &mut html);
+		self.strings.keep(StrKind::InnerHtml, html)
+	}
+	fn outer_html(&self) -> &str {
+		let mut html = String::new();
+		self.tree.borrow().html(self.id, &mut html);
+		self.strings.keep(StrKind::Html, html)
+	}
+	fn insert_adjacent(&mut self, position: &InsertPosition, ele: &BoxDynElement) {
+		let id = match self.adopted_id(ele.cloned().to_node()) {
+			Ok(id) if id != self.id => id,
+			Ok(_) => return,
+			Err(e) => {
+				if let Some(doc) = self.owner_document() {
+					doc.trigger_error(e);
+				}
+				return;
+			}
+		};
+		self.strings.clear();
+		let result = self.mutate(|tree| match position {
+			InsertPosition::BeforeEnd => tree.append(self.id, id),
+			InsertPosition::AfterBegin => tree.insert(self.id, 0, id),
+			InsertPosition::BeforeBegin | InsertPosition::AfterEnd => {
+				let parent = match tree.nodes[self.id].parent {
+					Some(parent) => parent,
+					None => return Ok(()),
+				};
+				// check before detaching, so the tree is unchanged if the node contains the parent
+				if tree.contains(id, parent) {
+					return tree.insert(parent, 0, id);
+				}
+				tree.detach(id);
+				let index = tree.index(self.id);
+				let index = match position {
+					InsertPosition::AfterEnd => index + 1,
+					_ => index,
+				};
+				tree.insert(parent, index, id)
+			}
+		});
+		if let Err(e) = result {
+			if let Some(doc) = self.owner_document() {
+				doc.trigger_error(Box::new(e));
+			}
+		}
+	}
+	fn template_content<'b>(&self) -> Option<BoxDynElement<'b>> {
+		let content = self.tree.borrow().template_content(self.id);
+		content.map(|content| Node::boxed(&self.tree, content))
+	}
+	fn clone_deep<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		let copy = self.tree.borrow_mut().deep_clone(self.id);
+		Ok(Node::boxed(&self.tree, copy))
+	}
+	fn clone_shallow<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		let copy = self.tree.borrow_mut().shallow_clone(self.id);
+		Ok(Node::boxed(&self.tree, copy))
+	}
+	fn remove_child(&mut self, ele: BoxDynElement) {
+		let id = match self.local_id(ele.to_node()) {
+			Some(id) => id,
+			None => return,
+		};
+		self.strings.clear();
+		self.mutate(|tree| {
+			if tree.nodes[id].parent == Some(self.id) {
+				tree.detach(id);
+			}
+		});
+	}
+	fn insert_child(&mut self, index: usize, node: &BoxDynNode) -> Result<(), Box<dyn Error>> {
+		let id = self.adopted_id(node.clone_node().to_node())?;
+		self.strings.clear();
+		self.mutate(|tree| tree.insert(self.id, index, id))?;
+		Ok(())
+	}
+	fn reparse<'b>(&mut self, source: &str) -> Result<Elements<'b>, Box<dyn Error>> {
+		if self.tree.borrow().nodes[self.id].parent.is_none() {
+			return Err(Box::new(IError::InvalidTraitMethodCall {
+				method: "reparse".into(),
+				message: "Can't reparse the element without parent.".into(),
+			}));
+		}
+		// only the source is parsed, the parsed nodes are moved into the parent
+		self.strings.clear();
+		let ids = self.mutate(|tree| tree.reparse(self.id, source));
+		let tree = self.tree.borrow();
+		Ok(Elements::with_nodes(
+			ids
+				.into_iter()
+				.filter(|id| matches!(tree.nodes[*id].kind, NodeKind::Element(_)))
+				.map(|id| Node::boxed(&self.tree, id))
+				.collect(),
+		))
+	}
+	fn into_text<'b>(self: Box<Self>) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		Ok(self)
+	}
+}
+
+impl ITextTrait for Node {
+	fn remove(self: Box<Self>) {
+		self.mutate(|tree| tree.detach(self.id));
+	}
+	fn append_text(&mut self, content: &str) {
+		self.strings.clear();
+		self.mutate(|tree| {
+			let content = format!("{}{}", tree.nodes[self.id].content, content);
+			tree.set_content(self.id, content);
+		});
+	}
+	fn prepend_text(&mut self, content: &str) {
+		self.strings.clear();
+		self.mutate(|tree| {
+			let content = format!("{}{}", content, tree.nodes[self.id].content);
+			tree.set_content(self.id, content);
+		});
+	}
+	fn split_text<'b>(&mut self, offset: usize) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		self.strings.clear();
+		let text = self.mutate(|tree| {
+			let content = &tree.nodes[self.id].content;
+			if !content.is_char_boundary(offset) {
+				return Err(format!("The offset {} is not a char boundary.", offset));
+			}
+			let (content, rest) = (content[..offset].to_string(), content[offset..].to_string());
+			tree.set_content(self.id, content);
+			let text = tree.create(NodeKind::Text, &rest);
+			if let Some(parent) = tree.nodes[self.id].parent {
+				let index = tree.index(self.id);
+				tree.link(parent, index + 1, text);
+			}
+			Ok(text)
+		})?;
+		Ok(Box::new(Node::new(&self.tree, text)))
+	}
+	fn wrap(&mut self, wrapper: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+		let wrapper = self
+			.local_id(wrapper.cloned().to_node())
+			.ok_or("The wrapper is not in the same tree.")?;
+		self.strings.clear();
+		self.mutate(|tree| {
+			if tree.contains(wrapper, self.id) {
+				return Err(IError::HierarchyRequest {
+					message: String::from("The wrapper can't be the text's ancestor."),
+				});
+			}
+			if let Some(parent) = tree.nodes[self.id].parent {
+				let index = tree.index(self.id);
+				tree.insert(parent, index, wrapper)?;
+			}
+			tree.append(wrapper, self.id)
+		})?;
+		Ok(())
+	}
+}
+
+impl IUncareNodeTrait for Node {
+	fn remove(self: Box<Self>) -> Result<(), Box<dyn Error>> {
+		self.mutate(|tree| tree.detach(self.id));
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::super::tree::{NodeKind, Tree};
+	use super::{Document, Node};
+	use crate::adapter::NodeId;
+	use crate::interface::{IDocumentTrait, IElementTrait, INodeTrait};
+	use std::rc::Rc;
+	fn parse_nothing(_: &mut Tree, _: NodeId, _: &str) {}
+	#[test]
+	fn test_node_strings() {
+		let mut tree = Tree::new(parse_nothing);
+		let root = tree.root();
+		let div = tree.create(NodeKind::Element(String::from("div")), "");
+		let text = tree.create(NodeKind::Text, "one");
+		tree.append(root, div).unwrap();
+		tree.append(div, text).unwrap();
+		let doc = Document::from(tree);
+		let node = Node::new(doc.get_ref(), div);
+		let copy = Node::new(doc.get_ref(), div);
+		// the names are borrowed from the tree, not copied for each handle
+		assert_eq!(node.tag_name(), "DIV");
+		assert_eq!(node.local_name(), "div");
+		assert!(std::ptr::eq(node.tag_name(), copy.tag_name()));
+		// the same html is kept once by the handle
+		let html = node.outer_html();
+		assert!(std::ptr::eq(html, node.outer_html()));
+		assert_eq!(html, "<div>one</div>");
+		node.clone_node().set_text("two");
+		assert_eq!(node.outer_html(), "<div>two</div>");
+		assert_eq!(html, "<div>one</div>");
+		assert!(std::ptr::eq(node.text_content(), node.text_content()));
+		assert!(std::ptr::eq(doc.source_code(), doc.source_code()));
+		// the names of the element are shared by the tree and the handles
+		let names = doc.get_ref().borrow().nodes[div].names.clone().unwrap();
+		assert!(Rc::ptr_eq(&names.0, &node.names().unwrap().0));
+		// the shared tree has the same node ids, but its nodes are not the same
+		let shared = Document::from(doc.get_ref().borrow().share());
+		assert_ne!(node.uuid(), Node::new(shared.get_ref(), div).uuid());
+	}
+	#[test]
+	fn test_transaction_panic() {
+		let mut doc = super::super::parse_with_source("<div></div>");
+		let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			doc.transaction(&mut |_| panic!("The handle panics."))
+		}));
+		assert!(result.is_err());
+		// the journal is unregistered, the mutations are not recorded anymore
+		let tree = doc.get_ref().borrow();
+		assert!(tree.observers.is_empty());
+		assert!(tree.pending.is_none());
+	}
+	#[test]
+	fn test_free_detached() {
+		let doc = super::super::parse_with_source("<div><p>one</p></div>");
+		let div = doc
+			.get_root_node()
+			.typed()
+			.into_element()
+			.unwrap()
+			.children();
+		let mut div = div.get(0).unwrap().cloned();
+		let len = doc.get_ref().borrow().len();
+		// the replaced nodes are freed
+		for _ in 0..1000 {
+			div.set_html("<p>two</p>");
+		}
+		assert_eq!(doc.get_ref().borrow().len(), len);
+		// the detached node is kept until its handles are dropped
+		let p = div.children().get(0).unwrap().cloned();
+		let id = Node::from_element(&p).unwrap();
+		div.set_html("");
+		assert_eq!(p.outer_html(), "<p>two</p>");
+		assert_eq!(doc.get_ref().borrow().len(), len);
+		drop(p);
+		assert_eq!(doc.get_ref().borrow().len(), len - 2);
+		assert!(doc.element(id).is_none());
+	}
+}
@@ -0,0 +1,254 @@
+use super::node::Document;
+use super::tree::{NodeKind, SourceSpan, Tree};
+use crate::adapter::NodeId;
+use crate::html::spec::{is_implied_end, is_text_only_element, is_void_element};
+use crate::interface::{
+	decode_attr_value, parse_start_tag_attrs, svg_tag_name, IAttrValue, HTML_NAMESPACE, SVG_NAMESPACE,
+};
+use crate::rewrite::{tag_end, tag_name};
+
+// the open element, its start offset and the offset after its start tag
+struct OpenElement {
+	id: NodeId,
+	// the parent of the child nodes, the template's contents or the element itself
+	content: NodeId,
+	name: String,
+	// the html raw text element, the svg `style` and `title` are not
+	is_raw: bool,
+	start: usize,
+	inner_start: usize,
+}
+
+// the parser keeps the spans of the nodes if the source is tracked
+struct SourceParser<'a> {
+	tree: &'a mut Tree,
+	parent: NodeId,
+	stack: Vec<OpenElement>,
+	spans: Option<Vec<(NodeId, SourceSpan)>>,
+}
+
+impl<'a> SourceParser<'a> {
+	fn current(&self) -> NodeId {
+		self.stack.last().map_or(self.parent, |open| open.content)
+	}
+	fn set_span(&mut self, id: NodeId, span: SourceSpan) {
+		if let Some(spans) = &mut self.spans {
+			spans.push((id, span));
+		}
+	}
+	// append the node into the current element
+	fn append(&mut self, kind: NodeKind, content: &str) -> NodeId {
+		let id = self.tree.create(kind, content);
+		let parent = self.current();
+		self.tree.link(parent, usize::MAX, id);
+		id
+	}
+	fn add(&mut self, kind: NodeKind, content: &str, start: usize, end: usize) {
+		let id = self.append(kind, content);
+		self.set_span(id, SourceSpan::new(start, end));
+	}
+	// close the open elements from the index, the contents end at `inner_end` and the element at the index
+	// ends at `end`, the elements above it have no end tags
+	fn close_from(&mut self, index: usize, inner_end: usize, end: usize) {
+		while let Some(open) = self.stack.pop() {
+			let is_last = self.stack.len() == index;
+			let span = SourceSpan {
+				start: open.start,
+				end: if is_last { end } else { inner_end },
+				inner_start: open.inner_start,
+				inner_end,
+				..Default::default()
+			};
+			self.set_span(open.id, span);
+			if open.content != open.id {
+				let content_span = SourceSpan {
+					start: open.inner_start,
+					end: inner_end,
+					inner_start: open.inner_start,
+					inner_end,
+					..Default::default()
+				};
+				self.set_span(open.content, content_span);
+			}
+			if is_last {
+				break;
+			}
+		}
+	}
+	fn parse(&mut self, html: &str) {
+		let mut pos = 0;
+		while pos < html.len() {
+			let rest = &html[pos..];
+			// the text of the raw text element until its end tag
+			if let Some(open) = self.stack.last() {
+				if open.is_raw && pos == open.inner_start {
+					let close = format!("</{}", open.name);
+					let len = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+					if len > 0 {
+						self.add(NodeKind::Text, &rest[..len], pos, pos + len);
+						pos += len;
+						continue;
+					}
+				}
+			}
+			if let Some(comment) = rest.strip_prefix("<!--") {
+				let (content, len) = match comment.find("-->") {
+					Some(index) => (&comment[..index], index + 7),
+					None => (comment, rest.len()),
+				};
+				self.add(NodeKind::Comment, content, pos, pos + len);
+				pos += len;
+				continue;
+			}
+			if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+				let (content, len) = match cdata.find("]]>") {
+					Some(index) => (&cdata[..index], index + 12),
+					None => (cdata, rest.len()),
+				};
+				self.add(NodeKind::CData, content, pos, pos + len);
+				pos += len;
+				continue;
+			}
+			let next = rest.strip_prefix('<').and_then(|tag| tag.chars().next());
+			let is_tag =
+				next.is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '/' || ch == '!' || ch == '?');
+			if !is_tag {
+				let first_len = rest.chars().next().map_or(1, |ch| ch.len_utf8());
+				let len = rest[first_len..]
+					.find('<')
+					.map_or(rest.len(), |index| index + first_len);
+				self.add(
+					NodeKind::Text,
+					&decode_attr_value(&rest[..len]),
+					pos,
+					pos + len,
+				);
+				pos += len;
+				continue;
+			}
+			let len = tag_end(rest).unwrap_or(rest.len());
+			let tag = &rest[..len];
+			match next {
+				Some('/') => {
+					let name = tag_name(tag);
+					if let Some(index) = self
+						.stack
+						.iter()
+						.rposition(|open| open.name.eq_ignore_ascii_case(&name))
+					{
+						self.close_from(index, pos, pos + len);
+					}
+				}
+				Some('!') => {
+					let content = tag[2..].trim_end_matches('>');
+					self.add(NodeKind::Doctype, content, pos, pos + len);
+				}
+				Some('?') => {
+					let content = tag[2..].trim_end_matches('>').trim_end_matches('?');
+					let (target, data) = content.split_once(' ').unwrap_or((content, ""));
+					self.add(
+						NodeKind::ProcessingInstruction(String::from(target)),
+						data.trim(),
+						pos,
+						pos + len,
+					);
+				}
+				_ => {
+					let name = tag_name(tag);
+					while let Some(open) = self.stack.last() {
+						if !is_implied_end(&open.name, &name) {
+							break;
+						}
+						self.close_from(self.stack.len() - 1, pos, pos);
+					}
+					// the svg tag names are case-sensitive, such as `linearGradient`
+					let namespace = self.tree.child_namespace(self.current(), &name);
+					let name = match namespace {
+						SVG_NAMESPACE => svg_tag_name(&name).map_or(name, String::from),
+						_ => name,
+					};
+					let id = self.append(NodeKind::Element(name.clone()), "");
+					self.tree.set_namespace(id, namespace);
+					let attrs = self.tree.attrs_mut(id);
+					for (attr_name, value) in parse_start_tag_attrs(tag) {
+						// the first attribute wins, the same as the html parsers
+						if attrs.contains(&attr_name) {
+							continue;
+						}
+						match value {
+							IAttrValue::Value(value, _) => attrs.set(&attr_name, Some(&value)),
+							IAttrValue::True => attrs.set(&attr_name, None),
+						}
+					}
+					if tag.ends_with("/>") || is_void_element(&name) {
+						self.set_span(id, SourceSpan::new(pos, pos + len));
+					} else {
+						// the span is set when it's closed
+						let content = if name == "template" {
+							self.tree.ensure_template_content(id)
+						} else {
+							id
+						};
+						self.stack.push(OpenElement {
+							id,
+							content,
+							is_raw: is_text_only_element(&name) && namespace == HTML_NAMESPACE,
+							name,
+							start: pos,
+							inner_start: pos + len,
+						});
+					}
+				}
+			}
+			pos += len;
+		}
+		if !self.stack.is_empty() {
+			self.close_from(0, html.len(), html.len());
+		}
+	}
+}
+
+/// pub fn `parse_fragment`
+/// parse the html fragment by the lenient source parser and append the nodes into the parent,
+/// the nodes have no spans, the default `FragmentParser` of `parse_with_source`
+pub fn parse_fragment(tree: &mut Tree, parent: NodeId, html: &str) {
+	SourceParser {
+		tree,
+		parent,
+		stack: Vec::new(),
+		spans: None,
+	}
+	.parse(html);
+}
+
+/// pub fn `parse_with_source`
+/// parse the html and track the spans of the nodes in the source, so the unchanged parts are kept
+/// by `Document::to_html_preserving_source`, the parser is lenient and doesn't add the implied
+/// `html`, `head` and `body` elements
+pub fn parse_with_source(html: &str) -> Document {
+	let mut tree = Tree::new(parse_fragment);
+	let root = tree.root();
+	let mut parser = SourceParser {
+		tree: &mut tree,
+		parent: root,
+		stack: Vec::new(),
+		spans: Some(Vec::new()),
+	};
+	parser.parse(html);
+	let spans = parser.spans.take().unwrap_or_default();
+	tree.set_source(html);
+	tree.set_span(
+		root,
+		Some(SourceSpan {
+			start: 0,
+			end: html.len(),
+			inner_start: 0,
+			inner_end: html.len(),
+			..Default::default()
+		}),
+	);
+	for (id, span) in spans {
+		tree.set_span(id, Some(span));
+	}
+	Document::from(tree)
+}
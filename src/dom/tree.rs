@@ -0,0 +1,803 @@
+use crate::adapter::{Arena, AttrMap, NodeId};
+use crate::error::Error as IError;
+use crate::html::spec;
+use crate::interface::{element_namespace, MutationKind, MutationObservers, HTML_NAMESPACE};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// the ids of the trees, the node ids are only unique in a tree
+static TREE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// the kind of a node in the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+	Document,
+	// the lowercase tag name
+	Element(String),
+	Text,
+	Comment,
+	// the content between `<!` and `>`, such as `DOCTYPE html`
+	Doctype,
+	CData,
+	// the target of the processing instruction
+	ProcessingInstruction(String),
+	// the inert contents of a `template` element, not in the child nodes of the template
+	DocumentFragment,
+}
+
+/// parse the html and append the nodes into the parent, used by `set_html`
+pub type FragmentParser = fn(&mut Tree, NodeId, &str);
+
+/// the byte ranges of a node in the source html, with the changes since it's parsed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceSpan {
+	// the range of the whole node
+	pub start: usize,
+	pub end: usize,
+	// the range of the element's contents between the start tag and the end tag
+	pub inner_start: usize,
+	pub inner_end: usize,
+	// the attributes or the content are changed
+	pub is_changed: bool,
+	// the child nodes are inserted or removed
+	pub is_children_changed: bool,
+}
+
+impl SourceSpan {
+	/// pub fn `new`
+	/// the span of a node without the contents, such as a text or a void element
+	pub fn new(start: usize, end: usize) -> Self {
+		SourceSpan {
+			start,
+			end,
+			inner_start: end,
+			inner_end: end,
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone)]
+pub(super) struct NodeData {
+	pub(super) kind: NodeKind,
+	pub(super) attrs: AttrMap,
+	pub(super) content: String,
+	pub(super) parent: Option<NodeId>,
+	pub(super) children: Vec<NodeId>,
+	pub(super) span: Option<SourceSpan>,
+	// the contents of the template element
+	pub(super) template: Option<NodeId>,
+	// the namespace of the element set by the parser
+	pub(super) namespace: Option<Rc<str>>,
+	// the texts of the node and its descendants are written without escaping
+	pub(super) is_raw_zone: bool,
+	// the uppercase tag name and the local name of the element, borrowed by the node handles
+	pub(super) names: Option<(Rc<str>, Rc<str>)>,
+}
+
+impl NodeData {
+	fn new(kind: NodeKind) -> Self {
+		let names = match &kind {
+			NodeKind::Element(name) => {
+				Some((Rc::from(name.to_ascii_uppercase()), Rc::from(name.as_str())))
+			}
+			_ => None,
+		};
+		NodeData {
+			names,
+			kind,
+			attrs: AttrMap::new(),
+			content: String::new(),
+			parent: None,
+			children: Vec::new(),
+			span: None,
+			template: None,
+			namespace: None,
+			is_raw_zone: false,
+		}
+	}
+}
+
+// the nodes shared by the copy-on-write trees, a shared node is copied when it's mutated
+#[derive(Clone, Default)]
+pub(super) struct Nodes(Arena<Rc<NodeData>>);
+
+impl Nodes {
+	fn insert(&mut self, data: NodeData) -> NodeId {
+		self.0.insert(Rc::new(data))
+	}
+	fn len(&self) -> usize {
+		self.0.len()
+	}
+	fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+	fn is_shared(&self, id: NodeId) -> bool {
+		Rc::strong_count(&self.0[id]) > 1
+	}
+	fn contains(&self, id: NodeId) -> bool {
+		self.0.contains(id)
+	}
+	fn iter(&self) -> impl Iterator<Item = (NodeId, &NodeData)> {
+		self.0.iter().map(|(id, node)| (id, node.as_ref()))
+	}
+	fn remove(&mut self, id: NodeId) {
+		self.0.remove(id);
+	}
+}
+
+// the counts of the node handles, shared by the tree and the handles so they're counted without borrowing the tree
+#[derive(Default)]
+pub(super) struct Handles {
+	counts: RefCell<HashMap<NodeId, usize>>,
+	// the detached nodes and the nodes lost their last handle, checked by `collect`
+	released: RefCell<Vec<NodeId>>,
+}
+
+impl Handles {
+	pub(super) fn acquire(&self, id: NodeId) {
+		*self.counts.borrow_mut().entry(id).or_insert(0) += 1;
+	}
+	pub(super) fn release(&self, id: NodeId) {
+		let mut counts = self.counts.borrow_mut();
+		if let Some(count) = counts.get_mut(&id) {
+			*count -= 1;
+			if *count == 0 {
+				counts.remove(&id);
+				self.released.borrow_mut().push(id);
+			}
+		}
+	}
+	fn is_used(&self, id: NodeId) -> bool {
+		self.counts.borrow().contains_key(&id)
+	}
+}
+
+impl Index<NodeId> for Nodes {
+	type Output = NodeData;
+	fn index(&self, id: NodeId) -> &NodeData {
+		&self.0[id]
+	}
+}
+
+impl IndexMut<NodeId> for Nodes {
+	fn index_mut(&mut self, id: NodeId) -> &mut NodeData {
+		Rc::make_mut(&mut self.0[id])
+	}
+}
+
+/// the nodes of a document stored in a generational arena, linked by the ids
+pub struct Tree {
+	// the unique id of the tree, never reused even if the tree's address is
+	pub(super) id: usize,
+	pub(super) nodes: Nodes,
+	pub(super) root: NodeId,
+	pub(super) url: Option<String>,
+	// the parsed html if the spans of the nodes are tracked
+	source: Option<Rc<str>>,
+	parser: FragmentParser,
+	// the mutation records not notified yet, only recorded when observed
+	pub(super) pending: Option<Vec<(MutationKind, NodeId, Option<NodeId>)>>,
+	pub(super) observers: Rc<MutationObservers>,
+	pub(super) handles: Rc<Handles>,
+	// the strict mode for the untrusted trees, the nodes can't be raw zones
+	deny_raw_zones: bool,
+}
+
+impl Tree {
+	pub fn new(parser: FragmentParser) -> Self {
+		let mut nodes = Nodes::default();
+		let root = nodes.insert(NodeData::new(NodeKind::Document));
+		Tree {
+			id: TREE_ID.fetch_add(1, Ordering::Relaxed),
+			nodes,
+			root,
+			url: None,
+			source: None,
+			parser,
+			pending: None,
+			observers: Rc::new(MutationObservers::new()),
+			handles: Rc::new(Handles::default()),
+			deny_raw_zones: false,
+		}
+	}
+	/// pub fn `root`
+	/// the id of the document node
+	pub fn root(&self) -> NodeId {
+		self.root
+	}
+	/// pub fn `len`
+	/// the count of the nodes, include the detached ones not freed yet
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+	/// pub fn `is_empty`
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+	/// pub fn `is_valid`
+	/// check if the node of the id is not freed
+	pub fn is_valid(&self, id: NodeId) -> bool {
+		self.nodes.contains(id)
+	}
+	/// pub fn `kind`
+	pub fn kind(&self, id: NodeId) -> &NodeKind {
+		&self.nodes[id].kind
+	}
+	/// pub fn `content`
+	/// the content of the text, comment and the other character data nodes
+	pub fn content(&self, id: NodeId) -> &str {
+		&self.nodes[id].content
+	}
+	/// pub fn `attrs`
+	pub fn attrs(&self, id: NodeId) -> &AttrMap {
+		&self.nodes[id].attrs
+	}
+	/// pub fn `attrs_mut`
+	pub fn attrs_mut(&mut self, id: NodeId) -> &mut AttrMap {
+		&mut self.nodes[id].attrs
+	}
+	/// pub fn `parent`
+	pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+		self.nodes[id].parent
+	}
+	/// pub fn `children`
+	pub fn children(&self, id: NodeId) -> &[NodeId] {
+		&self.nodes[id].children
+	}
+	/// pub fn `template_content`
+	/// the document fragment holds the contents of the template element
+	pub fn template_content(&self, id: NodeId) -> Option<NodeId> {
+		self.nodes[id].template
+	}
+	/// pub fn `ensure_template_content`
+	/// the contents of the template element, an empty fragment is created if it has none
+	pub fn ensure_template_content(&mut self, id: NodeId) -> NodeId {
+		if let Some(content) = self.nodes[id].template {
+			return content;
+		}
+		let content = self.create(NodeKind::DocumentFragment, "");
+		self.nodes[id].template = Some(content);
+		content
+	}
+	/// pub fn `namespace`
+	/// the namespace of the element set by the parser, or inferred from the ancestors if it's not set,
+	/// `None` for the other nodes
+	pub fn namespace(&self, id: NodeId) -> Option<&str> {
+		let node = &self.nodes[id];
+		match (&node.kind, &node.namespace, node.parent) {
+			(NodeKind::Element(_), Some(namespace), _) => Some(namespace),
+			(NodeKind::Element(name), None, Some(parent)) => Some(self.child_namespace(parent, name)),
+			(NodeKind::Element(name), None, None) => Some(element_namespace(HTML_NAMESPACE, "", name)),
+			_ => None,
+		}
+	}
+	/// pub fn `set_namespace`
+	pub fn set_namespace(&mut self, id: NodeId, namespace: &str) {
+		self.nodes[id].namespace = Some(Rc::from(namespace));
+	}
+	/// pub fn `is_raw_zone`
+	/// the node is marked as a raw zone by `set_raw_zone`
+	pub fn is_raw_zone(&self, id: NodeId) -> bool {
+		self.nodes[id].is_raw_zone
+	}
+	/// pub fn `set_raw_zone`
+	/// mark the node as a raw zone, the texts of the node and its descendants are written without escaping,
+	/// it fails if the raw zones are denied
+	pub fn set_raw_zone(&mut self, id: NodeId, is_raw_zone: bool) -> Result<(), IError> {
+		if is_raw_zone && self.deny_raw_zones {
+			return Err(self.raw_zone_denied(id));
+		}
+		let old_value = std::mem::replace(&mut self.nodes[id].is_raw_zone, is_raw_zone);
+		self.record(MutationKind::RawZone { old_value }, id, None);
+		Ok(())
+	}
+	/// pub fn `has_raw_zone`
+	/// the node or one of its descendants is a raw zone
+	pub fn has_raw_zone(&self, id: NodeId) -> bool {
+		self.raw_zone(id).is_some()
+	}
+	// the first raw zone in the node and its descendants
+	pub(super) fn raw_zone(&self, id: NodeId) -> Option<NodeId> {
+		let node = &self.nodes[id];
+		if node.is_raw_zone {
+			return Some(id);
+		}
+		node
+			.children
+			.iter()
+			.chain(node.template.iter())
+			.find_map(|child| self.raw_zone(*child))
+	}
+	pub(super) fn raw_zone_denied(&self, id: NodeId) -> IError {
+		let name = match &self.nodes[id].kind {
+			NodeKind::Element(name) => name.clone(),
+			_ => String::from("#text"),
+		};
+		IError::RawZoneDenied { name }
+	}
+	/// pub fn `deny_raw_zones`
+	/// check the tree is in the strict mode, see `set_deny_raw_zones`
+	pub fn deny_raw_zones(&self) -> bool {
+		self.deny_raw_zones
+	}
+	/// pub fn `set_deny_raw_zones`
+	/// the strict mode for the untrusted trees, no node can be marked as a raw zone or adopted with
+	/// the raw zones, so the html is always escaped. it fails if the tree already has a raw zone
+	pub fn set_deny_raw_zones(&mut self, deny_raw_zones: bool) -> Result<(), IError> {
+		if deny_raw_zones {
+			let raw_zone = self
+				.nodes
+				.iter()
+				.find_map(|(id, node)| node.is_raw_zone.then_some(id));
+			if let Some(id) = raw_zone {
+				return Err(self.raw_zone_denied(id));
+			}
+		}
+		self.deny_raw_zones = deny_raw_zones;
+		Ok(())
+	}
+	// the node or one of its ancestors is a raw zone
+	fn is_in_raw_zone(&self, id: NodeId) -> bool {
+		let mut cur = Some(id);
+		while let Some(id) = cur {
+			if self.nodes[id].is_raw_zone {
+				return true;
+			}
+			cur = self.nodes[id].parent;
+		}
+		false
+	}
+	// the namespace of the element named `name` in the parent, by the tree construction rules of the html parser
+	pub(crate) fn child_namespace(&self, parent: NodeId, name: &str) -> &'static str {
+		match &self.nodes[parent].kind {
+			NodeKind::Element(parent_name) => element_namespace(
+				self.namespace(parent).unwrap_or(HTML_NAMESPACE),
+				parent_name,
+				name,
+			),
+			_ => element_namespace(HTML_NAMESPACE, "", name),
+		}
+	}
+	/// pub fn `source`
+	/// the parsed html if the spans of the nodes are tracked
+	pub fn source(&self) -> Option<&str> {
+		self.source.as_deref()
+	}
+	/// pub fn `set_source`
+	/// track the spans of the nodes in the source html, the spans are set by `set_span`
+	pub fn set_source(&mut self, source: &str) {
+		self.source = Some(Rc::from(source));
+	}
+	/// pub fn `span`
+	/// the span of the node in the source html, `None` for the nodes not from the source
+	pub fn span(&self, id: NodeId) -> Option<&SourceSpan> {
+		self.nodes[id].span.as_ref()
+	}
+	/// pub fn `set_span`
+	pub fn set_span(&mut self, id: NodeId, span: Option<SourceSpan>) {
+		self.nodes[id].span = span;
+	}
+	/// pub fn `create`
+	/// create a detached node
+	pub fn create(&mut self, kind: NodeKind, content: &str) -> NodeId {
+		let mut data = NodeData::new(kind);
+		data.content = String::from(content);
+		self.nodes.insert(data)
+	}
+	/// pub fn `detach`
+	/// remove the node from its parent, the detached subtree is freed by `collect` if no handle refers to it
+	pub fn detach(&mut self, id: NodeId) {
+		if self.nodes[id].parent.is_some() {
+			let index = self.index(id);
+			let parent = self.nodes[id].parent.take().unwrap();
+			self.nodes[parent].children.remove(index);
+			self.record(MutationKind::ChildRemoved { index }, parent, Some(id));
+			self.discard(id);
+		}
+	}
+	// check the node at the next `collect`, it's freed if it's still detached and no handle refers to it
+	pub(super) fn discard(&mut self, id: NodeId) {
+		self.handles.released.borrow_mut().push(id);
+	}
+	/// pub fn `collect`
+	/// free the detached subtrees no handle refers to, the template contents are freed with the template,
+	/// the ids of the freed nodes are invalid
+	pub fn collect(&mut self) {
+		// the pending records refer to the nodes by the ids
+		if self
+			.pending
+			.as_ref()
+			.is_some_and(|pending| !pending.is_empty())
+		{
+			return;
+		}
+		let released = std::mem::take(&mut *self.handles.released.borrow_mut());
+		for id in released {
+			if !self.is_valid(id) {
+				continue;
+			}
+			let mut top = id;
+			while let Some(parent) = self.nodes[top].parent {
+				top = parent;
+			}
+			if top == self.root || self.nodes[top].kind == NodeKind::DocumentFragment {
+				continue;
+			}
+			let mut subtree = vec![top];
+			let mut index = 0;
+			while let Some(&id) = subtree.get(index) {
+				let node = &self.nodes[id];
+				subtree.extend(node.children.iter().chain(&node.template));
+				index += 1;
+			}
+			// the handle checks the subtree again when it's dropped
+			if subtree.iter().any(|id| self.handles.is_used(*id)) {
+				continue;
+			}
+			for id in subtree {
+				self.nodes.remove(id);
+			}
+		}
+	}
+	/// pub fn `contains`
+	/// check if the node is the ancestor itself or one of its descendants
+	pub fn contains(&self, ancestor: NodeId, id: NodeId) -> bool {
+		let mut cur = Some(id);
+		while let Some(id) = cur {
+			if id == ancestor {
+				return true;
+			}
+			cur = self.nodes[id].parent;
+		}
+		false
+	}
+	/// pub fn `insert`
+	/// move the node into the parent at the index, the node can't be the parent or one of its ancestors
+	pub fn insert(&mut self, parent: NodeId, index: usize, id: NodeId) -> Result<(), IError> {
+		if self.contains(id, parent) {
+			return Err(IError::HierarchyRequest {
+				message: String::from("The node can't be inserted into itself or its descendants."),
+			});
+		}
+		self.link(parent, index, id);
+		Ok(())
+	}
+	/// pub fn `append`
+	/// move the node into the parent as the last child, the node can't be the parent or one of its ancestors
+	pub fn append(&mut self, parent: NodeId, id: NodeId) -> Result<(), IError> {
+		self.insert(parent, usize::MAX, id)
+	}
+	// move the node into the parent at the index without checking the ancestors,
+	// the node must not contain the parent, such as a new node
+	pub(crate) fn link(&mut self, parent: NodeId, index: usize, id: NodeId) {
+		self.detach(id);
+		let index = index.min(self.nodes[parent].children.len());
+		self.nodes[id].parent = Some(parent);
+		self.nodes[parent].children.insert(index, id);
+		self.record(MutationKind::ChildInserted { index }, parent, Some(id));
+	}
+	/// pub fn `index`
+	/// the index in the parent's child nodes
+	pub fn index(&self, id: NodeId) -> usize {
+		self.nodes[id]
+			.parent
+			.and_then(|parent| {
+				self.nodes[parent]
+					.children
+					.iter()
+					.position(|child| *child == id)
+			})
+			.unwrap_or(0)
+	}
+	pub(super) fn record(&mut self, kind: MutationKind, target: NodeId, node: Option<NodeId>) {
+		// the changed parts of the source are serialized
+		if self.nodes[target].span.is_some() {
+			if let Some(span) = &mut self.nodes[target].span {
+				match kind {
+					MutationKind::Attribute { .. }
+					| MutationKind::Text { .. }
+					| MutationKind::RawZone { .. } => span.is_changed = true,
+					MutationKind::ChildInserted { .. } | MutationKind::ChildRemoved { .. } => {
+						span.is_children_changed = true
+					}
+				}
+			}
+		}
+		if let Some(pending) = &mut self.pending {
+			pending.push((kind, target, node));
+		}
+	}
+	// make the changes without records, such as building the new nodes
+	fn unrecorded<R>(&mut self, handle: impl FnOnce(&mut Tree) -> R) -> R {
+		let pending = self.pending.take();
+		let result = handle(self);
+		self.pending = pending;
+		result
+	}
+	pub(super) fn set_content(&mut self, id: NodeId, content: String) {
+		let old_value = std::mem::replace(&mut self.nodes[id].content, content);
+		self.record(MutationKind::Text { old_value }, id, None);
+	}
+	pub(super) fn clear(&mut self, id: NodeId) {
+		for child in self.nodes[id].children.clone() {
+			self.detach(child);
+		}
+	}
+	// parse the html into the parent, only the top level nodes are recorded
+	pub(super) fn parse_into(&mut self, parent: NodeId, html: &str) {
+		let from = self.nodes[parent].children.len();
+		let parser = self.parser;
+		self.unrecorded(|tree| parser(tree, parent, html));
+		for index in from..self.nodes[parent].children.len() {
+			let child = self.nodes[parent].children[index];
+			self.record(MutationKind::ChildInserted { index }, parent, Some(child));
+		}
+	}
+	/// pub fn `reparse`
+	/// parse the html in the context of the node's parent and replace the node by the parsed nodes,
+	/// the other nodes are kept, return the new nodes, nothing is changed if the node has no parent
+	pub fn reparse(&mut self, id: NodeId, html: &str) -> Vec<NodeId> {
+		let parent = match self.nodes[id].parent {
+			Some(parent) => parent,
+			None => return Vec::new(),
+		};
+		let children = self.unrecorded(|tree| {
+			// the holder has the name and the namespace of the parent, so the context is the same
+			let holder = tree.create(tree.nodes[parent].kind.clone(), "");
+			if let Some(namespace) = tree.namespace(parent).map(String::from) {
+				tree.set_namespace(holder, &namespace);
+			}
+			let parser = tree.parser;
+			parser(tree, holder, html);
+			let children = tree.nodes[holder].children.clone();
+			tree.clear(holder);
+			tree.discard(holder);
+			children
+		});
+		let index = self.index(id);
+		self.detach(id);
+		for (offset, child) in children.iter().enumerate() {
+			self.link(parent, index + offset, *child);
+		}
+		children
+	}
+	/// pub fn `import`
+	/// copy the node and its descendants from another tree, the copy is detached
+	pub fn import(&mut self, from: &Tree, id: NodeId) -> NodeId {
+		self.unrecorded(|tree| tree.import_node(from, id))
+	}
+	fn import_node(&mut self, from: &Tree, id: NodeId) -> NodeId {
+		let node = &from.nodes[id];
+		let copy = self.create(node.kind.clone(), &node.content);
+		self.nodes[copy].attrs = node.attrs.clone();
+		self.nodes[copy].is_raw_zone = node.is_raw_zone;
+		// the copy keeps the namespace wherever it's inserted
+		self.nodes[copy].namespace = from.namespace(id).map(Rc::from);
+		for child in &node.children {
+			let child = self.import_node(from, *child);
+			self.link(copy, usize::MAX, child);
+		}
+		if let Some(content) = node.template {
+			self.nodes[copy].template = Some(self.import_node(from, content));
+		}
+		copy
+	}
+	/// pub fn `deep_clone`
+	/// copy the node and its descendants in the tree, the copy is detached
+	pub fn deep_clone(&mut self, id: NodeId) -> NodeId {
+		self.unrecorded(|tree| tree.copy_node(id, true))
+	}
+	/// pub fn `shallow_clone`
+	/// copy the node without its child nodes and template contents, the copy is detached
+	pub fn shallow_clone(&mut self, id: NodeId) -> NodeId {
+		self.unrecorded(|tree| tree.copy_node(id, false))
+	}
+	fn copy_node(&mut self, id: NodeId, deep: bool) -> NodeId {
+		let node = &self.nodes[id];
+		let (kind, attrs, content) = (node.kind.clone(), node.attrs.clone(), node.content.clone());
+		let (children, template, is_raw_zone) =
+			(node.children.clone(), node.template, node.is_raw_zone);
+		let namespace = self.namespace(id).map(Rc::from);
+		let copy = self.create(kind, &content);
+		self.nodes[copy].attrs = attrs;
+		self.nodes[copy].namespace = namespace;
+		self.nodes[copy].is_raw_zone = is_raw_zone;
+		if !deep {
+			return copy;
+		}
+		for child in children {
+			let child = self.copy_node(child, true);
+			self.link(copy, usize::MAX, child);
+		}
+		if let Some(content) = template {
+			self.nodes[copy].template = Some(self.copy_node(content, true));
+		}
+		copy
+	}
+	/// pub fn `share`
+	/// a copy-on-write clone of the tree with the same ids, the nodes are shared until mutated
+	pub fn share(&self) -> Tree {
+		Tree {
+			id: TREE_ID.fetch_add(1, Ordering::Relaxed),
+			nodes: self.nodes.clone(),
+			root: self.root,
+			url: self.url.clone(),
+			source: self.source.clone(),
+			parser: self.parser,
+			pending: None,
+			observers: Rc::new(MutationObservers::new()),
+			handles: Rc::new(Handles::default()),
+			deny_raw_zones: self.deny_raw_zones,
+		}
+	}
+	/// pub fn `is_shared`
+	/// check if the node is still shared with another tree
+	pub fn is_shared(&self, id: NodeId) -> bool {
+		self.nodes.is_shared(id)
+	}
+	/// pub fn `to_document`
+	/// a new tree with the copies of the document's child nodes, the url and the parser
+	pub fn to_document(&self) -> Tree {
+		let mut tree = Tree::new(self.parser);
+		tree.url = self.url.clone();
+		tree.deny_raw_zones = self.deny_raw_zones;
+		let root = tree.root;
+		for child in &self.nodes[self.root].children {
+			let child = tree.import(self, *child);
+			tree.link(root, usize::MAX, child);
+		}
+		tree
+	}
+	pub(super) fn text(&self, id: NodeId, result: &mut String) {
+		let node = &self.nodes[id];
+		match node.kind {
+			NodeKind::Text => result.push_str(&node.content),
+			NodeKind::Element(_) | NodeKind::Document | NodeKind::DocumentFragment => {
+				for child in &node.children {
+					self.text(*child, result);
+				}
+			}
+			_ => {}
+		}
+	}
+	// the html of the child nodes, the template's are its contents
+	pub(super) fn inner_html(&self, id: NodeId, result: &mut String) {
+		let node = &self.nodes[id];
+		for child in &node.children {
+			self.html(*child, result);
+		}
+		if let Some(content) = node.template {
+			self.inner_html(content, result);
+		}
+	}
+	pub(super) fn html(&self, id: NodeId, result: &mut String) {
+		let node = &self.nodes[id];
+		match &node.kind {
+			NodeKind::Document | NodeKind::DocumentFragment => self.inner_html(id, result),
+			// the strict tree has no raw zones
+			NodeKind::Text if self.is_in_raw_zone(id) => result.push_str(&node.content),
+			NodeKind::Text => result.push_str(
+				&node
+					.content
+					.replace('&', "&amp;")
+					.replace('<', "&lt;")
+					.replace('>', "&gt;"),
+			),
+			NodeKind::Comment => result.push_str(&format!("<!--{}-->", node.content)),
+			NodeKind::Doctype => result.push_str(&format!("<!{}>", node.content)),
+			NodeKind::CData => result.push_str(&format!("<![CDATA[{}]]>", node.content)),
+			NodeKind::ProcessingInstruction(target) => {
+				result.push_str(&format!("<?{} {}?>", target, node.content))
+			}
+			NodeKind::Element(name) => {
+				result.push_str(&format!("<{}{}>", name, node.attrs.to_html()));
+				if spec::is_void_element(name) {
+					return;
+				}
+				if self.is_raw_text_element(id) {
+					self.text(id, result);
+					result.push_str(&format!("</{}>", name));
+					return;
+				}
+				self.inner_html(id, result);
+				result.push_str(&format!("</{}>", name));
+			}
+		}
+	}
+	// the html script or style element, the svg ones are not raw text elements
+	fn is_raw_text_element(&self, id: NodeId) -> bool {
+		matches!(&self.nodes[id].kind, NodeKind::Element(name) if spec::is_raw_text_element(name))
+			&& self.namespace(id) == Some(HTML_NAMESPACE)
+	}
+	// the text is in a script or a style element
+	fn is_raw_text(&self, id: NodeId) -> bool {
+		self.nodes[id]
+			.parent
+			.is_some_and(|parent| self.is_raw_text_element(parent))
+	}
+	// copy the unchanged source of the node, only the changed parts are serialized
+	pub(super) fn source_html(&self, id: NodeId, result: &mut String) {
+		let node = &self.nodes[id];
+		let (source, span) = match (&self.source, &node.span) {
+			(Some(source), Some(span)) => (source, span),
+			_ => return self.html(id, result),
+		};
+		match &node.kind {
+			NodeKind::Element(_) | NodeKind::Document | NodeKind::DocumentFragment => {
+				let start_tag = &source[span.start..span.inner_start];
+				match &node.kind {
+					NodeKind::Element(name) if span.is_changed => {
+						let slash = if start_tag.ends_with("/>") { " /" } else { "" };
+						result.push_str(&format!("<{}{}{}>", name, node.attrs.to_html(), slash));
+					}
+					_ => result.push_str(start_tag),
+				}
+				// the gaps between the children are kept if the children are not changed
+				let mut cursor = if span.is_children_changed {
+					None
+				} else {
+					Some(span.inner_start)
+				};
+				// the template's contents are the last child, the span is its inner range
+				for child in node.children.iter().chain(&node.template) {
+					let child_span = self.nodes[*child].span;
+					if let (Some(from), Some(child_span)) = (cursor, child_span) {
+						if child_span.start >= from {
+							result.push_str(&source[from..child_span.start]);
+						}
+					}
+					self.source_html(*child, result);
+					cursor = cursor.and(child_span).map(|child_span| child_span.end);
+				}
+				if let Some(from) = cursor.filter(|from| *from <= span.inner_end) {
+					result.push_str(&source[from..span.inner_end]);
+				}
+				result.push_str(&source[span.inner_end..span.end]);
+			}
+			// the raw texts of the script and the style are not escaped
+			NodeKind::Text if span.is_changed && self.is_raw_text(id) => result.push_str(&node.content),
+			_ if span.is_changed => self.html(id, result),
+			_ => result.push_str(&source[span.start..span.end]),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{NodeKind, Tree};
+	use crate::adapter::NodeId;
+	fn parse_nothing(_: &mut Tree, _: NodeId, _: &str) {}
+	#[test]
+	fn test_tree_share() {
+		let mut tree = Tree::new(parse_nothing);
+		let root = tree.root();
+		let div = tree.create(NodeKind::Element(String::from("div")), "");
+		let p = tree.create(NodeKind::Element(String::from("p")), "");
+		let text = tree.create(NodeKind::Text, "one");
+		tree.append(root, div).unwrap();
+		tree.append(div, p).unwrap();
+		tree.append(p, text).unwrap();
+		let mut copy = tree.share();
+		assert!(copy.is_shared(text) && tree.is_shared(div));
+		// only the mutated nodes are copied
+		copy.set_content(text, String::from("two"));
+		copy.attrs_mut(div).set("id", Some("main"));
+		assert!(!copy.is_shared(text) && !tree.is_shared(text));
+		assert!(!copy.is_shared(div));
+		assert!(copy.is_shared(p) && copy.is_shared(root));
+		let (mut html, mut copy_html) = (String::new(), String::new());
+		tree.html(root, &mut html);
+		copy.html(root, &mut copy_html);
+		assert_eq!(html, "<div><p>one</p></div>");
+		assert_eq!(copy_html, "<div id=\"main\"><p>two</p></div>");
+		// the new nodes are not shared
+		let b = copy.create(NodeKind::Element(String::from("b")), "");
+		copy.append(p, b).unwrap();
+		assert!(!copy.is_shared(b) && !copy.is_shared(p));
+		assert_eq!(tree.children(p), &[text]);
+	}
+}
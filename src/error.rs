@@ -7,4 +7,25 @@ pub enum Error {
 	MethodOnInvalidSelector { method: String, error: String },
 	#[error("Call method '{method}' cause an error: {message}")]
 	InvalidTraitMethodCall { method: String, message: String },
+	#[error("Invalid pattern:'{pattern}'<{reason}>")]
+	InvalidPattern { pattern: String, reason: String },
+	#[error("Invalid node path:'{path}'<{reason}>")]
+	InvalidNodePath { path: String, reason: String },
+	#[error("Invalid structure '{rule}': {message}")]
+	InvalidStructure { rule: String, message: String },
+	#[error("The query is aborted, it {reason}")]
+	QueryBudgetExceeded { reason: String },
+	#[error("Process the document at index {index} failed: {message}")]
+	BatchFailed { index: usize, message: String },
+	#[error("Hierarchy request error: {message}")]
+	HierarchyRequest { message: String },
+	#[error("Unsupported charset '{charset}'")]
+	UnsupportedCharset { charset: String },
+	#[error("The characters in the {context} can't be encoded in '{charset}', the character references are not decoded there")]
+	UnencodableRawText { charset: String, context: String },
+	#[error("The raw zone '{name}' is denied in the strict mode")]
+	RawZoneDenied { name: String },
+	#[cfg(feature = "http")]
+	#[error("Fetch url '{url}' failed: {source}")]
+	FetchFailed { url: String, source: reqwest::Error },
 }
@@ -0,0 +1,190 @@
+use crate::constants::ATTR_CLASS;
+use crate::interface::{BoxDynElement, Elements, IAttrValue, IDocumentTrait};
+
+// the minimum text length of a paragraph to be scored
+const MIN_PARAGRAPH_LEN: usize = 25;
+// class or id names that make a element more likely to be the content
+const POSITIVE_NAMES: [&str; 10] = [
+	"article", "body", "content", "entry", "main", "page", "post", "text", "blog", "story",
+];
+// class or id names that make a element less likely to be the content
+const NEGATIVE_NAMES: [&str; 12] = [
+	"comment", "meta", "footer", "footnote", "sidebar", "sponsor", "ad-", "promo", "related",
+	"share", "nav", "menu",
+];
+// selectors for the byline
+const BYLINE_SELECTORS: [&str; 4] = [
+	"[rel='author']",
+	"[itemprop='author']",
+	".byline",
+	".author",
+];
+
+pub struct Article<'a> {
+	// title of the article
+	pub title: Option<String>,
+	// author line of the article
+	pub byline: Option<String>,
+	// the element contains the main content
+	pub content: BoxDynElement<'a>,
+	// the score of the content element
+	pub score: f64,
+}
+
+impl<'a> Article<'a> {
+	/// pub fn `text`
+	/// get the text of the content element
+	pub fn text(&self) -> &str {
+		self.content.text()
+	}
+	/// pub fn `html`
+	/// get the html of the content element
+	pub fn html(&self) -> &str {
+		self.content.inner_html()
+	}
+}
+
+// the class name and id of the element
+fn get_class_and_id(ele: &BoxDynElement) -> String {
+	let mut names = String::with_capacity(20);
+	for attr_name in &[ATTR_CLASS, "id"] {
+		if let Some(IAttrValue::Value(v, _)) = ele.get_attribute(attr_name) {
+			names.push_str(&v.to_ascii_lowercase());
+			names.push(' ');
+		}
+	}
+	names
+}
+
+// the weight by class name and id
+fn get_class_weight(ele: &BoxDynElement) -> f64 {
+	let names = get_class_and_id(ele);
+	let mut weight = 0.0;
+	if NEGATIVE_NAMES.iter().any(|name| names.contains(name)) {
+		weight -= 25.0;
+	}
+	if POSITIVE_NAMES.iter().any(|name| names.contains(name)) {
+		weight += 25.0;
+	}
+	weight
+}
+
+// the initial score by tag name
+fn get_tag_score(ele: &BoxDynElement) -> f64 {
+	let score = match ele.tag_name().to_ascii_lowercase().as_str() {
+		"article" => 10.0,
+		"div" => 5.0,
+		"pre" | "td" | "blockquote" => 3.0,
+		"address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+		"h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+		_ => 0.0,
+	};
+	score + get_class_weight(ele)
+}
+
+// the ratio of link text length to all text length
+fn get_link_density(ele: &BoxDynElement) -> f64 {
+	let text_len = ele.text().trim().chars().count();
+	if text_len == 0 {
+		return 0.0;
+	}
+	let links = Elements::with_node(ele).find("a");
+	let link_len = links.text().trim().chars().count();
+	link_len as f64 / text_len as f64
+}
+
+// add score to a candidate, initialize it if not scored
+fn add_score<'a>(
+	candidates: &mut Vec<(BoxDynElement<'a>, f64)>,
+	ele: BoxDynElement<'a>,
+	score: f64,
+) {
+	if let Some(pair) = candidates.iter_mut().find(|(cand, _)| cand.is(&ele)) {
+		pair.1 += score;
+	} else {
+		let init_score = get_tag_score(&ele);
+		candidates.push((ele, init_score + score));
+	}
+}
+
+// find the title of the article, prefer the document's title
+fn get_title(doc: &dyn IDocumentTrait, content: &BoxDynElement) -> Option<String> {
+	if let Some(title) = doc.title() {
+		let title = title.trim();
+		if !title.is_empty() {
+			return Some(String::from(title));
+		}
+	}
+	let heading = Elements::with_node(content).find("h1");
+	let heading = heading.text().trim();
+	if !heading.is_empty() {
+		return Some(String::from(heading));
+	}
+	None
+}
+
+// find the byline from the meta or the author elements
+fn get_byline(doc: &dyn IDocumentTrait, body: &Elements) -> Option<String> {
+	if let Some(head) = &doc.head() {
+		let meta = Elements::with_node(head).find("meta[name='author']");
+		if let Some(IAttrValue::Value(author, _)) = meta.attr("content") {
+			let author = author.trim();
+			if !author.is_empty() {
+				return Some(String::from(author));
+			}
+		}
+	}
+	for selector in &BYLINE_SELECTORS {
+		let byline = body.find(selector);
+		let byline = byline.text().trim();
+		if !byline.is_empty() && byline.chars().count() < 100 {
+			return Some(String::from(byline));
+		}
+	}
+	None
+}
+
+/// pub fn `article`
+/// find the main content of the document by scoring the paragraphs' ancestors,
+/// just like the readability algorithm does
+pub fn article<'a>(doc: &dyn IDocumentTrait) -> Option<Article<'a>> {
+	let body = doc.body()?;
+	let body = Elements::with_node(&body);
+	let paragraphs = body.find("p, pre, td, blockquote");
+	let mut candidates: Vec<(BoxDynElement, f64)> = Vec::with_capacity(paragraphs.length());
+	for ele in paragraphs.get_ref() {
+		let text = ele.text().trim();
+		let text_len = text.chars().count();
+		if text_len < MIN_PARAGRAPH_LEN {
+			continue;
+		}
+		// the paragraph itself, the commas, and the text length
+		let score = 1.0 + text.matches([',', '，']).count() as f64 + ((text_len / 100) as f64).min(3.0);
+		if let Some(parent) = ele.parent() {
+			let grand_parent = parent.parent();
+			add_score(&mut candidates, parent, score);
+			if let Some(grand_parent) = grand_parent {
+				add_score(&mut candidates, grand_parent, score / 2.0);
+			}
+		}
+	}
+	// scale the score by the link density
+	let mut top: Option<(BoxDynElement, f64)> = None;
+	for (ele, score) in candidates {
+		let score = score * (1.0 - get_link_density(&ele));
+		let is_top = match &top {
+			Some((_, top_score)) => score > *top_score,
+			None => true,
+		};
+		if is_top {
+			top = Some((ele, score));
+		}
+	}
+	let (content, score) = top?;
+	Some(Article {
+		title: get_title(doc, &content),
+		byline: get_byline(doc, &body),
+		content,
+		score,
+	})
+}
@@ -0,0 +1,267 @@
+use crate::interface::{BoxDynElement, Elements, IAttrValue};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormValue {
+	// the value of the text controls, checkboxes, radios and selects
+	Text(String),
+	// the file input, the dom knows nothing about the file but the name
+	File(String),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FormData {
+	pub entries: Vec<(String, FormValue)>,
+}
+
+// the control tag names of a form
+const CONTROLS_SELECTOR: &str = "input, select, textarea";
+
+// https://url.spec.whatwg.org/#urlencoded-serializing
+fn url_encode(content: &str, result: &mut String) {
+	for byte in content.bytes() {
+		match byte {
+			b'*' | b'-' | b'.' | b'_' | b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' => {
+				result.push(byte as char)
+			}
+			b' ' => result.push('+'),
+			_ => result.push_str(&format!("%{:02X}", byte)),
+		}
+	}
+}
+
+// escape the name and filename in the multipart header
+fn multipart_escape(content: &str) -> String {
+	content
+		.replace('\r', "%0D")
+		.replace('\n', "%0A")
+		.replace('"', "%22")
+}
+
+// the newlines of the textarea's value are submitted as CRLF
+fn normalize_newlines(content: &str) -> String {
+	content
+		.replace("\r\n", "\n")
+		.replace('\r', "\n")
+		.replace('\n', "\r\n")
+}
+
+// get the string value of an attribute, `IAttrValue::True` is an empty string
+fn get_attr_string(ele: &BoxDynElement, name: &str) -> Option<String> {
+	ele.get_attribute(name).map(|value| value.to_string())
+}
+
+// the control is disabled by itself or by a disabled fieldset
+fn is_disabled(ele: &BoxDynElement) -> bool {
+	if ele.has_attribute("disabled") {
+		return true;
+	}
+	let mut cur = ele.parent();
+	let mut child: Option<BoxDynElement> = None;
+	while let Some(parent) = cur {
+		let tag_name = parent.tag_name().to_ascii_lowercase();
+		if tag_name == "form" {
+			break;
+		}
+		if tag_name == "fieldset" && parent.has_attribute("disabled") {
+			// the controls in the first legend of the disabled fieldset are not disabled
+			let in_first_legend = match &child {
+				Some(child) if child.tag_name().eq_ignore_ascii_case("legend") => {
+					let legend = Elements::with_node(&parent).children("legend");
+					legend.get(0).map(|first| first.is(child)).unwrap_or(false)
+				}
+				_ => false,
+			};
+			if !in_first_legend {
+				return true;
+			}
+		}
+		cur = parent.parent();
+		child = Some(parent);
+	}
+	false
+}
+
+// the option is disabled by itself or by a disabled optgroup
+fn is_option_disabled(ele: &BoxDynElement) -> bool {
+	if ele.has_attribute("disabled") {
+		return true;
+	}
+	match ele.parent() {
+		Some(parent) => {
+			parent.tag_name().eq_ignore_ascii_case("optgroup") && parent.has_attribute("disabled")
+		}
+		None => false,
+	}
+}
+
+// the option's value, or the text content with collapsed whitespaces
+fn get_option_value(ele: &BoxDynElement) -> String {
+	get_attr_string(ele, "value").unwrap_or_else(|| {
+		ele
+			.text()
+			.split_whitespace()
+			.collect::<Vec<&str>>()
+			.join(" ")
+	})
+}
+
+// append the entries of the control
+fn append_entries(ele: &BoxDynElement, entries: &mut Vec<(String, FormValue)>) {
+	let name = match get_attr_string(ele, "name") {
+		Some(name) if !name.is_empty() => name,
+		_ => return,
+	};
+	if is_disabled(ele) {
+		return;
+	}
+	let tag_name = ele.tag_name().to_ascii_lowercase();
+	match tag_name.as_str() {
+		"input" => {
+			let input_type = get_attr_string(ele, "type")
+				.unwrap_or_default()
+				.to_ascii_lowercase();
+			let value = get_attr_string(ele, "value");
+			match input_type.as_str() {
+				// no submitter, the buttons are not successful
+				"submit" | "image" | "reset" | "button" => {}
+				"checkbox" | "radio" => {
+					if ele.has_attribute("checked") {
+						let value = value.unwrap_or_else(|| String::from("on"));
+						entries.push((name, FormValue::Text(value)));
+					}
+				}
+				"file" => {
+					entries.push((name, FormValue::File(String::new())));
+				}
+				"hidden" if name.eq_ignore_ascii_case("_charset_") => {
+					entries.push((name, FormValue::Text(String::from("UTF-8"))));
+				}
+				_ => {
+					entries.push((name, FormValue::Text(value.unwrap_or_default())));
+				}
+			}
+		}
+		"textarea" => {
+			entries.push((name, FormValue::Text(normalize_newlines(ele.text()))));
+		}
+		"select" => {
+			let options = Elements::with_node(ele).find("option");
+			let selected = options
+				.get_ref()
+				.iter()
+				.filter(|option| option.has_attribute("selected"))
+				.collect::<Vec<_>>();
+			if ele.has_attribute("multiple") {
+				for option in selected {
+					if !is_option_disabled(option) {
+						entries.push((name.clone(), FormValue::Text(get_option_value(option))));
+					}
+				}
+			} else if let Some(option) = selected.last() {
+				// only the last selected option of a single select is selected
+				if !is_option_disabled(option) {
+					entries.push((name, FormValue::Text(get_option_value(option))));
+				}
+			} else {
+				// the first option of a drop-down select is selected by default
+				let size = match ele.get_attribute("size") {
+					Some(IAttrValue::Value(size, _)) => size.trim().parse::<usize>().unwrap_or(1),
+					_ => 1,
+				};
+				if size <= 1 {
+					if let Some(option) = options
+						.get_ref()
+						.iter()
+						.find(|option| !is_option_disabled(option))
+					{
+						entries.push((name, FormValue::Text(get_option_value(option))));
+					}
+				}
+			}
+		}
+		_ => {}
+	}
+}
+
+impl FormData {
+	/// build the form data from the forms or the controls
+	pub(crate) fn from_elements(elements: &Elements) -> Self {
+		let mut entries = Vec::with_capacity(10);
+		for ele in elements.get_ref() {
+			if ele.tag_name().eq_ignore_ascii_case("form") {
+				let form = Elements::with_node(ele);
+				let form_id = get_attr_string(ele, "id");
+				// the controls associated to another form by the `form` attribute are excluded
+				let mut controls =
+					form.find(CONTROLS_SELECTOR).filter_by(|_, control| {
+						match get_attr_string(control, "form") {
+							Some(owner) => Some(&owner) == form_id.as_ref(),
+							None => true,
+						}
+					});
+				// the controls outside but associated by the `form` attribute
+				if let (Some(id), Some(doc)) = (&form_id, ele.owner_document()) {
+					if let Some(root) = &doc.get_root_node().root_element() {
+						let associated = Elements::with_node(root)
+							.find(CONTROLS_SELECTOR)
+							.filter_by(|_, control| get_attr_string(control, "form").as_ref() == Some(id))
+							.not_in(&controls);
+						if !associated.is_empty() {
+							controls = controls.add(associated);
+						}
+					}
+				}
+				for control in controls.get_ref() {
+					append_entries(control, &mut entries);
+				}
+			} else {
+				append_entries(ele, &mut entries);
+			}
+		}
+		FormData { entries }
+	}
+	/// pub fn `to_urlencoded`
+	/// serialize the entries as `application/x-www-form-urlencoded`, the files use their names
+	pub fn to_urlencoded(&self) -> String {
+		let mut result = String::with_capacity(self.entries.len() * 10);
+		for (index, (name, value)) in self.entries.iter().enumerate() {
+			if index > 0 {
+				result.push('&');
+			}
+			url_encode(name, &mut result);
+			result.push('=');
+			match value {
+				FormValue::Text(value) | FormValue::File(value) => url_encode(value, &mut result),
+			}
+		}
+		result
+	}
+	/// pub fn `to_multipart`
+	/// serialize the entries as `multipart/form-data` with the boundary
+	pub fn to_multipart(&self, boundary: &str) -> String {
+		let mut result = String::with_capacity(self.entries.len() * 50);
+		for (name, value) in &self.entries {
+			result.push_str("--");
+			result.push_str(boundary);
+			result.push_str("\r\nContent-Disposition: form-data; name=\"");
+			result.push_str(&multipart_escape(name));
+			result.push('"');
+			match value {
+				FormValue::Text(value) => {
+					result.push_str("\r\n\r\n");
+					result.push_str(value);
+				}
+				FormValue::File(filename) => {
+					result.push_str("; filename=\"");
+					result.push_str(&multipart_escape(filename));
+					result.push_str("\"\r\nContent-Type: application/octet-stream\r\n\r\n");
+				}
+			}
+			result.push_str("\r\n");
+		}
+		result.push_str("--");
+		result.push_str(boundary);
+		result.push_str("--\r\n");
+		result
+	}
+}
@@ -0,0 +1,250 @@
+use crate::css::{length_px, matches_media, Viewport};
+use crate::interface::{
+	BoxDynElement, Elements, IAttrValue, Srcset, SrcsetCandidate, SrcsetDescriptor,
+};
+use crate::utils::resolve_url;
+
+// the attributes of the lazy loaded source, the first one present wins
+const LAZY_SRC_ATTRS: [&str; 4] = ["data-src", "data-lazy-src", "data-original", "data-lazy"];
+const LAZY_SRCSET_ATTRS: [&str; 2] = ["data-srcset", "data-lazy-srcset"];
+// the image types supported by the browsers, the `<source>` with other types is skipped
+const SUPPORTED_TYPES: [&str; 10] = [
+	"image/avif",
+	"image/webp",
+	"image/apng",
+	"image/png",
+	"image/jpeg",
+	"image/gif",
+	"image/svg+xml",
+	"image/bmp",
+	"image/x-icon",
+	"image/vnd.microsoft.icon",
+];
+
+/// a `<source>` of the `<picture>`, the urls of the `srcset` are resolved
+pub struct PictureSource<'a> {
+	pub element: BoxDynElement<'a>,
+	// the candidates of the lazy-load `srcset` if present, otherwise the `srcset` attribute
+	pub srcset: Srcset,
+	pub sizes: Option<String>,
+	pub media: Option<String>,
+	// the `type` attribute, such as `image/webp`
+	pub mime_type: Option<String>,
+}
+
+/// the descriptor of an `<img>` element, the urls are resolved against the base url of the document,
+/// or kept as they are if the document has no absolute base url
+pub struct Image<'a> {
+	pub element: BoxDynElement<'a>,
+	// the url of the lazy-load attribute if present, otherwise the `src` attribute
+	pub src: Option<String>,
+	// the attribute name of the `src`, such as `src` or `data-src`
+	pub src_attr: Option<&'static str>,
+	// the candidates of the lazy-load `srcset` if present, otherwise the `srcset` attribute
+	pub srcset: Srcset,
+	pub sizes: Option<String>,
+	pub alt: Option<String>,
+	// the `width` and `height` attributes in pixels
+	pub width: Option<u32>,
+	pub height: Option<u32>,
+	// the `loading` attribute in lower case, such as `lazy`
+	pub loading: Option<String>,
+	// the image is loaded lazily by the `loading` attribute or the lazy-load attributes
+	pub is_lazy: bool,
+	// the `<source>` elements before the image in the `<picture>`
+	pub sources: Vec<PictureSource<'a>>,
+}
+
+// the non-empty attribute value
+fn attr_value(ele: &BoxDynElement, name: &str) -> Option<String> {
+	match ele.get_attribute(name)? {
+		IAttrValue::Value(value, _) if !value.trim().is_empty() => Some(value),
+		_ => None,
+	}
+}
+
+// the first non-empty attribute of the names
+fn first_attr(ele: &BoxDynElement, names: &[&'static str]) -> Option<(&'static str, String)> {
+	names
+		.iter()
+		.find_map(|&name| attr_value(ele, name).map(|value| (name, value)))
+}
+
+// the dimension attribute, such as `300` or `300px`
+fn dimension(ele: &BoxDynElement, name: &str) -> Option<u32> {
+	attr_value(ele, name)?
+		.trim()
+		.trim_end_matches("px")
+		.parse()
+		.ok()
+}
+
+// the url resolved against the base url, or the url itself if it can't be resolved
+fn resolve(base: &str, url: &str) -> String {
+	resolve_url(base, url).unwrap_or_else(|| String::from(url.trim()))
+}
+
+// the lazy-load `srcset` or the `srcset` attribute, the urls are resolved
+fn resolved_srcset(ele: &BoxDynElement, base: &str) -> Srcset {
+	let mut srcset: Srcset = first_attr(ele, &LAZY_SRCSET_ATTRS)
+		.or_else(|| first_attr(ele, &["srcset"]))
+		.and_then(|(_, value)| value.parse().ok())
+		.unwrap_or_default();
+	for candidate in srcset.candidates.iter_mut() {
+		candidate.url = resolve(base, &candidate.url);
+	}
+	srcset
+}
+
+// the `<source>` elements before the image in the `<picture>`
+fn picture_sources<'a>(ele: &BoxDynElement<'a>, base: &str) -> Vec<PictureSource<'a>> {
+	let is_in_picture = ele
+		.parent()
+		.is_some_and(|parent| parent.tag_name().eq_ignore_ascii_case("picture"));
+	if !is_in_picture {
+		return Vec::new();
+	}
+	Elements::with_nodes(vec![ele.cloned()])
+		.prev_all("source")
+		.get_ref()
+		.iter()
+		.map(|source| PictureSource {
+			element: source.cloned(),
+			srcset: resolved_srcset(source, base),
+			sizes: attr_value(source, "sizes"),
+			media: attr_value(source, "media"),
+			mime_type: attr_value(source, "type"),
+		})
+		.collect()
+}
+
+// the source size of the `sizes` attribute, the first size with the matched media condition,
+// `100vw` if no size matched
+fn source_size(sizes: Option<&str>, viewport: &Viewport) -> f32 {
+	if let Some(sizes) = sizes {
+		for size in sizes.split(',') {
+			let size = size.trim();
+			let (media, length) = match size.rfind(')') {
+				Some(end) if size.starts_with('(') => (&size[..=end], &size[end + 1..]),
+				_ => ("", size),
+			};
+			if !matches_media(media, viewport) {
+				continue;
+			}
+			if let Some(px) = length_px(length, viewport).filter(|px| *px > 0.0) {
+				return px;
+			}
+		}
+	}
+	viewport.width
+}
+
+// the url of the candidate a browser would choose, the smallest density not less than the device
+// pixel ratio, or the largest density if all are less
+fn choose_candidate(srcset: &Srcset, sizes: Option<&str>, viewport: &Viewport) -> Option<String> {
+	let size = source_size(sizes, viewport);
+	let mut best: Option<(f32, &str)> = None;
+	for candidate in &srcset.candidates {
+		let density = match candidate.descriptor {
+			SrcsetDescriptor::None => 1.0,
+			SrcsetDescriptor::Density(density) => density,
+			SrcsetDescriptor::Width(width) => width as f32 / size,
+		};
+		let is_better = match best {
+			None => true,
+			Some((best_density, _)) => {
+				let is_enough = density >= viewport.device_pixel_ratio;
+				let is_best_enough = best_density >= viewport.device_pixel_ratio;
+				match (is_enough, is_best_enough) {
+					(true, true) => density < best_density,
+					(true, false) => true,
+					(false, true) => false,
+					(false, false) => density > best_density,
+				}
+			}
+		};
+		if is_better {
+			best = Some((density, &candidate.url));
+		}
+	}
+	best.map(|(_, url)| String::from(url))
+}
+
+// the image type is supported
+fn is_supported_type(mime_type: &str) -> bool {
+	let mime_type = mime_type.split(';').next().unwrap_or("").trim();
+	SUPPORTED_TYPES
+		.iter()
+		.any(|supported| supported.eq_ignore_ascii_case(mime_type))
+}
+
+impl<'a> Image<'a> {
+	/// pub fn `best_source`
+	/// the url a browser would load on the viewport, the first `<source>` of the `<picture>` with the
+	/// matched `media` and the supported `type` is used, otherwise the `srcset` and the `src` of the image.
+	/// the candidate is chosen by the `sizes` and the device pixel ratio
+	pub fn best_source(&self, viewport: &Viewport) -> Option<String> {
+		for source in &self.sources {
+			let is_matched = source
+				.media
+				.as_deref()
+				.is_none_or(|media| matches_media(media, viewport))
+				&& source.mime_type.as_deref().is_none_or(is_supported_type);
+			if is_matched && !source.srcset.candidates.is_empty() {
+				return choose_candidate(&source.srcset, source.sizes.as_deref(), viewport);
+			}
+		}
+		// the `src` is a `1x` candidate if the `srcset` has neither `1x` nor width descriptors
+		let mut srcset = self.srcset.clone();
+		if let Some(src) = &self.src {
+			let has_default = srcset.candidates.iter().any(|candidate| {
+				matches!(
+					candidate.descriptor,
+					SrcsetDescriptor::None | SrcsetDescriptor::Width(_)
+				) || candidate.descriptor == SrcsetDescriptor::Density(1.0)
+			});
+			if !has_default {
+				srcset.candidates.push(SrcsetCandidate {
+					url: src.clone(),
+					descriptor: SrcsetDescriptor::None,
+				});
+			}
+		}
+		choose_candidate(&srcset, self.sizes.as_deref(), viewport)
+	}
+}
+
+// get the image descriptor of the `<img>` element
+fn extract_image<'a>(ele: &BoxDynElement<'a>) -> Image<'a> {
+	let base = ele
+		.owner_document()
+		.and_then(|doc| doc.base_url())
+		.unwrap_or_default();
+	let lazy_src = first_attr(ele, &LAZY_SRC_ATTRS);
+	let lazy_srcset = first_attr(ele, &LAZY_SRCSET_ATTRS);
+	let loading = attr_value(ele, "loading").map(|loading| loading.trim().to_ascii_lowercase());
+	let is_lazy = lazy_src.is_some() || lazy_srcset.is_some() || loading.as_deref() == Some("lazy");
+	let (src_attr, src) = match lazy_src.or_else(|| first_attr(ele, &["src"])) {
+		Some((name, value)) => (Some(name), Some(resolve(&base, &value))),
+		None => (None, None),
+	};
+	Image {
+		src,
+		src_attr,
+		srcset: resolved_srcset(ele, &base),
+		sizes: attr_value(ele, "sizes"),
+		alt: ele.get_attribute("alt").map(|alt| alt.to_string()),
+		width: dimension(ele, "width"),
+		height: dimension(ele, "height"),
+		loading,
+		is_lazy,
+		sources: picture_sources(ele, &base),
+		element: ele.cloned(),
+	}
+}
+
+// the `<img>` elements in the set and their descendants, in document order
+pub(crate) fn extract_images<'a>(eles: &Elements<'a>) -> Vec<Image<'a>> {
+	let images = eles.filter("img").add(eles.find("img"));
+	images.get_ref().iter().map(extract_image).collect()
+}
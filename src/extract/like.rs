@@ -0,0 +1,296 @@
+use crate::error::Error as IError;
+use crate::html::spec::is_void_element;
+use crate::interface::{parse_start_tag_attrs, BoxDynElement, Elements, IAttrValue};
+use std::collections::HashMap;
+
+// the tag name matches any element
+const ANY_TAG: &str = "*";
+
+/// a subtree matched the example, with the values captured by the `{name}` markers
+pub struct LikeMatch<'a> {
+	pub element: BoxDynElement<'a>,
+	pub captures: HashMap<String, String>,
+}
+
+// a part of the value pattern
+#[derive(Debug)]
+enum Part {
+	Literal(String),
+	// `*`, any chars
+	Any,
+	// `{name}`, any chars saved by the name
+	Capture(String),
+}
+
+// the pattern of an attribute value or a text
+#[derive(Debug)]
+struct ValuePattern(Vec<Part>);
+
+impl ValuePattern {
+	fn parse(value: &str) -> Self {
+		let mut parts = Vec::new();
+		let mut literal = String::new();
+		let mut rest = value;
+		while let Some(ch) = rest.chars().next() {
+			let marker = match ch {
+				'*' => Some((Part::Any, 1)),
+				'{' => rest.find('}').and_then(|end| {
+					let name = &rest[1..end];
+					let is_name = !name.is_empty()
+						&& name
+							.chars()
+							.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '-');
+					if is_name {
+						Some((Part::Capture(String::from(name)), end + 1))
+					} else {
+						None
+					}
+				}),
+				_ => None,
+			};
+			match marker {
+				Some((part, len)) => {
+					if !literal.is_empty() {
+						parts.push(Part::Literal(std::mem::take(&mut literal)));
+					}
+					parts.push(part);
+					rest = &rest[len..];
+				}
+				None => {
+					literal.push(ch);
+					rest = &rest[ch.len_utf8()..];
+				}
+			}
+		}
+		if !literal.is_empty() {
+			parts.push(Part::Literal(literal));
+		}
+		ValuePattern(parts)
+	}
+	fn matches(&self, value: &str, captures: &mut HashMap<String, String>) -> bool {
+		match_parts(&self.0, value, captures)
+	}
+}
+
+// match the parts from the start of the value, the markers take the shortest chars
+fn match_parts(parts: &[Part], value: &str, captures: &mut HashMap<String, String>) -> bool {
+	let (part, rest) = match parts.split_first() {
+		Some(first) => first,
+		None => return value.is_empty(),
+	};
+	match part {
+		Part::Literal(literal) => {
+			value.starts_with(literal.as_str()) && match_parts(rest, &value[literal.len()..], captures)
+		}
+		Part::Any | Part::Capture(_) => {
+			let ends = value
+				.char_indices()
+				.map(|(index, _)| index)
+				.chain(std::iter::once(value.len()));
+			for end in ends {
+				if match_parts(rest, &value[end..], captures) {
+					if let Part::Capture(name) = part {
+						captures.insert(name.clone(), String::from(&value[..end]));
+					}
+					return true;
+				}
+			}
+			false
+		}
+	}
+}
+
+// collapse the whitespaces into one space and trim the edges
+fn normalize_whitespace(content: &str) -> String {
+	content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// the pattern of an element
+#[derive(Debug, Default)]
+struct ElementPattern {
+	// the lowercase tag name, `*` matches any element
+	tag: String,
+	attrs: Vec<(String, ValuePattern)>,
+	children: Vec<ElementPattern>,
+	// the text of the leaf element
+	text: String,
+}
+
+impl ElementPattern {
+	fn new(tag: String, attrs: Vec<(String, IAttrValue)>) -> Self {
+		let attrs = attrs
+			.into_iter()
+			.map(|(name, value)| {
+				let value = match value {
+					IAttrValue::Value(value, _) => ValuePattern::parse(&value),
+					IAttrValue::True => ValuePattern(vec![Part::Any]),
+				};
+				(name.to_ascii_lowercase(), value)
+			})
+			.collect();
+		ElementPattern {
+			tag,
+			attrs,
+			..Default::default()
+		}
+	}
+	fn matches(&self, ele: &BoxDynElement, captures: &mut HashMap<String, String>) -> bool {
+		if self.tag != ANY_TAG && !ele.tag_name().eq_ignore_ascii_case(&self.tag) {
+			return false;
+		}
+		for (name, pattern) in &self.attrs {
+			let value = match ele.get_attribute(name) {
+				Some(value) => String::from(value.as_str()),
+				None => return false,
+			};
+			let is_matched = match pattern.0.as_slice() {
+				// the classes in the example are contained in the element's classes
+				[Part::Literal(classes)] if name == "class" => {
+					let own: Vec<&str> = value.split_ascii_whitespace().collect();
+					classes
+						.split_ascii_whitespace()
+						.all(|class_name| own.contains(&class_name))
+				}
+				_ => pattern.matches(&value, captures),
+			};
+			if !is_matched {
+				return false;
+			}
+		}
+		if self.children.is_empty() {
+			let text = normalize_whitespace(&self.text);
+			return text.is_empty()
+				|| ValuePattern::parse(&text).matches(&normalize_whitespace(ele.text()), captures);
+		}
+		// the children of the example are matched in order, the other children are skipped
+		let children = ele.children();
+		let children = children.get_ref();
+		let mut start = 0;
+		for pattern in &self.children {
+			let mut is_found = false;
+			while start < children.len() {
+				let mut child_captures = captures.clone();
+				start += 1;
+				if pattern.matches(&children[start - 1], &mut child_captures) {
+					*captures = child_captures;
+					is_found = true;
+					break;
+				}
+			}
+			if !is_found {
+				return false;
+			}
+		}
+		true
+	}
+}
+
+// the tag name at the start of the tag
+fn tag_name(tag: &str) -> String {
+	tag
+		.trim_start_matches(['<', '/'])
+		.chars()
+		.take_while(|ch| !(ch.is_ascii_whitespace() || *ch == '/' || *ch == '>'))
+		.collect::<String>()
+		.to_ascii_lowercase()
+}
+
+// the byte index after the end of the tag starts the html, the quoted `>` is skipped
+fn tag_end(html: &str) -> usize {
+	let mut quote: Option<char> = None;
+	for (index, ch) in html.char_indices() {
+		match quote {
+			Some(cur) if cur == ch => quote = None,
+			Some(_) => {}
+			None if ch == '"' || ch == '\'' => quote = Some(ch),
+			None if ch == '>' => return index + 1,
+			None => {}
+		}
+	}
+	html.len()
+}
+
+// close the element at the top of the stack
+fn close_top(stack: &mut Vec<ElementPattern>) {
+	if stack.len() > 1 {
+		let pattern = stack.pop().expect("The stack must have the element");
+		stack
+			.last_mut()
+			.expect("The stack must have the parent")
+			.children
+			.push(pattern);
+	}
+}
+
+// parse the example into the pattern, the example must have one root element
+fn parse_example(example: &str) -> Result<ElementPattern, IError> {
+	let mut stack = vec![ElementPattern::default()];
+	let mut rest = example;
+	while !rest.is_empty() {
+		if let Some(comment) = rest.strip_prefix("<!--") {
+			rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+		} else if rest.starts_with("</") {
+			let end = tag_end(rest);
+			let name = tag_name(&rest[..end]);
+			if let Some(index) = stack.iter().rposition(|pattern| pattern.tag == name) {
+				while stack.len() > index {
+					close_top(&mut stack);
+				}
+			}
+			rest = &rest[end..];
+		} else if rest.starts_with('<')
+			&& rest[1..].starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '*')
+		{
+			let end = tag_end(rest);
+			let tag = &rest[..end];
+			let name = tag_name(tag);
+			let pattern = ElementPattern::new(name.clone(), parse_start_tag_attrs(tag));
+			stack.push(pattern);
+			if tag.ends_with("/>") || is_void_element(&name) {
+				close_top(&mut stack);
+			}
+			rest = &rest[end..];
+		} else {
+			// the first char is kept in the text even if it's a `<`
+			let first_len = rest.chars().next().map_or(1, |ch| ch.len_utf8());
+			let end = rest[first_len..]
+				.find('<')
+				.map_or(rest.len(), |index| index + first_len);
+			if let Some(top) = stack.last_mut() {
+				top.text.push_str(&rest[..end]);
+			}
+			rest = &rest[end..];
+		}
+	}
+	while stack.len() > 1 {
+		close_top(&mut stack);
+	}
+	let mut root = stack.pop().expect("The stack must have the root");
+	if root.children.len() != 1 || !normalize_whitespace(&root.text).is_empty() {
+		return Err(IError::InvalidPattern {
+			pattern: String::from(example),
+			reason: String::from("The example must have exactly one root element"),
+		});
+	}
+	Ok(root.children.remove(0))
+}
+
+/// pub fn `find_like`
+/// find the elements shaped like the example html in document order. in the attribute values and
+/// the texts, `*` matches any chars and `{name}` captures the chars. the classes of the example are
+/// contained in the element's classes, the children of the example are matched in order and the other
+/// children are skipped, the text of the example is only checked on the elements without children
+pub fn find_like<'a>(root: &Elements<'a>, example: &str) -> Result<Vec<LikeMatch<'a>>, IError> {
+	let pattern = parse_example(example)?;
+	let mut result = Vec::new();
+	for ele in root.find(&pattern.tag).get_ref() {
+		let mut captures = HashMap::new();
+		if pattern.matches(ele, &mut captures) {
+			result.push(LikeMatch {
+				element: ele.cloned(),
+				captures,
+			});
+		}
+	}
+	Ok(result)
+}
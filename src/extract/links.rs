@@ -0,0 +1,194 @@
+use crate::interface::{BoxDynElement, Elements, IAttrValue, IDocumentTrait};
+use url::{ParseError, Url};
+
+// the elements and attributes that reference to other resources
+const LINK_ATTRS: [(&str, &str); 9] = [
+	("a", "href"),
+	("area", "href"),
+	("link", "href"),
+	("img", "src"),
+	("img", "srcset"),
+	("source", "src"),
+	("source", "srcset"),
+	("script", "src"),
+	("iframe", "src"),
+];
+
+pub struct Link<'a> {
+	// the element has the reference
+	pub element: BoxDynElement<'a>,
+	// the attribute name, 'href', 'src' or 'srcset'
+	pub attr_name: &'static str,
+	// the raw url, for 'srcset' it's one of the candidates
+	pub raw: String,
+	// the resolved url, none if the raw url can't be resolved
+	pub url: Option<Url>,
+}
+
+// split the srcset into (url, descriptor) candidates
+// https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute
+pub(crate) fn split_srcset(srcset: &str) -> Vec<(&str, &str)> {
+	let mut result = Vec::with_capacity(3);
+	let mut rest = srcset;
+	loop {
+		rest = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace() || ch == ',');
+		if rest.is_empty() {
+			break;
+		}
+		let url_end = rest
+			.find(|ch: char| ch.is_ascii_whitespace())
+			.unwrap_or(rest.len());
+		let url = &rest[..url_end];
+		rest = &rest[url_end..];
+		if url.ends_with(',') {
+			// no descriptors
+			result.push((url.trim_end_matches(','), ""));
+			continue;
+		}
+		let descriptor_end = rest.find(',').unwrap_or(rest.len());
+		result.push((url, rest[..descriptor_end].trim()));
+		rest = &rest[descriptor_end..];
+	}
+	result
+}
+
+/// pub fn `base_url`
+/// get the base url of the document, the `<base href>` is resolved against the `base_url`
+pub fn base_url(doc: &dyn IDocumentTrait, base_url: &str) -> Result<Url, ParseError> {
+	let base = Url::parse(base_url)?;
+	if let Some(head) = &doc.head() {
+		let base_ele = Elements::with_node(head).find("base[href]");
+		if let Some(IAttrValue::Value(href, _)) = base_ele.attr("href") {
+			if let Ok(url) = base.join(href.trim()) {
+				return Ok(url);
+			}
+		}
+	}
+	Ok(base)
+}
+
+/// pub fn `links`
+/// collect all the references of links, images, stylesheets and scripts,
+/// resolve them against the base url of the document
+pub fn links<'a>(doc: &dyn IDocumentTrait, base: &str) -> Result<Vec<Link<'a>>, ParseError> {
+	let base = base_url(doc, base)?;
+	Ok(collect_links(doc, &base))
+}
+
+// collect the links resolved against the base url
+fn collect_links<'a>(doc: &dyn IDocumentTrait, base: &Url) -> Vec<Link<'a>> {
+	let mut result = Vec::with_capacity(20);
+	let root = match doc.get_root_node().root_element() {
+		Some(root) => Elements::with_node(&root),
+		None => return result,
+	};
+	let selector = LINK_ATTRS
+		.iter()
+		.map(|(tag_name, attr_name)| format!("{}[{}]", tag_name, attr_name))
+		.collect::<Vec<String>>()
+		.join(",");
+	for ele in root.find(&selector).get_ref() {
+		let tag_name = ele.tag_name().to_ascii_lowercase();
+		for &(_, attr_name) in LINK_ATTRS
+			.iter()
+			.filter(|(cur_tag_name, _)| *cur_tag_name == tag_name)
+		{
+			if let Some(IAttrValue::Value(value, _)) = ele.get_attribute(attr_name) {
+				let raws = if attr_name == "srcset" {
+					split_srcset(&value)
+						.into_iter()
+						.map(|(url, _)| String::from(url))
+						.collect()
+				} else {
+					vec![String::from(value.trim())]
+				};
+				for raw in raws {
+					if raw.is_empty() {
+						continue;
+					}
+					result.push(Link {
+						element: ele.cloned(),
+						attr_name,
+						url: base.join(&raw).ok(),
+						raw,
+					});
+				}
+			}
+		}
+	}
+	result
+}
+
+/// pub fn `absolutize_links`
+/// rewrite all the references to absolute urls in place, return the count of rewrited attributes
+pub fn absolutize_links(doc: &dyn IDocumentTrait, base: &str) -> Result<usize, ParseError> {
+	let base = base_url(doc, base)?;
+	let mut count = 0;
+	let mut links = collect_links(doc, &base);
+	for Link {
+		element,
+		attr_name,
+		url,
+		raw,
+	} in links.iter_mut()
+	{
+		if *attr_name == "srcset" {
+			// rewrite the whole srcset once, the candidates share one attribute
+			if let Some(IAttrValue::Value(value, _)) = element.get_attribute(attr_name) {
+				let srcset = split_srcset(&value)
+					.into_iter()
+					.map(|(raw, descriptor)| {
+						let url = base
+							.join(raw)
+							.map(String::from)
+							.unwrap_or_else(|_| String::from(raw));
+						if descriptor.is_empty() {
+							url
+						} else {
+							format!("{} {}", url, descriptor)
+						}
+					})
+					.collect::<Vec<String>>()
+					.join(", ");
+				if srcset != value {
+					element.set_attribute(attr_name, Some(&srcset));
+					count += 1;
+				}
+			}
+		} else if let Some(url) = url {
+			// the absolute urls are kept
+			if url.as_str() != raw {
+				element.set_attribute(attr_name, Some(url.as_str()));
+				count += 1;
+			}
+		}
+	}
+	Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+	use super::split_srcset;
+	#[test]
+	fn test_split_srcset() {
+		assert_eq!(
+			split_srcset("a.png 1x, b.png 2x"),
+			vec![("a.png", "1x"), ("b.png", "2x")]
+		);
+		assert_eq!(
+			split_srcset("a.png, b.png 100w"),
+			vec![("a.png", ""), ("b.png", "100w")]
+		);
+		// the commas in the url are kept, only the trailing commas are removed
+		assert_eq!(
+			split_srcset("a.png,b.png 100w"),
+			vec![("a.png,b.png", "100w")]
+		);
+		assert_eq!(
+			split_srcset("a.png,, b.png"),
+			vec![("a.png", ""), ("b.png", "")]
+		);
+		assert_eq!(split_srcset(" , a.png "), vec![("a.png", "")]);
+		assert!(split_srcset("").is_empty());
+	}
+}
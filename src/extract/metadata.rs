@@ -0,0 +1,114 @@
+use crate::interface::{BoxDynElement, Elements, IAttrValue};
+
+#[derive(Debug, Default, Clone)]
+pub struct Metadata {
+	// the text of `<title>`
+	pub title: Option<String>,
+	// `<meta name="description">`
+	pub description: Option<String>,
+	// `<link rel="canonical">`
+	pub canonical: Option<String>,
+	// the `og:*` properties in source order, keys keep the `og:` prefix
+	pub open_graph: Vec<(String, String)>,
+	// the `twitter:*` properties in source order, keys keep the `twitter:` prefix
+	pub twitter: Vec<(String, String)>,
+	// other named `<meta>`s, such as 'keywords', 'author', 'robots'
+	pub metas: Vec<(String, String)>,
+	// the raw content of `<script type="application/ld+json">` blocks
+	pub json_ld: Vec<String>,
+}
+
+// get a trimed string attribute
+fn get_attr_value(ele: &BoxDynElement, name: &str) -> Option<String> {
+	if let Some(IAttrValue::Value(v, _)) = ele.get_attribute(name) {
+		let v = v.trim();
+		if !v.is_empty() {
+			return Some(String::from(v));
+		}
+	}
+	None
+}
+
+impl Metadata {
+	/// pub fn `from_root`
+	/// collect the metadata from the `<head>` and the json-ld blocks in the whole document
+	pub fn from_root(root: &BoxDynElement, head: Option<&BoxDynElement>) -> Self {
+		let mut meta = Metadata::default();
+		if let Some(head) = head {
+			let head = Elements::with_node(head);
+			let title = head.find("title");
+			if !title.is_empty() {
+				meta.title = Some(String::from(title.text().trim()));
+			}
+			let canonical = head.find("link[rel~='canonical'][href]");
+			if let Some(ele) = canonical.get(0) {
+				meta.canonical = get_attr_value(ele, "href");
+			}
+			for ele in head.find("meta[content]").get_ref() {
+				let content = get_attr_value(ele, "content").unwrap_or_default();
+				// open graph use the `property`, twitter use the `name`, but they're often mixed
+				let key = get_attr_value(ele, "property").or_else(|| get_attr_value(ele, "name"));
+				if let Some(key) = key {
+					let lower_key = key.to_ascii_lowercase();
+					if lower_key.starts_with("og:") {
+						meta.open_graph.push((key, content));
+					} else if lower_key.starts_with("twitter:") {
+						meta.twitter.push((key, content));
+					} else if lower_key == "description" {
+						meta.description = Some(content);
+					} else {
+						meta.metas.push((key, content));
+					}
+				}
+			}
+		}
+		let scripts = Elements::with_node(root).find("script[type='application/ld+json']");
+		for ele in scripts.get_ref() {
+			let content = ele.text().trim();
+			if !content.is_empty() {
+				meta.json_ld.push(String::from(content));
+			}
+		}
+		meta
+	}
+	/// pub fn `og`
+	/// get the first open graph property, `og("title")` for `og:title`
+	pub fn og(&self, name: &str) -> Option<&str> {
+		Metadata::get_prefixed(&self.open_graph, "og:", name)
+	}
+	/// pub fn `twitter_card`
+	/// get the first twitter card property, `twitter_card("card")` for `twitter:card`
+	pub fn twitter_card(&self, name: &str) -> Option<&str> {
+		Metadata::get_prefixed(&self.twitter, "twitter:", name)
+	}
+	/// pub fn `meta`
+	/// get the first named meta's content
+	pub fn meta(&self, name: &str) -> Option<&str> {
+		self
+			.metas
+			.iter()
+			.find(|(key, _)| key.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.as_str())
+	}
+	/// pub fn `json_ld_values`
+	/// parse the json-ld blocks, the invalid blocks are ignored
+	#[cfg(feature = "serde")]
+	pub fn json_ld_values(&self) -> Vec<serde_json::Value> {
+		self
+			.json_ld
+			.iter()
+			.filter_map(|block| serde_json::from_str(block).ok())
+			.collect()
+	}
+	// get the property's value by prefix and name
+	fn get_prefixed<'b>(props: &'b [(String, String)], prefix: &str, name: &str) -> Option<&'b str> {
+		props
+			.iter()
+			.find(|(key, _)| {
+				let (key_prefix, key_name) = (key.get(..prefix.len()), key.get(prefix.len()..));
+				matches!(key_prefix, Some(key_prefix) if key_prefix.eq_ignore_ascii_case(prefix))
+					&& matches!(key_name, Some(key_name) if key_name.eq_ignore_ascii_case(name))
+			})
+			.map(|(_, value)| value.as_str())
+	}
+}
@@ -0,0 +1,288 @@
+use crate::interface::{BoxDynElement, Elements, IAttrValue, IDocumentTrait};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Item {
+	// the `itemtype` or `typeof` types, rdfa terms are expanded with the `vocab`
+	pub types: Vec<String>,
+	// the `itemid`, or the `resource`/`about` of rdfa
+	pub id: Option<String>,
+	// the properties in source order, a property with several names is repeated
+	pub properties: Vec<(String, ItemValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemValue {
+	// the text, url or machine readable value
+	Text(String),
+	// a nested item
+	Item(Item),
+}
+
+impl ItemValue {
+	/// pub fn `as_text`
+	/// get the value if it's not a nested item
+	pub fn as_text(&self) -> Option<&str> {
+		match self {
+			ItemValue::Text(value) => Some(value),
+			ItemValue::Item(_) => None,
+		}
+	}
+	/// pub fn `as_item`
+	/// get the nested item
+	pub fn as_item(&self) -> Option<&Item> {
+		match self {
+			ItemValue::Item(item) => Some(item),
+			ItemValue::Text(_) => None,
+		}
+	}
+}
+
+impl Item {
+	/// pub fn `get`
+	/// get the first value of the property
+	pub fn get(&self, name: &str) -> Option<&ItemValue> {
+		self
+			.properties
+			.iter()
+			.find(|(key, _)| key == name)
+			.map(|(_, value)| value)
+	}
+	/// pub fn `get_all`
+	/// get all the values of the property
+	pub fn get_all(&self, name: &str) -> Vec<&ItemValue> {
+		self
+			.properties
+			.iter()
+			.filter(|(key, _)| key == name)
+			.map(|(_, value)| value)
+			.collect()
+	}
+	/// pub fn `is_type`
+	/// check if the item has the type
+	pub fn is_type(&self, item_type: &str) -> bool {
+		self.types.iter().any(|cur_type| cur_type == item_type)
+	}
+}
+
+// get the string attribute, `IAttrValue::True` is none
+fn get_attr_value(ele: &BoxDynElement, name: &str) -> Option<String> {
+	match ele.get_attribute(name) {
+		Some(IAttrValue::Value(value, _)) => Some(value),
+		_ => None,
+	}
+}
+
+// split the space separated tokens of an attribute
+fn get_attr_tokens(ele: &BoxDynElement, name: &str) -> Vec<String> {
+	get_attr_value(ele, name)
+		.map(|value| value.split_whitespace().map(String::from).collect())
+		.unwrap_or_default()
+}
+
+// the text with collapsed whitespaces
+fn get_text(ele: &BoxDynElement) -> String {
+	ele
+		.text()
+		.split_whitespace()
+		.collect::<Vec<&str>>()
+		.join(" ")
+}
+
+// get the root element of the document
+fn get_root<'a>(doc: &dyn IDocumentTrait) -> Option<Elements<'a>> {
+	doc
+		.get_root_node()
+		.root_element()
+		.map(|root| Elements::with_node(&root))
+}
+
+/* ------------------------------- microdata ------------------------------- */
+
+// https://html.spec.whatwg.org/multipage/microdata.html#values
+fn get_microdata_value(ele: &BoxDynElement) -> String {
+	let attr_name = match ele.tag_name().to_ascii_lowercase().as_str() {
+		"meta" => "content",
+		"audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => "src",
+		"a" | "area" | "link" => "href",
+		"object" => "data",
+		"data" | "meter" => "value",
+		"time" => "datetime",
+		_ => return get_text(ele),
+	};
+	get_attr_value(ele, attr_name)
+		.map(|value| String::from(value.trim()))
+		.unwrap_or_else(|| {
+			if attr_name == "datetime" {
+				get_text(ele)
+			} else {
+				String::new()
+			}
+		})
+}
+
+// build the item of an `itemscope` element, `scopes` are the item elements being built
+fn get_microdata_item<'a>(
+	ele: &BoxDynElement<'a>,
+	root: &Elements,
+	scopes: &mut Vec<BoxDynElement<'a>>,
+) -> Item {
+	let mut item = Item {
+		types: get_attr_tokens(ele, "itemtype"),
+		id: get_attr_value(ele, "itemid").map(|id| String::from(id.trim())),
+		properties: Vec::new(),
+	};
+	scopes.push(ele.cloned());
+	let mut pendings: Vec<BoxDynElement> = ele
+		.children()
+		.get_ref()
+		.iter()
+		.map(|child| child.cloned())
+		.collect();
+	// the elements referenced by `itemref` are crawled as if they are children
+	for id in get_attr_tokens(ele, "itemref") {
+		let refs = root.find("[id]").filter_by(
+			|_, cur| matches!(cur.get_attribute("id"), Some(IAttrValue::Value(cur_id, _)) if cur_id == id),
+		);
+		if let Some(ref_ele) = refs.get(0) {
+			pendings.push(ref_ele.cloned());
+		}
+	}
+	pendings.reverse();
+	while let Some(cur) = pendings.pop() {
+		let is_scope = cur.has_attribute("itemscope");
+		let names = get_attr_tokens(&cur, "itemprop");
+		if !names.is_empty() {
+			let value = if is_scope {
+				// ignore the cyclic references
+				if scopes.iter().any(|scope| scope.is(&cur)) {
+					continue;
+				}
+				ItemValue::Item(get_microdata_item(&cur, root, scopes))
+			} else {
+				ItemValue::Text(get_microdata_value(&cur))
+			};
+			for name in names {
+				item.properties.push((name, value.clone()));
+			}
+		}
+		// the properties of the nested items are not the item's
+		if !is_scope {
+			let children = cur.children();
+			for child in children.get_ref().iter().rev() {
+				pendings.push(child.cloned());
+			}
+		}
+	}
+	scopes.pop();
+	item
+}
+
+/// pub fn `microdata`
+/// extract the top level microdata items, the items with `itemprop` are nested in the properties
+pub fn microdata(doc: &dyn IDocumentTrait) -> Vec<Item> {
+	let root = match get_root(doc) {
+		Some(root) => root,
+		None => return Vec::new(),
+	};
+	let mut scopes = Vec::new();
+	root
+		.find("[itemscope]")
+		.add(root.filter("[itemscope]"))
+		.get_ref()
+		.iter()
+		.filter(|ele| !ele.has_attribute("itemprop"))
+		.map(|ele| get_microdata_item(ele, &root, &mut scopes))
+		.collect()
+}
+
+/* --------------------------------- rdfa ---------------------------------- */
+
+// expand the term with the vocabulary, the prefixed names and urls are kept
+fn expand_term(term: &str, vocab: Option<&str>) -> String {
+	match vocab {
+		Some(vocab) if !term.contains(':') => format!("{}{}", vocab, term),
+		_ => String::from(term),
+	}
+}
+
+// the value of a rdfa property without `typeof`
+fn get_rdfa_value(ele: &BoxDynElement) -> String {
+	for attr_name in &["content", "resource", "href", "src"] {
+		if let Some(value) = get_attr_value(ele, attr_name) {
+			return String::from(value.trim());
+		}
+	}
+	if ele.tag_name().eq_ignore_ascii_case("time") {
+		if let Some(value) = get_attr_value(ele, "datetime") {
+			return String::from(value.trim());
+		}
+	}
+	get_text(ele)
+}
+
+// walk the tree, add the properties to the current item or the top level items
+fn walk_rdfa(
+	ele: &BoxDynElement,
+	vocab: Option<&str>,
+	mut item: Option<&mut Item>,
+	tops: &mut Vec<Item>,
+) {
+	let vocab_attr = get_attr_value(ele, "vocab").map(|vocab| String::from(vocab.trim()));
+	let vocab = match &vocab_attr {
+		Some(vocab) if !vocab.is_empty() => Some(vocab.as_str()),
+		Some(_) => None,
+		None => vocab,
+	};
+	let names: Vec<String> = get_attr_tokens(ele, "property")
+		.iter()
+		.map(|name| expand_term(name, vocab))
+		.collect();
+	if ele.has_attribute("typeof") {
+		let mut cur = Item {
+			types: get_attr_tokens(ele, "typeof")
+				.iter()
+				.map(|name| expand_term(name, vocab))
+				.collect(),
+			id: ["resource", "about"]
+				.iter()
+				.find_map(|attr_name| get_attr_value(ele, attr_name))
+				.map(|id| String::from(id.trim())),
+			properties: Vec::new(),
+		};
+		for child in ele.children().get_ref() {
+			walk_rdfa(child, vocab, Some(&mut cur), tops);
+		}
+		match item {
+			Some(item) if !names.is_empty() => {
+				for name in names {
+					item.properties.push((name, ItemValue::Item(cur.clone())));
+				}
+			}
+			_ => tops.push(cur),
+		}
+		return;
+	}
+	if let Some(item) = item.as_deref_mut() {
+		if !names.is_empty() {
+			let value = get_rdfa_value(ele);
+			for name in names {
+				item.properties.push((name, ItemValue::Text(value.clone())));
+			}
+		}
+	}
+	for child in ele.children().get_ref() {
+		walk_rdfa(child, vocab, item.as_deref_mut(), tops);
+	}
+}
+
+/// pub fn `rdfa`
+/// extract the rdfa lite items, the terms are expanded with the `vocab`
+pub fn rdfa(doc: &dyn IDocumentTrait) -> Vec<Item> {
+	let mut tops = Vec::new();
+	if let Some(root) = get_root(doc) {
+		for ele in root.get_ref() {
+			walk_rdfa(ele, None, None, &mut tops);
+		}
+	}
+	tops
+}
@@ -0,0 +1,44 @@
+// article, main content extraction
+mod article;
+pub use article::{article, Article};
+// table, structured rows extraction
+mod table;
+pub(crate) use table::extract_table;
+pub use table::Table;
+// images, the sources, srcset candidates and lazy-load attributes
+mod image;
+pub(crate) use image::extract_images;
+pub use image::{Image, PictureSource};
+// metadata, meta tags and json-ld
+mod metadata;
+pub use metadata::Metadata;
+// form, form data serialization
+mod form;
+pub use form::{FormData, FormValue};
+// links, references extraction and resolution
+#[cfg(feature = "url")]
+mod links;
+#[cfg(feature = "url")]
+pub use links::{absolutize_links, base_url, links, Link};
+// pagination, the next, prev, canonical and numeric page links
+#[cfg(feature = "url")]
+mod pagination;
+#[cfg(feature = "url")]
+pub use pagination::{pagination, Pagination};
+// find the subtrees shaped like an example html
+mod like;
+pub use like::{find_like, LikeMatch};
+// outline, the heading hierarchy for the table of contents
+mod outline;
+pub use outline::{outline, OutlineItem};
+// find the sibling groups with the similar structure, the candidate records of a list
+mod repeating;
+pub use repeating::{repeating_items, RepeatingItems};
+// declarative records extraction by the schema of the fields
+#[cfg(feature = "serde")]
+mod schema;
+#[cfg(feature = "serde")]
+pub use schema::{ExtractSchema, Field, FieldValue, Schema};
+// microdata and rdfa, structured items extraction
+mod microdata;
+pub use microdata::{microdata, rdfa, Item, ItemValue};
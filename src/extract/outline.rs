@@ -0,0 +1,137 @@
+use crate::interface::{BoxDynElement, Elements};
+
+// the sectioning contents, the headings in them are nested under the headings before them
+const SECTIONING_TAGS: [&str; 4] = ["article", "aside", "nav", "section"];
+// the sectioning roots have their own outlines, they are not in the outline of the document
+const SECTIONING_ROOT_TAGS: [&str; 5] = ["blockquote", "details", "dialog", "fieldset", "figure"];
+
+/// a heading of the outline with its sub headings
+pub struct OutlineItem<'a> {
+	// the heading element, the highest ranked heading of a `hgroup`
+	pub element: BoxDynElement<'a>,
+	// the rank of the heading, 1 for `h1`
+	pub level: u8,
+	// the trimmed text of the heading
+	pub text: String,
+	// the nearest sectioning element contains the heading, `None` if the heading is not in any
+	pub section: Option<BoxDynElement<'a>>,
+	pub children: Vec<OutlineItem<'a>>,
+}
+
+// the rank of the heading element, such as 2 for `h2`
+fn heading_level(ele: &BoxDynElement) -> Option<u8> {
+	let tag_name = ele.tag_name().to_ascii_lowercase();
+	match tag_name.as_bytes() {
+		[b'h', level @ b'1'..=b'6'] => Some(level - b'0'),
+		_ => None,
+	}
+}
+
+// the tag name is in the names
+fn is_one_of(ele: &BoxDynElement, names: &[&str]) -> bool {
+	let tag_name = ele.tag_name();
+	names.iter().any(|name| name.eq_ignore_ascii_case(tag_name))
+}
+
+// the items are in the same sectioning element
+fn is_same_section(a: &Option<BoxDynElement>, b: &Option<BoxDynElement>) -> bool {
+	match (a, b) {
+		(Some(a), Some(b)) => a.is(b),
+		(None, None) => true,
+		_ => false,
+	}
+}
+
+// insert the heading by its rank, it's nested under the last heading with a lower rank in the same section
+fn insert<'a>(items: &mut Vec<OutlineItem<'a>>, item: OutlineItem<'a>) {
+	match items.last_mut() {
+		Some(last) if last.level < item.level && is_same_section(&last.section, &item.section) => {
+			insert(&mut last.children, item)
+		}
+		_ => items.push(item),
+	}
+}
+
+// nest the headings of a sectioning element under the last heading before it, as deep as the ranks allow
+fn attach<'a>(
+	items: &mut Vec<OutlineItem<'a>>,
+	section_items: Vec<OutlineItem<'a>>,
+	min_level: u8,
+) {
+	match items.last_mut() {
+		Some(last) => {
+			let is_deeper = last.children.last().is_some_and(|child| {
+				child.level < min_level && is_same_section(&child.section, &last.section)
+			});
+			if is_deeper {
+				attach(&mut last.children, section_items, min_level);
+			} else {
+				last.children.extend(section_items);
+			}
+		}
+		None => items.extend(section_items),
+	}
+}
+
+// collect the headings of the element's descendants into the items of the section
+fn collect<'a>(
+	ele: &BoxDynElement<'a>,
+	section: &Option<BoxDynElement<'a>>,
+	items: &mut Vec<OutlineItem<'a>>,
+) {
+	for child in ele.children().get_ref() {
+		if let Some(level) = heading_level(child) {
+			insert(
+				items,
+				OutlineItem {
+					element: child.cloned(),
+					level,
+					text: String::from(child.text().trim()),
+					section: section.as_ref().map(|section| section.cloned()),
+					children: Vec::new(),
+				},
+			);
+		} else if child.tag_name().eq_ignore_ascii_case("hgroup") {
+			// the highest ranked heading stands for the group
+			let heading = child
+				.children()
+				.get_ref()
+				.iter()
+				.filter_map(|heading| heading_level(heading).map(|level| (level, heading.cloned())))
+				.min_by_key(|(level, _)| *level);
+			if let Some((level, heading)) = heading {
+				insert(
+					items,
+					OutlineItem {
+						text: String::from(heading.text().trim()),
+						element: heading,
+						level,
+						section: section.as_ref().map(|section| section.cloned()),
+						children: Vec::new(),
+					},
+				);
+			}
+		} else if is_one_of(child, &SECTIONING_TAGS) {
+			let mut section_items = Vec::new();
+			collect(child, &Some(child.cloned()), &mut section_items);
+			if let Some(min_level) = section_items.iter().map(|item| item.level).min() {
+				attach(items, section_items, min_level);
+			}
+		} else if !is_one_of(child, &SECTIONING_ROOT_TAGS) {
+			collect(child, section, items);
+		}
+	}
+}
+
+/// pub fn `outline`
+/// build the heading hierarchy of the elements' descendants in document order. the headings are nested
+/// by their ranks, the headings in a sectioning element such as `article` are nested under the last heading
+/// before it, so each `article` can start with a `h1`. the headings in the sectioning roots such as
+/// `blockquote` are skipped
+pub fn outline<'a>(root: &Elements<'a>) -> Vec<OutlineItem<'a>> {
+	let mut items = Vec::new();
+	for ele in root.get_ref() {
+		collect(ele, &None, &mut items);
+	}
+	items
+}
@@ -0,0 +1,152 @@
+use super::links::base_url;
+use crate::interface::{BoxDynElement, Elements, IAttrValue, IDocumentTrait};
+use url::{ParseError, Url};
+
+// the texts of the next and the prev links, compared in lower case
+const NEXT_TEXTS: [&str; 8] = ["next", "next page", "next »", "»", "›", ">", ">>", "下一页"];
+const PREV_TEXTS: [&str; 8] = [
+	"prev",
+	"previous",
+	"previous page",
+	"« prev",
+	"«",
+	"‹",
+	"<",
+	"上一页",
+];
+// the tags between the numeric page link and the page list, such as `<li><a>2</a></li>`
+const PAGE_ITEM_TAGS: [&str; 2] = ["li", "span"];
+
+/// the pagination of the document, the urls are resolved against the base url
+#[derive(Debug, Clone, Default)]
+pub struct Pagination {
+	// `rel="next"`, or the link looks like the next page link
+	pub next: Option<Url>,
+	// `rel="prev"`, or the link looks like the prev page link
+	pub prev: Option<Url>,
+	// `<link rel="canonical">`
+	pub canonical: Option<Url>,
+	// the numeric page links of the largest page list, sorted by the page number
+	pub pages: Vec<(usize, Url)>,
+}
+
+// the `href` attribute resolved against the base url
+fn resolve_href(ele: &BoxDynElement, base: &Url) -> Option<Url> {
+	match ele.get_attribute("href")? {
+		IAttrValue::Value(href, _) if !href.trim().is_empty() => base.join(href.trim()).ok(),
+		_ => None,
+	}
+}
+
+// the attribute value in lower case
+fn lower_attr(ele: &BoxDynElement, name: &str) -> String {
+	match ele.get_attribute(name) {
+		Some(IAttrValue::Value(value, _)) => value.to_ascii_lowercase(),
+		_ => String::new(),
+	}
+}
+
+// the first link has the rel, then the first link looks like the direction by the aria label,
+// the class names or the text
+fn find_direction(root: &Elements, rel: &str, texts: &[&str], base: &Url) -> Option<Url> {
+	let rel_links = root.find(&format!("link[rel~='{0}'][href], a[rel~='{0}'][href]", rel));
+	if let Some(url) = rel_links
+		.get_ref()
+		.iter()
+		.find_map(|ele| resolve_href(ele, base))
+	{
+		return Some(url);
+	}
+	let links = root.find("a[href]");
+	let by_label = links.get_ref().iter().find(|ele| {
+		// the `previous` labels contain `prev`
+		lower_attr(ele, "aria-label").contains(rel)
+	});
+	let by_class = || {
+		links.get_ref().iter().find(|ele| {
+			lower_attr(ele, "class")
+				.split_ascii_whitespace()
+				.any(|name| name == rel || name.ends_with(&format!("-{}", rel)))
+		})
+	};
+	let by_text = || {
+		links.get_ref().iter().find(|ele| {
+			let text = ele.text().trim().to_lowercase();
+			texts.contains(&text.as_str())
+		})
+	};
+	by_label
+		.or_else(by_class)
+		.or_else(by_text)
+		.and_then(|ele| resolve_href(ele, base))
+}
+
+// the numeric page links of the largest list, the list is the parent of the links or of their items
+fn find_pages(root: &Elements, base: &Url) -> Vec<(usize, Url)> {
+	let mut lists: Vec<(BoxDynElement, Vec<(usize, Url)>)> = Vec::new();
+	for ele in root.find("a[href]").get_ref() {
+		let number = match ele.text().trim().parse::<usize>() {
+			Ok(number) if number > 0 => number,
+			_ => continue,
+		};
+		let url = match resolve_href(ele, base) {
+			Some(url) => url,
+			None => continue,
+		};
+		let mut list = match ele.parent() {
+			Some(parent) => parent,
+			None => continue,
+		};
+		if PAGE_ITEM_TAGS
+			.iter()
+			.any(|tag_name| list.tag_name().eq_ignore_ascii_case(tag_name))
+		{
+			if let Some(grand_parent) = list.parent() {
+				list = grand_parent;
+			}
+		}
+		match lists.iter_mut().find(|(cur, _)| cur.is(&list)) {
+			Some((_, pages)) => pages.push((number, url)),
+			None => lists.push((list, vec![(number, url)])),
+		}
+	}
+	let mut pages = lists
+		.into_iter()
+		.map(|(_, pages)| pages)
+		.fold(Vec::new(), |largest, pages| {
+			if pages.len() > largest.len() {
+				pages
+			} else {
+				largest
+			}
+		});
+	if pages.len() < 2 {
+		return Vec::new();
+	}
+	pages.sort_by_key(|(number, _)| *number);
+	pages.dedup_by_key(|(number, _)| *number);
+	pages
+}
+
+/// pub fn `pagination`
+/// find the next, the prev, the canonical and the numeric page links of the document,
+/// the `rel` links are preferred, then the links look like the pagination by the aria labels,
+/// the class names and the texts
+pub fn pagination(doc: &dyn IDocumentTrait, base: &str) -> Result<Pagination, ParseError> {
+	let base = base_url(doc, base)?;
+	let root = match doc.get_root_node().root_element() {
+		Some(root) => Elements::with_node(&root),
+		None => return Ok(Default::default()),
+	};
+	let canonical = root
+		.find("link[rel~='canonical'][href]")
+		.get_ref()
+		.iter()
+		.find_map(|ele| resolve_href(ele, &base));
+	Ok(Pagination {
+		next: find_direction(&root, "next", &NEXT_TEXTS, &base),
+		prev: find_direction(&root, "prev", &PREV_TEXTS, &base),
+		canonical,
+		pages: find_pages(&root, &base),
+	})
+}
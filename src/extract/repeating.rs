@@ -0,0 +1,86 @@
+use crate::constants::ATTR_CLASS;
+use crate::html::spec::is_void_element;
+use crate::interface::{BoxDynElement, ClassList, Elements, IAttrValue};
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+// the minimum count of the similar siblings to be a candidate
+const MIN_ITEMS: usize = 2;
+// the elements never be the records
+const SKIPPED_TAGS: [&str; 5] = ["script", "style", "template", "noscript", "option"];
+
+/// the sibling elements with the similar structure, the candidate records of a list
+pub struct RepeatingItems<'a> {
+	// the parent element of the items
+	pub container: BoxDynElement<'a>,
+	// the similar items in document order
+	pub items: Elements<'a>,
+	// the count of the elements in the items, the more and the richer items score higher
+	pub score: usize,
+}
+
+// the tag name and the sorted class names, such as `li.active.item`
+fn tag_shape(ele: &BoxDynElement) -> String {
+	let mut shape = ele.tag_name().to_ascii_lowercase();
+	if let Some(IAttrValue::Value(value, _)) = ele.get_attribute(ATTR_CLASS) {
+		let mut names = ClassList::split(&value);
+		names.sort_unstable();
+		names.dedup();
+		for name in names {
+			shape.push('.');
+			shape.push_str(name);
+		}
+	}
+	shape
+}
+
+// the structural hash of the element, by its tag shape and the distinct tag shapes of its children,
+// so the items with the same children in different counts such as the lists of tags are similar
+fn structural_hash(ele: &BoxDynElement) -> u64 {
+	let mut child_shapes: Vec<String> = ele.children().get_ref().iter().map(tag_shape).collect();
+	child_shapes.sort_unstable();
+	child_shapes.dedup();
+	let mut hasher = DefaultHasher::new();
+	tag_shape(ele).hash(&mut hasher);
+	child_shapes.hash(&mut hasher);
+	hasher.finish()
+}
+
+// the item may be a record
+fn is_record_like(ele: &BoxDynElement) -> bool {
+	let tag_name = ele.tag_name();
+	!is_void_element(tag_name)
+		&& !SKIPPED_TAGS
+			.iter()
+			.any(|skipped| skipped.eq_ignore_ascii_case(tag_name))
+}
+
+/// pub fn `repeating_items`
+/// find the sibling groups with the similar structure in the scope and their descendants, such as the
+/// cards of a list, the groups are sorted by the score, the higher is more likely the records
+pub fn repeating_items<'a>(scope: &Elements<'a>) -> Vec<RepeatingItems<'a>> {
+	let mut result = Vec::new();
+	let containers = scope.add(scope.find("*"));
+	for container in containers.get_ref() {
+		let children = container.children();
+		if children.length() < MIN_ITEMS {
+			continue;
+		}
+		let records = children.filter_by(|_, ele| is_record_like(ele));
+		for (_, items) in records.group_by(structural_hash) {
+			let count = items.length();
+			if count < MIN_ITEMS {
+				continue;
+			}
+			result.push(RepeatingItems {
+				container: container.cloned(),
+				score: count + items.find("*").length(),
+				items,
+			});
+		}
+	}
+	// the stable sort keeps the document order of the groups with the same score
+	result.sort_by_key(|group| Reverse(group.score));
+	result
+}
@@ -0,0 +1,134 @@
+use crate::interface::{BoxDynElement, Elements, IAttrValue};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// the value taken from the matched element of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+	// the trimmed text
+	Text,
+	// the inner html
+	Html,
+	// the outer html
+	OuterHtml,
+	// the attribute value, the elements without the attribute are skipped
+	Attr(String),
+}
+
+/// a field of the records
+#[derive(Debug, Clone)]
+pub struct Field {
+	// the key of the field in the record
+	pub name: String,
+	// the selector in the record element, the empty selector is the record element itself
+	pub selector: String,
+	pub value: FieldValue,
+	// take the values of all the matched elements as an array, otherwise the first value or `null`
+	pub is_list: bool,
+}
+
+/// the schema of the records, each element matched the record selector is a record,
+/// the record is a json object with the values of the fields
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+	// the selector of the record elements, the empty selector takes the scope elements as the records
+	pub record: String,
+	pub fields: Vec<Field>,
+}
+
+/// the types extracted by the schema, such as `doc.extract::<Product>()`
+pub trait ExtractSchema: DeserializeOwned {
+	fn schema() -> Schema;
+}
+
+// the value of the element, `None` if the attribute is missing
+fn element_value(ele: &BoxDynElement, value: &FieldValue) -> Option<String> {
+	match value {
+		FieldValue::Text => Some(String::from(ele.text().trim())),
+		FieldValue::Html => Some(String::from(ele.inner_html())),
+		FieldValue::OuterHtml => Some(String::from(ele.outer_html())),
+		FieldValue::Attr(name) => match ele.get_attribute(name)? {
+			IAttrValue::Value(value, _) => Some(value),
+			IAttrValue::True => Some(String::new()),
+		},
+	}
+}
+
+impl Schema {
+	/// pub fn `new`
+	/// a schema without fields, the elements matched the selector are the records
+	pub fn new(record: &str) -> Self {
+		Schema {
+			record: String::from(record),
+			fields: Vec::new(),
+		}
+	}
+	/// pub fn `field`
+	/// add a field takes the value of the first matched element
+	pub fn field(mut self, name: &str, selector: &str, value: FieldValue) -> Self {
+		self.fields.push(Field {
+			name: String::from(name),
+			selector: String::from(selector),
+			value,
+			is_list: false,
+		});
+		self
+	}
+	/// pub fn `list`
+	/// add a field takes the values of all the matched elements
+	pub fn list(mut self, name: &str, selector: &str, value: FieldValue) -> Self {
+		self.fields.push(Field {
+			name: String::from(name),
+			selector: String::from(selector),
+			value,
+			is_list: true,
+		});
+		self
+	}
+	/// pub fn `extract_values`
+	/// the records in the scope as json objects, in document order
+	pub fn extract_values(&self, scope: &Elements) -> Vec<Value> {
+		let records = if self.record.trim().is_empty() {
+			scope.cloned()
+		} else {
+			scope.find(&self.record)
+		};
+		let mut result = Vec::with_capacity(records.length());
+		for record in records.get_ref() {
+			let record = Elements::with_nodes(vec![record.cloned()]);
+			let mut object = Map::with_capacity(self.fields.len());
+			for field in &self.fields {
+				let matched = if field.selector.trim().is_empty() {
+					record.cloned()
+				} else {
+					record.find(&field.selector)
+				};
+				let mut values = matched
+					.get_ref()
+					.iter()
+					.filter_map(|ele| element_value(ele, &field.value))
+					.map(Value::String);
+				let value = if field.is_list {
+					Value::Array(values.collect())
+				} else {
+					values.next().unwrap_or(Value::Null)
+				};
+				object.insert(field.name.clone(), value);
+			}
+			result.push(Value::Object(object));
+		}
+		result
+	}
+	/// pub fn `extract`
+	/// the records in the scope deserialized as the type
+	pub fn extract<T: DeserializeOwned>(
+		&self,
+		scope: &Elements,
+	) -> Result<Vec<T>, serde_json::Error> {
+		self
+			.extract_values(scope)
+			.into_iter()
+			.map(serde_json::from_value)
+			.collect()
+	}
+}
@@ -0,0 +1,160 @@
+use crate::interface::{BoxDynElement, IAttrValue};
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Table {
+	// the header cells, from the last row of `thead` or a leading row of `th`
+	pub headers: Vec<String>,
+	// the body rows, spanned cells are repeated in every row and column they cover
+	pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+	/// pub fn `records`
+	/// get the rows keyed by the headers, the cells without a header are ignored
+	pub fn records(&self) -> Vec<HashMap<String, String>> {
+		self
+			.rows
+			.iter()
+			.map(|row| {
+				self
+					.headers
+					.iter()
+					.zip(row.iter())
+					.map(|(header, cell)| (header.clone(), cell.clone()))
+					.collect::<HashMap<String, String>>()
+			})
+			.collect()
+	}
+	/// pub fn `write_csv`
+	/// write the headers and rows as csv
+	#[cfg(feature = "csv")]
+	pub fn write_csv<W: std::io::Write>(&self, writer: W) -> Result<(), csv::Error> {
+		let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(writer);
+		if !self.headers.is_empty() {
+			writer.write_record(&self.headers)?;
+		}
+		for row in &self.rows {
+			writer.write_record(row)?;
+		}
+		writer.flush()?;
+		Ok(())
+	}
+}
+
+// get the span attribute, `colspan` or `rowspan`
+fn get_span(ele: &BoxDynElement, name: &str) -> usize {
+	if let Some(IAttrValue::Value(v, _)) = ele.get_attribute(name) {
+		if let Ok(span) = v.trim().parse::<usize>() {
+			// same as the browsers, the span is at least 1 and at most 1000
+			return span.clamp(1, 1000);
+		}
+	}
+	1
+}
+
+// the text of the cell, whitespaces are collapsed
+fn get_cell_text(ele: &BoxDynElement) -> String {
+	ele
+		.text()
+		.split_whitespace()
+		.collect::<Vec<&str>>()
+		.join(" ")
+}
+
+// get the rows of the table in order of `thead`, `tbody`/`tr`, `tfoot`, skip the nested tables
+fn get_rows<'a>(table: &BoxDynElement) -> (Vec<BoxDynElement<'a>>, usize) {
+	let mut heads = Vec::new();
+	let mut bodys = Vec::new();
+	let mut foots = Vec::new();
+	for child in table.children().get_ref() {
+		match child.tag_name().to_ascii_lowercase().as_str() {
+			"thead" => heads.extend(child.children().get_ref().iter().map(|row| row.cloned())),
+			"tbody" => bodys.extend(child.children().get_ref().iter().map(|row| row.cloned())),
+			"tfoot" => foots.extend(child.children().get_ref().iter().map(|row| row.cloned())),
+			"tr" => bodys.push(child.cloned()),
+			_ => {}
+		}
+	}
+	let head_count = heads.len();
+	let mut rows = heads;
+	rows.extend(bodys);
+	rows.extend(foots);
+	rows.retain(|row| row.tag_name().eq_ignore_ascii_case("tr"));
+	(rows, head_count)
+}
+
+type SpannedCell = Option<(String, usize)>;
+
+// fill the cells spanned from the rows above
+fn fill_pendings(cells: &mut Vec<String>, col: &mut usize, pendings: &mut [SpannedCell]) {
+	while let Some(Some((text, left))) = pendings.get_mut(*col) {
+		cells.push(text.clone());
+		*left -= 1;
+		if *left == 0 {
+			pendings[*col] = None;
+		}
+		*col += 1;
+	}
+}
+
+/// build a table from a `table` element
+pub(crate) fn extract_table(table: &BoxDynElement) -> Table {
+	let (rows, mut head_count) = get_rows(table);
+	let mut grid: Vec<Vec<String>> = Vec::with_capacity(rows.len());
+	// the cells spanned from the rows above: (text, rows left)
+	let mut pendings: Vec<SpannedCell> = Vec::new();
+	let mut is_header_row: Vec<bool> = Vec::with_capacity(rows.len());
+	for row in &rows {
+		let mut cells: Vec<String> = Vec::with_capacity(pendings.len());
+		let mut only_th = true;
+		let mut col = 0;
+		for cell in row.children().get_ref() {
+			let tag_name = cell.tag_name().to_ascii_lowercase();
+			if tag_name != "td" && tag_name != "th" {
+				continue;
+			}
+			if tag_name == "td" {
+				only_th = false;
+			}
+			fill_pendings(&mut cells, &mut col, &mut pendings);
+			let text = get_cell_text(cell);
+			let colspan = get_span(cell, "colspan");
+			let rowspan = get_span(cell, "rowspan");
+			for _ in 0..colspan {
+				cells.push(text.clone());
+				if rowspan > 1 {
+					if pendings.len() <= col {
+						pendings.resize(col + 1, None);
+					}
+					pendings[col] = Some((text.clone(), rowspan - 1));
+				}
+				col += 1;
+			}
+		}
+		// the trailing spanned cells, keep the empty holes between them
+		while col < pendings.len() {
+			if pendings[col].is_some() {
+				fill_pendings(&mut cells, &mut col, &mut pendings);
+			} else {
+				col += 1;
+				if pendings[col..].iter().any(|pending| pending.is_some()) {
+					cells.push(String::new());
+				}
+			}
+		}
+		is_header_row.push(only_th && !cells.is_empty());
+		grid.push(cells);
+	}
+	// no `thead`, use the leading rows of `th` as the headers
+	if head_count == 0 {
+		head_count = is_header_row.iter().take_while(|&&is_th| is_th).count();
+	}
+	let mut rows = grid;
+	let body = rows.split_off(head_count.min(rows.len()));
+	let headers = rows.pop().unwrap_or_default();
+	Table {
+		headers,
+		rows: body,
+	}
+}
@@ -0,0 +1,2 @@
+//! the knowledge of the html spec shared by the parsers, the serializer and the selectors
+pub mod spec;
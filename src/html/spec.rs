@@ -0,0 +1,290 @@
+//! the element tables of the html spec, the names are lowercase and the query functions match
+//! them ASCII case-insensitively
+
+/// the void elements, they have no contents and no end tags
+pub const VOID_ELEMENTS: [&str; 14] = [
+	"area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+	"track", "wbr",
+];
+/// the raw text elements, the tags in their contents are not parsed and the texts are not escaped
+pub const RAW_TEXT_ELEMENTS: [&str; 6] =
+	["script", "style", "xmp", "iframe", "noembed", "noframes"];
+/// the escapable raw text elements, the tags in their contents are not parsed but the character
+/// references are decoded
+pub const ESCAPABLE_RAW_TEXT_ELEMENTS: [&str; 2] = ["textarea", "title"];
+/// the elements close an open `p` element, they can't be in a `p`
+pub const P_CLOSING_ELEMENTS: [&str; 32] = [
+	"address",
+	"article",
+	"aside",
+	"blockquote",
+	"details",
+	"dialog",
+	"div",
+	"dl",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"header",
+	"hgroup",
+	"hr",
+	"main",
+	"menu",
+	"nav",
+	"ol",
+	"p",
+	"pre",
+	"section",
+	"summary",
+	"table",
+	"ul",
+];
+/// the elements rendered as blocks and the metadata elements of the head, the whitespaces beside them
+/// are not rendered, the other elements such as `span`, `svg` and the custom elements are inline
+pub const BLOCK_ELEMENTS: [&str; 55] = [
+	"address",
+	"article",
+	"aside",
+	"base",
+	"blockquote",
+	"body",
+	"caption",
+	"col",
+	"colgroup",
+	"dd",
+	"details",
+	"dialog",
+	"div",
+	"dl",
+	"dt",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"head",
+	"header",
+	"hgroup",
+	"hr",
+	"html",
+	"legend",
+	"li",
+	"link",
+	"main",
+	"menu",
+	"meta",
+	"nav",
+	"ol",
+	"optgroup",
+	"option",
+	"p",
+	"pre",
+	"search",
+	"section",
+	"summary",
+	"table",
+	"tbody",
+	"td",
+	"tfoot",
+	"th",
+	"thead",
+	"title",
+	"tr",
+	"ul",
+];
+/// the parser drops the first newline in the elements
+pub const NEWLINE_DROPPED_ELEMENTS: [&str; 3] = ["pre", "textarea", "listing"];
+/// the elements of the html standard, the obsolete elements such as `font` are not in it
+pub const KNOWN_ELEMENTS: [&str; 115] = [
+	"a",
+	"abbr",
+	"address",
+	"area",
+	"article",
+	"aside",
+	"audio",
+	"b",
+	"base",
+	"bdi",
+	"bdo",
+	"blockquote",
+	"body",
+	"br",
+	"button",
+	"canvas",
+	"caption",
+	"cite",
+	"code",
+	"col",
+	"colgroup",
+	"data",
+	"datalist",
+	"dd",
+	"del",
+	"details",
+	"dfn",
+	"dialog",
+	"div",
+	"dl",
+	"dt",
+	"em",
+	"embed",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"head",
+	"header",
+	"hgroup",
+	"hr",
+	"html",
+	"i",
+	"iframe",
+	"img",
+	"input",
+	"ins",
+	"kbd",
+	"label",
+	"legend",
+	"li",
+	"link",
+	"main",
+	"map",
+	"mark",
+	"math",
+	"menu",
+	"meta",
+	"meter",
+	"nav",
+	"noscript",
+	"object",
+	"ol",
+	"optgroup",
+	"option",
+	"output",
+	"p",
+	"param",
+	"picture",
+	"pre",
+	"progress",
+	"q",
+	"rp",
+	"rt",
+	"ruby",
+	"s",
+	"samp",
+	"script",
+	"search",
+	"section",
+	"select",
+	"slot",
+	"small",
+	"source",
+	"span",
+	"strong",
+	"style",
+	"sub",
+	"summary",
+	"sup",
+	"svg",
+	"table",
+	"tbody",
+	"td",
+	"template",
+	"textarea",
+	"tfoot",
+	"th",
+	"thead",
+	"time",
+	"title",
+	"tr",
+	"track",
+	"u",
+	"ul",
+	"var",
+	"video",
+	"wbr",
+];
+
+fn contains(list: &[&str], name: &str) -> bool {
+	list.iter().any(|item| item.eq_ignore_ascii_case(name))
+}
+
+/// pub fn `is_void_element`
+/// the element has no contents and no end tag, such as `br` and `img`
+pub fn is_void_element(name: &str) -> bool {
+	contains(&VOID_ELEMENTS, name)
+}
+
+/// pub fn `is_raw_text_element`
+/// the element's texts are written without escaping, such as `script` and `style`
+pub fn is_raw_text_element(name: &str) -> bool {
+	contains(&RAW_TEXT_ELEMENTS, name)
+}
+
+/// pub fn `is_escapable_raw_text_element`
+/// the element's contents are texts with the character references, `textarea` and `title`
+pub fn is_escapable_raw_text_element(name: &str) -> bool {
+	contains(&ESCAPABLE_RAW_TEXT_ELEMENTS, name)
+}
+
+/// pub fn `is_text_only_element`
+/// the element contains only texts, the tags in its contents are not parsed
+pub fn is_text_only_element(name: &str) -> bool {
+	is_raw_text_element(name) || is_escapable_raw_text_element(name)
+}
+
+/// pub fn `is_block_element`
+/// the element is rendered as a block or in the head, the whitespaces beside it can be removed
+pub fn is_block_element(name: &str) -> bool {
+	contains(&BLOCK_ELEMENTS, name)
+}
+
+/// pub fn `is_newline_dropped_element`
+/// the parser drops the first newline in the element, so the serializer doubles it
+pub fn is_newline_dropped_element(name: &str) -> bool {
+	contains(&NEWLINE_DROPPED_ELEMENTS, name)
+}
+
+/// pub fn `is_known_element`
+/// the element is in the html standard, the custom elements such as `my-widget` are not
+pub fn is_known_element(name: &str) -> bool {
+	contains(&KNOWN_ELEMENTS, name)
+}
+
+/// pub fn `is_implied_end`
+/// the open element is ended by the start tag without an end tag, such as the `li` before another `li`
+pub fn is_implied_end(open: &str, tag: &str) -> bool {
+	let tag = tag.to_ascii_lowercase();
+	let tag = tag.as_str();
+	match open.to_ascii_lowercase().as_str() {
+		"li" => tag == "li",
+		"dt" | "dd" => tag == "dt" || tag == "dd",
+		"option" => tag == "option" || tag == "optgroup",
+		"tr" => tag == "tr",
+		"td" | "th" => tag == "td" || tag == "th" || tag == "tr",
+		"p" => contains(&P_CLOSING_ELEMENTS, tag),
+		_ => false,
+	}
+}
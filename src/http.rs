@@ -0,0 +1,524 @@
+use crate::css::Stylesheet;
+use crate::error::Error as IError;
+#[cfg(feature = "serde")]
+use crate::extract::ExtractSchema;
+use crate::extract::{base_url, LikeMatch, Metadata, OutlineItem};
+use crate::interface::{
+	BoxDynElement, BoxDynNode, Elements, IDocumentTrait, IErrorHandle, MutationCallback, NodePath,
+	NodeVisitor, ScriptSource, TransactionHandle,
+};
+use crate::lint::LintIssue;
+use crate::serialize::{FormatOptions, MinifyOptions};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252, X_USER_DEFINED};
+use std::error::Error;
+use std::rc::Rc;
+use url::Url;
+
+// the bytes to prescan for the `<meta charset>`
+const PRESCAN_LEN: usize = 1024;
+
+/// a document fetched from an url, keeps the final url after redirects
+pub struct Document<D: IDocumentTrait> {
+	doc: D,
+	url: Url,
+	base_url: Url,
+}
+
+// the ascii whitespaces and the `/` between the attributes of the prescan
+fn is_prescan_space(byte: u8) -> bool {
+	matches!(byte, b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+// get an attribute of the tag at the position by the prescan of the html spec,
+// return the lowercase name, the value and the position after it, `None` at the end of the tag
+fn prescan_attribute(bytes: &[u8], mut pos: usize) -> Option<(Vec<u8>, Vec<u8>, usize)> {
+	while pos < bytes.len() && (is_prescan_space(bytes[pos]) || bytes[pos] == b'/') {
+		pos += 1;
+	}
+	if pos >= bytes.len() || bytes[pos] == b'>' {
+		return None;
+	}
+	let mut name = Vec::new();
+	loop {
+		let byte = *bytes.get(pos)?;
+		match byte {
+			b'=' if !name.is_empty() => break,
+			b'/' | b'>' => return Some((name, Vec::new(), pos)),
+			_ if is_prescan_space(byte) => {
+				while pos < bytes.len() && is_prescan_space(bytes[pos]) {
+					pos += 1;
+				}
+				if bytes.get(pos) != Some(&b'=') {
+					return Some((name, Vec::new(), pos));
+				}
+				break;
+			}
+			_ => name.push(byte.to_ascii_lowercase()),
+		}
+		pos += 1;
+	}
+	// skip the `=` and the whitespaces after it
+	pos += 1;
+	while pos < bytes.len() && is_prescan_space(bytes[pos]) {
+		pos += 1;
+	}
+	let mut value = Vec::new();
+	match *bytes.get(pos)? {
+		quote @ (b'"' | b'\'') => {
+			let len = bytes[pos + 1..].iter().position(|byte| *byte == quote)?;
+			value.extend(
+				bytes[pos + 1..pos + 1 + len]
+					.iter()
+					.map(u8::to_ascii_lowercase),
+			);
+			pos += len + 2;
+		}
+		b'>' => return Some((name, value, pos)),
+		_ => {
+			while pos < bytes.len() && !is_prescan_space(bytes[pos]) && bytes[pos] != b'>' {
+				value.push(bytes[pos].to_ascii_lowercase());
+				pos += 1;
+			}
+		}
+	}
+	Some((name, value, pos))
+}
+
+// the charset in the content of `<meta http-equiv>` or the `Content-Type` header, the lowercase
+// `charset` followed by `=` and the value in quotes or until the `;`
+fn content_charset(content: &[u8]) -> Option<&'static Encoding> {
+	let mut pos = 0;
+	loop {
+		let index = content[pos..]
+			.windows(7)
+			.position(|window| window.eq_ignore_ascii_case(b"charset"))?;
+		pos += index + 7;
+		while pos < content.len() && is_prescan_space(content[pos]) {
+			pos += 1;
+		}
+		if content.get(pos) == Some(&b'=') {
+			break;
+		}
+	}
+	pos += 1;
+	while pos < content.len() && is_prescan_space(content[pos]) {
+		pos += 1;
+	}
+	let value = match *content.get(pos)? {
+		quote @ (b'"' | b'\'') => {
+			let len = content[pos + 1..].iter().position(|byte| *byte == quote)?;
+			&content[pos + 1..pos + 1 + len]
+		}
+		_ => {
+			let rest = &content[pos..];
+			let len = rest
+				.iter()
+				.position(|byte| is_prescan_space(*byte) || *byte == b';')
+				.unwrap_or(rest.len());
+			&rest[..len]
+		}
+	};
+	Encoding::for_label(value)
+}
+
+// the charset declared by the `<meta>`, `None` if the meta is not a charset declaration
+fn meta_charset(bytes: &[u8], pos: &mut usize) -> Option<&'static Encoding> {
+	let mut names: Vec<Vec<u8>> = Vec::new();
+	let mut got_pragma = false;
+	// `Some(true)` if the charset is from the content, it needs the `http-equiv`
+	let mut need_pragma = None;
+	let mut charset = None;
+	while let Some((name, value, next)) = prescan_attribute(bytes, *pos) {
+		*pos = next;
+		// the first attribute wins
+		if names.contains(&name) {
+			continue;
+		}
+		match name.as_slice() {
+			b"http-equiv" => got_pragma = value == b"content-type",
+			b"content" if charset.is_none() => {
+				if let Some(encoding) = content_charset(&value) {
+					charset = Some(encoding);
+					need_pragma = Some(true);
+				}
+			}
+			b"charset" => {
+				charset = Encoding::for_label(&value);
+				need_pragma = Some(false);
+			}
+			_ => {}
+		}
+		names.push(name);
+	}
+	match need_pragma {
+		Some(true) if !got_pragma => None,
+		Some(_) => charset,
+		None => None,
+	}
+}
+
+// prescan the bytes for the `<meta charset>` or `<meta http-equiv="content-type">`, the comments
+// and the attributes of the other tags are skipped, the utf-16 charsets are read as utf-8
+fn get_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+	let bytes = &bytes[..bytes.len().min(PRESCAN_LEN)];
+	let mut pos = 0;
+	while pos < bytes.len() {
+		let rest = &bytes[pos..];
+		if rest.starts_with(b"<!--") {
+			let end = rest[2..].windows(3).position(|window| window == b"-->");
+			pos += end.map_or(rest.len(), |index| index + 5);
+			continue;
+		}
+		let is_meta = rest.len() > 5
+			&& rest[..5].eq_ignore_ascii_case(b"<meta")
+			&& (is_prescan_space(rest[5]) || rest[5] == b'/');
+		if is_meta {
+			pos += 5;
+			if let Some(encoding) = meta_charset(bytes, &mut pos) {
+				return Some(match encoding {
+					_ if encoding == UTF_16BE || encoding == UTF_16LE => UTF_8,
+					_ if encoding == X_USER_DEFINED => WINDOWS_1252,
+					_ => encoding,
+				});
+			}
+			continue;
+		}
+		let tag = rest.strip_prefix(b"</").or_else(|| rest.strip_prefix(b"<"));
+		if let Some(tag) = tag.filter(|tag| tag.first().is_some_and(u8::is_ascii_alphabetic)) {
+			// skip the tag name and the attributes, the values may contain `>`
+			pos += rest.len() - tag.len();
+			while pos < bytes.len() && !is_prescan_space(bytes[pos]) && bytes[pos] != b'>' {
+				pos += 1;
+			}
+			while let Some((_, _, next)) = prescan_attribute(bytes, pos) {
+				pos = next;
+			}
+			pos += 1;
+			continue;
+		}
+		if rest.starts_with(b"<!") || rest.starts_with(b"</") || rest.starts_with(b"<?") {
+			pos += rest
+				.iter()
+				.position(|byte| *byte == b'>')
+				.map_or(rest.len(), |index| index + 1);
+			continue;
+		}
+		pos += 1;
+	}
+	None
+}
+
+// decode the html, the byte order mark wins, then the header and the `<meta>` charset
+fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+	let encoding = content_type
+		.and_then(|content_type| content_charset(content_type.as_bytes()))
+		.or_else(|| get_charset(bytes))
+		.unwrap_or(UTF_8);
+	let (html, _, _) = encoding.decode(bytes);
+	html.into_owned()
+}
+
+impl<D: IDocumentTrait> Document<D> {
+	/// pub fn `from_url`
+	/// fetch the url, follow the redirects, decode the html with the response charset and parse it
+	pub async fn from_url<F>(url: &str, parse: F) -> Result<Self, IError>
+	where
+		F: FnOnce(&str) -> D,
+	{
+		let fetch_failed = |source| IError::FetchFailed {
+			url: String::from(url),
+			source,
+		};
+		let response = reqwest::get(url)
+			.await
+			.and_then(|response| response.error_for_status())
+			.map_err(fetch_failed)?;
+		let final_url = response.url().clone();
+		let content_type = response
+			.headers()
+			.get(reqwest::header::CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.map(String::from);
+		let bytes = response.bytes().await.map_err(fetch_failed)?;
+		let html = decode_html(&bytes, content_type.as_deref());
+		Ok(Document::with_url(parse(&html), final_url))
+	}
+	/// pub fn `with_url`
+	/// wrap a parsed document with its url, the `<base href>` is resolved against the url
+	pub fn with_url(mut doc: D, url: Url) -> Self {
+		// the backends not keeping the url can't match `:target`
+		let _ = doc.set_url(url.as_str());
+		let base_url = base_url(&doc, url.as_str()).unwrap_or_else(|_| url.clone());
+		Document { doc, url, base_url }
+	}
+	/// pub fn `base_url`
+	/// the url to resolve the relative links with
+	pub fn base_url(&self) -> &Url {
+		&self.base_url
+	}
+	/// pub fn `get_ref`
+	/// get the parsed document
+	pub fn get_ref(&self) -> &D {
+		&self.doc
+	}
+	/// pub fn `into_inner`
+	/// get the parsed document and drop the url
+	pub fn into_inner(self) -> D {
+		self.doc
+	}
+}
+
+// all the methods are forwarded to the parsed document, so its own implementations such as `deep_clone`
+// and `on_mutation` are kept, the url and the base url are resolved by the wrapper
+impl<D: IDocumentTrait> IDocumentTrait for Document<D> {
+	fn get_element_by_id<'b>(&self, id: &str) -> Option<BoxDynElement<'b>> {
+		self.doc.get_element_by_id(id)
+	}
+	fn source_code(&self) -> &str {
+		self.doc.source_code()
+	}
+	fn url(&self) -> Option<&str> {
+		Some(self.url.as_str())
+	}
+	fn set_url(&mut self, url: &str) -> Result<(), Box<dyn Error>> {
+		self.url = Url::parse(url)?;
+		self.base_url = base_url(&self.doc, url).unwrap_or_else(|_| self.url.clone());
+		let _ = self.doc.set_url(url);
+		Ok(())
+	}
+	// resolved again, the `<base href>` may be changed after the document is wrapped
+	fn base_url(&self) -> Option<String> {
+		let url = base_url(&self.doc, self.url.as_str()).unwrap_or_else(|_| self.url.clone());
+		Some(url.into())
+	}
+	fn set_base_url(&mut self, url: &str) -> Result<(), Box<dyn Error>> {
+		self.doc.set_base_url(url)?;
+		self.base_url = base_url(&self.doc, self.url.as_str()).unwrap_or_else(|_| self.url.clone());
+		Ok(())
+	}
+	fn get_root_node<'b>(&self) -> BoxDynNode<'b> {
+		self.doc.get_root_node()
+	}
+	fn find<'b>(&self, selector: &str) -> Elements<'b> {
+		self.doc.find(selector)
+	}
+	fn document_element<'b>(&self) -> Option<BoxDynElement<'b>> {
+		self.doc.document_element()
+	}
+	fn title(&self) -> Option<String> {
+		self.doc.title()
+	}
+	fn set_title(&mut self, title: &str) -> Result<(), Box<dyn Error>> {
+		self.doc.set_title(title)
+	}
+	fn add_stylesheet(&mut self, href: &str) -> Result<(), Box<dyn Error>> {
+		self.doc.add_stylesheet(href)
+	}
+	fn add_script(&mut self, script: ScriptSource) -> Result<(), Box<dyn Error>> {
+		self.doc.add_script(script)
+	}
+	fn head<'b>(&self) -> Option<BoxDynElement<'b>> {
+		self.doc.head()
+	}
+	fn body<'b>(&self) -> Option<BoxDynElement<'b>> {
+		self.doc.body()
+	}
+	fn charset(&self) -> Option<String> {
+		self.doc.charset()
+	}
+	fn set_charset(&mut self, charset: &str) -> Result<(), Box<dyn Error>> {
+		self.doc.set_charset(charset)
+	}
+	fn meta(&self, name: &str) -> Option<String> {
+		self.doc.meta(name)
+	}
+	fn set_meta(&mut self, name: &str, content: &str) -> Result<(), Box<dyn Error>> {
+		self.doc.set_meta(name, content)
+	}
+	fn metadata(&self) -> Metadata {
+		self.doc.metadata()
+	}
+	fn find_like<'b>(&self, example: &str) -> Result<Vec<LikeMatch<'b>>, Box<dyn Error>> {
+		self.doc.find_like(example)
+	}
+	#[cfg(feature = "serde")]
+	fn extract<T: ExtractSchema>(&self) -> Result<Vec<T>, serde_json::Error> {
+		self.doc.extract()
+	}
+	fn outline<'b>(&self) -> Vec<OutlineItem<'b>> {
+		self.doc.outline()
+	}
+	fn from_path<'b>(&self, path: &NodePath) -> Option<BoxDynElement<'b>> {
+		self.doc.from_path(path)
+	}
+	fn stylesheet(&self) -> Stylesheet {
+		self.doc.stylesheet()
+	}
+	// the copy keeps the url, but it's the parsed document's copy without the wrapper
+	fn deep_clone<'b>(&self) -> Result<Box<dyn IDocumentTrait + 'b>, Box<dyn Error>> {
+		let mut doc = self.doc.deep_clone()?;
+		let _ = doc.set_url(self.url.as_str());
+		Ok(doc)
+	}
+	fn adopt<'b>(&mut self, node: &BoxDynNode) -> Result<BoxDynNode<'b>, Box<dyn Error>> {
+		self.doc.adopt(node)
+	}
+	fn on_mutation(&mut self, callback: MutationCallback) -> Result<usize, Box<dyn Error>> {
+		self.doc.on_mutation(callback)
+	}
+	fn off_mutation(&mut self, id: usize) -> Result<(), Box<dyn Error>> {
+		self.doc.off_mutation(id)
+	}
+	fn transaction(&mut self, handle: &mut TransactionHandle) -> Result<(), Box<dyn Error>> {
+		self.doc.transaction(handle)
+	}
+	fn visit_mut(&mut self, visitor: &mut dyn NodeVisitor) -> Result<(), Box<dyn Error>> {
+		self.doc.visit_mut(visitor)
+	}
+	fn lint<'b>(&self) -> Vec<LintIssue<'b>> {
+		self.doc.lint()
+	}
+	fn duplicate_ids<'b>(&self) -> Vec<(String, Elements<'b>)> {
+		self.doc.duplicate_ids()
+	}
+	fn uniquify_ids(&mut self, prefix: &str) -> Vec<(String, String)> {
+		self.doc.uniquify_ids(prefix)
+	}
+	fn minify(&self, options: MinifyOptions) -> String {
+		self.doc.minify(options)
+	}
+	fn format(&self, options: FormatOptions) -> String {
+		self.doc.format(options)
+	}
+	#[cfg(feature = "encoding")]
+	fn encode_html(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+		self.doc.encode_html()
+	}
+	fn onerror(&self) -> Option<Rc<IErrorHandle>> {
+		self.doc.onerror()
+	}
+	fn trigger_error(&self, error: Box<dyn Error>) {
+		self.doc.trigger_error(error)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{decode_html, get_charset, Document};
+	use crate::dom::parse_with_source;
+	use crate::interface::IDocumentTrait;
+	use encoding_rs::{GBK, UTF_8, WINDOWS_1252};
+	use std::cell::Cell;
+	use std::rc::Rc;
+	use url::Url;
+	#[test]
+	fn test_decode_html() {
+		let gbk = [0xC4, 0xE3, 0xBA, 0xC3];
+		assert_eq!(decode_html(&gbk, Some("text/html; charset=GBK")), "你好");
+		let html = [b"<meta charset='gbk'>".to_vec(), gbk.to_vec()].concat();
+		assert_eq!(
+			decode_html(&html, Some("text/html")),
+			"<meta charset='gbk'>你好"
+		);
+		let html = b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=iso-8859-1\">\xE9";
+		assert!(decode_html(html, None).ends_with('é'));
+		// the byte order mark wins
+		let html = [&[0xEF, 0xBB, 0xBF][..], "é".as_bytes()].concat();
+		assert_eq!(
+			decode_html(&html, Some("text/html; charset=iso-8859-1")),
+			"é"
+		);
+	}
+	#[test]
+	fn test_get_charset() {
+		assert_eq!(get_charset(b"<meta charset=\"gbk\">"), Some(GBK));
+		assert_eq!(get_charset(b"<META CHARSET=GBK>"), Some(GBK));
+		// the charset in the texts, the comments and the other tags' attributes is not a declaration
+		assert_eq!(
+			get_charset(b"<title>charset=gbk</title><meta charset=\"utf-8\">"),
+			Some(UTF_8)
+		);
+		assert_eq!(
+			get_charset(b"<!-- <meta charset=\"gbk\"> --><meta charset=\"utf-8\">"),
+			Some(UTF_8)
+		);
+		assert_eq!(
+			get_charset(b"<a title=\"x > <meta charset=gbk>\"></a><meta charset=\"utf-8\">"),
+			Some(UTF_8)
+		);
+		assert_eq!(get_charset(b"<metadata charset=\"gbk\">"), None);
+		// the content needs the `http-equiv`, the `charset` attribute doesn't
+		assert_eq!(
+			get_charset(b"<meta name=\"x\" content=\"text/html; charset=gbk\">"),
+			None
+		);
+		assert_eq!(
+			get_charset(b"<meta content='text/html; charset=gbk' http-equiv='content-type'>"),
+			Some(GBK)
+		);
+		assert_eq!(
+			get_charset(b"<meta http-equiv=\"content-type\" content=\"text/html; charset='gbk'\">"),
+			Some(GBK)
+		);
+		// the unknown charsets are skipped, the utf-16 is read as utf-8
+		assert_eq!(
+			get_charset(b"<meta charset=\"none\"><meta charset=\"latin1\">"),
+			Some(WINDOWS_1252)
+		);
+		assert_eq!(get_charset(b"<meta charset=\"utf-16le\">"), Some(UTF_8));
+		// only the first bytes are scanned
+		let html = [vec![b' '; 1024], b"<meta charset=\"gbk\">".to_vec()].concat();
+		assert_eq!(get_charset(&html), None);
+	}
+	#[test]
+	fn test_document() -> Result<(), Box<dyn std::error::Error>> {
+		crate::init();
+		let html = "<html><head><base href=\"/docs/\"><title>a</title></head><body><p id=\"p\">1</p><p id=\"p\">2</p></body></html>";
+		let url = Url::parse("https://example.com/a/b.html#p")?;
+		let mut doc = Document::with_url(parse_with_source(html), url);
+		assert_eq!(doc.url(), Some("https://example.com/a/b.html#p"));
+		assert_eq!(doc.get_ref().url(), doc.url());
+		assert_eq!(doc.base_url().as_str(), "https://example.com/docs/");
+		assert_eq!(
+			IDocumentTrait::base_url(&doc).as_deref(),
+			Some("https://example.com/docs/")
+		);
+		doc.set_base_url("/other/")?;
+		assert_eq!(doc.base_url().as_str(), "https://example.com/other/");
+		doc.set_url("https://example.org/")?;
+		assert_eq!(doc.base_url().as_str(), "https://example.org/other/");
+		// the methods are forwarded to the parsed document
+		assert_eq!(doc.title().as_deref(), Some("a"));
+		doc.set_charset("gbk")?;
+		assert_eq!(doc.charset().as_deref(), Some("gbk"));
+		assert_eq!(doc.duplicate_ids().len(), 1);
+		assert_eq!(doc.find(":target").length(), 0);
+		let count = Rc::new(Cell::new(0));
+		let counter = count.clone();
+		let id = doc.on_mutation(Box::new(move |_| counter.set(counter.get() + 1)))?;
+		doc.set_title("b")?;
+		assert!(count.get() > 0);
+		doc.off_mutation(id)?;
+		let before = count.get();
+		doc.set_title("c")?;
+		assert_eq!(count.get(), before);
+		// the copy keeps the url
+		let copy = doc.deep_clone()?;
+		assert_eq!(copy.url(), Some("https://example.org/"));
+		assert_eq!(copy.title().as_deref(), Some("c"));
+		let p = copy.get_element_by_id("p").unwrap();
+		let adopted = doc.adopt(&p.clone_node())?;
+		assert_eq!(adopted.text_content(), "1");
+		assert_eq!(copy.find("p").length(), 1);
+		// the rollback of the transaction relies on the forwarded `on_mutation`
+		let mut title = doc.find("title").get(0).unwrap().cloned();
+		let result = doc.transaction(&mut |_| {
+			title.set_text("d");
+			Err("failed".into())
+		});
+		assert!(result.is_err());
+		assert_eq!(doc.title().as_deref(), Some("c"));
+		assert!(doc.encode_html().is_ok());
+		Ok(())
+	}
+}
@@ -0,0 +1,147 @@
+use crate::css::{parse_declarations, Declaration};
+use std::convert::Infallible;
+use std::str::FromStr;
+
+/// the descriptor of a `srcset` candidate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SrcsetDescriptor {
+	// no descriptor, same as `1x`
+	None,
+	// the width descriptor, such as `480w`
+	Width(u32),
+	// the pixel density descriptor, such as `1.5x`
+	Density(f32),
+}
+
+/// an image candidate of the `srcset` attribute
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrcsetCandidate {
+	pub url: String,
+	pub descriptor: SrcsetDescriptor,
+}
+
+/// the parsed `srcset` attribute, the invalid candidates are dropped
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Srcset {
+	pub candidates: Vec<SrcsetCandidate>,
+}
+
+// parse the descriptors of a candidate, the height descriptor `h` is allowed but ignored
+fn parse_srcset_descriptor(content: &str) -> Option<SrcsetDescriptor> {
+	let mut descriptor = SrcsetDescriptor::None;
+	let mut has_height = false;
+	for token in content.split_ascii_whitespace() {
+		let unit = token.chars().last()?;
+		let value = &token[..token.len() - unit.len_utf8()];
+		match (unit, descriptor) {
+			('w', SrcsetDescriptor::None) => {
+				let width = value.parse::<u32>().ok().filter(|width| *width > 0)?;
+				descriptor = SrcsetDescriptor::Width(width);
+			}
+			('x', SrcsetDescriptor::None) if !has_height => {
+				let density = value
+					.parse::<f32>()
+					.ok()
+					.filter(|density| *density >= 0.0)?;
+				descriptor = SrcsetDescriptor::Density(density);
+			}
+			('h', SrcsetDescriptor::None) | ('h', SrcsetDescriptor::Width(_)) if !has_height => {
+				value.parse::<u32>().ok().filter(|height| *height > 0)?;
+				has_height = true;
+			}
+			_ => return None,
+		}
+	}
+	// the height descriptor must be used with a width descriptor
+	if has_height && !matches!(descriptor, SrcsetDescriptor::Width(_)) {
+		return None;
+	}
+	Some(descriptor)
+}
+
+/// https://html.spec.whatwg.org/multipage/images.html#parsing-a-srcset-attribute
+impl FromStr for Srcset {
+	type Err = Infallible;
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		let mut candidates = Vec::new();
+		let mut rest = value;
+		loop {
+			rest = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace() || ch == ',');
+			if rest.is_empty() {
+				break;
+			}
+			let url_end = rest
+				.find(|ch: char| ch.is_ascii_whitespace())
+				.unwrap_or(rest.len());
+			let mut url = &rest[..url_end];
+			rest = &rest[url_end..];
+			let mut descriptors = "";
+			if url.ends_with(',') {
+				// the url ends with commas has no descriptors
+				url = url.trim_end_matches(',');
+			} else {
+				// the descriptors end at the comma out of the parentheses
+				let mut depth = 0;
+				let mut end = rest.len();
+				for (index, ch) in rest.char_indices() {
+					match ch {
+						'(' => depth += 1,
+						')' if depth > 0 => depth -= 1,
+						',' if depth == 0 => {
+							end = index;
+							break;
+						}
+						_ => {}
+					}
+				}
+				descriptors = &rest[..end];
+				rest = &rest[end..];
+			}
+			if url.is_empty() {
+				continue;
+			}
+			if let Some(descriptor) = parse_srcset_descriptor(descriptors) {
+				candidates.push(SrcsetCandidate {
+					url: String::from(url),
+					descriptor,
+				});
+			}
+		}
+		Ok(Srcset { candidates })
+	}
+}
+
+/// the parsed `style` attribute
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InlineStyle {
+	pub declarations: Vec<Declaration>,
+}
+
+impl InlineStyle {
+	/// pub fn `get`
+	/// get the value of the property, the important declaration wins, then the later one
+	pub fn get(&self, property: &str) -> Option<&str> {
+		let declarations = self
+			.declarations
+			.iter()
+			.rev()
+			.filter(|declaration| declaration.name == property);
+		let mut result = None;
+		for declaration in declarations {
+			if declaration.important {
+				return Some(&declaration.value);
+			}
+			result = result.or(Some(declaration.value.as_str()));
+		}
+		result
+	}
+}
+
+impl FromStr for InlineStyle {
+	type Err = Infallible;
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		Ok(InlineStyle {
+			declarations: parse_declarations(value),
+		})
+	}
+}
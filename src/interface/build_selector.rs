@@ -0,0 +1,129 @@
+use super::{BoxDynElement, Elements, IAttrValue, INodeType};
+
+/// how `build_selector` builds the selector of an element
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum SelectorStrategy {
+	// the shortest selector matched only the element, the unique id first,
+	// then the tag name with the classes, then the `:nth-child()`, the ancestors are added until it's unique
+	#[default]
+	Shortest,
+	// the full path from the root with the tag names and the `:nth-child()`,
+	// the ids and the classes are not used, so it's stable when the attributes are changed
+	Path,
+}
+
+// the name can be written as an identity in the selectors, such as `#id` or `.class`
+fn is_identity(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars
+		.next()
+		.is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+		&& chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+// the id selector, the attribute selector if the id is not an identity
+fn id_selector(id: &str) -> String {
+	if is_identity(id) {
+		format!("#{}", id)
+	} else {
+		format!("[id=\"{}\"]", id.replace('\\', "\\\\").replace('"', "\\\""))
+	}
+}
+
+// the number of the elements matched the selector, in the root and its descendants
+fn count_in(root: &Elements, selector: &str) -> usize {
+	root.filter(selector).length() + root.find(selector).length()
+}
+
+// the element's index in the element children of the parent, from 1
+fn nth_child(ele: &BoxDynElement, siblings: &Elements) -> usize {
+	siblings
+		.get_ref()
+		.iter()
+		.position(|sibling| sibling.is(ele))
+		.map_or(1, |index| index + 1)
+}
+
+// the shortest step matched only the element in its siblings
+fn shortest_step(ele: &BoxDynElement, siblings: &Elements) -> String {
+	// the local name keeps the case of the svg and mathml elements
+	let tag = ele.local_name();
+	if siblings.filter(tag).length() == 1 {
+		return String::from(tag);
+	}
+	let classes: Vec<String> = match ele.get_attribute("class") {
+		Some(IAttrValue::Value(value, _)) => value
+			.split_ascii_whitespace()
+			.filter(|name| is_identity(name))
+			.map(|name| format!(".{}", name))
+			.collect(),
+		_ => Vec::new(),
+	};
+	// one class, then all the classes
+	let mut candidates: Vec<String> = classes
+		.iter()
+		.map(|class_name| format!("{}{}", tag, class_name))
+		.collect();
+	if classes.len() > 1 {
+		candidates.push(format!("{}{}", tag, classes.concat()));
+	}
+	for step in candidates {
+		if siblings.filter(&step).length() == 1 {
+			return step;
+		}
+	}
+	format!("{}:nth-child({})", tag, nth_child(ele, siblings))
+}
+
+// the selector of the element matched only the element in its root
+pub(crate) fn build_selector(ele: &BoxDynElement, strategy: SelectorStrategy) -> String {
+	let root = Elements::with_nodes(vec![ele.root()]);
+	let mut steps: Vec<String> = Vec::new();
+	let mut cur = ele.cloned();
+	loop {
+		let parent = cur.parent();
+		// the element is a child of the document or has no parent
+		let is_top = parent.as_ref().is_none_or(|parent| {
+			matches!(
+				parent.node_type(),
+				INodeType::Document | INodeType::DocumentFragement
+			)
+		});
+		if strategy == SelectorStrategy::Shortest {
+			if let Some(IAttrValue::Value(id, _)) = cur.get_attribute("id") {
+				let step = id_selector(&id);
+				if !id.is_empty() && count_in(&root, &step) == 1 {
+					steps.push(step);
+					break;
+				}
+			}
+		}
+		let step = match &parent {
+			Some(parent) => {
+				let siblings = parent.children();
+				match strategy {
+					SelectorStrategy::Shortest => shortest_step(&cur, &siblings),
+					SelectorStrategy::Path => format!(
+						"{}:nth-child({})",
+						cur.local_name(),
+						nth_child(&cur, &siblings)
+					),
+				}
+			}
+			None => String::from(cur.local_name()),
+		};
+		steps.push(step);
+		if strategy == SelectorStrategy::Shortest {
+			let selector = steps.iter().rev().cloned().collect::<Vec<_>>().join(" > ");
+			if count_in(&root, &selector) == 1 {
+				return selector;
+			}
+		}
+		match parent {
+			Some(parent) if !is_top => cur = parent,
+			_ => break,
+		}
+	}
+	steps.reverse();
+	steps.join(" > ")
+}
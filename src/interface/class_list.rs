@@ -0,0 +1,126 @@
+use super::{BoxDynElement, IAttrValue};
+use crate::constants::ATTR_CLASS;
+
+/// the class names of an element, like the `DOMTokenList` of `element.classList`.
+/// it's live, each method reads the `class` attribute and the changes are written back to it
+pub struct ClassList<'a> {
+	ele: BoxDynElement<'a>,
+}
+
+impl<'a> ClassList<'a> {
+	pub fn new(ele: BoxDynElement<'a>) -> Self {
+		ClassList { ele }
+	}
+	/// pub fn `split`
+	/// split the value of the `class` attribute into the class names
+	pub fn split(value: &str) -> Vec<&str> {
+		value.split_ascii_whitespace().collect()
+	}
+	// the unique class names in order
+	fn tokens(&self) -> Vec<String> {
+		let mut tokens: Vec<String> = Vec::new();
+		if let Some(IAttrValue::Value(value, _)) = self.ele.get_attribute(ATTR_CLASS) {
+			for name in ClassList::split(&value) {
+				if !tokens.iter().any(|token| token == name) {
+					tokens.push(String::from(name));
+				}
+			}
+		}
+		tokens
+	}
+	// write the class names back to the attribute
+	fn set_tokens(&mut self, tokens: &[String]) {
+		self.ele.set_attribute(ATTR_CLASS, Some(&tokens.join(" ")));
+	}
+	/// pub fn `value`
+	/// the value of the `class` attribute
+	pub fn value(&self) -> String {
+		match self.ele.get_attribute(ATTR_CLASS) {
+			Some(IAttrValue::Value(value, _)) => value,
+			_ => String::new(),
+		}
+	}
+	/// pub fn `to_vec`
+	/// the unique class names in order
+	pub fn to_vec(&self) -> Vec<String> {
+		self.tokens()
+	}
+	/// pub fn `length`
+	pub fn length(&self) -> usize {
+		self.tokens().len()
+	}
+	/// pub fn `is_empty`
+	pub fn is_empty(&self) -> bool {
+		self.length() == 0
+	}
+	/// pub fn `item`
+	pub fn item(&self, index: usize) -> Option<String> {
+		self.tokens().into_iter().nth(index)
+	}
+	/// pub fn `contains`
+	pub fn contains(&self, class_name: &str) -> bool {
+		match self.ele.get_attribute(ATTR_CLASS) {
+			Some(IAttrValue::Value(value, _)) => ClassList::split(&value).contains(&class_name),
+			_ => false,
+		}
+	}
+	/// pub fn `add`
+	/// add the class names split by whitespaces, the existed names are kept in place
+	pub fn add(&mut self, class_names: &str) -> &mut Self {
+		let mut tokens = self.tokens();
+		let mut changed = false;
+		for name in ClassList::split(class_names) {
+			if !tokens.iter().any(|token| token == name) {
+				tokens.push(String::from(name));
+				changed = true;
+			}
+		}
+		if changed {
+			self.set_tokens(&tokens);
+		}
+		self
+	}
+	/// pub fn `remove`
+	/// remove the class names split by whitespaces
+	pub fn remove(&mut self, class_names: &str) -> &mut Self {
+		let mut tokens = self.tokens();
+		let total = tokens.len();
+		let class_names = ClassList::split(class_names);
+		tokens.retain(|token| !class_names.contains(&token.as_str()));
+		if tokens.len() != total {
+			self.set_tokens(&tokens);
+		}
+		self
+	}
+	/// pub fn `toggle`
+	/// remove the class name if it exists, otherwise add it, return if the class name exists after toggled
+	pub fn toggle(&mut self, class_name: &str) -> bool {
+		if self.contains(class_name) {
+			self.remove(class_name);
+			false
+		} else {
+			self.add(class_name);
+			true
+		}
+	}
+	/// pub fn `replace`
+	/// replace the class name with the new one in place, return false if the class name doesn't exist
+	pub fn replace(&mut self, class_name: &str, new_class_name: &str) -> bool {
+		let mut tokens = self.tokens();
+		let index = match tokens.iter().position(|token| token == class_name) {
+			Some(index) => index,
+			None => return false,
+		};
+		tokens[index] = String::from(new_class_name);
+		// keep the first one if the new class name exists
+		let mut is_found = false;
+		tokens.retain(|token| {
+			if token != new_class_name {
+				return true;
+			}
+			!std::mem::replace(&mut is_found, true)
+		});
+		self.set_tokens(&tokens);
+		true
+	}
+}
@@ -1,15 +1,214 @@
-use super::{BoxDynElement, BoxDynNode, Elements};
-use crate::utils::to_static_str;
+use super::element::insert_adjacent_nodes;
+use super::{
+	resolve_path, visit_mut, BoxDynElement, BoxDynNode, Elements, IAttrValue, IEnumTyped,
+	InsertPosition, MutationCallback, NodePath, NodeVisitor, Transaction, TransactionHandle,
+};
+use crate::css::Stylesheet;
+use crate::error::Error as IError;
+#[cfg(feature = "serde")]
+use crate::extract::ExtractSchema;
+use crate::extract::{find_like, outline, LikeMatch, Metadata, OutlineItem};
+use crate::lint::{lint, LintIssue};
+#[cfg(feature = "encoding")]
+use crate::serialize::{encode_html, serialize};
+use crate::serialize::{format, minify, FormatOptions, MinifyOptions};
+use crate::utils::resolve_url;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::rc::Rc;
 
 pub type MaybeDoc<'a> = Option<Box<dyn IDocumentTrait + 'a>>;
 pub type IErrorHandle = Box<dyn Fn(Box<dyn Error>)>;
+
+/// the script added by `add_script`
+#[derive(Debug, Clone, Copy)]
+pub enum ScriptSource<'s> {
+	// an external script, `<script src="...">`
+	Src(&'s str),
+	// an inline script
+	Inline(&'s str),
+}
+
+// insert the html into the parent by `insert_adjacent_nodes`, the existed children are kept, if the backend
+// can't copy the parent the children are parsed again. return the inserted elements
+fn insert_html<'b>(
+	parent: &mut BoxDynElement,
+	position: &InsertPosition,
+	html: &str,
+) -> Elements<'b> {
+	let is_prepend = matches!(position, InsertPosition::AfterBegin);
+	match insert_adjacent_nodes(parent, position, "insert_html", &|holder| {
+		holder.set_html(html)
+	}) {
+		Ok(nodes) => Elements::with_nodes(
+			nodes
+				.into_iter()
+				.filter_map(|node| node.typed().into_element())
+				.collect(),
+		),
+		Err(_) => {
+			let total = parent.children().length();
+			let content = if is_prepend {
+				format!("{}{}", html, parent.inner_html())
+			} else {
+				format!("{}{}", parent.inner_html(), html)
+			};
+			parent.set_html(&content);
+			let children = parent.children();
+			let count = children.length() - total;
+			let range = if is_prepend {
+				0..count
+			} else {
+				total..total + count
+			};
+			Elements::with_nodes(
+				children.get_ref()[range]
+					.iter()
+					.map(|ele| ele.cloned())
+					.collect(),
+			)
+		}
+	}
+}
+
+// the head of the document, create it at the start of the `html` element if missing
+fn head_or_create<'b>(
+	head: Option<BoxDynElement<'b>>,
+	html: Option<BoxDynElement>,
+) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+	if let Some(head) = head {
+		return Ok(head);
+	}
+	let mut html = html.ok_or_else(|| IError::InvalidStructure {
+		rule: "head".into(),
+		message: "The document has no 'html' element to insert the 'head'.".into(),
+	})?;
+	insert_html(&mut html, &InsertPosition::AfterBegin, "<head></head>")
+		.get(0)
+		.map(|head| head.cloned())
+		.ok_or_else(|| {
+			Box::new(IError::InvalidTraitMethodCall {
+				method: "set_html".into(),
+				message: "The 'head' element can't be created.".into(),
+			}) as Box<dyn Error>
+		})
+}
+
+// the value of the attribute, the attribute without value is an empty string
+fn attr_string(ele: &BoxDynElement, name: &str) -> Option<String> {
+	match ele.get_attribute(name)? {
+		IAttrValue::Value(value, _) => Some(value),
+		IAttrValue::True => Some(String::new()),
+	}
+}
+
+// the id of the element, the empty and whitespace-only ids are not ids, the same as the lint
+fn element_id(ele: &BoxDynElement) -> Option<String> {
+	match ele.get_attribute("id")? {
+		IAttrValue::Value(id, _) if !id.trim().is_empty() => Some(id),
+		_ => None,
+	}
+}
+
+// the charset in the `content` of `<meta http-equiv="content-type">`, such as `text/html; charset=utf-8`
+fn content_type_charset(content: &str) -> Option<&str> {
+	let lower = content.to_ascii_lowercase();
+	let index = lower.find("charset")?;
+	let value = content[index + 7..].trim_start().strip_prefix('=')?;
+	let value = value.trim_start().trim_start_matches(['"', '\'']);
+	let end = value
+		.find(|ch: char| ch == ';' || ch == '"' || ch == '\'' || ch.is_ascii_whitespace())
+		.unwrap_or(value.len());
+	Some(&value[..end]).filter(|value| !value.is_empty())
+}
+
+// find the `<meta>` in the head with the attribute value matched case-insensitively
+fn find_meta<'b>(head: &BoxDynElement, attr_name: &str, value: &str) -> Option<BoxDynElement<'b>> {
+	Elements::with_node(head)
+		.find("meta")
+		.get_ref()
+		.iter()
+		.find(|meta| {
+			attr_string(meta, attr_name).is_some_and(|name| name.trim().eq_ignore_ascii_case(value))
+		})
+		.map(|meta| meta.cloned())
+}
+// the journal of a transaction registered on the document, unregistered when dropped, even if the handle panics
+struct Journal<'d, D: IDocumentTrait + ?Sized> {
+	doc: &'d mut D,
+	id: Option<usize>,
+}
+
+impl<'d, D: IDocumentTrait + ?Sized> Journal<'d, D> {
+	fn off(mut self) -> Result<(), Box<dyn Error>> {
+		match self.id.take() {
+			Some(id) => self.doc.off_mutation(id),
+			None => Ok(()),
+		}
+	}
+}
+
+impl<'d, D: IDocumentTrait + ?Sized> Drop for Journal<'d, D> {
+	fn drop(&mut self) {
+		if let Some(id) = self.id.take() {
+			let _ = self.doc.off_mutation(id);
+		}
+	}
+}
+
 pub trait IDocumentTrait {
 	fn get_element_by_id<'b>(&self, id: &str) -> Option<BoxDynElement<'b>>;
-	fn source_code(&self) -> &'static str;
+	fn source_code(&self) -> &str;
+	// the url of the document, such as the final url after redirects
+	fn url(&self) -> Option<&str> {
+		None
+	}
+	// set the url of the document, the fragment is used by the `:target` selector
+	fn set_url(&mut self, _url: &str) -> Result<(), Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "set_url".into(),
+			message: "The set_url method is not implemented.".into(),
+		}))
+	}
+	// the base url to resolve the relative urls, the first `<base href>` resolved against the document url,
+	// or the document url if there is no `<base href>`
+	fn base_url(&self) -> Option<String> {
+		let href = self.get_root_node().root_element().and_then(|root| {
+			Elements::with_node(&root)
+				.find("base[href]")
+				.get(0)
+				.and_then(|base| attr_string(base, "href"))
+		});
+		let url = self.url().unwrap_or("");
+		match href {
+			Some(href) => resolve_url(url, &href).or_else(|| self.url().map(String::from)),
+			None => self.url().map(String::from),
+		}
+	}
+	// set the base url, change the href of the `<base>` or insert one at the start of the head
+	fn set_base_url(&mut self, url: &str) -> Result<(), Box<dyn Error>> {
+		let mut head = head_or_create(self.head(), self.document_element())?;
+		let bases = Elements::with_node(&head).find("base");
+		let base = match bases.get(0) {
+			Some(base) => Some(base.cloned()),
+			None => insert_html(&mut head, &InsertPosition::AfterBegin, "<base>")
+				.get(0)
+				.map(|base| base.cloned()),
+		};
+		if let Some(mut base) = base {
+			base.set_attribute("href", Some(url));
+		}
+		Ok(())
+	}
 	// get root node
 	fn get_root_node<'b>(&self) -> BoxDynNode<'b>;
+	// the elements matched the selector in the whole document
+	fn find<'b>(&self, selector: &str) -> Elements<'b> {
+		match self.get_root_node().typed().into_element() {
+			Some(root) => Elements::with_nodes(vec![root]).find(selector),
+			None => Elements::new(),
+		}
+	}
 	// document element, html tag
 	fn document_element<'b>(&self) -> Option<BoxDynElement<'b>> {
 		if let Some(root) = &self.get_root_node().root_element() {
@@ -19,16 +218,76 @@ pub trait IDocumentTrait {
 		None
 	}
 	// title
-	fn title(&self) -> Option<&'static str> {
+	fn title(&self) -> Option<String> {
 		if let Some(root) = &self.get_root_node().root_element() {
 			let root = Elements::with_node(root);
 			let title = root.find("head").eq(0).find("title");
-			if !title.is_empty() {
-				return Some(to_static_str(String::from(title.text())));
-			}
+			return title.get(0).map(|title| String::from(title.text_content()));
 		}
 		None
 	}
+	// set the text of the `<title>`, a new one is appended to the head if missing
+	fn set_title(&mut self, title: &str) -> Result<(), Box<dyn Error>> {
+		let mut head = head_or_create(self.head(), self.document_element())?;
+		let titles = Elements::with_node(&head).find("title");
+		let element = match titles.get(0) {
+			Some(element) => Some(element.cloned()),
+			None => insert_html(&mut head, &InsertPosition::BeforeEnd, "<title></title>")
+				.get(0)
+				.map(|element| element.cloned()),
+		};
+		if let Some(mut element) = element {
+			element.set_text(title);
+		}
+		Ok(())
+	}
+	// append a `<link rel="stylesheet">` to the head, the stylesheet with the same href is not added again
+	fn add_stylesheet(&mut self, href: &str) -> Result<(), Box<dyn Error>> {
+		let mut head = head_or_create(self.head(), self.document_element())?;
+		let links = Elements::with_node(&head).find("link[rel][href]");
+		let is_added = links.get_ref().iter().any(|link| {
+			attr_string(link, "href").is_some_and(|value| value == href)
+				&& attr_string(link, "rel").is_some_and(|rel| {
+					rel
+						.split_ascii_whitespace()
+						.any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+				})
+		});
+		if !is_added {
+			if let Some(mut link) = insert_html(&mut head, &InsertPosition::BeforeEnd, "<link>")
+				.get(0)
+				.map(|link| link.cloned())
+			{
+				link.set_attribute("rel", Some("stylesheet"));
+				link.set_attribute("href", Some(href));
+			}
+		}
+		Ok(())
+	}
+	// append a `<script>` to the head, the external script with the same src is not added again
+	fn add_script(&mut self, script: ScriptSource) -> Result<(), Box<dyn Error>> {
+		let mut head = head_or_create(self.head(), self.document_element())?;
+		if let ScriptSource::Src(src) = script {
+			let scripts = Elements::with_node(&head).find("script[src]");
+			if scripts
+				.get_ref()
+				.iter()
+				.any(|ele| attr_string(ele, "src").is_some_and(|value| value == src))
+			{
+				return Ok(());
+			}
+		}
+		if let Some(mut ele) = insert_html(&mut head, &InsertPosition::BeforeEnd, "<script></script>")
+			.get(0)
+			.map(|ele| ele.cloned())
+		{
+			match script {
+				ScriptSource::Src(src) => ele.set_attribute("src", Some(src)),
+				ScriptSource::Inline(content) => ele.set_text(content),
+			}
+		}
+		Ok(())
+	}
 	// head
 	fn head<'b>(&self) -> Option<BoxDynElement<'b>> {
 		if let Some(root) = &self.get_root_node().root_element() {
@@ -45,6 +304,271 @@ pub trait IDocumentTrait {
 		}
 		None
 	}
+	// the charset declared by `<meta charset>` or `<meta http-equiv="content-type">`
+	fn charset(&self) -> Option<String> {
+		let head = self.head()?;
+		let metas = Elements::with_node(&head).find("meta");
+		metas.get_ref().iter().find_map(|meta| {
+			if let Some(charset) = attr_string(meta, "charset") {
+				return Some(String::from(charset.trim())).filter(|charset| !charset.is_empty());
+			}
+			let http_equiv = attr_string(meta, "http-equiv")?;
+			if !http_equiv.trim().eq_ignore_ascii_case("content-type") {
+				return None;
+			}
+			content_type_charset(&attr_string(meta, "content")?).map(String::from)
+		})
+	}
+	// set the charset, the existed declaration is changed, otherwise a `<meta charset>` is inserted
+	// at the start of the head
+	fn set_charset(&mut self, charset: &str) -> Result<(), Box<dyn Error>> {
+		if let Some(head) = self.head() {
+			let metas = Elements::with_node(&head).find("meta[charset]");
+			if let Some(meta) = metas.get(0) {
+				meta.cloned().set_attribute("charset", Some(charset));
+				return Ok(());
+			}
+			if let Some(mut meta) = find_meta(&head, "http-equiv", "content-type") {
+				let content = format!("text/html; charset={}", charset);
+				meta.set_attribute("content", Some(&content));
+				return Ok(());
+			}
+		}
+		let mut head = head_or_create(self.head(), self.document_element())?;
+		if let Some(mut meta) = insert_html(&mut head, &InsertPosition::AfterBegin, "<meta>")
+			.get(0)
+			.map(|meta| meta.cloned())
+		{
+			meta.set_attribute("charset", Some(charset));
+		}
+		Ok(())
+	}
+	// the content of the `<meta name>`, the name is ASCII case-insensitive, such as `viewport`
+	fn meta(&self, name: &str) -> Option<String> {
+		find_meta(&self.head()?, "name", name).and_then(|meta| attr_string(&meta, "content"))
+	}
+	// set the content of the `<meta name>`, a new one is appended to the head if missing
+	fn set_meta(&mut self, name: &str, content: &str) -> Result<(), Box<dyn Error>> {
+		let mut head = head_or_create(self.head(), self.document_element())?;
+		if let Some(mut meta) = find_meta(&head, "name", name) {
+			meta.set_attribute("content", Some(content));
+			return Ok(());
+		}
+		if let Some(mut meta) = insert_html(&mut head, &InsertPosition::BeforeEnd, "<meta>")
+			.get(0)
+			.map(|meta| meta.cloned())
+		{
+			meta.set_attribute("name", Some(name));
+			meta.set_attribute("content", Some(content));
+		}
+		Ok(())
+	}
+	// metadata, title, description, open graph, twitter cards and json-ld
+	fn metadata(&self) -> Metadata {
+		if let Some(root) = &self.get_root_node().root_element() {
+			return Metadata::from_root(root, self.head().as_ref());
+		}
+		Default::default()
+	}
+	// find the elements shaped like the example html, `*` matches any chars and `{name}` captures the chars
+	// in the attribute values and the texts, see `extract::find_like`
+	fn find_like<'b>(&self, example: &str) -> Result<Vec<LikeMatch<'b>>, Box<dyn Error>> {
+		match self.get_root_node().typed().into_element() {
+			Some(root) => Ok(find_like(&Elements::with_nodes(vec![root]), example)?),
+			None => Ok(Vec::new()),
+		}
+	}
+	// the records of the document by the schema of the type, see `extract::Schema`
+	#[cfg(feature = "serde")]
+	fn extract<T: ExtractSchema>(&self) -> Result<Vec<T>, serde_json::Error>
+	where
+		Self: Sized,
+	{
+		match self.get_root_node().typed().into_element() {
+			Some(root) => Elements::with_nodes(vec![root]).extract(),
+			None => Ok(Vec::new()),
+		}
+	}
+	// the heading hierarchy of the document with the sectioning elements, see `extract::outline`
+	fn outline<'b>(&self) -> Vec<OutlineItem<'b>> {
+		match self.get_root_node().typed().into_element() {
+			Some(root) => outline(&Elements::with_nodes(vec![root])),
+			None => Vec::new(),
+		}
+	}
+	// the element at the path of `IElementTrait::node_path`, `None` if the structure is changed
+	#[allow(clippy::wrong_self_convention)]
+	fn from_path<'b>(&self, path: &NodePath) -> Option<BoxDynElement<'b>> {
+		let root = self.get_root_node().typed().into_element()?;
+		resolve_path(root, path)
+	}
+	// the stylesheet of the `<style>` elements, the external stylesheets are not loaded
+	fn stylesheet(&self) -> Stylesheet {
+		match self.get_root_node().typed().into_element() {
+			Some(root) => {
+				let css: Vec<String> = Elements::with_node(&root)
+					.find("style")
+					.get_ref()
+					.iter()
+					.map(|style| String::from(style.text()))
+					.collect();
+				Stylesheet::parse(&css.join("\n"))
+			}
+			None => Default::default(),
+		}
+	}
+	// an independent copy of the document with new nodes, changing the copy doesn't affect the document,
+	// the documents are not `Send`, so the copy can't be moved to another thread, parse the html there instead
+	fn deep_clone<'b>(&self) -> Result<Box<dyn IDocumentTrait + 'b>, Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "deep_clone".into(),
+			message: "The deep_clone method is not implemented.".into(),
+		}))
+	}
+	// adopt the node, maybe of another document, it's removed from its parent and can be inserted into
+	// this document, return the node owned by this document. the default keeps the node itself, it works
+	// for the backends whose nodes can move between the documents
+	fn adopt<'b>(&mut self, node: &BoxDynNode) -> Result<BoxDynNode<'b>, Box<dyn Error>> {
+		match node.clone_node().typed() {
+			IEnumTyped::Element(ele) => {
+				if let Some(mut parent) = ele.parent() {
+					parent.remove_child(ele);
+				}
+			}
+			IEnumTyped::Text(text) => {
+				if node.parent().is_some() {
+					text.remove();
+				}
+			}
+			IEnumTyped::UncareNode(_) => {
+				return Err(Box::new(IError::InvalidTraitMethodCall {
+					method: "adopt".into(),
+					message: "Only the elements and the texts can be adopted.".into(),
+				}))
+			}
+		}
+		Ok(node.clone_node())
+	}
+	// register the callback called after each mutation, the attribute changes, the child insertions
+	// and removals, and the text changes, return the id for `off_mutation`
+	fn on_mutation(&mut self, _callback: MutationCallback) -> Result<usize, Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "on_mutation".into(),
+			message: "The on_mutation method is not implemented.".into(),
+		}))
+	}
+	// unregister the callback by the id returned from `on_mutation`
+	fn off_mutation(&mut self, _id: usize) -> Result<(), Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "off_mutation".into(),
+			message: "The off_mutation method is not implemented.".into(),
+		}))
+	}
+	// run the handle in a transaction, the recorded mutations are rolled back if it returns an error,
+	// it relies on `on_mutation`
+	fn transaction(&mut self, handle: &mut TransactionHandle) -> Result<(), Box<dyn Error>> {
+		let tx = Transaction::new(self.get_root_node());
+		let id = self.on_mutation(tx.journal())?;
+		let journal = Journal {
+			doc: self,
+			id: Some(id),
+		};
+		let result = handle(&tx);
+		journal.off()?;
+		if result.is_err() {
+			tx.rollback()?;
+		}
+		result
+	}
+	// visit all the nodes of the document, the visitor can remove or replace the nodes and skip the children
+	fn visit_mut(&mut self, visitor: &mut dyn NodeVisitor) -> Result<(), Box<dyn Error>> {
+		visit_mut(&self.get_root_node(), visitor)
+	}
+	// lint the document, find the invalid nesting, duplicate ids and unknown elements
+	fn lint<'b>(&self) -> Vec<LintIssue<'b>> {
+		match self.get_root_node().typed().into_element() {
+			Some(root) => lint(&root),
+			None => Vec::new(),
+		}
+	}
+	// the groups of the elements sharing the same id in document order, the `#id` selector and
+	// `get_element_by_id` only find the first element of each group, the empty ids are skipped
+	fn duplicate_ids<'b>(&self) -> Vec<(String, Elements<'b>)> {
+		let mut groups: Vec<(String, Elements<'b>)> = Vec::new();
+		let mut indexes: HashMap<String, usize> = HashMap::new();
+		if let Some(root) = self.get_root_node().typed().into_element() {
+			for ele in root.childrens().get_ref() {
+				if let Some(id) = element_id(ele) {
+					let index = *indexes.entry(id.clone()).or_insert_with(|| {
+						groups.push((id, Elements::new()));
+						groups.len() - 1
+					});
+					groups[index].1.push(ele.cloned());
+				}
+			}
+		}
+		groups.retain(|(_, eles)| eles.length() > 1);
+		groups
+	}
+	// keep the id of the first element in each duplicate group and rename the others to `{prefix}{id}-{n}`,
+	// the `n` counts from 1 and skips the ids already used, return the old and the new ids in document order
+	fn uniquify_ids(&mut self, prefix: &str) -> Vec<(String, String)> {
+		let mut renamed = Vec::new();
+		let root = match self.get_root_node().typed().into_element() {
+			Some(root) => root,
+			None => return renamed,
+		};
+		let eles = root.childrens();
+		let mut used: HashSet<String> = HashSet::new();
+		for ele in eles.get_ref() {
+			if let Some(id) = element_id(ele) {
+				used.insert(id);
+			}
+		}
+		// the ids have been seen and the last `n` of them
+		let mut seen: HashMap<String, usize> = HashMap::new();
+		for ele in eles.get_ref() {
+			let id = match element_id(ele) {
+				Some(id) => id,
+				None => continue,
+			};
+			let n = match seen.get_mut(&id) {
+				Some(n) => n,
+				None => {
+					seen.insert(id, 0);
+					continue;
+				}
+			};
+			let new_id = loop {
+				*n += 1;
+				let new_id = format!("{}{}-{}", prefix, id, n);
+				if !used.contains(&new_id) {
+					break new_id;
+				}
+			};
+			ele.cloned().set_attribute("id", Some(&new_id));
+			used.insert(new_id.clone());
+			renamed.push((id, new_id));
+		}
+		renamed
+	}
+	// minify the document, remove the comments, whitespaces and redundant attributes
+	fn minify(&self, options: MinifyOptions) -> String {
+		minify(&self.get_root_node(), options)
+	}
+	// format the document, one block element per line
+	fn format(&self, options: FormatOptions) -> String {
+		format(&self.get_root_node(), options)
+	}
+	// serialize the document in its declared charset, `utf-8` if no charset is declared,
+	// the characters can't be represented in the charset are written as numeric character references,
+	// it fails if they are in the comments or the raw texts such as `script`, see `serialize::encode_html`
+	#[cfg(feature = "encoding")]
+	fn encode_html(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+		let html = serialize(&self.get_root_node(), &Default::default())?;
+		let charset = self.charset().unwrap_or_else(|| String::from("utf-8"));
+		Ok(encode_html(&html, &charset)?)
+	}
 	// onerror
 	fn onerror(&self) -> Option<Rc<IErrorHandle>> {
 		None
@@ -1,12 +1,25 @@
-use super::{BoxDynNode, BoxDynText, Elements, INodeTrait, INodeType, Texts};
-use crate::{constants::DEF_NODES_LEN, error::Error as IError};
+use super::namespace::{infer_namespace, is_html_element};
+use super::{
+	build_selector, content_hash, node_path, text_with, BoxDynNode, BoxDynText, ClassList, Elements,
+	HashOptions, IEnumTyped, INodeTrait, INodeType, NodePath, SelectorStrategy, TextOptions, Texts,
+	TextsOptions,
+};
+use crate::{
+	constants::DEF_NODES_LEN,
+	css::computed_style,
+	error::Error as IError,
+	html::spec::is_text_only_element,
+	utils::{resolve_url, split_attr_name},
+};
 use std::error::Error;
+use std::fmt;
 use std::ops::Range;
+use std::str::FromStr;
 
 pub type BoxDynElement<'a> = Box<dyn IElementTrait + 'a>;
 pub type MaybeElement<'a> = Option<BoxDynElement<'a>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum IAttrValue {
 	Value(String, Option<char>),
 	True,
@@ -24,6 +37,14 @@ impl IAttrValue {
 			IAttrValue::True => false,
 		}
 	}
+	/// pub fn `as_str`
+	/// the value, the attribute without value is an empty string
+	pub fn as_str(&self) -> &str {
+		match self {
+			IAttrValue::Value(v, _) => v,
+			IAttrValue::True => "",
+		}
+	}
 	/// pub fn `to_list`
 	pub fn to_list(&self) -> Vec<&str> {
 		match self {
@@ -33,12 +54,12 @@ impl IAttrValue {
 	}
 }
 
-/// impl `ToString` for IAttrValue
-impl ToString for IAttrValue {
-	fn to_string(&self) -> String {
+/// impl `Display` for IAttrValue
+impl fmt::Display for IAttrValue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
-			IAttrValue::Value(v, _) => v.clone(),
-			IAttrValue::True => String::new(),
+			IAttrValue::Value(v, _) => f.write_str(v),
+			IAttrValue::True => Ok(()),
 		}
 	}
 }
@@ -51,6 +72,16 @@ pub enum InsertPosition {
 	AfterEnd,
 }
 
+/// how the element already in a tree is inserted
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InsertMode {
+	// detach the element from its parent and insert it, like the dom
+	#[default]
+	Move,
+	// insert a deep copy of the element, the element is kept where it is
+	Clone,
+}
+
 impl InsertPosition {
 	pub fn action(&self) -> &'static str {
 		use InsertPosition::*;
@@ -63,6 +94,140 @@ impl InsertPosition {
 	}
 }
 
+// decode the entities may appear in the attribute values of the serialized html
+pub(crate) fn decode_attr_value(value: &str) -> String {
+	if !value.contains('&') {
+		return String::from(value);
+	}
+	value
+		.replace("&quot;", "\"")
+		.replace("&#39;", "'")
+		.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&nbsp;", "\u{00A0}")
+		.replace("&amp;", "&")
+}
+
+// insert the child nodes of the holder at the position and return them, the holder is a shallow copy of
+// the position's parent filled by `fill`, so the html is parsed in the context of the parent without
+// copying the parent's descendants. the backends without `clone_shallow` fall back to `clone_deep`
+pub(crate) fn insert_adjacent_nodes<'b>(
+	ele: &BoxDynElement,
+	position: &InsertPosition,
+	method: &str,
+	fill: &dyn Fn(&mut BoxDynElement),
+) -> Result<Vec<BoxDynNode<'b>>, Box<dyn Error>> {
+	let (mut parent, index) = match position {
+		InsertPosition::AfterBegin => (ele.cloned(), 0),
+		InsertPosition::BeforeEnd => (ele.cloned(), ele.child_nodes_length()),
+		InsertPosition::BeforeBegin | InsertPosition::AfterEnd => {
+			let parent = ele.parent().ok_or_else(|| IError::InvalidTraitMethodCall {
+				method: method.into(),
+				message: format!("Can't {} the element without parent.", position.action()),
+			})?;
+			let index = match position {
+				InsertPosition::AfterEnd => ele.index() + 1,
+				_ => ele.index(),
+			};
+			(parent, index)
+		}
+	};
+	let mut holder = match parent.clone_shallow() {
+		Ok(holder) => holder,
+		Err(_) => {
+			let mut holder = parent.clone_deep()?;
+			holder.set_html("");
+			holder
+		}
+	};
+	fill(&mut holder);
+	let nodes = holder.child_nodes();
+	for (offset, node) in nodes.iter().enumerate() {
+		parent.insert_child(index + offset, node)?;
+	}
+	Ok(nodes)
+}
+
+// parse the attributes of the start tag at the beginning of the html
+pub(crate) fn parse_start_tag_attrs(html: &str) -> Vec<(String, IAttrValue)> {
+	let mut attrs = Vec::new();
+	let mut chars = html.trim_start().chars().peekable();
+	if chars.next() != Some('<') {
+		return attrs;
+	}
+	// skip the tag name
+	while let Some(ch) = chars.peek() {
+		if ch.is_ascii_whitespace() || *ch == '/' || *ch == '>' {
+			break;
+		}
+		chars.next();
+	}
+	loop {
+		while let Some(ch) = chars.peek() {
+			if ch.is_ascii_whitespace() || *ch == '/' {
+				chars.next();
+			} else {
+				break;
+			}
+		}
+		let mut name = String::new();
+		while let Some(&ch) = chars.peek() {
+			if ch.is_ascii_whitespace() || ch == '=' || ch == '>' || (ch == '/' && !name.is_empty()) {
+				break;
+			}
+			name.push(ch);
+			chars.next();
+		}
+		if name.is_empty() {
+			break;
+		}
+		while let Some(ch) = chars.peek() {
+			if ch.is_ascii_whitespace() {
+				chars.next();
+			} else {
+				break;
+			}
+		}
+		if chars.peek() != Some(&'=') {
+			attrs.push((name, IAttrValue::True));
+			continue;
+		}
+		chars.next();
+		while let Some(ch) = chars.peek() {
+			if ch.is_ascii_whitespace() {
+				chars.next();
+			} else {
+				break;
+			}
+		}
+		let mut value = String::new();
+		let quote = match chars.peek() {
+			Some(&quote) if quote == '"' || quote == '\'' => {
+				chars.next();
+				for ch in chars.by_ref() {
+					if ch == quote {
+						break;
+					}
+					value.push(ch);
+				}
+				Some(quote)
+			}
+			_ => {
+				while let Some(&ch) = chars.peek() {
+					if ch.is_ascii_whitespace() || ch == '>' {
+						break;
+					}
+					value.push(ch);
+					chars.next();
+				}
+				None
+			}
+		};
+		attrs.push((name, IAttrValue::Value(decode_attr_value(&value), quote)));
+	}
+	attrs
+}
+
 pub trait IElementTrait: INodeTrait {
 	fn is(&self, ele: &BoxDynElement) -> bool {
 		if let Some(uuid) = self.uuid() {
@@ -234,6 +399,17 @@ pub trait IElementTrait: INodeTrait {
 	}
 	// tag name
 	fn tag_name(&self) -> &str;
+	// the local name, case-sensitive for the svg and mathml elements such as `linearGradient`,
+	// the lowercase tag name of the html elements, in camel case for the svg elements
+	fn local_name(&self) -> &str;
+	// the namespace uri of the element, such as `http://www.w3.org/2000/svg` for the svg elements,
+	// `None` for the other nodes. inferred from the tag names of the ancestors by default
+	fn namespace_uri(&self) -> Option<&str> {
+		if !matches!(self.node_type(), INodeType::Element) {
+			return None;
+		}
+		Some(infer_namespace(&self.cloned()))
+	}
 	// childs
 	fn child_nodes_length(&self) -> usize;
 	fn child_nodes_item<'b>(&self, index: usize) -> Option<BoxDynNode<'b>>;
@@ -275,13 +451,142 @@ pub trait IElementTrait: INodeTrait {
 		}
 		Elements::new()
 	}
+	// the descendants matched the selector in document order, as the dom api the selector is matched
+	// in the whole document, so `#main a` finds the links in the `#main` element itself
+	fn query_selector_all<'b>(&self, selector: &str) -> Elements<'b> {
+		self.childrens().filter(selector)
+	}
+	// the first descendant matched the selector
+	fn query_selector<'b>(&self, selector: &str) -> MaybeElement<'b> {
+		self
+			.query_selector_all(selector)
+			.get(0)
+			.map(|ele| ele.cloned())
+	}
+	// the document of the element in a set, so the selectors are matched from the root, such as
+	// `ele.document().find("#main")`, the set is empty if the element has no owner document
+	fn document<'b>(&self) -> Elements<'b> {
+		match self.root_element() {
+			Some(root) => Elements::with_nodes(vec![root]),
+			None => Elements::new(),
+		}
+	}
 	// attribute
 	fn get_attribute(&self, name: &str) -> Option<IAttrValue>;
+	// set the attribute, an existing attribute keeps its position and a new one is appended to the end,
+	// so the serialized html isn't reordered by the updates
 	fn set_attribute(&mut self, name: &str, value: Option<&str>);
 	fn remove_attribute(&mut self, name: &str);
 	fn has_attribute(&self, name: &str) -> bool {
 		self.get_attribute(name).is_some()
 	}
+	// all the attributes in source order, then the appended ones in insertion order,
+	// parsed from the start tag of `outer_html` by default
+	fn attributes(&self) -> Vec<(String, IAttrValue)> {
+		parse_start_tag_attrs(self.outer_html())
+	}
+	// get the attribute by the namespace prefix and the local name, the prefix `None` means no prefix,
+	// `Some("*")` means any prefix, such as the selector `[*|href]`
+	fn get_attribute_ns(&self, prefix: Option<&str>, local_name: &str) -> Option<IAttrValue> {
+		match prefix {
+			None | Some("") => self.get_attribute(local_name),
+			Some("*") => self
+				.attributes()
+				.into_iter()
+				.find(|(name, _)| split_attr_name(name).1.eq_ignore_ascii_case(local_name))
+				.map(|(_, value)| value),
+			Some(prefix) => self.get_attribute(&format!("{}:{}", prefix, local_name)),
+		}
+	}
+	// the names of the attributes, in the same order as `attributes`
+	fn attribute_names(&self) -> Vec<String> {
+		self
+			.attributes()
+			.into_iter()
+			.map(|(name, _)| name)
+			.collect()
+	}
+	// add the attribute without value if `force` is true and it's missing, remove it if `force` is false,
+	// such as the boolean attribute `hidden`, return if the attribute exists after the call
+	fn toggle_attribute(&mut self, name: &str, force: bool) -> bool {
+		if !force {
+			self.remove_attribute(name);
+		} else if !self.has_attribute(name) {
+			self.set_attribute(name, None);
+		}
+		force
+	}
+	// rename the attribute with its value, such as `data-src` to `src`, the existing attribute named `to`
+	// is overwritten, return false if the attribute is missing
+	fn rename_attribute(&mut self, from: &str, to: &str) -> bool {
+		let value = match self.get_attribute(from) {
+			Some(value) => value,
+			None => return false,
+		};
+		if from.eq_ignore_ascii_case(to) {
+			return true;
+		}
+		self.remove_attribute(from);
+		match value {
+			IAttrValue::Value(value, _) => self.set_attribute(to, Some(&value)),
+			IAttrValue::True => self.set_attribute(to, None),
+		}
+		true
+	}
+	// copy the attributes from the other element, all the attributes if `names` is empty,
+	// the attributes missing in the other element are kept
+	fn copy_attributes_from(&mut self, other: &BoxDynElement, names: &[&str]) {
+		let attrs = if names.is_empty() {
+			other.attributes()
+		} else {
+			names
+				.iter()
+				.filter_map(|name| Some((String::from(*name), other.get_attribute(name)?)))
+				.collect()
+		};
+		for (name, value) in attrs {
+			match value {
+				IAttrValue::Value(value, _) => self.set_attribute(&name, Some(&value)),
+				IAttrValue::True => self.set_attribute(&name, None),
+			}
+		}
+	}
+	// the live class names, the changes are written back to the `class` attribute
+	fn class_list<'b>(&self) -> ClassList<'b> {
+		ClassList::new(self.cloned())
+	}
+	// the url attribute such as `href` resolved against the base url of the owner document,
+	// `None` if the attribute is missing, or the url is relative and there is no absolute base url
+	fn absolute_attr(&self, name: &str) -> Option<String> {
+		let value = match self.get_attribute(name)? {
+			IAttrValue::Value(value, _) => value,
+			IAttrValue::True => String::new(),
+		};
+		let base = self
+			.owner_document()
+			.and_then(|doc| doc.base_url())
+			.unwrap_or_default();
+		resolve_url(&base, &value)
+	}
+	// the boolean attributes such as `disabled` are true when present, whatever the value is,
+	// so `disabled="false"` is still true
+	fn get_bool_attribute(&self, name: &str) -> bool {
+		self.has_attribute(name)
+	}
+	// the enumerated attributes such as `contenteditable`, return the keyword matched the value ASCII
+	// case-insensitively, the attribute without value matches the empty keyword `""`.
+	// `None` means the attribute is missing or the value is invalid, so the caller can apply
+	// the attribute's own missing value default or invalid value default
+	fn get_enum_attribute<'k>(&self, name: &str, keywords: &[&'k str]) -> Option<&'k str> {
+		let value = match self.get_attribute(name)? {
+			IAttrValue::Value(value, _) => value,
+			IAttrValue::True => String::new(),
+		};
+		keywords
+			.iter()
+			.find(|keyword| keyword.eq_ignore_ascii_case(&value))
+			.copied()
+	}
 	// html
 	fn html(&self) -> &str {
 		self.inner_html()
@@ -289,13 +594,229 @@ pub trait IElementTrait: INodeTrait {
 	fn inner_html(&self) -> &str;
 	fn outer_html(&self) -> &str;
 
-	// append child, insert before, remove child
+	// the inert contents of the `template` element, a document fragment not in its child nodes,
+	// `None` for the other elements
+	fn template_content<'b>(&self) -> Option<BoxDynElement<'b>> {
+		None
+	}
+	// a deep copy of the element and its descendants, not in the document tree
+	fn clone_deep<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "clone_deep".into(),
+			message: "The clone_deep method is not implemented.".into(),
+		}))
+	}
+	// a copy of the element without its child nodes, the tag name, the namespace and the attributes are kept,
+	// not in the document tree, the html is parsed in it as in the element
+	fn clone_shallow<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "clone_shallow".into(),
+			message: "The clone_shallow method is not implemented.".into(),
+		}))
+	}
+	// append child, insert before, remove child, the element in a tree is detached from its parent first
 	fn insert_adjacent(&mut self, position: &InsertPosition, ele: &BoxDynElement);
+	// insert the element or its deep copy by the mode, return the inserted one. moving the element itself
+	// or its ancestors is an error, the copy relies on `clone_deep`. the element of another document
+	// is adopted by the owner document first, so the returned one may be a new node.
+	// a document fragment such as the `template_content` is instantiated, the copies of its child nodes
+	// are inserted whatever the mode is, and the fragment itself is returned
+	fn insert_adjacent_with<'b>(
+		&mut self,
+		position: &InsertPosition,
+		ele: &BoxDynElement,
+		mode: InsertMode,
+	) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		if matches!(
+			ele.node_type(),
+			INodeType::Document | INodeType::DocumentFragement
+		) {
+			let html = ele.inner_html();
+			insert_adjacent_nodes(
+				&self.cloned(),
+				position,
+				"insert_adjacent_with",
+				&|holder| holder.set_html(html),
+			)?;
+			return Ok(ele.cloned());
+		}
+		if matches!(
+			position,
+			InsertPosition::BeforeBegin | InsertPosition::AfterEnd
+		) && self.parent().is_none()
+		{
+			return Err(Box::new(IError::InvalidTraitMethodCall {
+				method: "insert_adjacent_with".into(),
+				message: format!("Can't {} the element without parent.", position.action()),
+			}));
+		}
+		let inserted = match mode {
+			InsertMode::Move => {
+				let mut cur = Some(self.cloned());
+				while let Some(node) = cur {
+					if node.is(ele) {
+						return Err(Box::new(IError::InvalidTraitMethodCall {
+							method: "insert_adjacent_with".into(),
+							message: format!(
+								"Can't {} the element into itself or its descendants.",
+								position.action()
+							),
+						}));
+					}
+					cur = node.parent();
+				}
+				ele.cloned()
+			}
+			InsertMode::Clone => ele.clone_deep()?,
+		};
+		let inserted = match self.owner_document() {
+			Some(mut doc) => doc
+				.adopt(&inserted.clone_node())?
+				.typed()
+				.into_element()
+				.ok_or("The adopted node must be an element.")?,
+			None => inserted,
+		};
+		self.insert_adjacent(position, &inserted);
+		Ok(inserted)
+	}
 	fn remove_child(&mut self, ele: BoxDynElement);
+	// insert the node, such as a removed text, as the child at the index, or append it if the index is out of range
+	fn insert_child(&mut self, _index: usize, _node: &BoxDynNode) -> Result<(), Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "insert_child".into(),
+			message: "The insert_child method is not implemented.".into(),
+		}))
+	}
+	// parse the html in the context of the parent at the position and insert the nodes, the texts and
+	// comments are kept, it relies on `clone_shallow` and `insert_child`
+	fn insert_adjacent_html(
+		&mut self,
+		position: &InsertPosition,
+		html: &str,
+	) -> Result<(), Box<dyn Error>> {
+		insert_adjacent_nodes(
+			&self.cloned(),
+			position,
+			"insert_adjacent_html",
+			&|holder| holder.set_html(html),
+		)?;
+		Ok(())
+	}
+	// parse the source as the new outer html of the element in the context of its parent and replace the
+	// element by the parsed nodes, the other nodes are kept, return the new top level elements,
+	// only the source is parsed, the parent is copied without its descendants as the parsing context,
+	// it relies on `clone_shallow` and `insert_child`
+	fn reparse<'b>(&mut self, source: &str) -> Result<Elements<'b>, Box<dyn Error>> {
+		let mut parent = self
+			.parent()
+			.ok_or_else(|| IError::InvalidTraitMethodCall {
+				method: "reparse".into(),
+				message: "Can't reparse the element without parent.".into(),
+			})?;
+		let inserted = insert_adjacent_nodes(
+			&self.cloned(),
+			&InsertPosition::BeforeBegin,
+			"reparse",
+			&|holder| holder.set_html(source),
+		)?;
+		parent.remove_child(self.cloned());
+		Ok(Elements::with_nodes(
+			inserted
+				.into_iter()
+				.filter_map(|node| node.typed().into_element())
+				.collect(),
+		))
+	}
+	// insert a text node at the position, the text is not parsed, nothing is inserted if it's empty
+	fn insert_adjacent_text(
+		&mut self,
+		position: &InsertPosition,
+		text: &str,
+	) -> Result<(), Box<dyn Error>> {
+		insert_adjacent_nodes(
+			&self.cloned(),
+			position,
+			"insert_adjacent_text",
+			&|holder| holder.set_text(text),
+		)?;
+		Ok(())
+	}
+	// the style of the property resolved from the document's `<style>` elements and the inline style,
+	// only the properties in `css::COMPUTED_PROPERTIES` are supported
+	fn computed_style(&self, property: &str) -> Option<String> {
+		let stylesheet = match self.owner_document() {
+			Some(doc) => doc.stylesheet(),
+			None => Default::default(),
+		};
+		computed_style(&self.cloned(), &stylesheet, property)
+	}
 	// texts
-	fn texts<'b>(&self, _limit_depth: u32) -> Option<Texts<'b>> {
+	// `limit_depth` 0 means no limit, 1 means only the child text nodes
+	fn texts<'b>(&self, limit_depth: u32) -> Option<Texts<'b>> {
+		Some(self.texts_with(&TextsOptions {
+			limit_depth,
+			..Default::default()
+		}))
+	}
+	// the text nodes in document order, filtered by the options
+	fn texts_with<'b>(&self, options: &TextsOptions) -> Texts<'b> {
+		fn loop_handle<'b>(
+			child_nodes: Vec<BoxDynNode<'b>>,
+			depth: u32,
+			options: &TextsOptions,
+			result: &mut Texts<'b>,
+		) {
+			for node in child_nodes {
+				match node.typed() {
+					IEnumTyped::Text(text)
+						if !(options.skip_whitespace && text.text_content().trim().is_empty()) =>
+					{
+						result.get_mut_ref().push(text);
+					}
+					IEnumTyped::Element(ele)
+						if (options.limit_depth == 0 || depth < options.limit_depth)
+							&& (options.include_content_tags
+								|| !(is_text_only_element(ele.tag_name()) && is_html_element(&ele))) =>
+					{
+						loop_handle(ele.child_nodes(), depth + 1, options, result);
+					}
+					_ => {}
+				}
+			}
+		}
+		let mut result = Texts::with_capacity(DEF_NODES_LEN);
+		loop_handle(self.child_nodes(), 1, options, &mut result);
+		result
+	}
+	// the language inherited from the closest element with the `lang` attribute, the empty value means unknown
+	fn lang(&self) -> Option<String> {
+		let mut cur = Some(self.cloned());
+		while let Some(ele) = cur {
+			if let Some(IAttrValue::Value(value, _)) = ele.get_attribute("lang") {
+				return Some(value);
+			}
+			cur = ele.parent();
+		}
 		None
 	}
+	// a stable hash of the element and its descendants, the same for the same normalized html,
+	// the attribute order doesn't matter, used to detect the changes and the duplicate records
+	fn content_hash(&self, options: &HashOptions) -> u64 {
+		content_hash(&self.cloned(), options)
+	}
+	// a css selector matched only the element in the document, used to find the element again
+	fn build_selector(&self, strategy: SelectorStrategy) -> String {
+		build_selector(&self.cloned(), strategy)
+	}
+	// the structural path from the document, resolved again by `IDocumentTrait::from_path`
+	fn node_path(&self) -> NodePath {
+		node_path(&self.cloned())
+	}
+	// the text of the descendants with the whitespace and block options
+	fn text_with(&self, options: &TextOptions) -> String {
+		text_with(&[self.cloned()], options)
+	}
 	// special for content tag, 'style','script','title','textarea'
 	#[allow(clippy::boxed_local)]
 	fn into_text<'b>(self: Box<Self>) -> Result<BoxDynText<'b>, Box<dyn Error>> {
@@ -305,3 +826,23 @@ pub trait IElementTrait: INodeTrait {
 		}))
 	}
 }
+
+// the elements are equal if they are the same node, the same as `is`
+impl<'a> PartialEq for dyn IElementTrait + 'a {
+	fn eq(&self, other: &Self) -> bool {
+		self.is(&other.cloned())
+	}
+}
+
+impl<'a> dyn IElementTrait + 'a {
+	/// pub fn `parsed_attribute`
+	/// parse the attribute value as the type, such as `Srcset` and `InlineStyle`,
+	/// `None` means the attribute is missing or the value is invalid
+	pub fn parsed_attribute<T: FromStr>(&self, name: &str) -> Option<T> {
+		let value = match self.get_attribute(name)? {
+			IAttrValue::Value(value, _) => value,
+			IAttrValue::True => String::new(),
+		};
+		value.parse().ok()
+	}
+}
@@ -1,18 +1,40 @@
-use super::{BoxDynElement, IAttrValue, INodeType, InsertPosition, MaybeDoc, Texts};
-use crate::utils::{get_class_list, retain_by_index, to_static_str};
-use crate::{constants::ATTR_CLASS, error::Error as IError};
+use super::{
+	text_blocks, text_stats, text_with, BoxDynElement, ClassList, IAttrValue, INodeType, InsertMode,
+	InsertPosition, MaybeDoc, TextBlock, TextOptions, TextStats, Texts, TextsOptions,
+};
+#[cfg(feature = "regex")]
+use super::{BoxDynText, IEnumTyped, TextMatch};
+use crate::css::{scope_css, MatchedRule, Stylesheet};
+use crate::error::Error as IError;
+#[cfg(feature = "serde")]
+use crate::extract::ExtractSchema;
+use crate::extract::{extract_images, extract_table, FormData, Image, Table};
+#[cfg(feature = "regex")]
+use crate::html::spec::is_text_only_element;
+use crate::rules::Registry;
+#[cfg(feature = "serde")]
+use crate::template::{render, Context};
+use crate::utils::{retain_by_index, to_static_str};
 use crate::{
 	constants::DEF_NODES_LEN,
 	selector::{
+		is_exceeded,
 		rule::{MatchAllHandle, MatchOneHandle},
-		Combinator, QueryProcess, Selector, SelectorSegment,
+		trace::{QueryTrace, TraceGroup, TraceStep},
+		with_budget, Combinator, QueryBudget, QueryProcess, Selector, SelectorSegment,
 	},
 };
+#[cfg(feature = "regex")]
+use regex::Regex;
+#[cfg(feature = "regex")]
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::hash::Hash;
 use std::{
 	cmp::Ordering,
 	collections::VecDeque,
 	ops::{Bound, RangeBounds},
+	time::Instant,
 };
 use std::{collections::HashMap, error::Error};
 
@@ -29,6 +51,34 @@ fn get_tree_indexs(ele: &BoxDynElement) -> VecDeque<usize> {
 	indexs
 }
 
+// the element and its ancestor elements, from the outermost ancestor to the element
+fn element_path<'b>(ele: &BoxDynElement<'b>) -> Vec<BoxDynElement<'b>> {
+	let mut path = vec![ele.cloned()];
+	let mut cur = ele.parent();
+	while let Some(parent) = cur {
+		if !matches!(parent.node_type(), INodeType::Element) {
+			break;
+		}
+		cur = parent.parent();
+		path.push(parent);
+	}
+	path.reverse();
+	path
+}
+
+// visit the descendants in document order, stop and return false when the handle returns false
+fn visit_descendants<'b>(
+	ele: &BoxDynElement<'b>,
+	handle: &mut dyn FnMut(&BoxDynElement<'b>) -> bool,
+) -> bool {
+	for child in ele.children().get_ref() {
+		if !handle(child) || !visit_descendants(child, handle) {
+			return false;
+		}
+	}
+	true
+}
+
 // compare indexs
 fn compare_indexs(a: &VecDeque<usize>, b: &VecDeque<usize>) -> Ordering {
 	let a_total = a.len();
@@ -201,7 +251,67 @@ impl<'a> Elements<'a> {
 		}
 		result
 	}
+	/// pub fn `group_by`
+	/// group the elements by the key of the handle, the groups are in the order of their first elements
+	pub fn group_by<F, K>(&self, handle: F) -> Vec<(K, Elements<'a>)>
+	where
+		F: Fn(&BoxDynElement) -> K,
+		K: Eq + Hash + Clone,
+	{
+		let mut result: Vec<(K, Elements<'a>)> = Vec::new();
+		let mut indexes: HashMap<K, usize> = HashMap::new();
+		for ele in self.get_ref() {
+			let key = handle(ele);
+			match indexes.get(&key) {
+				Some(&index) => result[index].1.push(ele.cloned()),
+				None => {
+					let mut group = Elements::new();
+					group.push(ele.cloned());
+					indexes.insert(key.clone(), result.len());
+					result.push((key, group));
+				}
+			}
+		}
+		result
+	}
 
+	/// pub fn `retain`
+	/// keep the elements matched the handle in place, without a new allocation
+	pub fn retain<F>(&mut self, mut handle: F) -> &mut Self
+	where
+		F: FnMut(usize, &BoxDynElement) -> bool,
+	{
+		let mut index = 0;
+		self.nodes.retain(|ele| {
+			let keep = handle(index, ele);
+			index += 1;
+			keep
+		});
+		self
+	}
+	/// pub fn `truncate`
+	/// keep the first `len` elements
+	pub fn truncate(&mut self, len: usize) -> &mut Self {
+		self.nodes.truncate(len);
+		self
+	}
+	/// pub fn `drain`
+	/// remove the elements in the range and return them, the range is clamped to the length
+	pub fn drain<T: RangeBounds<usize>>(&mut self, range: T) -> Elements<'a> {
+		let total = self.length();
+		let start = match range.start_bound() {
+			Bound::Included(&start) => start.min(total),
+			Bound::Excluded(&start) => start.saturating_add(1).min(total),
+			Bound::Unbounded => 0,
+		};
+		let end = match range.end_bound() {
+			Bound::Included(&end) => end.saturating_add(1).min(total),
+			Bound::Excluded(&end) => end.min(total),
+			Bound::Unbounded => total,
+		}
+		.max(start);
+		Elements::with_nodes(self.nodes.drain(start..end).collect())
+	}
 	/// pub fn `length`
 	pub fn length(&self) -> usize {
 		self.nodes.len()
@@ -210,8 +320,23 @@ impl<'a> Elements<'a> {
 	pub fn is_empty(&self) -> bool {
 		self.length() == 0
 	}
+	/// pub fn `position_of`
+	/// the index of the element in the set, compared by the node identity
+	pub fn position_of(&self, ele: &BoxDynElement) -> Option<usize> {
+		self.index_of(ele, 0)
+	}
+	/// pub fn `eq_node`
+	/// the two sets have the same nodes in the same order, compared by the node identity
+	pub fn eq_node(&self, other: &Elements) -> bool {
+		self.length() == other.length()
+			&& self
+				.get_ref()
+				.iter()
+				.zip(other.get_ref())
+				.all(|(ele, other_ele)| ele.is(other_ele))
+	}
 	/// pub fn `document`, a quick way to get document
-	pub fn document(&self) -> MaybeDoc {
+	pub fn document(&self) -> MaybeDoc<'_> {
 		for ele in self.get_ref() {
 			if let Some(doc) = ele.owner_document() {
 				return Some(doc);
@@ -245,7 +370,7 @@ impl<'a> Elements<'a> {
 				// parents
 				let indexs = get_tree_indexs(parent);
 				// new parent
-				if parents_indexs.get(&indexs).is_none() {
+				if !parents_indexs.contains(&indexs) {
 					parents_indexs.insert(indexs);
 					uniques.push(ele.cloned());
 				}
@@ -360,13 +485,9 @@ impl<'a> Elements<'a> {
 		}
 		result
 	}
-	// sort
+	// sort in document order, the tree indexs of each element are got once
 	fn sort(&mut self) {
-		self.get_mut_ref().sort_by(|a, b| {
-			let a_index = get_tree_indexs(a);
-			let b_index = get_tree_indexs(b);
-			compare_indexs(&a_index, &b_index)
-		});
+		self.get_mut_ref().sort_by_cached_key(get_tree_indexs);
 	}
 	// unique
 	fn unique(&mut self) {
@@ -384,16 +505,21 @@ impl<'a> Elements<'a> {
 **  [methods]
 */
 impl<'a> Elements<'a> {
-	// for all combinator selectors
+	// for all combinator selectors, the selector is a filter expression as jquery's, it's parsed once and
+	// matched against each element of the combinator, the empty selector keeps all the elements
 	fn select_with_comb(&self, method: &str, selector: &str, comb: Combinator) -> Elements<'a> {
-		if selector.is_empty() {
-			let segment = Selector::make_comb_all(comb);
-			let selector = Selector::from_segment(segment);
-			return self.find_selector(&selector);
+		let segment = Selector::make_comb_all(comb);
+		let result = self.find_selector(&Selector::from_segment(segment));
+		result.filter_by_expression(method, selector)
+	}
+	// filter the elements by the filter expression of the traversal methods
+	fn filter_by_expression(self, method: &str, selector: &str) -> Elements<'a> {
+		let selector = selector.trim();
+		if selector.is_empty() || self.is_empty() {
+			return self;
 		}
 		self.trigger_method(method, selector, |selector| {
-			selector.head_combinator(comb);
-			self.find_selector(&selector)
+			self.filter_type_handle(selector, &FilterType::Filter).0
 		})
 	}
 	// for all combinator until selectors
@@ -429,7 +555,7 @@ impl<'a> Elements<'a> {
 						// find the next element
 						cur_eles = cur_eles.find_selector(&next_selector);
 						if !cur_eles.is_empty() {
-							let meet_until = cur_eles.filter_type_handle(&selector, &FilterType::Is).1;
+							let meet_until = cur_eles.filter_type_handle(selector, &FilterType::Is).1;
 							// meet the until element, and not contains, stop before check element
 							if meet_until && !contains {
 								break;
@@ -501,41 +627,8 @@ impl<'a> Elements<'a> {
 	// siblings
 	pub fn siblings(&self, selector: &str) -> Elements<'a> {
 		let uniques = self.unique_all_siblings();
-		// when selector is empty or only
-		let mut siblings_selector: Selector;
-		let siblings_comb = Combinator::Siblings;
-		let mut child_selector: Selector;
-		let child_comb = Combinator::Children;
-		let selector = selector.trim();
-		if selector.is_empty() {
-			siblings_selector = Selector::from_segment(Selector::make_comb_all(siblings_comb));
-			child_selector = Selector::from_segment(Selector::make_comb_all(child_comb));
-		} else {
-			// self
-			let sib_selector = selector.parse::<Selector>();
-			if let Ok(sib_selector) = sib_selector {
-				// clone the selector to a child selector
-				child_selector = selector
-					.parse::<Selector>()
-					.expect("The selector has detected");
-				child_selector.head_combinator(child_comb);
-				// use siblings selector
-				siblings_selector = sib_selector;
-				siblings_selector.head_combinator(siblings_comb);
-			} else {
-				self.trigger_method_throw_error(
-					"siblings",
-					Box::new(IError::InvalidTraitMethodCall {
-						method: "siblings".to_string(),
-						message: format!(
-							"Invalid selector:{}",
-							sib_selector.err().expect("Selector parse error")
-						),
-					}),
-				);
-				return Elements::new();
-			}
-		}
+		let siblings_selector = Selector::from_segment(Selector::make_comb_all(Combinator::Siblings));
+		let child_selector = Selector::from_segment(Selector::make_comb_all(Combinator::Children));
 		// uniques
 		let mut result = Elements::with_capacity(DEF_NODES_LEN);
 		for (ele, is_parent) in &uniques {
@@ -549,7 +642,7 @@ impl<'a> Elements<'a> {
 		}
 		// sort the result
 		result.sort();
-		result
+		result.filter_by_expression("siblings", selector)
 	}
 	// children
 	pub fn children(&self, selector: &str) -> Elements<'a> {
@@ -691,7 +784,7 @@ impl<'a> Elements<'a> {
 									};
 								}
 								// check if the ele is in firsts
-								if tops.has_ele(ele, &first_comb, Some(&lookup[1..])) {
+								if tops.has_ele(ele, first_comb, Some(&lookup[1..])) {
 									cur_group.push(ele.cloned());
 									is_find = true;
 								} else {
@@ -730,14 +823,98 @@ impl<'a> Elements<'a> {
 				}
 			}
 		}
+		// the rules may collect the elements out of order, such as the siblings of a formula
+		result.sort_and_unique();
 		result
 	}
 
 	/// pub fn `find`
-	/// get elements by selector, support most of css selectors
+	/// get elements by selector, support most of css selectors.
+	/// the selector is matched in each element's descendants, when the elements are ancestors of each other,
+	/// the found elements are still matched once and kept in document order
 	pub fn find(&self, selector: &str) -> Elements<'a> {
 		self.trigger_method("find", selector, |selector| self.find_selector(selector))
 	}
+	// visit the elements `find` gets in document order, stop when the handle returns false,
+	// the selector without combinators is checked element by element without collecting the elements
+	fn visit_found(&self, selector: &Selector, handle: &mut dyn FnMut(&BoxDynElement<'a>) -> bool) {
+		// the elements found in a descendant are also found in its ancestor, only search the top elements
+		let mut scopes = self.cloned();
+		scopes.sort_and_unique();
+		let is_compound = selector.process.iter().all(|process| {
+			process.should_in.is_none()
+				&& process.query.len() == 1
+				&& process.query[0][0].1 == Combinator::ChildrenAll
+		});
+		for scope in scopes.unique_parents().get_ref() {
+			let is_continue = if is_compound {
+				visit_descendants(scope, &mut |ele| {
+					let (_, is_matched) =
+						Elements::with_node(ele).filter_type_handle(selector, &FilterType::Is);
+					!is_matched || handle(ele)
+				})
+			} else {
+				Elements::with_node(scope)
+					.find_selector(selector)
+					.get_ref()
+					.iter()
+					.all(&mut *handle)
+			};
+			if !is_continue {
+				return;
+			}
+		}
+	}
+	/// pub fn `find_with_budget`
+	/// the same as `find` but for the untrusted selectors, the query is aborted with an error once it
+	/// checks more elements or takes more time than the budget, the invalid selector is an error too
+	pub fn find_with_budget(
+		&self,
+		selector: &str,
+		budget: &QueryBudget,
+	) -> Result<Elements<'a>, IError> {
+		let selector = Selector::from_str(selector, false)?;
+		with_budget(budget, || self.find_selector(&selector))
+	}
+	/// pub fn `find_with_rules`
+	/// the same as `find` but the selector is parsed with the rules of the registry instead of the global rules
+	pub fn find_with_rules(
+		&self,
+		selector: &str,
+		registry: &Registry,
+	) -> Result<Elements<'a>, IError> {
+		let selector = Selector::from_str_with_rules(selector, false, registry)?;
+		Ok(self.find_selector(&selector))
+	}
+	/// pub fn `find_first`
+	/// get the first element of `find`, the search stops once an element is found
+	pub fn find_first(&self, selector: &str) -> Elements<'a> {
+		self.trigger_method("find_first", selector, |selector| {
+			let mut result = Elements::new();
+			self.visit_found(selector, &mut |ele| {
+				result.push(ele.cloned());
+				false
+			});
+			result
+		})
+	}
+	/// pub fn `count`
+	/// the count of the elements `find` gets, without collecting them
+	pub fn count(&self, selector: &str) -> usize {
+		self.trigger_method("count", selector, |selector| {
+			let mut total = 0;
+			self.visit_found(selector, &mut |_| {
+				total += 1;
+				true
+			});
+			total
+		})
+	}
+	/// pub fn `exists`
+	/// check if `find` gets any element, the search stops once an element is found
+	pub fn exists(&self, selector: &str) -> bool {
+		!self.find_first(selector).is_empty()
+	}
 	// select one rule
 	// the rule must not in cache
 	fn select_by_rule(
@@ -804,7 +981,7 @@ impl<'a> Elements<'a> {
 								for child in childs.get_ref() {
 									if cmp_index < total_matched {
 										let cmp_child = &matched_childs[cmp_index];
-										if child.is(&cmp_child) {
+										if child.is(cmp_child) {
 											cmp_index += 1;
 											result.get_mut_ref().push(child.cloned());
 										}
@@ -1010,7 +1187,7 @@ impl<'a> Elements<'a> {
 			}
 			Chain => {
 				// just filter
-				result = matcher.apply(&elements, None);
+				result = matcher.apply(elements, None);
 			}
 		};
 		result
@@ -1021,12 +1198,20 @@ impl<'a> Elements<'a> {
 		rules: &[SelectorSegment],
 		comb: Option<&Combinator>,
 	) -> Elements<'a> {
+		// the query over the budget ends quickly
+		if is_exceeded() {
+			return Elements::new();
+		}
 		let first_rule = &rules[0];
 		let comb = comb.unwrap_or(&first_rule.1);
-		let mut elements = if first_rule.0.in_cache && matches!(comb, Combinator::ChildrenAll) {
+		// the cache is looked up in the document, the detached subtrees are traversed
+		let use_cache = first_rule.0.in_cache
+			&& matches!(comb, Combinator::ChildrenAll)
+			&& elements.get_ref().iter().all(|ele| ele.is_connected());
+		let mut elements = if use_cache {
 			let (matcher, ..) = first_rule;
 			// set use cache true
-			let cached = matcher.apply(&elements, Some(true));
+			let cached = matcher.apply(elements, Some(true));
 			let count = cached.length();
 			if count > 0 {
 				let mut result = Elements::with_capacity(count);
@@ -1041,7 +1226,7 @@ impl<'a> Elements<'a> {
 				Elements::new()
 			}
 		} else {
-			Elements::select_by_rule(&elements, first_rule, Some(comb))
+			Elements::select_by_rule(elements, first_rule, Some(comb))
 		};
 		if !elements.is_empty() && rules.len() > 1 {
 			for rule in &rules[1..] {
@@ -1085,7 +1270,7 @@ impl<'a> Elements<'a> {
 			Parent => {
 				for ele in elements.get_ref() {
 					if let Some(parent) = &ele.parent() {
-						if self.includes(&parent) {
+						if self.includes(parent) {
 							return true;
 						}
 					}
@@ -1094,14 +1279,14 @@ impl<'a> Elements<'a> {
 			ParentAll => {
 				for ele in elements.get_ref() {
 					if let Some(parent) = &ele.parent() {
-						if self.includes(&parent) {
+						if self.includes(parent) {
 							return true;
 						}
 						if let Some(ancestor) = &parent.parent() {
-							if self.includes(&ancestor) {
+							if self.includes(ancestor) {
 								return true;
 							}
-							if self.has_ele(&ancestor, comb, None) {
+							if self.has_ele(ancestor, comb, None) {
 								return true;
 							}
 						}
@@ -1111,7 +1296,7 @@ impl<'a> Elements<'a> {
 			Prev => {
 				for ele in elements.get_ref() {
 					if let Some(prev) = &ele.previous_element_sibling() {
-						if self.includes(&prev) {
+						if self.includes(prev) {
 							return true;
 						}
 					}
@@ -1295,7 +1480,7 @@ impl<'a> Elements<'a> {
 	pub fn filter(&self, selector: &str) -> Elements<'a> {
 		const METHOD: &str = "filter";
 		self.trigger_method(METHOD, selector, |selector| {
-			self.filter_type_handle(&selector, &FilterType::Filter).0
+			self.filter_type_handle(selector, &FilterType::Filter).0
 		})
 	}
 
@@ -1319,6 +1504,22 @@ impl<'a> Elements<'a> {
 		self.filter_in_handle(search, FilterType::Filter).0
 	}
 
+	/// pub fn `partition`
+	/// split the elements into the matched and the unmatched elements of the selector, both keep the order
+	pub fn partition(&self, selector: &str) -> (Elements<'a>, Elements<'a>) {
+		let matched = self.filter(selector);
+		let mut unmatched = Elements::with_capacity(self.length() - matched.length());
+		let mut matched_eles = matched.get_ref().iter().peekable();
+		for ele in self.get_ref() {
+			if matched_eles.peek().is_some_and(|cur| cur.is(ele)) {
+				matched_eles.next();
+			} else {
+				unmatched.push(ele.cloned());
+			}
+		}
+		(matched, unmatched)
+	}
+
 	// is
 	pub fn is(&self, selector: &str) -> bool {
 		const METHOD: &str = "is";
@@ -1351,7 +1552,7 @@ impl<'a> Elements<'a> {
 	pub fn is_all(&self, selector: &str) -> bool {
 		const METHOD: &str = "is_all";
 		self.trigger_method(METHOD, selector, |selector| {
-			self.filter_type_handle(&selector, &FilterType::IsAll).1
+			self.filter_type_handle(selector, &FilterType::IsAll).1
 		})
 	}
 
@@ -1379,7 +1580,7 @@ impl<'a> Elements<'a> {
 	pub fn not(&self, selector: &str) -> Elements<'a> {
 		const METHOD: &str = "not";
 		self.trigger_method(METHOD, selector, |selector| {
-			self.filter_type_handle(&selector, &FilterType::Not).0
+			self.filter_type_handle(selector, &FilterType::Not).0
 		})
 	}
 
@@ -1403,7 +1604,9 @@ impl<'a> Elements<'a> {
 		self.filter_in_handle(search, FilterType::Not).0
 	}
 
-	// has
+	/// pub fn `has`
+	/// keep the elements containing a descendant matched the selector, like the `:has()` pseudo class,
+	/// the descendants are matched in the whole document as `is`, not only in the element as `find`
 	pub fn has(&self, selector: &str) -> Elements<'a> {
 		const METHOD: &str = "has";
 		fn loop_handle(ele: &BoxDynElement, selector: &Selector) -> bool {
@@ -1426,7 +1629,8 @@ impl<'a> Elements<'a> {
 		})
 	}
 
-	// has_in
+	/// pub fn `has_in`
+	/// keep the elements containing any of the search elements
 	pub fn has_in(&self, search: &Elements) -> Elements<'a> {
 		fn loop_handle(ele: &BoxDynElement, search: &Elements) -> bool {
 			let childs = ele.children();
@@ -1443,7 +1647,61 @@ impl<'a> Elements<'a> {
 			}
 			false
 		}
-		self.filter_by(|_, ele| loop_handle(ele, &search))
+		self.filter_by(|_, ele| loop_handle(ele, search))
+	}
+}
+
+/*
+*** Trace APIs
+**  [Methods]
+**  trace
+*/
+impl<'a> Elements<'a> {
+	/// pub fn `trace`
+	/// find the elements like `find`, but apply the rules step by step without the optimization,
+	/// record the matched and rejected candidates of each rule
+	pub fn trace(&self, selector: &str) -> Result<QueryTrace<'a>, IError> {
+		let start = Instant::now();
+		let chars: Vec<char> = selector.chars().collect();
+		let parsed = Selector::from_str(selector, false)?;
+		let mut trace = QueryTrace {
+			selector: String::from(selector),
+			..Default::default()
+		};
+		for QueryProcess { query, .. } in &parsed.process {
+			let mut group = TraceGroup::default();
+			let mut elements = self.cloned();
+			'group: for rules in query {
+				for segment in rules {
+					let step_start = Instant::now();
+					let (matcher, comb) = segment;
+					let candidates = match comb {
+						Combinator::Chain => elements.cloned(),
+						_ => Elements::select_by_rule(&elements, &Selector::make_comb_all(*comb), None),
+					};
+					let matched = Elements::select_by_rule(&elements, segment, None);
+					let rejected = candidates.not_in(&matched);
+					let (start, end) = matcher.span;
+					group.steps.push(TraceStep {
+						rule: chars[start..end].iter().collect::<String>(),
+						combinator: *comb,
+						candidates: candidates.length(),
+						matched: matched.cloned(),
+						rejected,
+						elapsed: step_start.elapsed(),
+					});
+					elements = matched;
+					if elements.is_empty() {
+						break 'group;
+					}
+				}
+			}
+			trace.result.get_mut_ref().extend(elements);
+			trace.groups.push(group);
+		}
+		trace.result.sort_and_unique();
+		trace.elapsed = start.elapsed();
+		Ok(trace)
 	}
 }
 
@@ -1555,14 +1813,14 @@ impl<'a> Elements<'a> {
 			first_indexs
 				.entry(index)
 				.or_insert_with(|| get_tree_indexs(&first[index]))
-		};
+		}
 		while fir_left_index <= fir_right_index && sec_left_index <= sec_right_index {
 			// the second left
 			let sec_left = &second[sec_left_index];
 			let sec_left_level = get_tree_indexs(sec_left);
 			// the first left
-			let fir_left_level = get_first_index_cached(&mut first_indexs, &first, fir_left_index);
-			match compare_indexs(&sec_left_level, &fir_left_level) {
+			let fir_left_level = get_first_index_cached(&mut first_indexs, first, fir_left_index);
+			match compare_indexs(&sec_left_level, fir_left_level) {
 				Ordering::Equal => {
 					// move forward both
 					sec_left_index += 1;
@@ -1571,8 +1829,8 @@ impl<'a> Elements<'a> {
 				Ordering::Greater => {
 					// second left is behind first left
 					// if second left is also behind first right
-					let fir_right_level = get_first_index_cached(&mut first_indexs, &first, fir_right_index);
-					match compare_indexs(&sec_left_level, &fir_right_level) {
+					let fir_right_level = get_first_index_cached(&mut first_indexs, first, fir_right_index);
+					match compare_indexs(&sec_left_level, fir_right_level) {
 						Ordering::Greater => {
 							// now second is all after first
 							afters.extend(sec_left_index..=sec_right_index);
@@ -1586,8 +1844,8 @@ impl<'a> Elements<'a> {
 							let mut mid = (l + r) / 2;
 							let mut find_equal = false;
 							while mid != l {
-								let mid_level = get_first_index_cached(&mut first_indexs, &first, mid);
-								match compare_indexs(&sec_left_level, &mid_level) {
+								let mid_level = get_first_index_cached(&mut first_indexs, first, mid);
+								match compare_indexs(&sec_left_level, mid_level) {
 									Ordering::Greater => {
 										// second left is behind middle
 										l = mid;
@@ -1623,7 +1881,7 @@ impl<'a> Elements<'a> {
 				Ordering::Less => {
 					let sec_right = &second[sec_right_index];
 					let sec_right_level = get_tree_indexs(sec_right);
-					match compare_indexs(&sec_right_level, &fir_left_level) {
+					match compare_indexs(&sec_right_level, fir_left_level) {
 						Ordering::Less => {
 							// now second is all before first
 							prevs.extend(sec_left_index..=sec_right_index);
@@ -1684,6 +1942,33 @@ impl<'a> Elements<'a> {
 		result
 	}
 
+	/// pub fn `common_ancestor`
+	/// the deepest element contains all the elements, an element contains itself, such as the list
+	/// container of the items, it's empty if the set is empty or the elements are in different trees
+	pub fn common_ancestor(&self) -> Elements<'a> {
+		let mut eles = self.get_ref().iter();
+		let mut path = match eles.next() {
+			Some(ele) => element_path(ele),
+			None => return Elements::new(),
+		};
+		for ele in eles {
+			let cur_path = element_path(ele);
+			let same_len = path
+				.iter()
+				.zip(&cur_path)
+				.take_while(|(ancestor, cur_ancestor)| ancestor.is(cur_ancestor))
+				.count();
+			path.truncate(same_len);
+			if path.is_empty() {
+				break;
+			}
+		}
+		match path.pop() {
+			Some(ancestor) => Elements::with_nodes(vec![ancestor]),
+			None => Elements::new(),
+		}
+	}
+
 	/// check if the ele list contains some ele
 	fn includes(&self, ele: &BoxDynElement) -> bool {
 		self.get_ref().iter().any(|n| ele.is(n))
@@ -1715,10 +2000,99 @@ impl<'a> Elements<'a> {
 	}
 }
 
+// wrap the matches in the adjacent text nodes, the matches are handled from the last so the offsets are kept
+#[cfg(feature = "regex")]
+fn wrap_text_run<'a>(
+	mut nodes: Vec<BoxDynText<'a>>,
+	regex: &Regex,
+	wrapper: &BoxDynElement,
+) -> Result<Vec<BoxDynElement<'a>>, Box<dyn Error>> {
+	let contents: Vec<String> = nodes
+		.iter()
+		.map(|node| String::from(node.text_content()))
+		.collect();
+	let joined = contents.concat();
+	let mut starts = Vec::with_capacity(contents.len());
+	let mut lens = Vec::with_capacity(contents.len());
+	let mut total = 0;
+	for content in &contents {
+		starts.push(total);
+		lens.push(content.len());
+		total += content.len();
+	}
+	// the index of the node contains the byte at the position
+	let locate = |pos: usize| -> usize {
+		(0..starts.len())
+			.find(|index| pos < starts[*index] + contents[*index].len())
+			.unwrap_or(starts.len() - 1)
+	};
+	let matches: Vec<(usize, usize)> = regex
+		.find_iter(&joined)
+		.filter(|found| found.start() < found.end())
+		.map(|found| (found.start(), found.end()))
+		.collect();
+	let mut wrappers = Vec::with_capacity(matches.len());
+	for (start, end) in matches.into_iter().rev() {
+		let (first, last) = (locate(start), locate(end - 1));
+		let end_offset = end - starts[last];
+		if end_offset < lens[last] {
+			nodes[last].split_text(end_offset)?;
+			lens[last] = end_offset;
+		}
+		let start_offset = start - starts[first];
+		let mut matched = if start_offset > 0 {
+			let matched = nodes[first].split_text(start_offset)?;
+			lens[first] = start_offset;
+			matched
+		} else {
+			nodes[first]
+				.clone_node()
+				.typed()
+				.into_text()
+				.expect("Text node must can use 'into_text'.")
+		};
+		if last > first {
+			// move the matched text of the following nodes into the first one
+			matched.append_text(&joined[starts[first + 1]..end]);
+			for node in nodes.drain(first + 1..=last) {
+				node.remove();
+			}
+		}
+		let copy = wrapper.clone_deep()?;
+		matched.wrap(&copy)?;
+		wrappers.push(copy);
+	}
+	wrappers.reverse();
+	Ok(wrappers)
+}
+
+// the content tags, the contents of the script and the style are raw texts, the template's is html
+const CONTENT_TAGS: [&str; 3] = ["script", "style", "template"];
+
+// the raw content of the content tag
+fn raw_content(ele: &BoxDynElement) -> String {
+	if ele.tag_name().eq_ignore_ascii_case("template") {
+		String::from(ele.inner_html())
+	} else {
+		String::from(ele.text_content())
+	}
+}
+
+// the element is a content tag
+fn is_content_tag(ele: &BoxDynElement) -> bool {
+	let tag_name = ele.tag_name();
+	CONTENT_TAGS
+		.iter()
+		.any(|name| tag_name.eq_ignore_ascii_case(name))
+}
+
 /*
 *** Content APIs
 **  [Methods]
-**  text, html, set_text, set_html, texts
+**  text, text_with, text_blocks, text_stats, html, set_text, set_html, set_raw_html, texts, texts_with,
+**  htmls, outer_htmls, text_contents, text_contents_with,
+**  find_text, replace_text, wrap_text,
+**  script_content, script_contents, set_script_content, template_content
 */
 impl<'a> Elements<'a> {
 	// -------------Content API----------------
@@ -1732,6 +2106,12 @@ impl<'a> Elements<'a> {
 		to_static_str(result)
 	}
 
+	/// pub fn `text_with`
+	/// get the text of the elements with the whitespace and block options, each element is a block
+	pub fn text_with(&self, options: &TextOptions) -> String {
+		text_with(self.get_ref(), options)
+	}
+
 	/// pub fn `set_text`
 	/// set each element's text to content
 	pub fn set_text(&mut self, content: &str) -> &mut Self {
@@ -1759,6 +2139,24 @@ impl<'a> Elements<'a> {
 		self
 	}
 
+	/// pub fn `set_raw_html`
+	/// set each element's content to the trusted markup without parsing it, the element is marked as a raw zone
+	/// so the serializer writes the markup as it is. the element is unchanged if it can't be marked, such as
+	/// in a strict tree, the error is triggered by the document
+	pub fn set_raw_html(&mut self, content: &str) -> &mut Self {
+		for ele in self.get_mut_ref() {
+			match ele.set_raw_zone(true) {
+				Ok(()) => ele.set_text(content),
+				Err(e) => {
+					if let Some(doc) = ele.owner_document() {
+						doc.trigger_error(e);
+					}
+				}
+			}
+		}
+		self
+	}
+
 	/// pub fn `outer_html`
 	/// get the first element's outer html
 	pub fn outer_html(&self) -> &str {
@@ -1768,6 +2166,112 @@ impl<'a> Elements<'a> {
 		""
 	}
 
+	/// pub fn `htmls`
+	/// get each element's html
+	pub fn htmls(&self) -> Vec<String> {
+		self
+			.get_ref()
+			.iter()
+			.map(|ele| String::from(ele.inner_html()))
+			.collect()
+	}
+
+	/// pub fn `outer_htmls`
+	/// get each element's outer html
+	pub fn outer_htmls(&self) -> Vec<String> {
+		self
+			.get_ref()
+			.iter()
+			.map(|ele| String::from(ele.outer_html()))
+			.collect()
+	}
+
+	/// pub fn `text_contents`
+	/// get each element's text, `text` joins them into one
+	pub fn text_contents(&self) -> Vec<String> {
+		self
+			.get_ref()
+			.iter()
+			.map(|ele| String::from(ele.text_content()))
+			.collect()
+	}
+
+	/// pub fn `text_blocks`
+	/// split the rendered texts of the elements by the block elements such as `p`, `li` and `br`,
+	/// each block has the element contains it and the language, the hidden elements are skipped
+	pub fn text_blocks(&self) -> Vec<TextBlock<'a>> {
+		text_blocks(self.get_ref())
+	}
+
+	/// pub fn `text_stats`
+	/// count the characters, words, paragraphs and link characters of the rendered texts in one pass
+	pub fn text_stats(&self) -> TextStats {
+		text_stats(self.get_ref())
+	}
+
+	/// pub fn `script_content`
+	/// get the raw content of the first `script`, `style` or `template` in the set and their descendants
+	pub fn script_content(&self) -> Option<String> {
+		self.script_contents().into_iter().next()
+	}
+
+	/// pub fn `script_contents`
+	/// get the raw contents of the `script`, `style` and `template` elements in the set, the elements are
+	/// replaced by their descendant content tags if they are not. the contents of the script and the style
+	/// are the raw texts, the template's is its html
+	pub fn script_contents(&self) -> Vec<String> {
+		let mut result = Vec::new();
+		for ele in self.get_ref() {
+			if is_content_tag(ele) {
+				result.push(raw_content(ele));
+				continue;
+			}
+			let descendants = Elements::with_nodes(vec![ele.cloned()]).find(&CONTENT_TAGS.join(","));
+			result.extend(descendants.get_ref().iter().map(raw_content));
+		}
+		result
+	}
+
+	/// pub fn `set_script_content`
+	/// set the raw content of each `script`, `style` and `template` element in the set, the others are
+	/// skipped. the content is not entity-escaped when it's serialized, the template's content is parsed as html
+	pub fn set_script_content(&mut self, content: &str) -> &mut Self {
+		for ele in self.get_mut_ref() {
+			if !is_content_tag(ele) {
+				continue;
+			}
+			if ele.tag_name().eq_ignore_ascii_case("template") {
+				ele.set_html(content);
+			} else {
+				ele.set_text(content);
+			}
+		}
+		self
+	}
+
+	/// pub fn `template_content`
+	/// get the inert contents of each `template` element in the set, the fragments are not matched by
+	/// the selectors of the document, insert them by `append` or the other insert APIs to instantiate
+	/// the copies of their child nodes
+	pub fn template_content(&self) -> Elements<'a> {
+		let contents = self
+			.get_ref()
+			.iter()
+			.filter_map(|ele| ele.template_content())
+			.collect();
+		Elements::with_nodes(contents)
+	}
+
+	/// pub fn `text_contents_with`
+	/// get each element's text with the options
+	pub fn text_contents_with(&self, options: &TextOptions) -> Vec<String> {
+		self
+			.get_ref()
+			.iter()
+			.map(|ele| ele.text_with(options))
+			.collect()
+	}
+
 	/// pub fn `texts`
 	/// get the text node of each element
 	pub fn texts(&self, limit_depth: u32) -> Texts<'a> {
@@ -1779,12 +2283,202 @@ impl<'a> Elements<'a> {
 		}
 		result
 	}
+	/// pub fn `texts_with`
+	/// get the text nodes of each element filtered by the options
+	pub fn texts_with(&self, options: &TextsOptions) -> Texts<'a> {
+		let mut result = Texts::with_capacity(DEF_NODES_LEN);
+		for ele in self.get_ref() {
+			result.get_mut_ref().extend(ele.texts_with(options));
+		}
+		result
+	}
+
+	// the elements not in the others, so the text nodes are not visited twice
+	#[cfg(feature = "regex")]
+	fn outermost(&self) -> Elements<'a> {
+		self.not_in(&self.find("*"))
+	}
+
+	/// pub fn `find_text`
+	/// find the matches of the regex in each text node of the elements, in document order
+	#[cfg(feature = "regex")]
+	pub fn find_text(&self, regex: &Regex) -> Vec<TextMatch<'a>> {
+		let mut result = Vec::with_capacity(DEF_NODES_LEN);
+		for node in self.outermost().texts(0) {
+			for found in regex.find_iter(node.text_content()) {
+				result.push(TextMatch {
+					node: node
+						.clone_node()
+						.typed()
+						.into_text()
+						.expect("Text node must can use 'into_text'."),
+					start: found.start(),
+					end: found.end(),
+					text: String::from(found.as_str()),
+				});
+			}
+		}
+		result
+	}
+
+	/// pub fn `replace_text`
+	/// replace the matches of the regex in each text node of the elements, the replacement can use `$1` and `$name`
+	#[cfg(feature = "regex")]
+	pub fn replace_text(&mut self, regex: &Regex, replacement: &str) -> &mut Self {
+		for mut node in self.outermost().texts(0) {
+			if let Cow::Owned(content) = regex.replace_all(node.text_content(), replacement) {
+				node.set_text(&content);
+			}
+		}
+		self
+	}
+	/// pub fn `wrap_text`
+	/// wrap each match of the regex in a deep copy of the wrapper's first element, return the copies in document order,
+	/// the text nodes are split at the match boundaries, a match spans adjacent text nodes is wrapped as one,
+	/// the wrapper should be in the same document
+	#[cfg(feature = "regex")]
+	pub fn wrap_text(&mut self, regex: &Regex, wrapper: &Elements) -> Elements<'a> {
+		let mut result = Elements::with_capacity(DEF_NODES_LEN);
+		let wrapper = match wrapper.get(0) {
+			Some(wrapper) => wrapper,
+			None => return result,
+		};
+		let outermost = self.outermost();
+		let parents = outermost.add(outermost.find("*"));
+		'parents: for parent in parents.get_ref() {
+			// the raw text elements can't contain elements
+			if is_text_only_element(parent.tag_name()) && super::is_html_element(parent) {
+				continue;
+			}
+			// the runs of the adjacent text nodes
+			let mut runs: Vec<Vec<BoxDynText<'a>>> = Vec::new();
+			let mut run = Vec::new();
+			for node in parent.child_nodes() {
+				match node.typed() {
+					IEnumTyped::Text(text) => run.push(text),
+					_ if !run.is_empty() => runs.push(std::mem::take(&mut run)),
+					_ => {}
+				}
+			}
+			if !run.is_empty() {
+				runs.push(run);
+			}
+			for run in runs {
+				match wrap_text_run(run, regex, wrapper) {
+					Ok(wrappers) => result.get_mut_ref().extend(wrappers),
+					Err(e) => {
+						if let Some(doc) = parent.owner_document() {
+							doc.trigger_error(e);
+						}
+						break 'parents;
+					}
+				}
+			}
+		}
+		// the parents are walked one by one, the copies in the nested elements are collected later
+		result.sort_and_unique();
+		result
+	}
+}
+
+/*
+*** Extract APIs
+**  [Methods]
+**  extract_table, extract_images, serialize_form, extract
+*/
+impl<'a> Elements<'a> {
+	/// pub fn `extract_table`
+	/// get the headers and rows of each `table` element in the set
+	pub fn extract_table(&self) -> Vec<Table> {
+		let mut result = Vec::with_capacity(self.length());
+		for ele in self.get_ref() {
+			if ele.tag_name().eq_ignore_ascii_case("table") {
+				result.push(extract_table(ele));
+			}
+		}
+		result
+	}
+
+	/// pub fn `extract_images`
+	/// get the descriptors of the `img` elements in the set and their descendants, with the parsed
+	/// `srcset` candidates and the lazy-load attributes such as `data-src`
+	pub fn extract_images(&self) -> Vec<Image<'a>> {
+		extract_images(self)
+	}
+
+	/// pub fn `serialize_form`
+	/// get the successful controls' name/value pairs of the forms, or the controls in the set
+	pub fn serialize_form(&self) -> FormData {
+		FormData::from_elements(self)
+	}
+
+	/// pub fn `extract`
+	/// the records in the set by the schema of the type, see `extract::Schema`
+	#[cfg(feature = "serde")]
+	pub fn extract<T: ExtractSchema>(&self) -> Result<Vec<T>, serde_json::Error> {
+		T::schema().extract(self)
+	}
+}
+
+/*
+*** Style APIs
+**  [Methods]
+**  matched_css_rules, scope_styles
+*/
+impl<'a> Elements<'a> {
+	/// pub fn `matched_css_rules`
+	/// get the stylesheet rules matched each element, ordered by the specificity then the source order
+	pub fn matched_css_rules<'s>(&self, stylesheet: &'s Stylesheet) -> Vec<Vec<MatchedRule<'s>>> {
+		self
+			.get_ref()
+			.iter()
+			.map(|ele| stylesheet.matched_rules(ele))
+			.collect()
+	}
+	/// pub fn `scope_styles`
+	/// add the scope attribute such as the one of `scope_attr` to the elements and their descendants,
+	/// the `style` elements in them are scoped by it, return the css scoped by it
+	pub fn scope_styles(&mut self, attr: &str, css: &str) -> String {
+		let eles = self.add(self.find("*"));
+		for ele in eles.get_ref() {
+			let mut ele = ele.cloned();
+			if ele.tag_name().eq_ignore_ascii_case("style") && super::is_html_element(&ele) {
+				let content = scope_css(ele.text_content(), attr);
+				ele.set_text(&content);
+			}
+			ele.set_attribute(attr, None);
+		}
+		scope_css(css, attr)
+	}
+}
+
+/*
+*** Template APIs
+**  [Methods]
+**  render
+*/
+#[cfg(feature = "serde")]
+impl<'a> Elements<'a> {
+	/// pub fn `render`
+	/// fill each element by the `data-bind`, `data-bind-{attr}` and `data-each` attributes with the context,
+	/// the elements with `data-each` are replaced by their copies
+	pub fn render(&mut self, context: &Context) -> &mut Self {
+		for index in 0..self.length() {
+			let mut ele = self.get_ref()[index].cloned();
+			if let Err(e) = render(&mut ele, context) {
+				if let Some(doc) = ele.owner_document() {
+					doc.trigger_error(e);
+				}
+			}
+		}
+		self
+	}
 }
 
 /*
 *** Attribute APIs
 **  [Methods]
-**  attr, set_attr, remove_attr,
+**  attr, set_attr, remove_attr, update_attr, toggle_attr, rename_attr, copy_attr_from,
 **  has_class, add_class, remove_class, toggle_class
 */
 impl<'a> Elements<'a> {
@@ -1814,107 +2508,86 @@ impl<'a> Elements<'a> {
 		self
 	}
 
-	/// pub fn `has_class`
-	pub fn has_class(&self, class_name: &str) -> bool {
-		let class_name = class_name.trim();
-		if !class_name.is_empty() {
-			let class_list = get_class_list(class_name);
-			for ele in self.get_ref() {
-				let class_value = ele.get_attribute(ATTR_CLASS);
-				if let Some(IAttrValue::Value(cls, _)) = class_value {
-					let orig_class_list = get_class_list(&cls);
-					for class_name in &class_list {
-						// if any of element contains the class
-						if orig_class_list.contains(class_name) {
-							return true;
-						}
-					}
+	/// pub fn `update_attr`
+	/// set each element's attribute to the result of the handle with the current value, `None` removes it,
+	/// the attribute is not written if the value is not changed
+	pub fn update_attr<F>(&mut self, attr_name: &str, mut handle: F) -> &mut Self
+	where
+		F: FnMut(Option<&str>) -> Option<String>,
+	{
+		for ele in self.get_mut_ref() {
+			let old_value = ele.get_attribute(attr_name);
+			let old_str = old_value.as_ref().map(|value| value.as_str());
+			match handle(old_str) {
+				Some(value) if old_str != Some(value.as_str()) => {
+					ele.set_attribute(attr_name, Some(&value))
 				}
+				Some(_) => {}
+				None if old_value.is_some() => ele.remove_attribute(attr_name),
+				None => {}
 			}
 		}
-		false
+		self
+	}
+
+	/// pub fn `toggle_attr`
+	/// add the attribute without value to each element if `force` is true, otherwise remove it
+	pub fn toggle_attr(&mut self, attr_name: &str, force: bool) -> &mut Self {
+		for ele in self.get_mut_ref() {
+			ele.toggle_attribute(attr_name, force);
+		}
+		self
+	}
+
+	/// pub fn `rename_attr`
+	/// rename each element's attribute with its value, such as unwrapping the lazy loaded `data-src` to `src`
+	pub fn rename_attr(&mut self, from: &str, to: &str) -> &mut Self {
+		for ele in self.get_mut_ref() {
+			ele.rename_attribute(from, to);
+		}
+		self
+	}
+
+	/// pub fn `copy_attr_from`
+	/// copy the attributes from the other element to each element, all the attributes if `names` is empty
+	pub fn copy_attr_from(&mut self, other: &BoxDynElement, names: &[&str]) -> &mut Self {
+		for ele in self.get_mut_ref() {
+			ele.copy_attributes_from(other, names);
+		}
+		self
+	}
+
+	/// pub fn `has_class`
+	pub fn has_class(&self, class_name: &str) -> bool {
+		let class_names = ClassList::split(class_name);
+		// if any of element contains the class
+		self.get_ref().iter().any(|ele| {
+			let class_list = ele.class_list();
+			class_names.iter().any(|name| class_list.contains(name))
+		})
 	}
 
 	/// pub fn `add_class`
 	pub fn add_class(&mut self, class_name: &str) -> &mut Self {
-		let class_name = class_name.trim();
-		if !class_name.is_empty() {
-			let class_list = get_class_list(class_name);
-			for ele in self.get_mut_ref() {
-				let class_value = ele.get_attribute(ATTR_CLASS);
-				if let Some(IAttrValue::Value(cls, _)) = class_value {
-					let mut orig_class_list = get_class_list(&cls);
-					for class_name in &class_list {
-						if !orig_class_list.contains(class_name) {
-							orig_class_list.push(class_name);
-						}
-					}
-					ele.set_attribute(ATTR_CLASS, Some(orig_class_list.join(" ").as_str()));
-					continue;
-				}
-				ele.set_attribute(ATTR_CLASS, Some(class_name));
-			}
+		for ele in self.get_ref() {
+			ele.class_list().add(class_name);
 		}
 		self
 	}
 	/// pub fn `remove_class`
 	pub fn remove_class(&mut self, class_name: &str) -> &mut Self {
-		let class_name = class_name.trim();
-		if !class_name.is_empty() {
-			let class_list = get_class_list(class_name);
-			for ele in self.get_mut_ref() {
-				let class_value = ele.get_attribute(ATTR_CLASS);
-				if let Some(IAttrValue::Value(cls, _)) = class_value {
-					let mut orig_class_list = get_class_list(&cls);
-					let mut removed_indexs: Vec<usize> = Vec::with_capacity(class_list.len());
-					for class_name in &class_list {
-						if let Some(index) = orig_class_list.iter().position(|name| name == class_name) {
-							removed_indexs.push(index);
-						}
-					}
-					if !removed_indexs.is_empty() {
-						retain_by_index(&mut orig_class_list, &removed_indexs);
-						ele.set_attribute(ATTR_CLASS, Some(orig_class_list.join(" ").as_str()));
-					}
-				}
-			}
+		for ele in self.get_ref() {
+			ele.class_list().remove(class_name);
 		}
 		self
 	}
 	/// pub fn `toggle_class`
 	pub fn toggle_class(&mut self, class_name: &str) -> &mut Self {
-		let class_name = class_name.trim();
-		if !class_name.is_empty() {
-			let class_list = get_class_list(class_name);
-			let total = class_list.len();
-			for ele in self.get_mut_ref() {
-				let class_value = ele.get_attribute(ATTR_CLASS);
-				if let Some(IAttrValue::Value(cls, _)) = class_value {
-					let mut orig_class_list = get_class_list(&cls);
-					let mut removed_indexs: Vec<usize> = Vec::with_capacity(total);
-					let mut added_class_list: Vec<&str> = Vec::with_capacity(total);
-					for class_name in &class_list {
-						if let Some(index) = orig_class_list.iter().position(|name| name == class_name) {
-							removed_indexs.push(index);
-						} else {
-							added_class_list.push(class_name);
-						}
-					}
-					let mut need_set = false;
-					if !removed_indexs.is_empty() {
-						retain_by_index(&mut orig_class_list, &removed_indexs);
-						need_set = true;
-					}
-					if !added_class_list.is_empty() {
-						orig_class_list.extend(added_class_list);
-						need_set = true;
-					}
-					if need_set {
-						ele.set_attribute(ATTR_CLASS, Some(orig_class_list.join(" ").as_str()));
-					}
-					continue;
-				}
-				ele.set_attribute(ATTR_CLASS, Some(class_name));
+		let class_names = ClassList::split(class_name);
+		for ele in self.get_ref() {
+			let mut class_list = ele.class_list();
+			for name in &class_names {
+				class_list.toggle(name);
 			}
 		}
 		self
@@ -1942,15 +2615,46 @@ impl<'a> Elements<'a> {
 		self.set_text("");
 		self
 	}
-	// `insert`
-	fn insert(&mut self, dest: &Elements, position: &InsertPosition) -> &mut Self {
-		for ele in self.get_mut_ref() {
-			for inserted in dest.get_ref().iter().rev() {
-				ele.insert_adjacent(position, inserted);
+	/// pub fn `insert_with`
+	/// insert the elements at the position of each element in the set, keep their order.
+	/// in the `Move` mode the last element gets the elements themselves and the others get the copies,
+	/// in the `Clone` mode all get the copies
+	pub fn insert_with(
+		&mut self,
+		elements: &Elements,
+		position: &InsertPosition,
+		mode: InsertMode,
+	) -> &mut Self {
+		let total = self.length();
+		// the elements inserted at the same point are inserted from the last one
+		let is_reverse = matches!(
+			position,
+			InsertPosition::AfterBegin | InsertPosition::AfterEnd
+		);
+		let mut inserted = elements.get_ref().iter().collect::<Vec<_>>();
+		if is_reverse {
+			inserted.reverse();
+		}
+		for (index, ele) in self.get_mut_ref().iter_mut().enumerate() {
+			let mode = if index + 1 == total {
+				mode
+			} else {
+				InsertMode::Clone
+			};
+			for node in &inserted {
+				if let Err(e) = ele.insert_adjacent_with(position, node, mode) {
+					if let Some(doc) = ele.owner_document() {
+						doc.trigger_error(e);
+					}
+				}
 			}
 		}
 		self
 	}
+	// `insert`
+	fn insert(&mut self, dest: &Elements, position: &InsertPosition) -> &mut Self {
+		self.insert_with(dest, position, InsertMode::Move)
+	}
 	/// pub fn `append`
 	pub fn append(&mut self, elements: &mut Elements) -> &mut Self {
 		self.insert(elements, &InsertPosition::BeforeEnd);
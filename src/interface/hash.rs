@@ -0,0 +1,95 @@
+use super::{BoxDynElement, BoxDynNode, INodeType};
+
+// the offset basis and the prime of the 64 bits FNV-1a hash
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// the options of `content_hash`
+#[derive(Debug, Clone, Default)]
+pub struct HashOptions {
+	// the attributes not hashed, such as the generated `id` or `data-timestamp`, case-insensitive
+	pub ignore_attributes: Vec<String>,
+	// collapse the whitespaces of the texts into one space, trim them and skip the whitespace only texts
+	pub ignore_whitespace: bool,
+	// skip the comments
+	pub ignore_comments: bool,
+}
+
+// the FNV-1a hasher, the value is stable across the platforms and the versions
+struct Hasher(u64);
+
+impl Hasher {
+	fn write(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.0 ^= u64::from(*byte);
+			self.0 = self.0.wrapping_mul(FNV_PRIME);
+		}
+	}
+	// write the content with a leading kind byte and its length, so the adjacent contents can't be confused
+	fn write_part(&mut self, kind: u8, content: &str) {
+		self.write(&[kind]);
+		self.write(&(content.len() as u64).to_le_bytes());
+		self.write(content.as_bytes());
+	}
+}
+
+// collapse the whitespaces into one space and trim the edges
+fn normalize_whitespace(content: &str) -> String {
+	content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn hash_element(ele: &BoxDynElement, options: &HashOptions, hasher: &mut Hasher) {
+	hasher.write_part(b'<', &ele.tag_name().to_ascii_lowercase());
+	let mut attrs: Vec<(String, String)> = ele
+		.attributes()
+		.into_iter()
+		.map(|(name, value)| (name.to_ascii_lowercase(), String::from(value.as_str())))
+		.filter(|(name, _)| {
+			!options
+				.ignore_attributes
+				.iter()
+				.any(|ignored| ignored.eq_ignore_ascii_case(name))
+		})
+		.collect();
+	attrs.sort();
+	for (name, value) in &attrs {
+		hasher.write_part(b'@', name);
+		hasher.write_part(b'=', value);
+	}
+	for node in ele.child_nodes() {
+		hash_node(&node, options, hasher);
+	}
+	hasher.write(b">");
+}
+
+fn hash_node(node: &BoxDynNode, options: &HashOptions, hasher: &mut Hasher) {
+	match node.node_type() {
+		INodeType::Element | INodeType::Document | INodeType::DocumentFragement => {
+			if let Some(ele) = node.clone_node().typed().into_element() {
+				hash_element(&ele, options, hasher);
+			}
+		}
+		INodeType::Text | INodeType::XMLCDATA => {
+			let content = node.text_content();
+			if options.ignore_whitespace {
+				let content = normalize_whitespace(content);
+				if !content.is_empty() {
+					hasher.write_part(b'"', &content);
+				}
+			} else {
+				hasher.write_part(b'"', content);
+			}
+		}
+		INodeType::Comment if !options.ignore_comments => {
+			hasher.write_part(b'!', node.text_content());
+		}
+		_ => {}
+	}
+}
+
+// the hash of the element and its descendants, the attributes are sorted by the lowercase names
+pub(crate) fn content_hash(ele: &BoxDynElement, options: &HashOptions) -> u64 {
+	let mut hasher = Hasher(FNV_OFFSET);
+	hash_element(ele, options, &mut hasher);
+	hasher.0
+}
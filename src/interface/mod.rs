@@ -1,21 +1,63 @@
 // node trait
 mod node;
-pub use node::{BoxDynNode, IEnumTyped, INodeTrait, INodeType};
+pub use node::{BoxDynNode, DocumentType, IEnumTyped, INodeTrait, INodeType};
 // element trait
 mod element;
-pub use element::{BoxDynElement, IAttrValue, IElementTrait, InsertPosition, MaybeElement};
+pub(crate) use element::{decode_attr_value, parse_start_tag_attrs};
+pub use element::{
+	BoxDynElement, IAttrValue, IElementTrait, InsertMode, InsertPosition, MaybeElement,
+};
+// the namespaces of the html, svg and mathml elements
+mod namespace;
+pub(crate) use namespace::{element_namespace, is_html_element, svg_tag_name};
+pub use namespace::{HTML_NAMESPACE, MATHML_NAMESPACE, SVG_NAMESPACE};
 // text trait
 mod text;
 pub use text::{BoxDynText, ITextTrait};
 // document trait
 mod document;
-pub use document::{IDocumentTrait, IErrorHandle, MaybeDoc};
+pub use document::{IDocumentTrait, IErrorHandle, MaybeDoc, ScriptSource};
 // uncare
 mod uncare;
 pub use uncare::{BoxDynUncareNode, IUncareNodeTrait};
 // texts
 mod texts;
-pub use texts::Texts;
+pub(crate) use texts::{text_blocks, text_stats, text_with};
+pub use texts::{TextBlock, TextMatch, TextOptions, TextStats, Texts, TextsOptions};
+// the typed attribute parsers
+mod attr_parser;
+pub use attr_parser::{InlineStyle, Srcset, SrcsetCandidate, SrcsetDescriptor};
+// class list
+mod class_list;
+pub use class_list::ClassList;
+// tree walker and traversal iterators
+mod walker;
+pub use walker::{BreadthFirst, DepthFirst, NodeFilter, NodeFilterResult, TreeWalker, WhatToShow};
+// the visitor for the whole tree transforms
+mod visitor;
+pub use visitor::{visit_mut, NodeVisitor, VisitAction};
+// mutation records and observers
+mod mutation;
+pub use mutation::{MutationCallback, MutationKind, MutationObservers, MutationRecord};
+// transaction with rollback
+mod transaction;
+pub use transaction::{Transaction, TransactionHandle};
+// the stable hash of the subtrees
+mod hash;
+pub(crate) use hash::content_hash;
+pub use hash::HashOptions;
+// build the selector of an element
+mod build_selector;
+pub(crate) use build_selector::build_selector;
+pub use build_selector::SelectorStrategy;
+// the structural paths of the elements
+mod node_path;
+pub(crate) use node_path::{node_path, resolve_path};
+pub use node_path::{NodePath, NodePathStep};
+// range
+mod range;
+pub use range::Range;
 // elements
 mod elements;
 pub use elements::Elements;
+pub(crate) use elements::FilterType;
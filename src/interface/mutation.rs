@@ -0,0 +1,81 @@
+use super::{BoxDynNode, IAttrValue};
+use std::cell::{Cell, RefCell};
+
+/// the kind of the mutation, with the old state for undoing it
+#[derive(Debug, Clone)]
+pub enum MutationKind {
+	// the attribute of the target is set or removed, the old value is none if it didn't exist,
+	// the index is its position in the attributes before the mutation, the count of them if it didn't exist
+	Attribute {
+		name: String,
+		old_value: Option<IAttrValue>,
+		index: usize,
+	},
+	// the node is inserted into the target at the index
+	ChildInserted {
+		index: usize,
+	},
+	// the node is removed from the target at the index
+	ChildRemoved {
+		index: usize,
+	},
+	// the content of the target, a text node, is changed
+	Text {
+		old_value: String,
+	},
+	// the target is marked as a raw zone or unmarked, see `INodeTrait::set_raw_zone`
+	RawZone {
+		old_value: bool,
+	},
+}
+
+/// the record of a mutation, `node` is the inserted or removed child node
+pub struct MutationRecord<'a> {
+	pub kind: MutationKind,
+	pub target: BoxDynNode<'a>,
+	pub node: Option<BoxDynNode<'a>>,
+}
+
+pub type MutationCallback = Box<dyn FnMut(&MutationRecord)>;
+
+/// the mutation callbacks registered on a document, used by the backends to notify the records
+#[derive(Default)]
+pub struct MutationObservers {
+	callbacks: RefCell<Vec<(usize, MutationCallback)>>,
+	next_id: Cell<usize>,
+}
+
+impl MutationObservers {
+	pub fn new() -> Self {
+		Default::default()
+	}
+	/// pub fn `add`
+	/// add the callback and return its id, it can't be called in a callback
+	pub fn add(&self, callback: MutationCallback) -> usize {
+		let id = self.next_id.get();
+		self.next_id.set(id + 1);
+		self.callbacks.borrow_mut().push((id, callback));
+		id
+	}
+	/// pub fn `remove`
+	/// remove the callback by the id, it can't be called in a callback
+	pub fn remove(&self, id: usize) -> bool {
+		let mut callbacks = self.callbacks.borrow_mut();
+		let total = callbacks.len();
+		callbacks.retain(|(callback_id, _)| *callback_id != id);
+		callbacks.len() != total
+	}
+	/// pub fn `is_empty`
+	pub fn is_empty(&self) -> bool {
+		self.callbacks.borrow().is_empty()
+	}
+	/// pub fn `notify`
+	/// call the callbacks with the record, the mutations made by the callbacks are not notified
+	pub fn notify(&self, record: &MutationRecord) {
+		if let Ok(mut callbacks) = self.callbacks.try_borrow_mut() {
+			for (_, callback) in callbacks.iter_mut() {
+				callback(record);
+			}
+		}
+	}
+}
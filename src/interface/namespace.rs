@@ -0,0 +1,174 @@
+use super::{BoxDynElement, INodeType};
+
+/// the namespace of the html elements
+pub const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+/// the namespace of the svg elements
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+/// the namespace of the mathml elements
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+// the svg elements contain the html elements
+const SVG_INTEGRATION_POINTS: [&str; 3] = ["foreignobject", "desc", "title"];
+// the mathml elements contain the html elements, except `mglyph` and `malignmark`
+const MATHML_INTEGRATION_POINTS: [&str; 5] = ["mi", "mo", "mn", "ms", "mtext"];
+// the html elements break out of the svg and the mathml contents
+const BREAKOUT_TAGS: [&str; 44] = [
+	"b",
+	"big",
+	"blockquote",
+	"body",
+	"br",
+	"center",
+	"code",
+	"dd",
+	"div",
+	"dl",
+	"dt",
+	"em",
+	"embed",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"head",
+	"hr",
+	"i",
+	"img",
+	"li",
+	"listing",
+	"menu",
+	"meta",
+	"nobr",
+	"ol",
+	"p",
+	"pre",
+	"ruby",
+	"s",
+	"small",
+	"span",
+	"strong",
+	"strike",
+	"sub",
+	"sup",
+	"table",
+	"tt",
+	"u",
+	"ul",
+	"var",
+];
+// the svg tag names in camel case, the html parser lowercases the names
+const SVG_TAG_NAMES: [&str; 37] = [
+	"altGlyph",
+	"altGlyphDef",
+	"altGlyphItem",
+	"animateColor",
+	"animateMotion",
+	"animateTransform",
+	"clipPath",
+	"feBlend",
+	"feColorMatrix",
+	"feComponentTransfer",
+	"feComposite",
+	"feConvolveMatrix",
+	"feDiffuseLighting",
+	"feDisplacementMap",
+	"feDistantLight",
+	"feDropShadow",
+	"feFlood",
+	"feFuncA",
+	"feFuncB",
+	"feFuncG",
+	"feFuncR",
+	"feGaussianBlur",
+	"feImage",
+	"feMerge",
+	"feMergeNode",
+	"feMorphology",
+	"feOffset",
+	"fePointLight",
+	"feSpecularLighting",
+	"feSpotLight",
+	"feTile",
+	"feTurbulence",
+	"foreignObject",
+	"glyphRef",
+	"linearGradient",
+	"radialGradient",
+	"textPath",
+];
+
+// the namespace of the element in the html contents
+fn html_child_namespace(name: &str) -> &'static str {
+	match name {
+		"svg" => SVG_NAMESPACE,
+		"math" => MATHML_NAMESPACE,
+		_ => HTML_NAMESPACE,
+	}
+}
+
+// the namespace of the element named `name` in the parent, by the tree construction rules of the html parser
+pub(crate) fn element_namespace(
+	parent_namespace: &str,
+	parent_name: &str,
+	name: &str,
+) -> &'static str {
+	let name = name.to_ascii_lowercase();
+	let parent_name = parent_name.to_ascii_lowercase();
+	let is_foreign = parent_namespace == SVG_NAMESPACE || parent_namespace == MATHML_NAMESPACE;
+	if is_foreign && BREAKOUT_TAGS.contains(&name.as_str()) {
+		return HTML_NAMESPACE;
+	}
+	match parent_namespace {
+		SVG_NAMESPACE if SVG_INTEGRATION_POINTS.contains(&parent_name.as_str()) => {
+			html_child_namespace(&name)
+		}
+		SVG_NAMESPACE => SVG_NAMESPACE,
+		MATHML_NAMESPACE
+			if MATHML_INTEGRATION_POINTS.contains(&parent_name.as_str())
+				&& name != "mglyph"
+				&& name != "malignmark" =>
+		{
+			html_child_namespace(&name)
+		}
+		MATHML_NAMESPACE if parent_name == "annotation-xml" && name == "svg" => SVG_NAMESPACE,
+		MATHML_NAMESPACE => MATHML_NAMESPACE,
+		_ => html_child_namespace(&name),
+	}
+}
+
+// the tag name of the svg element in camel case, such as `linearGradient`
+pub(crate) fn svg_tag_name(name: &str) -> Option<&'static str> {
+	SVG_TAG_NAMES
+		.iter()
+		.find(|tag_name| tag_name.eq_ignore_ascii_case(name))
+		.copied()
+}
+
+// infer the namespace of the element from the tag names of its ancestors
+pub(crate) fn infer_namespace(ele: &BoxDynElement) -> &'static str {
+	let mut names = vec![ele.tag_name().to_ascii_lowercase()];
+	let mut cur = ele.parent();
+	while let Some(parent) = cur {
+		if !matches!(parent.node_type(), INodeType::Element) {
+			break;
+		}
+		names.push(parent.tag_name().to_ascii_lowercase());
+		cur = parent.parent();
+	}
+	let mut namespace = HTML_NAMESPACE;
+	let mut parent_name = "";
+	for name in names.iter().rev() {
+		namespace = element_namespace(namespace, parent_name, name);
+		parent_name = name;
+	}
+	namespace
+}
+
+// the element is in the html namespace, the elements of the backends without namespaces are html elements
+pub(crate) fn is_html_element(ele: &BoxDynElement) -> bool {
+	ele
+		.namespace_uri()
+		.is_none_or(|namespace| namespace == HTML_NAMESPACE)
+}
@@ -1,10 +1,14 @@
 use super::{BoxDynElement, BoxDynText, BoxDynUncareNode, MaybeDoc, MaybeElement};
+use crate::error::Error as IError;
 use std::any::Any;
+use std::error::Error;
+use std::fmt;
 #[derive(Debug)]
 pub enum INodeType {
 	Element = 1,
 	Text = 3,
 	XMLCDATA = 4,
+	ProcessingInstruction = 7,
 	Comment = 8,
 	Document = 9,
 	HTMLDOCTYPE = 10,
@@ -18,6 +22,75 @@ impl INodeType {
 	}
 }
 
+/// the name, public id and system id of a doctype node
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentType {
+	pub name: String,
+	pub public_id: String,
+	pub system_id: String,
+}
+
+impl DocumentType {
+	/// pub fn `parse`
+	/// parse the doctype such as `<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "...">`,
+	/// the leading `<!` and the trailing `>` are optional
+	pub fn parse(content: &str) -> Option<Self> {
+		let content = content.trim();
+		let content = content.strip_prefix("<!").unwrap_or(content);
+		let content = content.strip_suffix('>').unwrap_or(content).trim();
+		if content.len() < 7 || !content[..7].eq_ignore_ascii_case("doctype") {
+			return None;
+		}
+		let mut rest = content[7..].trim_start();
+		let name_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+		let name = rest[..name_end].to_ascii_lowercase();
+		rest = rest[name_end..].trim_start();
+		// read a quoted id
+		fn quoted(rest: &mut &str) -> String {
+			let content = rest.trim_start();
+			let quote = match content.chars().next() {
+				Some(quote) if quote == '"' || quote == '\'' => quote,
+				_ => return String::new(),
+			};
+			let end = content[1..]
+				.find(quote)
+				.map_or(content.len(), |end| end + 1);
+			let id = String::from(&content[1..end]);
+			*rest = content.get(end + 1..).unwrap_or("");
+			id
+		}
+		let mut doctype = DocumentType {
+			name,
+			..Default::default()
+		};
+		if rest.len() >= 6 && rest[..6].eq_ignore_ascii_case("public") {
+			rest = &rest[6..];
+			doctype.public_id = quoted(&mut rest);
+			doctype.system_id = quoted(&mut rest);
+		} else if rest.len() >= 6 && rest[..6].eq_ignore_ascii_case("system") {
+			rest = &rest[6..];
+			doctype.system_id = quoted(&mut rest);
+		}
+		Some(doctype)
+	}
+}
+
+/// impl `Display` for DocumentType, write the doctype as html
+impl fmt::Display for DocumentType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "<!DOCTYPE {}", self.name)?;
+		if !self.public_id.is_empty() {
+			write!(f, " PUBLIC \"{}\"", self.public_id)?;
+			if !self.system_id.is_empty() {
+				write!(f, " \"{}\"", self.system_id)?;
+			}
+		} else if !self.system_id.is_empty() {
+			write!(f, " SYSTEM \"{}\"", self.system_id)?;
+		}
+		f.write_str(">")
+	}
+}
+
 pub type BoxDynNode<'a> = Box<dyn INodeTrait + 'a>;
 pub enum IEnumTyped<'a> {
 	Element(BoxDynElement<'a>),
@@ -51,8 +124,22 @@ pub trait INodeTrait {
 	fn parent<'b>(&self) -> MaybeElement<'b>;
 	// check if two ele are the same
 	fn uuid(&self) -> Option<&str>;
-	// owner document
-	fn owner_document(&self) -> MaybeDoc;
+	// the document the node belongs to, the detached nodes of the arena tree still have their document
+	fn owner_document(&self) -> MaybeDoc<'_>;
+	// the node is in the document tree, the nodes of a detached subtree or a template's contents are not
+	fn is_connected(&self) -> bool {
+		if matches!(self.node_type(), INodeType::Document) {
+			return true;
+		}
+		let mut cur = self.parent();
+		while let Some(parent) = cur {
+			if matches!(parent.node_type(), INodeType::Document) {
+				return true;
+			}
+			cur = parent.parent();
+		}
+		false
+	}
 	// root element
 	fn root_element<'b>(&self) -> Option<BoxDynElement<'b>> {
 		if let Some(doc) = &self.owner_document() {
@@ -61,7 +148,15 @@ pub trait INodeTrait {
 		}
 		None
 	}
-	// text
+	// the name, public id and system id of the doctype node
+	fn doctype(&self) -> Option<DocumentType> {
+		None
+	}
+	// the target of the processing instruction node such as `xml-stylesheet`, the data is the text content
+	fn pi_target(&self) -> Option<String> {
+		None
+	}
+	// text, the data of the comment, cdata and processing instruction nodes
 	fn text_content(&self) -> &str;
 	fn text(&self) -> &str {
 		self.text_content()
@@ -71,4 +166,16 @@ pub trait INodeTrait {
 	fn set_html(&mut self, content: &str);
 	// ele index
 	fn index(&self) -> usize;
+	// the node is a raw zone, the serializer writes the texts of the node and its descendants without
+	// escaping, such as the trusted markup rendered by the templates
+	fn is_raw_zone(&self) -> bool {
+		false
+	}
+	// mark the node as a raw zone or not, the backends without the raw zones can't mark the nodes
+	fn set_raw_zone(&mut self, _is_raw_zone: bool) -> Result<(), Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "set_raw_zone".into(),
+			message: "The set_raw_zone method is not implemented.".into(),
+		}))
+	}
 }
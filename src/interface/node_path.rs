@@ -0,0 +1,124 @@
+use super::{BoxDynElement, INodeType};
+use crate::error::Error as IError;
+use std::fmt;
+use std::str::FromStr;
+
+/// a step of the `NodePath`, the element's tag name and its index in the parent's element children
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct NodePathStep {
+	// the lowercase tag name
+	pub tag: String,
+	// the index in the element children of the parent, from 0
+	pub index: usize,
+}
+
+/// the structural path of an element from the root, such as `html[0]/body[1]/div[2]`,
+/// it can be saved as a string and resolved again by `IDocumentTrait::from_path`
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct NodePath(pub Vec<NodePathStep>);
+
+impl NodePath {
+	/// pub fn `steps`
+	/// the steps from the root to the element
+	pub fn steps(&self) -> &[NodePathStep] {
+		&self.0
+	}
+	/// pub fn `breadcrumb`
+	/// the tag names from the root to the element joined by the separator, such as `html > body > div`
+	pub fn breadcrumb(&self, separator: &str) -> String {
+		self
+			.0
+			.iter()
+			.map(|step| step.tag.as_str())
+			.collect::<Vec<_>>()
+			.join(separator)
+	}
+}
+
+impl fmt::Display for NodePath {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (index, step) in self.0.iter().enumerate() {
+			if index > 0 {
+				f.write_str("/")?;
+			}
+			write!(f, "{}[{}]", step.tag, step.index)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for NodePath {
+	type Err = IError;
+	fn from_str(path: &str) -> Result<Self, Self::Err> {
+		let error = |reason: &str| IError::InvalidNodePath {
+			path: String::from(path),
+			reason: String::from(reason),
+		};
+		if path.trim().is_empty() {
+			return Ok(NodePath::default());
+		}
+		let mut steps = Vec::new();
+		for step in path.trim().split('/') {
+			let step = step
+				.strip_suffix(']')
+				.ok_or_else(|| error("The step must end with the index in brackets, such as `div[0]`"))?;
+			let (tag, index) = step
+				.split_once('[')
+				.ok_or_else(|| error("The step must have the index in brackets, such as `div[0]`"))?;
+			if tag.is_empty() {
+				return Err(error("The step must have the tag name"));
+			}
+			let index = index
+				.parse::<usize>()
+				.map_err(|_| error("The index of the step must be a number"))?;
+			steps.push(NodePathStep {
+				tag: tag.to_ascii_lowercase(),
+				index,
+			});
+		}
+		Ok(NodePath(steps))
+	}
+}
+
+// the path of the element from its document or its top ancestor, the top ancestor itself is not a step
+pub(crate) fn node_path(ele: &BoxDynElement) -> NodePath {
+	let mut steps = Vec::new();
+	let mut cur = ele.cloned();
+	while let Some(parent) = cur.parent() {
+		let index = parent
+			.children()
+			.get_ref()
+			.iter()
+			.position(|child| child.is(&cur))
+			.unwrap_or_default();
+		steps.push(NodePathStep {
+			tag: cur.tag_name().to_ascii_lowercase(),
+			index,
+		});
+		if matches!(
+			parent.node_type(),
+			INodeType::Document | INodeType::DocumentFragement
+		) {
+			break;
+		}
+		cur = parent;
+	}
+	steps.reverse();
+	NodePath(steps)
+}
+
+// resolve the path from the root, each step's tag name must be matched
+pub(crate) fn resolve_path<'a>(
+	root: BoxDynElement<'a>,
+	path: &NodePath,
+) -> Option<BoxDynElement<'a>> {
+	let mut cur = root;
+	for step in &path.0 {
+		let child = cur.children().get(step.index)?.cloned();
+		if !child.tag_name().eq_ignore_ascii_case(&step.tag) {
+			return None;
+		}
+		cur = child;
+	}
+	Some(cur)
+}
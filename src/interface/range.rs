@@ -0,0 +1,446 @@
+use super::visitor::remove_node;
+use super::walker::is_same_node;
+use super::{BoxDynElement, BoxDynNode, BoxDynText, IEnumTyped, InsertPosition};
+use crate::error::Error as IError;
+use std::error::Error;
+
+// the boundary point relative to an ancestor, the indexes of the path and the offset
+type Boundary<'p> = (&'p [usize], usize);
+
+// the common ancestor element, and the paths from it to the boundary containers
+type Resolved<'a> = (BoxDynElement<'a>, Vec<usize>, Vec<usize>);
+
+// the boundary point seen from a node
+#[derive(Clone, Copy)]
+enum Edge<'p> {
+	// no boundary in the node, all the contents are selected
+	Whole,
+	// the boundary is in the node at the offset
+	Offset(usize),
+	// the boundary is in the child node at the index
+	Inside(usize, Boundary<'p>),
+}
+
+impl<'p> Edge<'p> {
+	fn from(boundary: Option<Boundary<'p>>) -> Self {
+		match boundary {
+			None => Edge::Whole,
+			Some(([], offset)) => Edge::Offset(offset),
+			Some(([index, rest @ ..], offset)) => Edge::Inside(*index, (rest, offset)),
+		}
+	}
+}
+
+fn invalid_range(method: &str, message: &str) -> Box<dyn Error> {
+	Box::new(IError::InvalidTraitMethodCall {
+		method: method.into(),
+		message: message.into(),
+	})
+}
+
+fn to_element<'a>(node: &BoxDynNode) -> Option<BoxDynElement<'a>> {
+	node.clone_node().typed().into_element()
+}
+
+// the ancestors of the node, from the node itself to the top node
+fn ancestors<'a>(node: &BoxDynNode) -> Vec<BoxDynNode<'a>> {
+	let mut result = vec![node.clone_node()];
+	while let Some(parent) = result[result.len() - 1].parent() {
+		result.push(parent.clone_node());
+	}
+	result
+}
+
+// the length of the node, the count of the child nodes for the element, the bytes of the content for the others
+fn node_length(node: &BoxDynNode) -> usize {
+	match to_element(node) {
+		Some(ele) => ele.child_nodes_length(),
+		None => node.text_content().len(),
+	}
+}
+
+fn check_offset(method: &str, node: &BoxDynNode, offset: usize) -> Result<(), Box<dyn Error>> {
+	let is_valid = match to_element(node) {
+		Some(ele) => offset <= ele.child_nodes_length(),
+		None => node.text_content().is_char_boundary(offset),
+	};
+	if is_valid {
+		Ok(())
+	} else {
+		Err(invalid_range(
+			method,
+			&format!("The offset {} is out of the node.", offset),
+		))
+	}
+}
+
+// keep or delete the contents between the boundaries in the node
+fn cut_contents(
+	node: &BoxDynNode,
+	start: Option<Boundary>,
+	end: Option<Boundary>,
+	is_delete: bool,
+) -> Result<(), Box<dyn Error>> {
+	let ele = match to_element(node) {
+		Some(ele) => ele,
+		None => {
+			// the character data
+			let content = node.text_content();
+			let from = start.map_or(0, |(_, offset)| offset);
+			let to = end.map_or(content.len(), |(_, offset)| offset);
+			let content = if is_delete {
+				format!("{}{}", &content[..from], &content[to..])
+			} else {
+				String::from(&content[from..to])
+			};
+			node.clone_node().set_text(&content);
+			return Ok(());
+		}
+	};
+	let cut_child = |index: usize, start: Option<Boundary>, end: Option<Boundary>| match ele
+		.child_nodes_item(index)
+	{
+		Some(child) => cut_contents(&child, start, end, is_delete),
+		None => Ok(()),
+	};
+	let (start, end) = (Edge::from(start), Edge::from(end));
+	// the partially selected child nodes
+	match (start, end) {
+		(Edge::Inside(index, inner), Edge::Inside(end_index, end_inner)) if index == end_index => {
+			cut_child(index, Some(inner), Some(end_inner))?;
+		}
+		_ => {
+			if let Edge::Inside(index, inner) = start {
+				cut_child(index, Some(inner), None)?;
+			}
+			if let Edge::Inside(index, inner) = end {
+				cut_child(index, None, Some(inner))?;
+			}
+		}
+	}
+	// remove the child nodes out of the range when keeping, or the fully selected ones when deleting
+	let first = match start {
+		Edge::Whole => 0,
+		Edge::Offset(offset) => offset,
+		Edge::Inside(index, _) => index + is_delete as usize,
+	};
+	let last = match end {
+		Edge::Whole => ele.child_nodes_length(),
+		Edge::Offset(offset) => offset,
+		Edge::Inside(index, _) => index + !is_delete as usize,
+	};
+	let removed: Vec<usize> = if is_delete {
+		(first..last).collect()
+	} else {
+		(0..first).chain(last..ele.child_nodes_length()).collect()
+	};
+	for index in removed.into_iter().rev() {
+		if let Some(child) = ele.child_nodes_item(index) {
+			remove_node(&child)?;
+		}
+	}
+	Ok(())
+}
+
+// insert the element after the text, the element takes the place of an empty text split from the end
+fn insert_after_text(text: &mut BoxDynText, ele: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+	let length = text.text_content().len();
+	let mut empty = text.split_text(length)?;
+	empty.wrap(ele)?;
+	empty.remove();
+	Ok(())
+}
+
+// split the text container at the offset, false if the offset is at the end,
+// the other character data can't be split
+fn split_at(container: &BoxDynNode, offset: usize) -> Result<bool, Box<dyn Error>> {
+	if offset >= node_length(container) {
+		return Ok(false);
+	}
+	match container.clone_node().typed().into_text() {
+		Some(mut text) => {
+			text.split_text(offset)?;
+			Ok(true)
+		}
+		None => Err(invalid_range(
+			"surround_contents",
+			"The range partially selects a node.",
+		)),
+	}
+}
+
+// insert the element at the boundary point
+fn insert_at(
+	container: &BoxDynNode,
+	offset: usize,
+	ele: &BoxDynElement,
+) -> Result<(), Box<dyn Error>> {
+	match container.clone_node().typed() {
+		IEnumTyped::Element(mut parent) => {
+			let child_element = |index: usize| {
+				parent
+					.child_nodes_item(index)
+					.and_then(|child| child.typed().into_element())
+			};
+			if let Some(mut next) = child_element(offset) {
+				next.insert_adjacent(&InsertPosition::BeforeBegin, ele);
+			} else if offset == 0 {
+				parent.insert_adjacent(&InsertPosition::AfterBegin, ele);
+			} else if let Some(mut prev) = child_element(offset - 1) {
+				prev.insert_adjacent(&InsertPosition::AfterEnd, ele);
+			} else if offset == parent.child_nodes_length() {
+				parent.insert_adjacent(&InsertPosition::BeforeEnd, ele);
+			} else {
+				// between two nodes are not elements
+				let prev = parent.child_nodes_item(offset - 1).unwrap();
+				let next = parent.child_nodes_item(offset).unwrap();
+				if let Some(mut text) = prev.typed().into_text() {
+					insert_after_text(&mut text, ele)?;
+				} else if let Some(mut text) = next.typed().into_text() {
+					// move the content into a new text, the empty one takes the place
+					text.split_text(0)?;
+					text.wrap(ele)?;
+					text.remove();
+				} else {
+					return Err(invalid_range(
+						"surround_contents",
+						"The element can't be inserted between the nodes.",
+					));
+				}
+			}
+		}
+		IEnumTyped::Text(mut text) => {
+			if offset < text.text_content().len() {
+				text.split_text(offset)?;
+			}
+			insert_after_text(&mut text, ele)?;
+		}
+		IEnumTyped::UncareNode(_) => {
+			return Err(invalid_range(
+				"surround_contents",
+				"The element can't be inserted into the node.",
+			));
+		}
+	}
+	Ok(())
+}
+
+/// a dom style range between two boundary points, the offset is the index of the child nodes
+/// for the element, or the byte offset of the content for the text and the other nodes
+/// https://dom.spec.whatwg.org/#interface-range
+pub struct Range<'a> {
+	start: BoxDynNode<'a>,
+	start_offset: usize,
+	end: BoxDynNode<'a>,
+	end_offset: usize,
+}
+
+impl<'a> Range<'a> {
+	pub fn new(
+		start: &BoxDynNode,
+		start_offset: usize,
+		end: &BoxDynNode,
+		end_offset: usize,
+	) -> Result<Self, Box<dyn Error>> {
+		check_offset("Range::new", start, start_offset)?;
+		check_offset("Range::new", end, end_offset)?;
+		let range = Range {
+			start: start.clone_node(),
+			start_offset,
+			end: end.clone_node(),
+			end_offset,
+		};
+		let (_, start_path, end_path) = range.resolve("Range::new")?;
+		// compare the boundary points in document order
+		let start_point = [&start_path[..], &[start_offset]].concat();
+		let end_point = [&end_path[..], &[end_offset]].concat();
+		if start_point > end_point {
+			return Err(invalid_range(
+				"Range::new",
+				"The start boundary point is after the end.",
+			));
+		}
+		Ok(range)
+	}
+	/// pub fn `select_node`
+	/// the range contains the node
+	pub fn select_node(node: &BoxDynNode) -> Result<Self, Box<dyn Error>> {
+		let parent = node
+			.parent()
+			.ok_or_else(|| invalid_range("Range::select_node", "The node has no parent."))?
+			.clone_node();
+		let index = node.index();
+		Range::new(&parent, index, &parent, index + 1)
+	}
+	/// pub fn `select_node_contents`
+	/// the range contains the contents of the node
+	pub fn select_node_contents(node: &BoxDynNode) -> Self {
+		Range {
+			start: node.clone_node(),
+			start_offset: 0,
+			end: node.clone_node(),
+			end_offset: node_length(node),
+		}
+	}
+	/// pub fn `between`
+	/// the range contains the nodes after the start node and before the end node, such as
+	/// everything between two headings
+	pub fn between(start: &BoxDynNode, end: &BoxDynNode) -> Result<Self, Box<dyn Error>> {
+		let no_parent = || invalid_range("Range::between", "The node has no parent.");
+		let start_parent = start.parent().ok_or_else(no_parent)?.clone_node();
+		let end_parent = end.parent().ok_or_else(no_parent)?.clone_node();
+		Range::new(&start_parent, start.index() + 1, &end_parent, end.index())
+	}
+	/// pub fn `start_container`
+	pub fn start_container(&self) -> &BoxDynNode<'a> {
+		&self.start
+	}
+	/// pub fn `start_offset`
+	pub fn start_offset(&self) -> usize {
+		self.start_offset
+	}
+	/// pub fn `end_container`
+	pub fn end_container(&self) -> &BoxDynNode<'a> {
+		&self.end
+	}
+	/// pub fn `end_offset`
+	pub fn end_offset(&self) -> usize {
+		self.end_offset
+	}
+	/// pub fn `collapsed`
+	pub fn collapsed(&self) -> bool {
+		self.start_offset == self.end_offset && is_same_node(&self.start, &self.end)
+	}
+	// resolve the common ancestor and the paths of the boundary points
+	fn resolve(&self, method: &str) -> Result<Resolved<'a>, Box<dyn Error>> {
+		let starts = ancestors(&self.start);
+		let ends = ancestors(&self.end);
+		let common = starts
+			.iter()
+			.rev()
+			.zip(ends.iter().rev())
+			.take_while(|(node, other)| is_same_node(node, other))
+			.count();
+		if common == 0 {
+			return Err(invalid_range(
+				method,
+				"The boundary points are not in the same tree.",
+			));
+		}
+		// the text container has no child nodes, use its parent
+		let (common, ancestor) = match to_element(&starts[starts.len() - common]) {
+			Some(ele) => (common, ele),
+			None if common > 1 => (
+				common - 1,
+				to_element(&starts[starts.len() - common + 1]).unwrap(),
+			),
+			None => {
+				return Err(invalid_range(method, "The text container has no parent."));
+			}
+		};
+		let path_of = |chain: &[BoxDynNode]| -> Vec<usize> {
+			chain[..chain.len() - common]
+				.iter()
+				.rev()
+				.map(|node| node.index())
+				.collect()
+		};
+		Ok((ancestor, path_of(&starts), path_of(&ends)))
+	}
+	/// pub fn `common_ancestor_container`
+	/// the deepest element contains both the boundary points
+	pub fn common_ancestor_container(&self) -> Result<BoxDynElement<'a>, Box<dyn Error>> {
+		self
+			.resolve("common_ancestor_container")
+			.map(|(ancestor, _, _)| ancestor)
+	}
+	/// pub fn `clone_contents`
+	/// copy the contents, return a detached copy of the common ancestor which child nodes are the copied contents
+	pub fn clone_contents<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		let (ancestor, start, end) = self.resolve("clone_contents")?;
+		let fragment = ancestor.clone_deep()?;
+		cut_contents(
+			&fragment.clone_node(),
+			Some((&start, self.start_offset)),
+			Some((&end, self.end_offset)),
+			false,
+		)?;
+		Ok(fragment)
+	}
+	/// pub fn `delete_contents`
+	/// remove the contents from the tree, the partially selected containers are kept, then collapse the range to the start
+	pub fn delete_contents(&mut self) -> Result<(), Box<dyn Error>> {
+		let (ancestor, start, end) = self.resolve("delete_contents")?;
+		cut_contents(
+			&ancestor.clone_node(),
+			Some((&start, self.start_offset)),
+			Some((&end, self.end_offset)),
+			true,
+		)?;
+		self.end = self.start.clone_node();
+		self.end_offset = self.start_offset;
+		Ok(())
+	}
+	/// pub fn `extract_contents`
+	/// same as `clone_contents` then `delete_contents`, the contents are returned as copies
+	pub fn extract_contents<'b>(&mut self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		let fragment = self.clone_contents()?;
+		self.delete_contents()?;
+		Ok(fragment)
+	}
+	/// pub fn `surround_contents`
+	/// move the contents into the wrapper, the old child nodes of the wrapper are dropped,
+	/// then insert the wrapper at the start and select it, the moved nodes are the same nodes
+	pub fn surround_contents(&mut self, wrapper: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+		let (ancestor, start, end) = self.resolve("surround_contents")?;
+		// only the text containers can be partially selected
+		let is_partial = |path: &[usize], container: &BoxDynNode| {
+			path.len() > 1 || (path.len() == 1 && to_element(container).is_some())
+		};
+		if is_partial(&start, &self.start) || is_partial(&end, &self.end) {
+			return Err(invalid_range(
+				"surround_contents",
+				"The range partially selects an element.",
+			));
+		}
+		// split the texts at the boundaries, then the contents are the child nodes of the ancestor,
+		// the end is split first, so the start is still in the same text if both are
+		let mut end_index = match end.first() {
+			Some(&index) if self.end_offset == 0 => index,
+			Some(&index) => {
+				split_at(&self.end, self.end_offset)?;
+				index + 1
+			}
+			None => self.end_offset,
+		};
+		let start_index = match start.first() {
+			Some(&index) if self.start_offset == 0 => index,
+			Some(&index) => {
+				if split_at(&self.start, self.start_offset)? {
+					end_index += 1;
+				}
+				index + 1
+			}
+			None => self.start_offset,
+		};
+		let contents: Vec<BoxDynNode> = (start_index..end_index)
+			.filter_map(|index| ancestor.child_nodes_item(index))
+			.collect();
+		let mut wrapper = wrapper.cloned();
+		wrapper.set_html("");
+		insert_at(&ancestor.clone_node(), start_index, &wrapper)?;
+		for node in &contents {
+			wrapper.insert_child(usize::MAX, node)?;
+		}
+		let parent = wrapper
+			.parent()
+			.ok_or_else(|| invalid_range("surround_contents", "The wrapper is not inserted."))?
+			.clone_node();
+		let index = wrapper.index();
+		self.start = parent.clone_node();
+		self.start_offset = index;
+		self.end = parent;
+		self.end_offset = index + 1;
+		Ok(())
+	}
+}
@@ -1,5 +1,16 @@
-use super::INodeTrait;
+use super::{BoxDynElement, INodeTrait};
+use crate::error::Error as IError;
+use std::error::Error;
 pub type BoxDynText<'a> = Box<dyn ITextTrait + 'a>;
+
+// the error of the methods not implemented by the backend
+fn not_implemented(method: &str) -> Box<dyn Error> {
+	Box::new(IError::InvalidTraitMethodCall {
+		method: method.into(),
+		message: format!("The {} method is not implemented.", method),
+	})
+}
+
 pub trait ITextTrait: INodeTrait {
 	// remove the ele
 	fn remove(self: Box<Self>);
@@ -7,4 +18,12 @@ pub trait ITextTrait: INodeTrait {
 	fn append_text(&mut self, content: &str);
 	// prepend text at the start
 	fn prepend_text(&mut self, content: &str);
+	// split the text at the byte offset, keep the text before and return a new text node after it with the rest
+	fn split_text<'b>(&mut self, _offset: usize) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		Err(not_implemented("split_text"))
+	}
+	// move the text node into the wrapper, the wrapper takes the place of the text node
+	fn wrap(&mut self, _wrapper: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+		Err(not_implemented("wrap"))
+	}
 }
@@ -1,4 +1,344 @@
-use super::BoxDynText;
+use super::{BoxDynElement, BoxDynText, IEnumTyped};
+use crate::rules::pseudo::is_not_rendered;
+
+// the elements separate the texts into blocks
+const BLOCK_TAGS: [&str; 44] = [
+	"address",
+	"article",
+	"aside",
+	"blockquote",
+	"body",
+	"br",
+	"caption",
+	"dd",
+	"details",
+	"dialog",
+	"div",
+	"dl",
+	"dt",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"header",
+	"hgroup",
+	"hr",
+	"html",
+	"legend",
+	"li",
+	"main",
+	"menu",
+	"nav",
+	"ol",
+	"option",
+	"p",
+	"pre",
+	"section",
+	"summary",
+	"table",
+	"td",
+	"th",
+	"tr",
+	"ul",
+];
+
+/// the options of `texts_with`
+#[derive(Debug, Clone)]
+pub struct TextsOptions {
+	// the depth of the descendants, `0` means no limit, `1` means only the child text nodes
+	pub limit_depth: u32,
+	// include the texts in `script`, `style`, `textarea` and `title`
+	pub include_content_tags: bool,
+	// skip the text nodes only contain whitespaces
+	pub skip_whitespace: bool,
+}
+
+impl Default for TextsOptions {
+	fn default() -> Self {
+		TextsOptions {
+			limit_depth: 0,
+			include_content_tags: true,
+			skip_whitespace: false,
+		}
+	}
+}
+
+/// the options of `text_with`, the default options get the same text as `text`
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+	// trim the whitespaces at the start and the end, of each block if `block_separator` is set
+	pub trim: bool,
+	// collapse the whitespaces into one space, and remove them at the edges of the blocks if `block_separator` is set
+	pub collapse_whitespace: bool,
+	// join the texts of the blocks, such as `p`, `li` and `br`, with the separator, the whitespace only
+	// blocks are skipped. `None` joins the texts directly
+	pub block_separator: Option<String>,
+	// include the texts of the hidden elements, such as `script` and the elements with the `hidden`
+	// attribute or `display: none`, only the element and its descendants are checked
+	pub include_hidden: bool,
+}
+
+impl Default for TextOptions {
+	fn default() -> Self {
+		TextOptions {
+			trim: false,
+			collapse_whitespace: false,
+			block_separator: None,
+			include_hidden: true,
+		}
+	}
+}
+
+// collapse the whitespaces into one space
+fn collapse_whitespace(content: &str) -> String {
+	let mut result = String::with_capacity(content.len());
+	let mut in_whitespace = false;
+	for ch in content.chars() {
+		if ch.is_whitespace() {
+			in_whitespace = true;
+			continue;
+		}
+		if in_whitespace {
+			result.push(' ');
+			in_whitespace = false;
+		}
+		result.push(ch);
+	}
+	if in_whitespace {
+		result.push(' ');
+	}
+	result
+}
+
+/// a block of the text, split by the block elements such as `p`, `li` and `br`
+pub struct TextBlock<'a> {
+	// the text with the whitespaces collapsed
+	pub text: String,
+	// the closest block element contains the text, or the element in the set
+	pub element: BoxDynElement<'a>,
+	// the language inherited from the closest element with the `lang` attribute
+	pub lang: Option<String>,
+}
+
+// a block collecting the texts
+struct Block<'a> {
+	text: String,
+	element: BoxDynElement<'a>,
+}
+
+// collect the texts of the element into the blocks, the last block is the current one,
+// the texts after a nested block belong to the `owner` again
+fn collect_blocks<'a>(
+	ele: &BoxDynElement<'a>,
+	owner: &BoxDynElement<'a>,
+	options: &TextOptions,
+	blocks: &mut Vec<Block<'a>>,
+) {
+	if !options.include_hidden && is_not_rendered(ele) {
+		return;
+	}
+	let is_block = BLOCK_TAGS.contains(&ele.tag_name().to_ascii_lowercase().as_str());
+	let child_owner = if is_block {
+		blocks.push(Block {
+			text: String::new(),
+			element: ele.cloned(),
+		});
+		ele
+	} else {
+		owner
+	};
+	for node in ele.child_nodes() {
+		match node.typed() {
+			IEnumTyped::Text(text) => {
+				if let Some(block) = blocks.last_mut() {
+					block.text.push_str(text.text_content());
+				}
+			}
+			IEnumTyped::Element(child) => collect_blocks(&child, child_owner, options, blocks),
+			IEnumTyped::UncareNode(_) => {}
+		}
+	}
+	if is_block {
+		blocks.push(Block {
+			text: String::new(),
+			element: owner.cloned(),
+		});
+	}
+}
+
+// the blocks of the elements, each element is a block
+fn blocks_of<'a>(eles: &[BoxDynElement<'a>], options: &TextOptions) -> Vec<Block<'a>> {
+	let mut blocks = Vec::with_capacity(eles.len());
+	for ele in eles {
+		blocks.push(Block {
+			text: String::new(),
+			element: ele.cloned(),
+		});
+		collect_blocks(ele, ele, options, &mut blocks);
+	}
+	blocks
+}
+
+// get the text of the elements with the options, each element is a block
+pub(crate) fn text_with(eles: &[BoxDynElement], options: &TextOptions) -> String {
+	let blocks = blocks_of(eles, options);
+	let separator = match &options.block_separator {
+		Some(separator) => separator,
+		None => {
+			let mut result: String = blocks.iter().map(|block| block.text.as_str()).collect();
+			if options.collapse_whitespace {
+				result = collapse_whitespace(&result);
+			}
+			if options.trim {
+				return String::from(result.trim());
+			}
+			return result;
+		}
+	};
+	let mut result: Vec<String> = Vec::with_capacity(blocks.len());
+	for block in blocks {
+		if block.text.trim().is_empty() {
+			continue;
+		}
+		let mut text = if options.collapse_whitespace {
+			String::from(collapse_whitespace(&block.text).trim())
+		} else {
+			block.text
+		};
+		if options.trim {
+			text = String::from(text.trim());
+		}
+		result.push(text);
+	}
+	result.join(separator)
+}
+
+// the blocks of the rendered texts with the whitespaces collapsed, the whitespace only blocks are skipped
+pub(crate) fn text_blocks<'a>(eles: &[BoxDynElement<'a>]) -> Vec<TextBlock<'a>> {
+	let options = TextOptions {
+		include_hidden: false,
+		..Default::default()
+	};
+	blocks_of(eles, &options)
+		.into_iter()
+		.filter(|block| !block.text.trim().is_empty())
+		.map(|block| TextBlock {
+			text: String::from(collapse_whitespace(&block.text).trim()),
+			lang: block.element.lang(),
+			element: block.element,
+		})
+		.collect()
+}
+
+/// the statistics of the rendered texts, the hidden elements are skipped
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextStats {
+	// the characters without the whitespaces
+	pub characters: usize,
+	// the words split by the whitespaces and the block elements
+	pub words: usize,
+	// the blocks have texts, such as `p`, `li` and the texts beside them
+	pub paragraphs: usize,
+	// the characters in the links, without the whitespaces
+	pub link_characters: usize,
+}
+
+impl TextStats {
+	/// pub fn `link_text_ratio`
+	/// the ratio of the characters in the links, `0` if there is no character
+	pub fn link_text_ratio(&self) -> f64 {
+		if self.characters == 0 {
+			return 0.0;
+		}
+		self.link_characters as f64 / self.characters as f64
+	}
+}
+
+// the statistics and the state of the traversal
+#[derive(Default)]
+struct StatsCollector {
+	stats: TextStats,
+	in_word: bool,
+	in_paragraph: bool,
+}
+
+impl StatsCollector {
+	fn end_block(&mut self) {
+		self.in_word = false;
+		self.in_paragraph = false;
+	}
+	fn text(&mut self, content: &str, in_link: bool) {
+		for ch in content.chars() {
+			if ch.is_whitespace() {
+				self.in_word = false;
+				continue;
+			}
+			if !self.in_word {
+				self.in_word = true;
+				self.stats.words += 1;
+			}
+			if !self.in_paragraph {
+				self.in_paragraph = true;
+				self.stats.paragraphs += 1;
+			}
+			self.stats.characters += 1;
+			if in_link {
+				self.stats.link_characters += 1;
+			}
+		}
+	}
+	fn element(&mut self, ele: &BoxDynElement, in_link: bool) {
+		if is_not_rendered(ele) {
+			return;
+		}
+		let tag_name = ele.tag_name().to_ascii_lowercase();
+		let is_block = BLOCK_TAGS.contains(&tag_name.as_str());
+		let in_link = in_link || tag_name == "a";
+		if is_block {
+			self.end_block();
+		}
+		for node in ele.child_nodes() {
+			match node.typed() {
+				IEnumTyped::Text(text) => self.text(text.text_content(), in_link),
+				IEnumTyped::Element(child) => self.element(&child, in_link),
+				IEnumTyped::UncareNode(_) => {}
+			}
+		}
+		if is_block {
+			self.end_block();
+		}
+	}
+}
+
+// the statistics of the elements' rendered texts in one traversal, each element is a block
+pub(crate) fn text_stats(eles: &[BoxDynElement]) -> TextStats {
+	let mut collector = StatsCollector::default();
+	for ele in eles {
+		collector.end_block();
+		collector.element(ele, false);
+	}
+	collector.stats
+}
+
+/// a match of the text search
+pub struct TextMatch<'a> {
+	// the text node contains the match
+	pub node: BoxDynText<'a>,
+	// the byte offset of the match start in the text
+	pub start: usize,
+	// the byte offset of the match end in the text
+	pub end: usize,
+	// the matched text
+	pub text: String,
+}
+
 #[derive(Default)]
 pub struct Texts<'a> {
 	nodes: Vec<BoxDynText<'a>>,
@@ -0,0 +1,132 @@
+use super::visitor::remove_node;
+use super::{BoxDynElement, BoxDynNode, IAttrValue, MutationCallback, MutationKind};
+use crate::error::Error as IError;
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+
+// a recorded mutation, the kind, the target and the child node
+type Change = (
+	MutationKind,
+	BoxDynNode<'static>,
+	Option<BoxDynNode<'static>>,
+);
+
+fn set_attribute(ele: &mut BoxDynElement, name: &str, value: &IAttrValue) {
+	match value {
+		IAttrValue::True => ele.set_attribute(name, None),
+		IAttrValue::Value(value, _) => ele.set_attribute(name, Some(value)),
+	}
+}
+
+// set the removed attribute back at its index, the attributes after it are set again to follow it
+fn restore_attribute(ele: &mut BoxDynElement, name: &str, value: &IAttrValue, index: usize) {
+	let following: Vec<(String, IAttrValue)> = ele.attributes().into_iter().skip(index).collect();
+	for (name, _) in &following {
+		ele.remove_attribute(name);
+	}
+	set_attribute(ele, name, value);
+	for (name, value) in &following {
+		set_attribute(ele, name, value);
+	}
+}
+
+// undo the mutation
+fn undo(change: &Change) -> Result<(), Box<dyn Error>> {
+	let (kind, target, node) = change;
+	let invalid = |message: &str| -> Box<dyn Error> {
+		Box::new(IError::InvalidTraitMethodCall {
+			method: "rollback".into(),
+			message: message.into(),
+		})
+	};
+	match kind {
+		MutationKind::Attribute {
+			name,
+			old_value,
+			index,
+		} => {
+			let mut ele = target
+				.clone_node()
+				.typed()
+				.into_element()
+				.ok_or_else(|| invalid("The attribute target is not an element."))?;
+			match old_value {
+				None => ele.remove_attribute(name),
+				Some(old_value) if ele.has_attribute(name) => set_attribute(&mut ele, name, old_value),
+				Some(old_value) => restore_attribute(&mut ele, name, old_value, *index),
+			}
+		}
+		MutationKind::Text { old_value } => target.clone_node().set_text(old_value),
+		MutationKind::RawZone { old_value } => target.clone_node().set_raw_zone(*old_value)?,
+		MutationKind::ChildInserted { .. } => {
+			let node = node
+				.as_ref()
+				.ok_or_else(|| invalid("The inserted node is missing."))?;
+			remove_node(node)?;
+		}
+		MutationKind::ChildRemoved { index } => {
+			let node = node
+				.as_ref()
+				.ok_or_else(|| invalid("The removed node is missing."))?;
+			let mut parent = target
+				.clone_node()
+				.typed()
+				.into_element()
+				.ok_or_else(|| invalid("The parent is not an element."))?;
+			parent.insert_child(*index, node)?;
+		}
+	}
+	Ok(())
+}
+
+pub type TransactionHandle<'h> = dyn FnMut(&Transaction) -> Result<(), Box<dyn Error>> + 'h;
+
+/// the transaction of `IDocumentTrait::transaction`, the mutations are recorded for rolling back
+pub struct Transaction<'a> {
+	root: BoxDynNode<'a>,
+	changes: Rc<RefCell<Vec<Change>>>,
+}
+
+impl<'a> Transaction<'a> {
+	pub fn new(root: BoxDynNode<'a>) -> Self {
+		Transaction {
+			root,
+			changes: Rc::new(RefCell::new(Vec::new())),
+		}
+	}
+	/// pub fn `journal`
+	/// the mutation callback records the changes of the transaction
+	pub fn journal(&self) -> MutationCallback {
+		let changes = self.changes.clone();
+		Box::new(move |record| {
+			changes.borrow_mut().push((
+				record.kind.clone(),
+				record.target.clone_node(),
+				record.node.as_ref().map(|node| node.clone_node()),
+			))
+		})
+	}
+	/// pub fn `root`
+	/// the root node of the document
+	pub fn root(&self) -> &BoxDynNode<'a> {
+		&self.root
+	}
+	/// pub fn `len`
+	/// the count of the recorded mutations
+	pub fn len(&self) -> usize {
+		self.changes.borrow().len()
+	}
+	/// pub fn `is_empty`
+	pub fn is_empty(&self) -> bool {
+		self.changes.borrow().is_empty()
+	}
+	/// pub fn `rollback`
+	/// undo the recorded mutations in reverse order, the records made by undoing are dropped
+	pub fn rollback(&self) -> Result<(), Box<dyn Error>> {
+		let changes = std::mem::take(&mut *self.changes.borrow_mut());
+		let result = changes.iter().rev().try_for_each(undo);
+		self.changes.borrow_mut().clear();
+		result
+	}
+}
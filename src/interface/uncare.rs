@@ -1,4 +1,14 @@
 use super::INodeTrait;
+use crate::error::Error as IError;
+use std::error::Error;
 
 pub type BoxDynUncareNode<'a> = Box<dyn IUncareNodeTrait + 'a>;
-pub trait IUncareNodeTrait: INodeTrait {}
+pub trait IUncareNodeTrait: INodeTrait {
+	// remove the node, such as a comment, from its parent
+	fn remove(self: Box<Self>) -> Result<(), Box<dyn Error>> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: "remove".into(),
+			message: "The remove method is not implemented.".into(),
+		}))
+	}
+}
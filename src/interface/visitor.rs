@@ -0,0 +1,103 @@
+use super::{BoxDynElement, BoxDynNode, IEnumTyped, InsertPosition};
+use crate::error::Error as IError;
+use std::error::Error;
+
+/// the action requested by the visitor after entering or leaving a node
+pub enum VisitAction<'a> {
+	// keep the node and visit its children
+	Continue,
+	// keep the node but don't visit its children, same as `Continue` when leaving
+	SkipChildren,
+	// remove the node and its descendants
+	Remove,
+	// replace the node with the element, such as a new one made by `clone_deep`, the element is not visited
+	Replace(BoxDynElement<'a>),
+}
+
+/// the visitor of `visit_mut`, the nodes are entered in document order and left after their children
+pub trait NodeVisitor {
+	fn enter<'b>(&mut self, _node: &BoxDynNode<'b>) -> VisitAction<'b> {
+		VisitAction::Continue
+	}
+	fn leave<'b>(&mut self, _node: &BoxDynNode<'b>) -> VisitAction<'b> {
+		VisitAction::Continue
+	}
+}
+
+// the error of the node can't be changed
+fn invalid_action(method: &str, message: &str) -> Box<dyn Error> {
+	Box::new(IError::InvalidTraitMethodCall {
+		method: method.into(),
+		message: message.into(),
+	})
+}
+
+// remove the node from its parent
+pub(super) fn remove_node(node: &BoxDynNode) -> Result<(), Box<dyn Error>> {
+	match node.clone_node().typed() {
+		IEnumTyped::Element(ele) => {
+			let mut parent = ele
+				.parent()
+				.ok_or_else(|| invalid_action("visit_mut", "The root node can't be removed."))?;
+			parent.remove_child(ele);
+		}
+		IEnumTyped::Text(text) => text.remove(),
+		IEnumTyped::UncareNode(node) => node.remove()?,
+	}
+	Ok(())
+}
+
+// replace the node with the element
+fn replace_node(node: &BoxDynNode, replacement: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+	match node.clone_node().typed() {
+		IEnumTyped::Element(mut ele) => {
+			let mut parent = ele
+				.parent()
+				.ok_or_else(|| invalid_action("visit_mut", "The root node can't be replaced."))?;
+			ele.insert_adjacent(&InsertPosition::BeforeBegin, replacement);
+			parent.remove_child(ele);
+		}
+		IEnumTyped::Text(mut text) => {
+			// the wrapper takes the place of the text, then remove the text from it
+			text.wrap(replacement)?;
+			text.remove();
+		}
+		IEnumTyped::UncareNode(_) => {
+			return Err(invalid_action(
+				"visit_mut",
+				"Only the elements and the text nodes can be replaced.",
+			));
+		}
+	}
+	Ok(())
+}
+
+// apply the action, return true if the node is removed or replaced
+fn apply(node: &BoxDynNode, action: VisitAction) -> Result<bool, Box<dyn Error>> {
+	match action {
+		VisitAction::Continue | VisitAction::SkipChildren => Ok(false),
+		VisitAction::Remove => remove_node(node).map(|_| true),
+		VisitAction::Replace(replacement) => replace_node(node, &replacement).map(|_| true),
+	}
+}
+
+/// pub fn `visit_mut`
+/// visit the node and its descendants, the child nodes are collected before visiting them,
+/// so the nodes inserted by the visitor are not visited
+pub fn visit_mut(node: &BoxDynNode, visitor: &mut dyn NodeVisitor) -> Result<(), Box<dyn Error>> {
+	let action = visitor.enter(node);
+	let skip_children = matches!(action, VisitAction::SkipChildren);
+	if apply(node, action)? {
+		return Ok(());
+	}
+	if !skip_children {
+		if let Some(ele) = node.clone_node().typed().into_element() {
+			for child in ele.child_nodes() {
+				visit_mut(&child, visitor)?;
+			}
+		}
+	}
+	let action = visitor.leave(node);
+	apply(node, action)?;
+	Ok(())
+}
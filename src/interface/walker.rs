@@ -0,0 +1,350 @@
+use super::{BoxDynNode, INodeType};
+use std::collections::VecDeque;
+use std::ops::BitOr;
+
+/// the node types shown by the `TreeWalker`, the same bits as the dom's `whatToShow`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhatToShow(u32);
+
+impl WhatToShow {
+	pub const ALL: WhatToShow = WhatToShow(0xFFFF_FFFF);
+	pub const ELEMENT: WhatToShow = WhatToShow(0x1);
+	pub const TEXT: WhatToShow = WhatToShow(0x4);
+	pub const CDATA_SECTION: WhatToShow = WhatToShow(0x8);
+	pub const PROCESSING_INSTRUCTION: WhatToShow = WhatToShow(0x40);
+	pub const COMMENT: WhatToShow = WhatToShow(0x80);
+	pub const DOCUMENT: WhatToShow = WhatToShow(0x100);
+	pub const DOCUMENT_TYPE: WhatToShow = WhatToShow(0x200);
+	pub const DOCUMENT_FRAGMENT: WhatToShow = WhatToShow(0x400);
+	/// pub fn `shows`
+	pub fn shows(&self, node_type: INodeType) -> bool {
+		let bit = 1u32 << (node_type as u32 - 1);
+		self.0 & bit != 0
+	}
+}
+
+impl BitOr for WhatToShow {
+	type Output = WhatToShow;
+	fn bitor(self, other: WhatToShow) -> WhatToShow {
+		WhatToShow(self.0 | other.0)
+	}
+}
+
+/// the result of the node filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeFilterResult {
+	// the node is returned
+	Accept,
+	// the node and its descendants are skipped
+	Reject,
+	// the node is skipped, but its descendants are checked
+	Skip,
+}
+
+pub type NodeFilter = Box<dyn Fn(&BoxDynNode) -> NodeFilterResult>;
+
+// check if the two nodes are the same node
+pub(super) fn is_same_node(node: &BoxDynNode, other: &BoxDynNode) -> bool {
+	if let (Some(uuid), Some(o_uuid)) = (node.uuid(), other.uuid()) {
+		return uuid == o_uuid;
+	}
+	match (
+		node.clone_node().typed().into_element(),
+		other.clone_node().typed().into_element(),
+	) {
+		(Some(ele), Some(other)) => ele.is(&other),
+		_ => false,
+	}
+}
+
+// the first or the last child node
+fn child_of<'a>(node: &BoxDynNode, is_first: bool) -> Option<BoxDynNode<'a>> {
+	let ele = node.clone_node().typed().into_element()?;
+	let total = ele.child_nodes_length();
+	if total == 0 {
+		return None;
+	}
+	ele.child_nodes_item(if is_first { 0 } else { total - 1 })
+}
+
+// the next or the previous sibling node
+fn sibling_of<'a>(node: &BoxDynNode, is_next: bool) -> Option<BoxDynNode<'a>> {
+	let parent = node.parent()?;
+	let index = node.index();
+	if is_next {
+		parent.child_nodes_item(index + 1)
+	} else if index > 0 {
+		parent.child_nodes_item(index - 1)
+	} else {
+		None
+	}
+}
+
+// the parent node
+fn parent_of<'a>(node: &BoxDynNode) -> Option<BoxDynNode<'a>> {
+	node.parent().map(|parent| parent.clone_node())
+}
+
+/// a dom style tree walker, move the current node by the methods, the nodes are filtered by
+/// the `what_to_show` and the node filter
+/// https://dom.spec.whatwg.org/#interface-treewalker
+pub struct TreeWalker<'a> {
+	root: BoxDynNode<'a>,
+	current: BoxDynNode<'a>,
+	what_to_show: WhatToShow,
+	filter: Option<NodeFilter>,
+}
+
+impl<'a> TreeWalker<'a> {
+	pub fn new(root: &BoxDynNode, what_to_show: WhatToShow) -> Self {
+		TreeWalker {
+			root: root.clone_node(),
+			current: root.clone_node(),
+			what_to_show,
+			filter: None,
+		}
+	}
+	/// pub fn `with_filter`
+	/// set the node filter, it's only called with the nodes shown by `what_to_show`
+	pub fn with_filter(mut self, filter: NodeFilter) -> Self {
+		self.filter = Some(filter);
+		self
+	}
+	/// pub fn `root`
+	pub fn root(&self) -> &BoxDynNode<'a> {
+		&self.root
+	}
+	/// pub fn `current_node`
+	pub fn current_node(&self) -> &BoxDynNode<'a> {
+		&self.current
+	}
+	/// pub fn `set_current_node`
+	pub fn set_current_node(&mut self, node: &BoxDynNode) {
+		self.current = node.clone_node();
+	}
+	// filter the node
+	fn check(&self, node: &BoxDynNode) -> NodeFilterResult {
+		if !self.what_to_show.shows(node.node_type()) {
+			return NodeFilterResult::Skip;
+		}
+		match &self.filter {
+			Some(filter) => filter(node),
+			None => NodeFilterResult::Accept,
+		}
+	}
+	fn is_root(&self, node: &BoxDynNode) -> bool {
+		is_same_node(node, &self.root)
+	}
+	// accept the node as the current node
+	fn accept(&mut self, node: BoxDynNode<'a>) -> Option<BoxDynNode<'a>> {
+		self.current = node.clone_node();
+		Some(node)
+	}
+	/// pub fn `parent_node`
+	pub fn parent_node(&mut self) -> Option<BoxDynNode<'a>> {
+		let mut node = self.current.clone_node();
+		while !self.is_root(&node) {
+			node = parent_of(&node)?;
+			if self.check(&node) == NodeFilterResult::Accept {
+				return self.accept(node);
+			}
+		}
+		None
+	}
+	// https://dom.spec.whatwg.org/#concept-traverse-children
+	fn traverse_children(&mut self, is_first: bool) -> Option<BoxDynNode<'a>> {
+		let mut node = child_of(&self.current, is_first)?;
+		loop {
+			match self.check(&node) {
+				NodeFilterResult::Accept => return self.accept(node),
+				NodeFilterResult::Skip => {
+					if let Some(child) = child_of(&node, is_first) {
+						node = child;
+						continue;
+					}
+				}
+				NodeFilterResult::Reject => {}
+			}
+			loop {
+				if let Some(sibling) = sibling_of(&node, is_first) {
+					node = sibling;
+					break;
+				}
+				let parent = parent_of(&node)?;
+				if self.is_root(&parent) || is_same_node(&parent, &self.current) {
+					return None;
+				}
+				node = parent;
+			}
+		}
+	}
+	/// pub fn `first_child`
+	pub fn first_child(&mut self) -> Option<BoxDynNode<'a>> {
+		self.traverse_children(true)
+	}
+	/// pub fn `last_child`
+	pub fn last_child(&mut self) -> Option<BoxDynNode<'a>> {
+		self.traverse_children(false)
+	}
+	// https://dom.spec.whatwg.org/#concept-traverse-siblings
+	fn traverse_siblings(&mut self, is_next: bool) -> Option<BoxDynNode<'a>> {
+		let mut node = self.current.clone_node();
+		if self.is_root(&node) {
+			return None;
+		}
+		loop {
+			let mut sibling = sibling_of(&node, is_next);
+			while let Some(cur) = sibling {
+				node = cur;
+				let result = self.check(&node);
+				if result == NodeFilterResult::Accept {
+					return self.accept(node);
+				}
+				sibling = child_of(&node, is_next);
+				if result == NodeFilterResult::Reject || sibling.is_none() {
+					sibling = sibling_of(&node, is_next);
+				}
+			}
+			node = parent_of(&node)?;
+			if self.is_root(&node) || self.check(&node) == NodeFilterResult::Accept {
+				return None;
+			}
+		}
+	}
+	/// pub fn `next_sibling`
+	pub fn next_sibling(&mut self) -> Option<BoxDynNode<'a>> {
+		self.traverse_siblings(true)
+	}
+	/// pub fn `previous_sibling`
+	pub fn previous_sibling(&mut self) -> Option<BoxDynNode<'a>> {
+		self.traverse_siblings(false)
+	}
+	/// pub fn `previous_node`
+	/// the previous node in document order
+	pub fn previous_node(&mut self) -> Option<BoxDynNode<'a>> {
+		let mut node = self.current.clone_node();
+		while !self.is_root(&node) {
+			let mut sibling = sibling_of(&node, false);
+			while let Some(cur) = sibling {
+				node = cur;
+				let mut result = self.check(&node);
+				while result != NodeFilterResult::Reject {
+					match child_of(&node, false) {
+						Some(child) => {
+							node = child;
+							result = self.check(&node);
+						}
+						None => break,
+					}
+				}
+				if result == NodeFilterResult::Accept {
+					return self.accept(node);
+				}
+				sibling = sibling_of(&node, false);
+			}
+			if self.is_root(&node) {
+				return None;
+			}
+			node = parent_of(&node)?;
+			if self.check(&node) == NodeFilterResult::Accept {
+				return self.accept(node);
+			}
+		}
+		None
+	}
+	/// pub fn `next_node`
+	/// the next node in document order
+	pub fn next_node(&mut self) -> Option<BoxDynNode<'a>> {
+		let mut node = self.current.clone_node();
+		let mut result = NodeFilterResult::Accept;
+		loop {
+			while result != NodeFilterResult::Reject {
+				match child_of(&node, true) {
+					Some(child) => {
+						node = child;
+						result = self.check(&node);
+						if result == NodeFilterResult::Accept {
+							return self.accept(node);
+						}
+					}
+					None => break,
+				}
+			}
+			// the next sibling of the node or its ancestors in the root
+			loop {
+				if self.is_root(&node) {
+					return None;
+				}
+				if let Some(sibling) = sibling_of(&node, true) {
+					node = sibling;
+					break;
+				}
+				node = parent_of(&node)?;
+			}
+			result = self.check(&node);
+			if result == NodeFilterResult::Accept {
+				return self.accept(node);
+			}
+		}
+	}
+}
+
+/// impl `Iterator` for TreeWalker, iterate the nodes after the current node by `next_node`
+impl<'a> Iterator for TreeWalker<'a> {
+	type Item = BoxDynNode<'a>;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_node()
+	}
+}
+
+// the child nodes of the node
+fn child_nodes<'a>(node: &BoxDynNode) -> Vec<BoxDynNode<'a>> {
+	match node.clone_node().typed().into_element() {
+		Some(ele) => ele.child_nodes(),
+		None => Vec::new(),
+	}
+}
+
+/// the depth-first iterator of the node and its descendants, in document order
+pub struct DepthFirst<'a> {
+	stack: Vec<BoxDynNode<'a>>,
+}
+
+impl<'a> DepthFirst<'a> {
+	pub fn new(root: &BoxDynNode) -> Self {
+		DepthFirst {
+			stack: vec![root.clone_node()],
+		}
+	}
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+	type Item = BoxDynNode<'a>;
+	fn next(&mut self) -> Option<Self::Item> {
+		let node = self.stack.pop()?;
+		self.stack.extend(child_nodes(&node).into_iter().rev());
+		Some(node)
+	}
+}
+
+/// the breadth-first iterator of the node and its descendants, level by level
+pub struct BreadthFirst<'a> {
+	queue: VecDeque<BoxDynNode<'a>>,
+}
+
+impl<'a> BreadthFirst<'a> {
+	pub fn new(root: &BoxDynNode) -> Self {
+		let mut queue = VecDeque::new();
+		queue.push_back(root.clone_node());
+		BreadthFirst { queue }
+	}
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+	type Item = BoxDynNode<'a>;
+	fn next(&mut self) -> Option<Self::Item> {
+		let node = self.queue.pop_front()?;
+		self.queue.extend(child_nodes(&node));
+		Some(node)
+	}
+}
@@ -0,0 +1,480 @@
+use super::ATTR_NAMESPACES;
+use crate::interface::{
+	BoxDynElement, BoxDynNode, BoxDynText, DocumentType, Elements, IAttrValue, IDocumentTrait,
+	IElementTrait, IEnumTyped, INodeTrait, INodeType, ITextTrait, IUncareNodeTrait, InsertPosition,
+	MaybeDoc, MaybeElement,
+};
+use crate::utils::{split_attr_name, StrCache, StrKind};
+use ::kuchiki::traits::TendrilSink;
+use ::kuchiki::{Attribute, ExpandedName, NodeData, NodeRef};
+use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
+
+/// a `kuchiki` node
+#[derive(Clone)]
+pub struct Node {
+	node: NodeRef,
+	uuid: String,
+	// the html and the texts returned by reference, freed with the handle
+	strings: StrCache,
+}
+
+/// a `kuchiki` document
+pub struct Document {
+	root: NodeRef,
+	strings: StrCache,
+}
+
+impl From<NodeRef> for Node {
+	fn from(node: NodeRef) -> Self {
+		let uuid = format!("{:p}", Rc::as_ptr(&node.0));
+		Node {
+			node,
+			uuid,
+			strings: StrCache::default(),
+		}
+	}
+}
+
+impl From<Node> for NodeRef {
+	fn from(node: Node) -> Self {
+		node.node
+	}
+}
+
+impl<'a> From<NodeRef> for Elements<'a> {
+	fn from(node: NodeRef) -> Self {
+		Elements::with_nodes(vec![Box::new(Node::from(node))])
+	}
+}
+
+// the document node, such as the result of `kuchiki::parse_html()`
+impl From<NodeRef> for Document {
+	fn from(root: NodeRef) -> Self {
+		Document {
+			root,
+			strings: StrCache::default(),
+		}
+	}
+}
+
+impl Document {
+	/// pub fn `elements`
+	/// the document node as `Elements`
+	pub fn elements<'b>(&self) -> Elements<'b> {
+		Elements::from(self.root.clone())
+	}
+	/// pub fn `get_ref`
+	/// get the document node
+	pub fn get_ref(&self) -> &NodeRef {
+		&self.root
+	}
+}
+
+impl Node {
+	/// pub fn `get_ref`
+	/// get the `NodeRef` of the node
+	pub fn get_ref(&self) -> &NodeRef {
+		&self.node
+	}
+	/// pub fn `from_element`
+	/// get the `NodeRef` of an element created by this backend
+	pub fn from_element(ele: &BoxDynElement) -> Option<NodeRef> {
+		ele
+			.cloned()
+			.to_node()
+			.downcast::<Node>()
+			.ok()
+			.map(|node| node.node)
+	}
+	/// pub fn `from_node`
+	/// get the `NodeRef` of a node created by this backend
+	pub fn from_node(node: &BoxDynNode) -> Option<NodeRef> {
+		node
+			.clone_node()
+			.to_node()
+			.downcast::<Node>()
+			.ok()
+			.map(|node| node.node)
+	}
+}
+
+// the attribute name with the prefix, such as `xlink:href`
+fn qualified_name(key: &ExpandedName, attr: &Attribute) -> String {
+	match &attr.prefix {
+		Some(prefix) => format!("{}:{}", prefix, key.local),
+		None => key.local.to_string(),
+	}
+}
+
+// remove all the children
+fn clear_children(node: &NodeRef) {
+	while let Some(child) = node.first_child() {
+		child.detach();
+	}
+}
+
+// copy the node, and its descendants if `deep`
+fn copy_node(node: &NodeRef, deep: bool) -> NodeRef {
+	let copy = match node.data() {
+		NodeData::Element(ele) => {
+			let attrs = ele.attributes.borrow().map.clone();
+			let copy = NodeRef::new_element(ele.name.clone(), attrs);
+			// the new template element has an empty fragment, copy the contents into it
+			let contents = copy
+				.as_element()
+				.and_then(|copy| copy.template_contents.as_ref());
+			if let (Some(from), Some(to), true) = (&ele.template_contents, contents, deep) {
+				for child in from.children() {
+					to.append(copy_node(&child, true));
+				}
+			}
+			copy
+		}
+		NodeData::Text(text) => NodeRef::new_text(text.borrow().clone()),
+		NodeData::Comment(comment) => NodeRef::new_comment(comment.borrow().clone()),
+		NodeData::Doctype(doctype) => NodeRef::new_doctype(
+			doctype.name.clone(),
+			doctype.public_id.clone(),
+			doctype.system_id.clone(),
+		),
+		NodeData::ProcessingInstruction(pi) => {
+			let pi = pi.borrow();
+			NodeRef::new_processing_instruction(pi.0.clone(), pi.1.clone())
+		}
+		NodeData::Document(_) => NodeRef::new_document(),
+		NodeData::DocumentFragment => NodeRef::new(NodeData::DocumentFragment),
+	};
+	if deep {
+		for child in node.children() {
+			copy.append(copy_node(&child, true));
+		}
+	}
+	copy
+}
+
+impl IDocumentTrait for Document {
+	fn get_element_by_id<'b>(&self, id: &str) -> Option<BoxDynElement<'b>> {
+		self
+			.root
+			.descendants()
+			.find(|node| match node.as_element() {
+				Some(ele) => ele.attributes.borrow().get("id") == Some(id),
+				None => false,
+			})
+			.map(|node| Box::new(Node::from(node)) as BoxDynElement)
+	}
+	fn source_code(&self) -> &str {
+		self.strings.keep(StrKind::Html, self.root.to_string())
+	}
+	fn get_root_node<'b>(&self) -> BoxDynNode<'b> {
+		Box::new(Node::from(self.root.clone()))
+	}
+	fn deep_clone<'b>(&self) -> Result<Box<dyn IDocumentTrait + 'b>, Box<dyn Error>> {
+		Ok(Box::new(Document {
+			root: copy_node(&self.root, true),
+			strings: StrCache::default(),
+		}))
+	}
+}
+
+impl INodeTrait for Node {
+	fn to_node(self: Box<Self>) -> Box<dyn Any> {
+		self
+	}
+	fn clone_node<'b>(&self) -> BoxDynNode<'b> {
+		Box::new(self.clone())
+	}
+	fn typed<'b>(self: Box<Self>) -> IEnumTyped<'b> {
+		match self.node.data() {
+			NodeData::Element(_) | NodeData::Document(_) | NodeData::DocumentFragment => {
+				IEnumTyped::Element(self)
+			}
+			NodeData::Text(_) => IEnumTyped::Text(self),
+			_ => IEnumTyped::UncareNode(self),
+		}
+	}
+	fn node_type(&self) -> INodeType {
+		match self.node.data() {
+			NodeData::Element(_) => INodeType::Element,
+			NodeData::Text(_) => INodeType::Text,
+			NodeData::Comment(_) => INodeType::Comment,
+			NodeData::Document(_) => INodeType::Document,
+			NodeData::DocumentFragment => INodeType::DocumentFragement,
+			NodeData::Doctype(_) => INodeType::HTMLDOCTYPE,
+			NodeData::ProcessingInstruction(_) => INodeType::ProcessingInstruction,
+		}
+	}
+	fn parent<'b>(&self) -> MaybeElement<'b> {
+		self
+			.node
+			.parent()
+			.map(|parent| Box::new(Node::from(parent)) as BoxDynElement)
+	}
+	fn uuid(&self) -> Option<&str> {
+		Some(&self.uuid)
+	}
+	fn owner_document(&self) -> MaybeDoc<'_> {
+		let root = self.node.inclusive_ancestors().last()?;
+		match root.data() {
+			NodeData::Document(_) => Some(Box::new(Document {
+				root,
+				strings: StrCache::default(),
+			})),
+			_ => None,
+		}
+	}
+	fn doctype(&self) -> Option<DocumentType> {
+		self.node.as_doctype().map(|doctype| DocumentType {
+			name: doctype.name.clone(),
+			public_id: doctype.public_id.clone(),
+			system_id: doctype.system_id.clone(),
+		})
+	}
+	fn pi_target(&self) -> Option<String> {
+		match self.node.data() {
+			NodeData::ProcessingInstruction(pi) => Some(pi.borrow().0.clone()),
+			_ => None,
+		}
+	}
+	fn text_content(&self) -> &str {
+		let text = if let Some(comment) = self.node.as_comment() {
+			comment.borrow().clone()
+		} else if let NodeData::ProcessingInstruction(pi) = self.node.data() {
+			pi.borrow().1.clone()
+		} else {
+			self.node.text_contents()
+		};
+		self.strings.keep(StrKind::Text, text)
+	}
+	fn set_text(&mut self, content: &str) {
+		if let Some(text) = self.node.as_text() {
+			*text.borrow_mut() = String::from(content);
+			return;
+		}
+		clear_children(&self.node);
+		if !content.is_empty() {
+			self.node.append(NodeRef::new_text(content));
+		}
+	}
+	fn set_html(&mut self, content: &str) {
+		let fragment = match self.node.as_element() {
+			Some(ele) => ::kuchiki::parse_fragment(ele.name.clone(), vec![]).one(content),
+			None => ::kuchiki::parse_html().one(content),
+		};
+		clear_children(&self.node);
+		// the fragment is parsed into a `html` element of a new document
+		let fragment = match self.node.as_element() {
+			Some(_) => fragment.first_child(),
+			None => Some(fragment),
+		};
+		if let Some(fragment) = fragment {
+			for child in fragment.children().collect::<Vec<NodeRef>>() {
+				self.node.append(child);
+			}
+		}
+	}
+	fn index(&self) -> usize {
+		self.node.preceding_siblings().count()
+	}
+}
+
+impl IElementTrait for Node {
+	fn is(&self, ele: &BoxDynElement) -> bool {
+		match Node::from_element(ele) {
+			Some(node) => self.node == node,
+			None => false,
+		}
+	}
+	fn tag_name(&self) -> &str {
+		match self.node.as_element() {
+			Some(ele) => self.strings.keep(
+				StrKind::TagName,
+				ele.name.local.to_ascii_uppercase().to_string(),
+			),
+			None => "",
+		}
+	}
+	fn local_name(&self) -> &str {
+		match self.node.as_element() {
+			Some(ele) => &ele.name.local,
+			None => "",
+		}
+	}
+	fn namespace_uri(&self) -> Option<&str> {
+		self.node.as_element().map(|ele| &*ele.name.ns)
+	}
+	fn child_nodes_length(&self) -> usize {
+		self.node.children().count()
+	}
+	fn child_nodes_item<'b>(&self, index: usize) -> Option<BoxDynNode<'b>> {
+		self
+			.node
+			.children()
+			.nth(index)
+			.map(|child| Box::new(Node::from(child)) as BoxDynNode)
+	}
+	// the parser keeps no difference between `attr` and `attr=""`, the attributes always have values
+	fn get_attribute(&self, name: &str) -> Option<IAttrValue> {
+		let ele = self.node.as_element()?;
+		let attrs = ele.attributes.borrow();
+		attrs
+			.map
+			.iter()
+			.find(|(key, attr)| qualified_name(key, attr).eq_ignore_ascii_case(name))
+			.map(|(_, attr)| IAttrValue::Value(attr.value.clone(), Some('"')))
+	}
+	// the attributes are sorted by the names, kuchiki doesn't keep the source order
+	fn attributes(&self) -> Vec<(String, IAttrValue)> {
+		match self.node.as_element() {
+			Some(ele) => ele
+				.attributes
+				.borrow()
+				.map
+				.iter()
+				.map(|(key, attr)| {
+					let value = IAttrValue::Value(attr.value.clone(), Some('"'));
+					(qualified_name(key, attr), value)
+				})
+				.collect(),
+			None => Vec::new(),
+		}
+	}
+	fn set_attribute(&mut self, name: &str, value: Option<&str>) {
+		if let Some(ele) = self.node.as_element() {
+			let value = String::from(value.unwrap_or(""));
+			let mut attrs = ele.attributes.borrow_mut();
+			match attrs
+				.map
+				.iter_mut()
+				.find(|(key, attr)| qualified_name(key, attr).eq_ignore_ascii_case(name))
+			{
+				Some((_, attr)) => attr.value = value,
+				None => {
+					let prefix = split_attr_name(name).0.and_then(|prefix| {
+						ATTR_NAMESPACES
+							.iter()
+							.find(|(known, _)| *known == prefix)
+							.map(|(_, url)| (prefix, *url))
+					});
+					match prefix {
+						Some((prefix, url)) => {
+							let key = ExpandedName::new(url, split_attr_name(name).1);
+							let prefix = Some(prefix.into());
+							attrs.map.insert(key, Attribute { prefix, value });
+						}
+						None => {
+							attrs.insert(name, value);
+						}
+					}
+				}
+			}
+		}
+	}
+	fn remove_attribute(&mut self, name: &str) {
+		if let Some(ele) = self.node.as_element() {
+			ele
+				.attributes
+				.borrow_mut()
+				.map
+				.retain(|key, attr| !qualified_name(key, attr).eq_ignore_ascii_case(name));
+		}
+	}
+	fn inner_html(&self) -> &str {
+		let html: String = self
+			.node
+			.children()
+			.map(|child| child.to_string())
+			.collect();
+		self.strings.keep(StrKind::InnerHtml, html)
+	}
+	fn outer_html(&self) -> &str {
+		self.strings.keep(StrKind::Html, self.node.to_string())
+	}
+	fn insert_adjacent(&mut self, position: &InsertPosition, ele: &BoxDynElement) {
+		let node = match Node::from_element(ele) {
+			Some(node) => node,
+			None => return,
+		};
+		if node == self.node {
+			return;
+		}
+		node.detach();
+		match position {
+			InsertPosition::BeforeBegin => self.node.insert_before(node),
+			InsertPosition::AfterBegin => self.node.prepend(node),
+			InsertPosition::BeforeEnd => self.node.append(node),
+			InsertPosition::AfterEnd => self.node.insert_after(node),
+		}
+	}
+	fn template_content<'b>(&self) -> Option<BoxDynElement<'b>> {
+		let contents = self.node.as_element()?.template_contents.clone()?;
+		Some(Box::new(Node::from(contents)))
+	}
+	fn clone_deep<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		Ok(Box::new(Node::from(copy_node(&self.node, true))))
+	}
+	fn clone_shallow<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		Ok(Box::new(Node::from(copy_node(&self.node, false))))
+	}
+	fn remove_child(&mut self, ele: BoxDynElement) {
+		if let Some(node) = Node::from_element(&ele) {
+			if node.parent().as_ref() == Some(&self.node) {
+				node.detach();
+			}
+		}
+	}
+	fn insert_child(&mut self, index: usize, node: &BoxDynNode) -> Result<(), Box<dyn Error>> {
+		let node = Node::from_node(node).ok_or("The node is not a kuchiki node.")?;
+		node.detach();
+		match self.node.children().nth(index) {
+			Some(child) => child.insert_before(node),
+			None => self.node.append(node),
+		}
+		Ok(())
+	}
+	fn into_text<'b>(self: Box<Self>) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		Ok(self)
+	}
+}
+
+impl ITextTrait for Node {
+	fn remove(self: Box<Self>) {
+		self.node.detach();
+	}
+	fn append_text(&mut self, content: &str) {
+		if let Some(text) = self.node.as_text() {
+			text.borrow_mut().push_str(content);
+		}
+	}
+	fn prepend_text(&mut self, content: &str) {
+		if let Some(text) = self.node.as_text() {
+			text.borrow_mut().insert_str(0, content);
+		}
+	}
+	fn split_text<'b>(&mut self, offset: usize) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		let text = self.node.as_text().ok_or("The node is not a text node.")?;
+		let mut content = text.borrow_mut();
+		if !content.is_char_boundary(offset) {
+			return Err(format!("The offset {} is not a char boundary.", offset).into());
+		}
+		let rest = NodeRef::new_text(content.split_off(offset));
+		self.node.insert_after(rest.clone());
+		Ok(Box::new(Node::from(rest)))
+	}
+	fn wrap(&mut self, wrapper: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+		let wrapper = Node::from_element(wrapper).ok_or("The wrapper is not a kuchiki node.")?;
+		wrapper.detach();
+		self.node.insert_before(wrapper.clone());
+		wrapper.append(self.node.clone());
+		Ok(())
+	}
+}
+
+impl IUncareNodeTrait for Node {
+	fn remove(self: Box<Self>) -> Result<(), Box<dyn Error>> {
+		self.node.detach();
+		Ok(())
+	}
+}
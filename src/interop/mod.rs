@@ -0,0 +1,13 @@
+// the namespaces of the prefixed attributes in html, such as `xlink:href`
+#[cfg(any(feature = "rcdom", feature = "kuchiki"))]
+const ATTR_NAMESPACES: [(&str, &str); 3] = [
+	("xlink", "http://www.w3.org/1999/xlink"),
+	("xml", "http://www.w3.org/XML/1998/namespace"),
+	("xmlns", "http://www.w3.org/2000/xmlns/"),
+];
+// markup5ever_rcdom handles
+#[cfg(feature = "rcdom")]
+pub mod rcdom;
+// kuchiki node refs
+#[cfg(feature = "kuchiki")]
+pub mod kuchiki;
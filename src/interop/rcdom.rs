@@ -0,0 +1,609 @@
+use super::ATTR_NAMESPACES;
+use crate::interface::{
+	BoxDynElement, BoxDynNode, BoxDynText, DocumentType, Elements, IAttrValue, IDocumentTrait,
+	IElementTrait, IEnumTyped, INodeTrait, INodeType, ITextTrait, IUncareNodeTrait, InsertPosition,
+	MaybeDoc, MaybeElement,
+};
+use crate::utils::{split_attr_name, StrCache, StrKind};
+use html5ever::serialize::{serialize, SerializeOpts, TraversalScope};
+use html5ever::tendril::{StrTendril, TendrilSink};
+use html5ever::{namespace_url, ns, Attribute, LocalName, Namespace, Prefix, QualName};
+use markup5ever_rcdom::{Handle, NodeData, RcDom, SerializableHandle};
+use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
+
+/// a `markup5ever_rcdom` node
+#[derive(Clone)]
+pub struct Node {
+	handle: Handle,
+	uuid: String,
+	// the html and the texts returned by reference, freed with the handle
+	strings: StrCache,
+}
+
+/// a `markup5ever_rcdom` document
+pub struct Document {
+	root: Handle,
+	strings: StrCache,
+}
+
+impl From<Handle> for Node {
+	fn from(handle: Handle) -> Self {
+		let uuid = format!("{:p}", Rc::as_ptr(&handle));
+		Node {
+			handle,
+			uuid,
+			strings: StrCache::default(),
+		}
+	}
+}
+
+impl From<Node> for Handle {
+	fn from(node: Node) -> Self {
+		node.handle
+	}
+}
+
+impl From<RcDom> for Document {
+	fn from(dom: RcDom) -> Self {
+		Document {
+			root: dom.document,
+			strings: StrCache::default(),
+		}
+	}
+}
+
+impl<'a> From<Handle> for Elements<'a> {
+	fn from(handle: Handle) -> Self {
+		Elements::with_nodes(vec![Box::new(Node::from(handle))])
+	}
+}
+
+impl Document {
+	/// pub fn `elements`
+	/// the document node as `Elements`
+	pub fn elements<'b>(&self) -> Elements<'b> {
+		Elements::from(self.root.clone())
+	}
+	/// pub fn `get_ref`
+	/// get the document node
+	pub fn get_ref(&self) -> &Handle {
+		&self.root
+	}
+}
+
+impl Node {
+	/// pub fn `get_ref`
+	/// get the `Handle` of the node
+	pub fn get_ref(&self) -> &Handle {
+		&self.handle
+	}
+	/// pub fn `from_element`
+	/// get the `Handle` of an element created by this backend
+	pub fn from_element(ele: &BoxDynElement) -> Option<Handle> {
+		ele
+			.cloned()
+			.to_node()
+			.downcast::<Node>()
+			.ok()
+			.map(|node| node.handle)
+	}
+	/// pub fn `from_node`
+	/// get the `Handle` of a node created by this backend
+	pub fn from_node(node: &BoxDynNode) -> Option<Handle> {
+		node
+			.clone_node()
+			.to_node()
+			.downcast::<Node>()
+			.ok()
+			.map(|node| node.handle)
+	}
+}
+
+// the attribute name with the prefix, such as `xlink:href`
+fn qualified_name(name: &QualName) -> String {
+	match &name.prefix {
+		Some(prefix) => format!("{}:{}", prefix, name.local),
+		None => name.local.to_string(),
+	}
+}
+
+// the attribute name of the qualified name, the known prefixes get their namespaces
+fn attr_qual_name(name: &str) -> QualName {
+	if let (Some(prefix), local_name) = split_attr_name(name) {
+		if let Some((_, url)) = ATTR_NAMESPACES.iter().find(|(known, _)| *known == prefix) {
+			return QualName::new(
+				Some(Prefix::from(prefix)),
+				Namespace::from(*url),
+				LocalName::from(local_name),
+			);
+		}
+	}
+	QualName::new(None, ns!(), LocalName::from(name))
+}
+
+// the parent handle
+fn get_parent(handle: &Handle) -> Option<Handle> {
+	let weak = handle.parent.take();
+	let parent = weak.as_ref().and_then(|weak| weak.upgrade());
+	handle.parent.set(weak);
+	parent
+}
+
+// the index in the parent's children
+fn get_index(parent: &Handle, handle: &Handle) -> Option<usize> {
+	parent
+		.children
+		.borrow()
+		.iter()
+		.position(|child| Rc::ptr_eq(child, handle))
+}
+
+// remove the node from its parent
+fn detach(handle: &Handle) {
+	if let Some(parent) = get_parent(handle) {
+		if let Some(index) = get_index(&parent, handle) {
+			parent.children.borrow_mut().remove(index);
+		}
+		handle.parent.set(None);
+	}
+}
+
+// insert the node into the parent's children
+fn insert_at(parent: &Handle, index: Option<usize>, handle: &Handle) {
+	detach(handle);
+	handle.parent.set(Some(Rc::downgrade(parent)));
+	let mut children = parent.children.borrow_mut();
+	match index {
+		Some(index) => {
+			let index = index.min(children.len());
+			children.insert(index, handle.clone());
+		}
+		None => children.push(handle.clone()),
+	}
+}
+
+// remove all the children
+fn clear_children(handle: &Handle) {
+	for child in handle.children.borrow_mut().drain(..) {
+		child.parent.set(None);
+	}
+}
+
+// collect the text of the node and the descendants
+fn collect_text(handle: &Handle, result: &mut String) {
+	match &handle.data {
+		NodeData::Text { contents } => result.push_str(&contents.borrow()),
+		NodeData::Element { .. } | NodeData::Document => {
+			for child in handle.children.borrow().iter() {
+				collect_text(child, result);
+			}
+		}
+		_ => {}
+	}
+}
+
+// copy the node, and its descendants if `deep`
+fn copy_node(handle: &Handle, deep: bool) -> Handle {
+	let data = match &handle.data {
+		NodeData::Document => NodeData::Document,
+		NodeData::Doctype {
+			name,
+			public_id,
+			system_id,
+		} => NodeData::Doctype {
+			name: name.clone(),
+			public_id: public_id.clone(),
+			system_id: system_id.clone(),
+		},
+		NodeData::Text { contents } => NodeData::Text {
+			contents: contents.clone(),
+		},
+		NodeData::Comment { contents } => NodeData::Comment {
+			contents: contents.clone(),
+		},
+		NodeData::Element {
+			name,
+			attrs,
+			template_contents,
+			mathml_annotation_xml_integration_point,
+		} => NodeData::Element {
+			name: name.clone(),
+			attrs: attrs.clone(),
+			template_contents: template_contents
+				.borrow()
+				.as_ref()
+				.map(|contents| copy_node(contents, deep))
+				.into(),
+			mathml_annotation_xml_integration_point: *mathml_annotation_xml_integration_point,
+		},
+		NodeData::ProcessingInstruction { target, contents } => NodeData::ProcessingInstruction {
+			target: target.clone(),
+			contents: contents.clone(),
+		},
+	};
+	let copy = markup5ever_rcdom::Node::new(data);
+	if deep {
+		for child in handle.children.borrow().iter() {
+			insert_at(&copy, None, &copy_node(child, true));
+		}
+	}
+	copy
+}
+
+// serialize the node or its children
+fn to_html(handle: &Handle, include_node: bool) -> String {
+	let traversal_scope = match handle.data {
+		NodeData::Element { .. } if include_node => TraversalScope::IncludeNode,
+		_ => TraversalScope::ChildrenOnly(None),
+	};
+	let mut bytes = Vec::new();
+	let result = serialize(
+		&mut bytes,
+		&SerializableHandle::from(handle.clone()),
+		SerializeOpts {
+			traversal_scope,
+			..Default::default()
+		},
+	);
+	match result {
+		Ok(_) => String::from_utf8(bytes).unwrap_or_default(),
+		Err(_) => String::new(),
+	}
+}
+
+impl IDocumentTrait for Document {
+	fn get_element_by_id<'b>(&self, id: &str) -> Option<BoxDynElement<'b>> {
+		fn loop_handle(handle: &Handle, id: &str) -> Option<Handle> {
+			for child in handle.children.borrow().iter() {
+				if let NodeData::Element { attrs, .. } = &child.data {
+					let is_matched = attrs
+						.borrow()
+						.iter()
+						.any(|attr| &*attr.name.local == "id" && &*attr.value == id);
+					if is_matched {
+						return Some(child.clone());
+					}
+					if let Some(handle) = loop_handle(child, id) {
+						return Some(handle);
+					}
+				}
+			}
+			None
+		}
+		loop_handle(&self.root, id).map(|handle| Box::new(Node::from(handle)) as BoxDynElement)
+	}
+	fn source_code(&self) -> &str {
+		self.strings.keep(StrKind::Html, to_html(&self.root, false))
+	}
+	fn get_root_node<'b>(&self) -> BoxDynNode<'b> {
+		Box::new(Node::from(self.root.clone()))
+	}
+	fn deep_clone<'b>(&self) -> Result<Box<dyn IDocumentTrait + 'b>, Box<dyn Error>> {
+		Ok(Box::new(Document {
+			root: copy_node(&self.root, true),
+			strings: StrCache::default(),
+		}))
+	}
+}
+
+impl INodeTrait for Node {
+	fn to_node(self: Box<Self>) -> Box<dyn Any> {
+		self
+	}
+	fn clone_node<'b>(&self) -> BoxDynNode<'b> {
+		Box::new(self.clone())
+	}
+	fn typed<'b>(self: Box<Self>) -> IEnumTyped<'b> {
+		match self.handle.data {
+			NodeData::Element { .. } | NodeData::Document => IEnumTyped::Element(self),
+			NodeData::Text { .. } => IEnumTyped::Text(self),
+			_ => IEnumTyped::UncareNode(self),
+		}
+	}
+	fn node_type(&self) -> INodeType {
+		match self.handle.data {
+			NodeData::Element { .. } => INodeType::Element,
+			NodeData::Text { .. } => INodeType::Text,
+			NodeData::Comment { .. } => INodeType::Comment,
+			NodeData::Document => INodeType::Document,
+			NodeData::Doctype { .. } => INodeType::HTMLDOCTYPE,
+			NodeData::ProcessingInstruction { .. } => INodeType::ProcessingInstruction,
+		}
+	}
+	fn parent<'b>(&self) -> MaybeElement<'b> {
+		get_parent(&self.handle).map(|parent| Box::new(Node::from(parent)) as BoxDynElement)
+	}
+	fn uuid(&self) -> Option<&str> {
+		Some(&self.uuid)
+	}
+	fn owner_document(&self) -> MaybeDoc<'_> {
+		let mut root = self.handle.clone();
+		while let Some(parent) = get_parent(&root) {
+			root = parent;
+		}
+		match root.data {
+			NodeData::Document => Some(Box::new(Document {
+				root,
+				strings: StrCache::default(),
+			})),
+			_ => None,
+		}
+	}
+	fn doctype(&self) -> Option<DocumentType> {
+		match &self.handle.data {
+			NodeData::Doctype {
+				name,
+				public_id,
+				system_id,
+			} => Some(DocumentType {
+				name: name.to_string(),
+				public_id: public_id.to_string(),
+				system_id: system_id.to_string(),
+			}),
+			_ => None,
+		}
+	}
+	fn pi_target(&self) -> Option<String> {
+		match &self.handle.data {
+			NodeData::ProcessingInstruction { target, .. } => Some(target.to_string()),
+			_ => None,
+		}
+	}
+	fn text_content(&self) -> &str {
+		let mut result = String::new();
+		match &self.handle.data {
+			NodeData::Comment { contents } => result.push_str(contents),
+			NodeData::ProcessingInstruction { contents, .. } => result.push_str(contents),
+			_ => collect_text(&self.handle, &mut result),
+		}
+		self.strings.keep(StrKind::Text, result)
+	}
+	fn set_text(&mut self, content: &str) {
+		if let NodeData::Text { contents } = &self.handle.data {
+			*contents.borrow_mut() = StrTendril::from(content);
+			return;
+		}
+		clear_children(&self.handle);
+		if !content.is_empty() {
+			let text = markup5ever_rcdom::Node::new(NodeData::Text {
+				contents: StrTendril::from(content).into(),
+			});
+			insert_at(&self.handle, None, &text);
+		}
+	}
+	fn set_html(&mut self, content: &str) {
+		let context = match &self.handle.data {
+			NodeData::Element { name, .. } => name.clone(),
+			_ => QualName::new(None, ns!(html), LocalName::from("body")),
+		};
+		let dom =
+			html5ever::parse_fragment(RcDom::default(), Default::default(), context, vec![]).one(content);
+		clear_children(&self.handle);
+		// the fragment is parsed into a `html` element of a new document
+		let fragment = dom.document.children.borrow().first().cloned();
+		if let Some(fragment) = fragment {
+			let children = fragment.children.borrow().clone();
+			for child in children {
+				insert_at(&self.handle, None, &child);
+			}
+		}
+	}
+	fn index(&self) -> usize {
+		get_parent(&self.handle)
+			.and_then(|parent| get_index(&parent, &self.handle))
+			.unwrap_or(0)
+	}
+}
+
+impl IElementTrait for Node {
+	fn is(&self, ele: &BoxDynElement) -> bool {
+		match Node::from_element(ele) {
+			Some(handle) => Rc::ptr_eq(&self.handle, &handle),
+			None => false,
+		}
+	}
+	fn tag_name(&self) -> &str {
+		match &self.handle.data {
+			NodeData::Element { name, .. } => self.strings.keep(
+				StrKind::TagName,
+				name.local.to_ascii_uppercase().to_string(),
+			),
+			_ => "",
+		}
+	}
+	fn local_name(&self) -> &str {
+		match &self.handle.data {
+			NodeData::Element { name, .. } => &name.local,
+			_ => "",
+		}
+	}
+	fn namespace_uri(&self) -> Option<&str> {
+		match &self.handle.data {
+			NodeData::Element { name, .. } => Some(&name.ns),
+			_ => None,
+		}
+	}
+	fn child_nodes_length(&self) -> usize {
+		self.handle.children.borrow().len()
+	}
+	fn child_nodes_item<'b>(&self, index: usize) -> Option<BoxDynNode<'b>> {
+		self
+			.handle
+			.children
+			.borrow()
+			.get(index)
+			.map(|child| Box::new(Node::from(child.clone())) as BoxDynNode)
+	}
+	// the parser keeps no difference between `attr` and `attr=""`, the attributes always have values
+	fn get_attribute(&self, name: &str) -> Option<IAttrValue> {
+		match &self.handle.data {
+			NodeData::Element { attrs, .. } => attrs
+				.borrow()
+				.iter()
+				.find(|attr| qualified_name(&attr.name).eq_ignore_ascii_case(name))
+				.map(|attr| IAttrValue::Value(attr.value.to_string(), Some('"'))),
+			_ => None,
+		}
+	}
+	fn attributes(&self) -> Vec<(String, IAttrValue)> {
+		match &self.handle.data {
+			NodeData::Element { attrs, .. } => attrs
+				.borrow()
+				.iter()
+				.map(|attr| {
+					let value = IAttrValue::Value(attr.value.to_string(), Some('"'));
+					(qualified_name(&attr.name), value)
+				})
+				.collect(),
+			_ => Vec::new(),
+		}
+	}
+	fn set_attribute(&mut self, name: &str, value: Option<&str>) {
+		if let NodeData::Element { attrs, .. } = &self.handle.data {
+			let value = StrTendril::from(value.unwrap_or(""));
+			let mut attrs = attrs.borrow_mut();
+			match attrs
+				.iter_mut()
+				.find(|attr| qualified_name(&attr.name).eq_ignore_ascii_case(name))
+			{
+				Some(attr) => attr.value = value,
+				None => attrs.push(Attribute {
+					name: attr_qual_name(name),
+					value,
+				}),
+			}
+		}
+	}
+	fn remove_attribute(&mut self, name: &str) {
+		if let NodeData::Element { attrs, .. } = &self.handle.data {
+			attrs
+				.borrow_mut()
+				.retain(|attr| !qualified_name(&attr.name).eq_ignore_ascii_case(name));
+		}
+	}
+	fn inner_html(&self) -> &str {
+		self
+			.strings
+			.keep(StrKind::InnerHtml, to_html(&self.handle, false))
+	}
+	fn outer_html(&self) -> &str {
+		self
+			.strings
+			.keep(StrKind::Html, to_html(&self.handle, true))
+	}
+	fn insert_adjacent(&mut self, position: &InsertPosition, ele: &BoxDynElement) {
+		let handle = match Node::from_element(ele) {
+			Some(handle) => handle,
+			None => return,
+		};
+		match position {
+			InsertPosition::BeforeEnd => insert_at(&self.handle, None, &handle),
+			InsertPosition::AfterBegin => insert_at(&self.handle, Some(0), &handle),
+			InsertPosition::BeforeBegin | InsertPosition::AfterEnd => {
+				if let Some(parent) = get_parent(&self.handle) {
+					detach(&handle);
+					if let Some(index) = get_index(&parent, &self.handle) {
+						let index = match position {
+							InsertPosition::AfterEnd => index + 1,
+							_ => index,
+						};
+						insert_at(&parent, Some(index), &handle);
+					}
+				}
+			}
+		}
+	}
+	fn template_content<'b>(&self) -> Option<BoxDynElement<'b>> {
+		match &self.handle.data {
+			NodeData::Element {
+				template_contents, ..
+			} => {
+				let contents = template_contents.borrow().clone()?;
+				Some(Box::new(Node::from(contents)))
+			}
+			_ => None,
+		}
+	}
+	fn clone_deep<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		Ok(Box::new(Node::from(copy_node(&self.handle, true))))
+	}
+	fn clone_shallow<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		Ok(Box::new(Node::from(copy_node(&self.handle, false))))
+	}
+	fn remove_child(&mut self, ele: BoxDynElement) {
+		if let Some(handle) = Node::from_element(&ele) {
+			if get_index(&self.handle, &handle).is_some() {
+				detach(&handle);
+			}
+		}
+	}
+	fn insert_child(&mut self, index: usize, node: &BoxDynNode) -> Result<(), Box<dyn Error>> {
+		let handle = Node::from_node(node).ok_or("The node is not a rcdom node.")?;
+		detach(&handle);
+		insert_at(&self.handle, Some(index), &handle);
+		Ok(())
+	}
+	fn into_text<'b>(self: Box<Self>) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		Ok(self)
+	}
+}
+
+impl ITextTrait for Node {
+	fn remove(self: Box<Self>) {
+		detach(&self.handle);
+	}
+	fn append_text(&mut self, content: &str) {
+		if let NodeData::Text { contents } = &self.handle.data {
+			contents.borrow_mut().push_slice(content);
+		}
+	}
+	fn prepend_text(&mut self, content: &str) {
+		if let NodeData::Text { contents } = &self.handle.data {
+			let mut contents = contents.borrow_mut();
+			let mut text = StrTendril::from(content);
+			text.push_tendril(&contents);
+			*contents = text;
+		}
+	}
+	fn split_text<'b>(&mut self, offset: usize) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		let rest = match &self.handle.data {
+			NodeData::Text { contents } => {
+				let mut contents = contents.borrow_mut();
+				if !contents.is_char_boundary(offset) {
+					return Err(format!("The offset {} is not a char boundary.", offset).into());
+				}
+				let rest = StrTendril::from(&contents[offset..]);
+				let len = contents.len();
+				contents.pop_back((len - offset) as u32);
+				rest
+			}
+			_ => return Err("The node is not a text node.".into()),
+		};
+		let text = markup5ever_rcdom::Node::new(NodeData::Text {
+			contents: rest.into(),
+		});
+		if let Some(parent) = get_parent(&self.handle) {
+			let index = get_index(&parent, &self.handle).map(|index| index + 1);
+			insert_at(&parent, index, &text);
+		}
+		Ok(Box::new(Node::from(text)))
+	}
+	fn wrap(&mut self, wrapper: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+		let wrapper = Node::from_element(wrapper).ok_or("The wrapper is not a rcdom node.")?;
+		if let Some(parent) = get_parent(&self.handle) {
+			let index = get_index(&parent, &self.handle);
+			insert_at(&parent, index, &wrapper);
+		}
+		insert_at(&wrapper, None, &self.handle);
+		Ok(())
+	}
+}
+
+impl IUncareNodeTrait for Node {
+	fn remove(self: Box<Self>) -> Result<(), Box<dyn Error>> {
+		detach(&self.handle);
+		Ok(())
+	}
+}
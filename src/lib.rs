@@ -10,6 +10,38 @@ pub mod interface;
 pub mod error;
 // utils for crate
 pub mod utils;
+// content extraction helpers
+pub mod extract;
+// html serializer, minify
+pub mod serialize;
+// logic-less templates filled from a serde context
+#[cfg(feature = "serde")]
+pub mod template;
+// lint the broken structures
+pub mod lint;
+// rewrite the html stream by the selectors
+pub mod rewrite;
+// stylesheet parsing and matching
+pub mod css;
+// helpers and conformance tests for the backends
+pub mod adapter;
+// the assertions for testing the rendered html
+pub mod assert;
+// parse and process many documents, in parallel with the `rayon` feature
+pub mod batch;
+// the tables of the html spec, such as the void elements
+pub mod html;
+// the arena backed tree backend
+pub mod dom;
+// browser dom backend
+#[cfg(feature = "wasm")]
+pub mod wasm;
+// fetch documents over http
+#[cfg(feature = "http")]
+pub mod http;
+// conversions from the trees of other html crates
+#[cfg(any(feature = "rcdom", feature = "kuchiki"))]
+pub mod interop;
 // constants
 pub(crate) mod constants;
 
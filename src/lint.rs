@@ -0,0 +1,149 @@
+//! the lint pass of the broken structures, such as the invalid nesting and the duplicate ids
+use crate::error::Error as IError;
+use crate::html::spec::{is_known_element, P_CLOSING_ELEMENTS};
+use crate::interface::{BoxDynElement, IAttrValue};
+use std::collections::HashMap;
+use std::fmt;
+
+// the parents of `li`
+const LIST_TAGS: [&str; 3] = ["ul", "ol", "menu"];
+// the foreign elements, their descendants are not html elements
+const FOREIGN_TAGS: [&str; 2] = ["svg", "math"];
+
+/// the rules of the lint pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintRule {
+	// a block element in a `p` element
+	BlockInParagraph,
+	// a `li` element not in a list
+	ListItemOutsideList,
+	// an `id` used by more than one element
+	DuplicateId,
+	// an element not in the html standard, the custom elements are allowed
+	UnknownElement,
+}
+
+impl LintRule {
+	/// pub fn `name`
+	/// the name of the rule
+	pub fn name(&self) -> &'static str {
+		use LintRule::*;
+		match self {
+			BlockInParagraph => "block-in-paragraph",
+			ListItemOutsideList => "list-item-outside-list",
+			DuplicateId => "duplicate-id",
+			UnknownElement => "unknown-element",
+		}
+	}
+}
+
+impl fmt::Display for LintRule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// an issue found by the lint pass
+pub struct LintIssue<'a> {
+	// the broken rule
+	pub rule: LintRule,
+	// the element breaks the rule
+	pub node: BoxDynElement<'a>,
+	// the error of the issue
+	pub error: IError,
+}
+
+impl<'a> LintIssue<'a> {
+	fn new(rule: LintRule, node: &BoxDynElement<'a>, message: String) -> Self {
+		LintIssue {
+			rule,
+			node: node.cloned(),
+			error: IError::InvalidStructure {
+				rule: rule.name().into(),
+				message,
+			},
+		}
+	}
+}
+
+impl<'a> fmt::Debug for LintIssue<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("LintIssue")
+			.field("rule", &self.rule)
+			.field("node", &self.node.tag_name())
+			.field("error", &self.error)
+			.finish()
+	}
+}
+
+// check if the element or its ancestors are `svg` or `math`
+fn is_foreign(ele: &BoxDynElement) -> bool {
+	let mut cur = Some(ele.cloned());
+	while let Some(ele) = cur {
+		if FOREIGN_TAGS.contains(&ele.tag_name().to_ascii_lowercase().as_str()) {
+			return true;
+		}
+		cur = ele.parent();
+	}
+	false
+}
+
+struct Linter<'a> {
+	issues: Vec<LintIssue<'a>>,
+	// the times of the ids have been seen
+	ids: HashMap<String, usize>,
+}
+
+impl<'a> Linter<'a> {
+	// check the element and its descendants, the parent's tag name and the foreign context are
+	// passed down from the parent
+	fn element(&mut self, ele: &BoxDynElement<'a>, parent_tag_name: &str, in_foreign: bool) {
+		let tag_name = ele.tag_name().to_ascii_lowercase();
+		if parent_tag_name == "p" && P_CLOSING_ELEMENTS.contains(&tag_name.as_str()) {
+			let message = format!("The '<{}>' element can't be in a '<p>' element.", tag_name);
+			self.issue(LintRule::BlockInParagraph, ele, message);
+		}
+		if tag_name == "li" && !LIST_TAGS.contains(&parent_tag_name) {
+			let message = String::from("The '<li>' element must be in a list element.");
+			self.issue(LintRule::ListItemOutsideList, ele, message);
+		}
+		// the empty and whitespace-only ids are not ids
+		if let Some(IAttrValue::Value(id, _)) = ele.get_attribute("id") {
+			if !id.trim().is_empty() {
+				let count = self.ids.entry(id.clone()).or_insert(0);
+				*count += 1;
+				if *count > 1 {
+					let message = format!("The id '{}' is used by more than one element.", id);
+					self.issue(LintRule::DuplicateId, ele, message);
+				}
+			}
+		}
+		if !in_foreign && !tag_name.contains('-') && !is_known_element(&tag_name) {
+			let message = format!("The '<{}>' element is unknown.", tag_name);
+			self.issue(LintRule::UnknownElement, ele, message);
+		}
+		self.children(ele, &tag_name, in_foreign);
+	}
+	fn children(&mut self, parent: &BoxDynElement<'a>, tag_name: &str, in_foreign: bool) {
+		let in_foreign = in_foreign || FOREIGN_TAGS.contains(&tag_name);
+		for child in parent.children().get_ref() {
+			self.element(child, tag_name, in_foreign);
+		}
+	}
+	fn issue(&mut self, rule: LintRule, ele: &BoxDynElement<'a>, message: String) {
+		self.issues.push(LintIssue::new(rule, ele, message));
+	}
+}
+
+/// pub fn `lint`
+/// check the descendants of the element in document order
+pub fn lint<'a>(root: &BoxDynElement<'a>) -> Vec<LintIssue<'a>> {
+	let mut linter = Linter {
+		issues: Vec::new(),
+		ids: HashMap::new(),
+	};
+	// the ancestors of the root are checked once, the descendants get the context from their parents
+	let tag_name = root.tag_name().to_ascii_lowercase();
+	linter.children(root, &tag_name, is_foreign(root));
+	linter.issues
+}
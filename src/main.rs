@@ -0,0 +1,316 @@
+//! `gotdom`, query the html by the css selectors from the command line, such as
+//! `curl -s https://example.com | gotdom "a[href]" -a href`
+use mesdoc::dom::{parse, Document};
+use mesdoc::interface::{BoxDynElement, Elements, IAttrValue, IDocumentTrait};
+use mesdoc::serialize::{format, FormatOptions};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+const USAGE: &str = "Usage: gotdom [OPTIONS] [SELECTOR]...
+
+Query the html by the css selectors, the selectors are applied in turn,
+each one finds in the matches of the previous one.
+
+Options:
+  -f, --file <FILE>         read the html from the file, `-` for stdin (default)
+  -u, --url <URL>           fetch the html from the url, needs the `http` feature
+  -t, --text                print the text contents of the matches
+  -a, --attribute <NAME>    print the attribute of the matches, the ones without it are skipped
+  -o, --output <FORMAT>     the output format, `html` (default), `text` or `json`
+  -1, --first               only print the first match
+  -p, --pretty              indent the html and the json
+  -h, --help                print the help";
+
+// the source of the html
+enum Input {
+	Stdin,
+	File(String),
+	Url(String),
+}
+
+// the format of the output
+#[derive(PartialEq)]
+enum Output {
+	Html,
+	Text,
+	Json,
+}
+
+struct Args {
+	input: Input,
+	selectors: Vec<String>,
+	output: Output,
+	attribute: Option<String>,
+	first: bool,
+	pretty: bool,
+}
+
+// parse the arguments, `None` if the help is asked
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+	let mut result = Args {
+		input: Input::Stdin,
+		selectors: Vec::new(),
+		output: Output::Html,
+		attribute: None,
+		first: false,
+		pretty: false,
+	};
+	let mut args = args.peekable();
+	while let Some(arg) = args.next() {
+		let mut value = |name: &str| {
+			args
+				.next()
+				.ok_or_else(|| format!("The option '{}' needs a value.", name))
+		};
+		match arg.as_str() {
+			"-h" | "--help" => return Ok(None),
+			"-f" | "--file" => {
+				result.input = match value(&arg)? {
+					file if file == "-" => Input::Stdin,
+					file => Input::File(file),
+				}
+			}
+			"-u" | "--url" => result.input = Input::Url(value(&arg)?),
+			"-t" | "--text" => result.output = Output::Text,
+			"-a" | "--attribute" => result.attribute = Some(value(&arg)?),
+			"-o" | "--output" => {
+				result.output = match value(&arg)?.as_str() {
+					"html" => Output::Html,
+					"text" => Output::Text,
+					"json" => Output::Json,
+					output => return Err(format!("The output format '{}' is not supported.", output)),
+				}
+			}
+			"-1" | "--first" => result.first = true,
+			"-p" | "--pretty" => result.pretty = true,
+			// the selectors start with `-` are after `--`
+			"--" => result.selectors.extend(args.by_ref()),
+			option if option.starts_with('-') && option.len() > 1 => {
+				return Err(format!("The option '{}' is unknown.", option))
+			}
+			_ => result.selectors.push(arg),
+		}
+	}
+	Ok(Some(result))
+}
+
+// read the html from the input
+fn read_html(input: &Input) -> Result<String, Box<dyn Error>> {
+	match input {
+		Input::Stdin => {
+			let mut html = String::new();
+			io::stdin().read_to_string(&mut html)?;
+			Ok(html)
+		}
+		Input::File(file) => Ok(fs::read_to_string(file)?),
+		Input::Url(url) => fetch_html(url),
+	}
+}
+
+// fetch the url and decode the html with the response charset
+#[cfg(feature = "http")]
+fn fetch_html(url: &str) -> Result<String, Box<dyn Error>> {
+	let runtime = tokio::runtime::Builder::new_current_thread()
+		.enable_all()
+		.build()?;
+	let doc = runtime.block_on(mesdoc::http::Document::from_url(url, parse))?;
+	Ok(String::from(doc.source_code()))
+}
+
+#[cfg(not(feature = "http"))]
+fn fetch_html(url: &str) -> Result<String, Box<dyn Error>> {
+	Err(format!("Can't fetch '{}', the `http` feature is not enabled.", url).into())
+}
+
+// the matches of the selectors, each selector finds in the matches of the previous one
+fn query<'b>(doc: &Document, selectors: &[String]) -> Elements<'b> {
+	let root = doc.get_root_node().typed().into_element();
+	let mut matches = Elements::with_nodes(root.into_iter().collect());
+	for selector in selectors {
+		matches = matches.find(selector);
+	}
+	matches
+}
+
+// the json value of the output
+enum Json {
+	String(String),
+	Array(Vec<Json>),
+	Object(Vec<(String, Json)>),
+}
+
+impl Json {
+	fn write(&self, pretty: bool, depth: usize, result: &mut String) {
+		let new_line = |depth: usize, result: &mut String| {
+			if pretty {
+				result.push('\n');
+				result.push_str(&"  ".repeat(depth));
+			}
+		};
+		match self {
+			Json::String(content) => write_json_string(content, result),
+			Json::Array(items) => {
+				result.push('[');
+				for (index, item) in items.iter().enumerate() {
+					if index > 0 {
+						result.push(',');
+					}
+					new_line(depth + 1, result);
+					item.write(pretty, depth + 1, result);
+				}
+				if !items.is_empty() {
+					new_line(depth, result);
+				}
+				result.push(']');
+			}
+			Json::Object(fields) => {
+				result.push('{');
+				for (index, (key, value)) in fields.iter().enumerate() {
+					if index > 0 {
+						result.push(',');
+					}
+					new_line(depth + 1, result);
+					write_json_string(key, result);
+					result.push_str(if pretty { ": " } else { ":" });
+					value.write(pretty, depth + 1, result);
+				}
+				if !fields.is_empty() {
+					new_line(depth, result);
+				}
+				result.push('}');
+			}
+		}
+	}
+}
+
+// write the string in double quotes, escape the quotes, the backslashes and the control characters
+fn write_json_string(content: &str, result: &mut String) {
+	result.push('"');
+	for ch in content.chars() {
+		match ch {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			'\n' => result.push_str("\\n"),
+			'\r' => result.push_str("\\r"),
+			'\t' => result.push_str("\\t"),
+			ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+			ch => result.push(ch),
+		}
+	}
+	result.push('"');
+}
+
+// the outer html of the element, indented if pretty
+fn element_html(ele: &BoxDynElement, pretty: bool) -> String {
+	if pretty {
+		format(&ele.clone_node(), FormatOptions::default())
+	} else {
+		String::from(ele.outer_html())
+	}
+}
+
+// the attribute value, the attribute without value is an empty string
+fn attribute_value(ele: &BoxDynElement, name: &str) -> Option<String> {
+	match ele.get_attribute(name)? {
+		IAttrValue::Value(value, _) => Some(value),
+		IAttrValue::True => Some(String::new()),
+	}
+}
+
+// the json of the element, its tag name, attributes, text and html
+fn element_json(ele: &BoxDynElement, pretty: bool) -> Json {
+	let attributes = ele
+		.attributes()
+		.into_iter()
+		.map(|(name, value)| {
+			let value = match value {
+				IAttrValue::Value(value, _) => value,
+				IAttrValue::True => String::new(),
+			};
+			(name, Json::String(value))
+		})
+		.collect();
+	Json::Object(vec![
+		(
+			String::from("tag"),
+			Json::String(String::from(ele.local_name())),
+		),
+		(String::from("attributes"), Json::Object(attributes)),
+		(
+			String::from("text"),
+			Json::String(String::from(ele.text_content())),
+		),
+		(
+			String::from("html"),
+			Json::String(element_html(ele, pretty)),
+		),
+	])
+}
+
+// the output of the matches
+fn render(matches: &Elements, args: &Args) -> String {
+	let eles = matches.get_ref();
+	let eles = if args.first && !eles.is_empty() {
+		&eles[..1]
+	} else {
+		eles
+	};
+	if args.output == Output::Json {
+		let items = eles
+			.iter()
+			.filter_map(|ele| match &args.attribute {
+				Some(name) => attribute_value(ele, name).map(Json::String),
+				None => Some(element_json(ele, args.pretty)),
+			})
+			.collect();
+		let mut result = String::new();
+		Json::Array(items).write(args.pretty, 0, &mut result);
+		result.push('\n');
+		return result;
+	}
+	let mut result = String::new();
+	for ele in eles {
+		let line = match (&args.attribute, &args.output) {
+			(Some(name), _) => match attribute_value(ele, name) {
+				Some(value) => value,
+				None => continue,
+			},
+			(None, Output::Text) => String::from(ele.text_content()),
+			(None, _) => element_html(ele, args.pretty),
+		};
+		result.push_str(&line);
+		result.push('\n');
+	}
+	result
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+	let html = read_html(&args.input)?;
+	let doc = parse(&html);
+	let matches = query(&doc, &args.selectors);
+	let output = render(&matches, args);
+	io::stdout().write_all(output.as_bytes())?;
+	Ok(())
+}
+
+fn main() {
+	mesdoc::init();
+	let args = match parse_args(env::args().skip(1)) {
+		Ok(Some(args)) => args,
+		Ok(None) => {
+			println!("{}", USAGE);
+			return;
+		}
+		Err(message) => {
+			eprintln!("gotdom: {}\n\n{}", message, USAGE);
+			process::exit(2);
+		}
+	};
+	if let Err(error) = run(&args) {
+		eprintln!("gotdom: {}", error);
+		process::exit(1);
+	}
+}
@@ -0,0 +1,381 @@
+//! rewrite the html while copying the input to the output by the selectors, without building the whole tree,
+//! only the start tags of the open elements and their previous siblings are kept to match the selectors
+use crate::adapter::NodeId;
+use crate::dom::{Document, NodeKind, Tree};
+use crate::error::Error as IError;
+use crate::html::spec::{is_implied_end, is_text_only_element, is_void_element};
+use crate::interface::{
+	parse_start_tag_attrs, svg_tag_name, BoxDynElement, Elements, IAttrValue, HTML_NAMESPACE,
+	SVG_NAMESPACE,
+};
+use crate::selector::Selector;
+use std::error::Error;
+use std::io::{self, Read, Write};
+
+// the bytes read from the input each time
+const CHUNK_SIZE: usize = 8192;
+
+/// the handle of the matched elements, the changed attributes are written into the output
+pub type RewriteHandle<'r> = Box<dyn FnMut(&mut BoxDynElement) + 'r>;
+
+enum RewriteAction<'r> {
+	Handle(RewriteHandle<'r>),
+	Remove,
+}
+
+/// the html rewriter, the handles are called with the matched elements in source order
+///
+/// the selectors are matched with the start tags, the ancestors and the previous siblings,
+/// so the selectors depend on the contents or the next siblings, such as `:empty`, `:has()`,
+/// `:contains()` and `:last-child`, are never matched
+///
+/// ```ignore
+/// let html = Rewriter::new()
+///     .on("img[data-src]", |ele| {
+///         let src = ele.get_attribute("data-src").map(|value| value.to_string());
+///         ele.set_attribute("src", src.as_deref());
+///     })
+///     .remove("script")
+///     .rewrite(html)?;
+/// ```
+pub struct Rewriter<'r> {
+	rules: Vec<(String, RewriteAction<'r>)>,
+	// the start tags of the open elements and their previous siblings
+	skeleton: Document,
+	// the open elements and their tag names
+	stack: Vec<(NodeId, String)>,
+	// the input not written yet, such as an incomplete tag
+	pending: String,
+	// the tag name of the open raw text element
+	raw: Option<String>,
+	// the index in the stack of the removed element, its contents are skipped
+	removing: Option<usize>,
+}
+
+impl<'r> Default for Rewriter<'r> {
+	fn default() -> Self {
+		Rewriter::new()
+	}
+}
+
+// the skeleton never parses the html
+fn skip_fragment(_: &mut Tree, _: NodeId, _: &str) {}
+
+// the tag name at the start of the tag
+pub(crate) fn tag_name(tag: &str) -> String {
+	tag
+		.trim_start_matches(['<', '/'])
+		.chars()
+		.take_while(|ch| !(ch.is_ascii_whitespace() || *ch == '/' || *ch == '>'))
+		.collect::<String>()
+		.to_ascii_lowercase()
+}
+
+// the byte index after the end of the tag, the quoted `>` is skipped
+pub(crate) fn tag_end(html: &str) -> Option<usize> {
+	let mut quote: Option<char> = None;
+	for (index, ch) in html.char_indices() {
+		match quote {
+			Some(cur) if cur == ch => quote = None,
+			Some(_) => {}
+			None if ch == '"' || ch == '\'' => quote = Some(ch),
+			None if ch == '>' => return Some(index + 1),
+			None => {}
+		}
+	}
+	None
+}
+
+impl<'r> Rewriter<'r> {
+	/// pub fn `new`
+	pub fn new() -> Self {
+		Rewriter {
+			rules: Vec::new(),
+			skeleton: Document::from(Tree::new(skip_fragment)),
+			stack: Vec::new(),
+			pending: String::new(),
+			raw: None,
+			removing: None,
+		}
+	}
+	fn add_rule(mut self, selector: &str, action: RewriteAction<'r>) -> Self {
+		self.rules.push((String::from(selector), action));
+		self
+	}
+	// the error of the first invalid selector
+	fn check(&self) -> Result<(), IError> {
+		for (selector, _) in &self.rules {
+			selector.parse::<Selector>()?;
+		}
+		Ok(())
+	}
+	/// pub fn `on`
+	/// call the handle with the elements matched the selector, the start tag is written with the changed attributes
+	pub fn on<F>(self, selector: &str, handle: F) -> Self
+	where
+		F: FnMut(&mut BoxDynElement) + 'r,
+	{
+		self.add_rule(selector, RewriteAction::Handle(Box::new(handle)))
+	}
+	/// pub fn `remove`
+	/// remove the elements matched the selector with their contents
+	pub fn remove(self, selector: &str) -> Self {
+		self.add_rule(selector, RewriteAction::Remove)
+	}
+	/// pub fn `write`
+	/// rewrite a chunk of the input, return the output can be written, an incomplete tag waits for the next chunk
+	pub fn write(&mut self, chunk: &str) -> Result<String, IError> {
+		self.check()?;
+		self.pending.push_str(chunk);
+		Ok(self.process(false))
+	}
+	/// pub fn `end`
+	/// end the input, return the rest output, the rewriter can be used for the next input
+	pub fn end(&mut self) -> Result<String, IError> {
+		self.check()?;
+		let output = self.process(true);
+		self.skeleton = Document::from(Tree::new(skip_fragment));
+		self.stack.clear();
+		self.raw = None;
+		self.removing = None;
+		Ok(output)
+	}
+	/// pub fn `rewrite`
+	/// rewrite the whole html
+	pub fn rewrite(&mut self, html: &str) -> Result<String, IError> {
+		let mut output = self.write(html)?;
+		output.push_str(&self.end()?);
+		Ok(output)
+	}
+	/// pub fn `rewrite_to`
+	/// read the utf-8 html from the input and write the rewritten html into the output chunk by chunk
+	pub fn rewrite_to<R: Read, W: Write>(
+		&mut self,
+		mut input: R,
+		mut output: W,
+	) -> Result<(), Box<dyn Error>> {
+		let mut buffer = vec![0; CHUNK_SIZE];
+		// the bytes of an incomplete char at the end of the last chunk
+		let mut rest: Vec<u8> = Vec::new();
+		loop {
+			let len = input.read(&mut buffer)?;
+			if len == 0 {
+				break;
+			}
+			rest.extend_from_slice(&buffer[..len]);
+			let valid_len = match std::str::from_utf8(&rest) {
+				Ok(_) => rest.len(),
+				Err(e) if e.error_len().is_none() => e.valid_up_to(),
+				Err(e) => return Err(Box::new(io::Error::new(io::ErrorKind::InvalidData, e))),
+			};
+			let chunk = std::str::from_utf8(&rest[..valid_len]).expect("The bytes must be valid utf-8");
+			output.write_all(self.write(chunk)?.as_bytes())?;
+			rest.drain(..valid_len);
+		}
+		if !rest.is_empty() {
+			return Err(Box::new(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"The input ends with an incomplete utf-8 char",
+			)));
+		}
+		output.write_all(self.end()?.as_bytes())?;
+		output.flush()?;
+		Ok(())
+	}
+	// write the pending input, the incomplete tags are kept if it's not the end
+	fn process(&mut self, is_end: bool) -> String {
+		let mut output = String::with_capacity(self.pending.len());
+		let pending = std::mem::take(&mut self.pending);
+		let mut pos = 0;
+		while pos < pending.len() {
+			let rest = &pending[pos..];
+			// the raw text until the end tag
+			if let Some(name) = &self.raw {
+				let close = format!("</{}", name);
+				match rest.to_ascii_lowercase().find(&close) {
+					Some(index) => {
+						self.emit(&mut output, &rest[..index]);
+						self.raw = None;
+						pos += index;
+						continue;
+					}
+					None if is_end => {
+						self.emit(&mut output, rest);
+						pos = pending.len();
+					}
+					None => {
+						// keep the chars may be the start of the end tag
+						let mut keep_from = rest.len().saturating_sub(close.len() - 1);
+						while !rest.is_char_boundary(keep_from) {
+							keep_from -= 1;
+						}
+						self.emit(&mut output, &rest[..keep_from]);
+						pos += keep_from;
+					}
+				}
+				break;
+			}
+			if let Some(comment) = rest.strip_prefix("<!--") {
+				match comment.find("-->") {
+					Some(index) => {
+						let len = index + 7;
+						self.emit(&mut output, &rest[..len]);
+						pos += len;
+						continue;
+					}
+					None if is_end => {
+						self.emit(&mut output, rest);
+						pos = pending.len();
+					}
+					None => {}
+				}
+				break;
+			}
+			let next = rest.strip_prefix('<').and_then(|tag| tag.chars().next());
+			let is_tag = rest.starts_with('<')
+				&& next.map_or(!is_end, |ch| {
+					ch.is_ascii_alphabetic() || ch == '/' || ch == '!' || ch == '?'
+				});
+			if !is_tag {
+				// the text until the next tag
+				let first_len = rest.chars().next().map_or(1, |ch| ch.len_utf8());
+				let end = rest[first_len..]
+					.find('<')
+					.map_or(rest.len(), |index| index + first_len);
+				self.emit(&mut output, &rest[..end]);
+				pos += end;
+				continue;
+			}
+			let end = match tag_end(rest) {
+				Some(end) => end,
+				None if is_end => {
+					self.emit(&mut output, rest);
+					pos = pending.len();
+					break;
+				}
+				None => break,
+			};
+			let tag = &rest[..end];
+			match next {
+				Some('/') => self.end_tag(&mut output, tag),
+				Some(ch) if ch.is_ascii_alphabetic() => self.start_tag(&mut output, tag),
+				// the doctype, the cdata and the processing instructions
+				_ => self.emit(&mut output, tag),
+			}
+			pos += end;
+		}
+		self.pending = String::from(&pending[pos..]);
+		output
+	}
+	// write the input if it's not in a removed element
+	fn emit(&self, output: &mut String, content: &str) {
+		if self.removing.is_none() {
+			output.push_str(content);
+		}
+	}
+	// close the elements from the index in the stack, the children of the closed elements are not needed any more
+	fn close_from(&mut self, index: usize) {
+		let mut tree = self.skeleton.get_ref().borrow_mut();
+		for (id, _) in self.stack.drain(index..) {
+			for child in tree.children(id).to_vec() {
+				tree.detach(child);
+			}
+		}
+		tree.collect();
+	}
+	fn end_tag(&mut self, output: &mut String, tag: &str) {
+		let name = tag_name(tag);
+		match self.stack.iter().rposition(|(_, open)| *open == name) {
+			Some(index) => {
+				let is_removed = self.removing.is_some_and(|removing| removing >= index);
+				self.emit(output, tag);
+				self.close_from(index);
+				if is_removed {
+					self.removing = None;
+				}
+			}
+			None => self.emit(output, tag),
+		}
+	}
+	fn start_tag(&mut self, output: &mut String, tag: &str) {
+		let name = tag_name(tag);
+		while let Some(index) = self
+			.stack
+			.last()
+			.filter(|(_, open)| is_implied_end(open, &name))
+			.map(|_| self.stack.len() - 1)
+		{
+			self.close_from(index);
+			if self.removing.is_some_and(|removing| removing >= index) {
+				self.removing = None;
+			}
+		}
+		// add the element into the skeleton, the svg tag names are case-sensitive such as `linearGradient`
+		let (id, local_name, namespace) = {
+			let mut tree = self.skeleton.get_ref().borrow_mut();
+			let parent = self.stack.last().map_or_else(|| tree.root(), |(id, _)| *id);
+			let namespace = tree.child_namespace(parent, &name);
+			let local_name = match namespace {
+				SVG_NAMESPACE => svg_tag_name(&name).map_or_else(|| name.clone(), String::from),
+				_ => name.clone(),
+			};
+			let id = tree.create(NodeKind::Element(local_name.clone()), "");
+			tree.set_namespace(id, namespace);
+			let attrs = tree.attrs_mut(id);
+			for (attr_name, value) in parse_start_tag_attrs(tag) {
+				// the first attribute wins, the same as the html parsers
+				if attrs.contains(&attr_name) {
+					continue;
+				}
+				match value {
+					IAttrValue::Value(value, _) => attrs.set(&attr_name, Some(&value)),
+					IAttrValue::True => attrs.set(&attr_name, None),
+				}
+			}
+			tree.link(parent, usize::MAX, id);
+			(id, local_name, namespace)
+		};
+		let is_self_closing = tag.ends_with("/>");
+		let is_open = !(is_self_closing || is_void_element(&name));
+		let mut is_removed = false;
+		let mut is_changed = false;
+		if self.removing.is_none() {
+			let mut ele = self
+				.skeleton
+				.element(id)
+				.expect("The skeleton must have the element");
+			for (selector, action) in self.rules.iter_mut() {
+				if !Elements::with_nodes(vec![ele.cloned()]).is(selector) {
+					continue;
+				}
+				match action {
+					RewriteAction::Handle(handle) => {
+						handle(&mut ele);
+						is_changed = true;
+					}
+					RewriteAction::Remove => {
+						is_removed = true;
+						break;
+					}
+				}
+			}
+		}
+		if is_removed {
+			if is_open {
+				self.removing = Some(self.stack.len());
+			}
+		} else if is_changed {
+			let attrs = self.skeleton.get_ref().borrow().attrs(id).to_html();
+			let slash = if is_self_closing { " /" } else { "" };
+			self.emit(output, &format!("<{}{}{}>", local_name, attrs, slash));
+		} else {
+			self.emit(output, tag);
+		}
+		if is_open {
+			if is_text_only_element(&name) && namespace == HTML_NAMESPACE {
+				self.raw = Some(name.clone());
+			}
+			self.stack.push((id, name));
+		}
+	}
+}
@@ -1,8 +1,7 @@
 use crate::constants::{NAME_SELECTOR_CLASS, PRIORITY_CLASS_SELECTOR};
-use crate::interface::{BoxDynElement, IAttrValue};
+use crate::interface::{BoxDynElement, ClassList, IAttrValue};
 use crate::selector::rule::{Matcher, MatcherData};
 use crate::selector::rule::{Rule, RuleDefItem, RuleItem};
-use crate::utils::get_class_list;
 
 pub fn init(rules: &mut Vec<RuleItem>) {
 	let rule = RuleDefItem(
@@ -12,13 +11,14 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 		vec![("identity", 0)],
 		Box::new(|data: MatcherData| {
 			// class name parameter
-			let class_name = Rule::param(&data, "identity").expect("The 'class' selector is not correct");
+			let class_name = Rule::param(&data, "identity")
+				.expect("The 'class' selector is not correct")
+				.to_string();
 			// matcher
 			Matcher {
 				one_handle: Some(Box::new(move |ele: &BoxDynElement, _| -> bool {
 					if let Some(IAttrValue::Value(names, _)) = ele.get_attribute("class") {
-						let class_list = get_class_list(&names);
-						return class_list.contains(&class_name);
+						return ClassList::split(&names).contains(&class_name.as_str());
 					}
 					false
 				})),
@@ -1,7 +1,7 @@
 use crate::selector::rule::{Matcher, MatcherData, Rule, RuleItem};
 use crate::{
 	constants::{NAME_SELECTOR_ID, PRIORITY_ID_SELECTOR},
-	interface::Elements,
+	interface::{Elements, IAttrValue},
 };
 
 pub fn init(rules: &mut Vec<RuleItem>) {
@@ -13,7 +13,9 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 			in_cache: true,
 			fields: vec![("identity", 0)],
 			handle: Box::new(|data: MatcherData| {
-				let id = Rule::param(&data, "identity").expect("The 'id' selector is not correct");
+				let id = Rule::param(&data, "identity")
+					.expect("The 'id' selector is not correct")
+					.to_string();
 				Matcher {
 					all_handle: Some(Box::new(move |eles: &Elements, use_cache: Option<bool>| {
 						let use_cache = use_cache.is_some();
@@ -21,10 +23,14 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 						if !eles.is_empty() {
 							let first_ele = eles
 								.get_ref()
-								.get(0)
+								.first()
 								.expect("The elements must have at least one element.");
-							if let Some(doc) = &first_ele.owner_document() {
-								if let Some(id_element) = doc.get_element_by_id(id) {
+							// the detached elements can't be found in the document, filter them by the attribute
+							let doc = first_ele
+								.owner_document()
+								.filter(|_| first_ele.is_connected());
+							if let Some(doc) = &doc {
+								if let Some(id_element) = doc.get_element_by_id(&id) {
 									if use_cache {
 										// just add, will checked if the element contains the id element
 										result.push(id_element);
@@ -38,6 +44,13 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 										}
 									}
 								}
+							} else if !use_cache {
+								for ele in eles.get_ref() {
+									if matches!(ele.get_attribute("id"), Some(IAttrValue::Value(value, _)) if value == id)
+									{
+										result.push(ele.cloned());
+									}
+								}
 							}
 						}
 						result
@@ -1,4 +1,6 @@
-use crate::selector::rule::{self, add_rules, RuleItem};
+use crate::constants::NAME_SELECTOR_ALL;
+use crate::selector::rule::{self, add_rules, Rule, RuleItem, RULES};
+use std::sync::Arc;
 pub(crate) mod all;
 pub(crate) mod attr;
 pub(crate) mod class;
@@ -19,3 +21,82 @@ pub(crate) fn init() {
 	all::init(&mut rules);
 	add_rules(rules);
 }
+
+// remove the rule by the name, the `all` rule is required by the combinators and can't be removed
+fn disable_in(rules: &mut Vec<(&'static str, Arc<Rule>)>, name: &str) -> bool {
+	if name == NAME_SELECTOR_ALL {
+		return false;
+	}
+	let total = rules.len();
+	rules.retain(|(rule_name, _)| *rule_name != name);
+	rules.len() != total
+}
+
+// replace the rule of the name, the rule keeps the registered name whatever the item's name is
+fn override_in(rules: &mut [(&'static str, Arc<Rule>)], name: &str, rule: Rule) -> bool {
+	if name == NAME_SELECTOR_ALL {
+		return false;
+	}
+	match rules.iter_mut().find(|(rule_name, _)| *rule_name == name) {
+		Some((_, old_rule)) => {
+			*old_rule = Arc::new(rule);
+			true
+		}
+		None => false,
+	}
+}
+
+/// pub fn `disable`
+/// remove the registered rule by the name, such as `:contains`, then the selectors using it are invalid,
+/// return false if the rule is not found, the `all` rule is required by the combinators and can't be disabled.
+/// it changes the global rules used by all the selectors, use `Registry` to change the rules of one caller
+pub fn disable(name: &str) -> bool {
+	disable_in(&mut RULES.lock().unwrap(), name)
+}
+
+/// pub fn `override_rule`
+/// replace the registered rule of the name with the new rule, the matching order and the name are kept,
+/// return false if the rule is not found, the `all` rule can't be overridden.
+/// it changes the global rules used by all the selectors, use `Registry` to change the rules of one caller
+pub fn override_rule(name: &str, item: RuleItem) -> bool {
+	// parse the rule before locking, the wrong rule panics
+	let rule = Rule::add(item.context, item.rule);
+	override_in(&mut RULES.lock().unwrap(), name, rule)
+}
+
+/// the rules owned by the caller, a copy of the global rules when created, disabling or overriding
+/// its rules doesn't affect the other selectors, see `Elements::find_with_rules`
+#[derive(Clone)]
+pub struct Registry {
+	rules: Vec<(&'static str, Arc<Rule>)>,
+}
+
+impl Default for Registry {
+	fn default() -> Self {
+		Registry::new()
+	}
+}
+
+impl Registry {
+	/// pub fn `new`
+	/// copy the global rules, call it after `init`
+	pub fn new() -> Self {
+		Registry {
+			rules: RULES.lock().unwrap().clone(),
+		}
+	}
+	/// pub fn `disable`
+	/// the same as `rules::disable` but only for the registry
+	pub fn disable(&mut self, name: &str) -> bool {
+		disable_in(&mut self.rules, name)
+	}
+	/// pub fn `override_rule`
+	/// the same as `rules::override_rule` but only for the registry
+	pub fn override_rule(&mut self, name: &str, item: RuleItem) -> bool {
+		let rule = Rule::add(item.context, item.rule);
+		override_in(&mut self.rules, name, rule)
+	}
+	pub(crate) fn rules(&self) -> &[(&'static str, Arc<Rule>)] {
+		&self.rules
+	}
+}
@@ -1,5 +1,5 @@
 use crate::constants::{NAME_SELECTOR_NAME, PRIORITY_NAME_SELECTOR};
-use crate::interface::BoxDynElement;
+use crate::interface::{is_html_element, BoxDynElement};
 use crate::selector::rule::{Matcher, MatcherData, Rule, RuleDefItem, RuleItem};
 
 pub fn init(rules: &mut Vec<RuleItem>) {
@@ -11,10 +11,12 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 		Box::new(|data: MatcherData| {
 			let name = Rule::param(&data, "identity")
 				.expect("The 'name' selector must have a tag name")
-				.to_ascii_uppercase();
+				.to_string();
 			Matcher {
+				// the html elements are matched case-insensitively, the svg and mathml elements are case-sensitive
 				one_handle: Some(Box::new(move |ele: &BoxDynElement, _| {
-					return ele.tag_name() == name;
+					ele.tag_name().eq_ignore_ascii_case(&name)
+						&& (is_html_element(ele) || ele.local_name() == name)
 				})),
 				..Default::default()
 			}
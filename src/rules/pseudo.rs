@@ -1,11 +1,18 @@
 use crate::constants::{DEF_NODES_LEN, PRIORITY_PSEUDO_SELECTOR};
-use crate::interface::{BoxDynElement, Elements, INodeType};
+use crate::html::spec::is_void_element;
+use crate::interface::{
+	is_html_element, BoxDynElement, Elements, IAttrValue, INodeType, InlineStyle,
+};
 use crate::selector::pattern::Nth;
 use crate::selector::rule::{Matcher, MatcherData, Rule, RuleDefItem, RuleItem};
 use std::cmp::Ordering;
 use std::{collections::HashMap, ops::Range};
 
 const PRIORITY: u32 = PRIORITY_PSEUDO_SELECTOR;
+// the elements never rendered
+const NOT_RENDERED_TAGS: [&str; 8] = [
+	"HEAD", "SCRIPT", "STYLE", "TEMPLATE", "TITLE", "META", "LINK", "BASE",
+];
 
 /// pseudo selector ":empty"
 fn pseudo_empty(rules: &mut Vec<RuleItem>) {
@@ -19,6 +26,10 @@ fn pseudo_empty(rules: &mut Vec<RuleItem>) {
 		vec![],
 		Box::new(|_: MatcherData| Matcher {
 			one_handle: Some(Box::new(|ele: &BoxDynElement, _| {
+				// the void elements have no contents, even if the backend appended nodes into them
+				if is_void_element(ele.tag_name()) && is_html_element(ele) {
+					return true;
+				}
 				let child_nodes = ele.child_nodes();
 				if child_nodes.is_empty() {
 					return true;
@@ -63,7 +74,7 @@ where
 			let mut is_first = false;
 			let mut in_next_group = false;
 			if let Some(prev_parent) = &data.parent {
-				if parent.is(&prev_parent) {
+				if parent.is(prev_parent) {
 					// sibling node, just add
 					data.range.end = index + 1;
 				} else {
@@ -159,8 +170,8 @@ fn make_asc_or_desc_nth_child(selector: &'static str, asc: bool) -> RuleDefItem
 				let eles = eles.get_ref();
 				let siblings = &eles[range.start..range.end];
 				let mut cur_end = range.len();
-				for (index, child) in childs.iter().rev().enumerate() {
-					let last_index = total - index - 1;
+				// the allowed indexs are counted from the last child
+				for (last_index, child) in childs.iter().rev().enumerate() {
 					// use binary search for faster speed
 					if allow_indexs.binary_search(&last_index).is_err() {
 						continue;
@@ -189,8 +200,8 @@ fn make_asc_or_desc_nth_child(selector: &'static str, asc: bool) -> RuleDefItem
 		PRIORITY,
 		vec![("nth", 0)],
 		Box::new(move |data: MatcherData| {
-			let n = Rule::param(&data, ("nth", 0, "n"));
-			let index = Rule::param(&data, ("nth", 0, "index"));
+			let n = Rule::param_int(&data, ("nth", 0, "n"));
+			let index = Rule::param_int(&data, ("nth", 0, "index"));
 			Matcher {
 				all_handle: Some(Box::new(move |eles: &Elements, _| {
 					let mut result: Elements = Elements::with_capacity(DEF_NODES_LEN);
@@ -207,7 +218,7 @@ fn make_asc_or_desc_nth_child(selector: &'static str, asc: bool) -> RuleDefItem
 								.as_ref()
 								.expect("parent must set in callback")
 								.children();
-							let finded = handle(&eles, &data.range, &allow_indexs, &childs);
+							let finded = handle(eles, &data.range, allow_indexs, &childs);
 							if !finded.is_empty() {
 								result.get_mut_ref().extend(finded);
 							}
@@ -290,7 +301,7 @@ fn get_allowed_name_ele(
 
 // collect available elements from siblings
 fn collect_avail_name_eles(
-	node_indexs: &mut Vec<usize>,
+	node_indexs: &[usize],
 	siblings: &[BoxDynElement],
 	finded: &mut Vec<BoxDynElement>,
 ) {
@@ -336,8 +347,8 @@ fn make_asc_or_desc_nth_of_type(selector: &'static str, asc: bool) -> RuleDefIte
 		PRIORITY,
 		vec![("nth", 0)],
 		Box::new(move |data: MatcherData| {
-			let n = Rule::param(&data, ("nth", 0, "n"));
-			let index = Rule::param(&data, ("nth", 0, "index"));
+			let n = Rule::param_int(&data, ("nth", 0, "n"));
+			let index = Rule::param_int(&data, ("nth", 0, "index"));
 			Matcher {
 				all_handle: Some(Box::new(move |eles: &Elements, _| {
 					let mut result: Elements = Elements::with_capacity(DEF_NODES_LEN);
@@ -375,7 +386,7 @@ fn make_asc_or_desc_nth_of_type(selector: &'static str, asc: bool) -> RuleDefIte
 								}
 								node_indexs.reverse();
 							}
-							collect_avail_name_eles(&mut node_indexs, siblings, result.get_mut_ref());
+							collect_avail_name_eles(&node_indexs, siblings, result.get_mut_ref());
 						},
 					);
 					result
@@ -554,9 +565,11 @@ fn pseudo_not(rules: &mut Vec<RuleItem>) {
 		PRIORITY,
 		vec![("selector", 0)],
 		Box::new(|data: MatcherData| {
-			let selector = Rule::param(&data, "selector").expect("selector param must have.");
+			let selector = Rule::param(&data, "selector")
+				.expect("selector param must have.")
+				.to_string();
 			Matcher {
-				all_handle: Some(Box::new(move |eles: &Elements, _| eles.not(selector))),
+				all_handle: Some(Box::new(move |eles: &Elements, _| eles.not(&selector))),
 				..Default::default()
 			}
 		}),
@@ -567,24 +580,55 @@ fn pseudo_not(rules: &mut Vec<RuleItem>) {
 /// pseudo selector: `:contains`
 fn pseudo_contains(rules: &mut Vec<RuleItem>) {
 	let name = ":contains";
-	let selector =
-		r##":contains({spaces}{regexp#(?:'((?:\\?+.)*?)'|"((?:\\?+.)*?)"|([^\s'"<>/=`]*))#}{spaces})"##;
+	let selector = ":contains({spaces}{string}{spaces})";
 	let rule = RuleDefItem(
 		name,
 		selector,
 		PRIORITY,
-		vec![("regexp", 0)],
+		vec![("string", 0)],
 		Box::new(|data: MatcherData| {
-			let search = Rule::param(&data, ("regexp", 0, "1"))
-				.or_else(|| Rule::param(&data, ("regexp", 0, "2")))
-				.or_else(|| Rule::param(&data, ("regexp", 0, "3")))
-				.expect("The :contains selector must have a content");
+			let search = Rule::param(&data, ("string", 0, "value"))
+				.expect("The :contains selector must have a content")
+				.to_string();
 			Matcher {
 				one_handle: Some(Box::new(move |ele: &BoxDynElement, _| {
 					if search.is_empty() {
 						return true;
 					}
-					ele.text().contains(search)
+					ele.text().contains(&search)
+				})),
+				..Default::default()
+			}
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:lang`
+fn pseudo_lang(rules: &mut Vec<RuleItem>) {
+	let name = ":lang";
+	let selector = ":lang({spaces}{string}{spaces})";
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		vec![("string", 0)],
+		Box::new(|data: MatcherData| {
+			let lang = Rule::param(&data, ("string", 0, "value"))
+				.expect("The :lang selector must have a language")
+				.to_ascii_lowercase();
+			Matcher {
+				one_handle: Some(Box::new(move |ele: &BoxDynElement, _| {
+					// the language is inherited from the closest element with the `lang` attribute
+					match ele.lang() {
+						Some(value) => {
+							let value = value.to_ascii_lowercase();
+							!lang.is_empty()
+								&& (value == lang
+									|| value.starts_with(&lang) && value[lang.len()..].starts_with('-'))
+						}
+						None => false,
+					}
 				})),
 				..Default::default()
 			}
@@ -593,6 +637,88 @@ fn pseudo_contains(rules: &mut Vec<RuleItem>) {
 	rules.push(rule.into());
 }
 
+/// pseudo selector: `:any-link`
+fn pseudo_alias_any_link(rules: &mut Vec<RuleItem>) {
+	let selector = ":any-link";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		vec![],
+		Box::new(|_| Rule::make_alias("a[href],area[href]")),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:link`, the links are never visited without a browser history
+fn pseudo_alias_link(rules: &mut Vec<RuleItem>) {
+	let selector = ":link";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		vec![],
+		Box::new(|_| Rule::make_alias("a[href],area[href]")),
+	);
+	rules.push(rule.into());
+}
+
+// get the fragment of the url, the percent-encoded bytes are decoded
+fn url_fragment(url: &str) -> Option<String> {
+	let (_, fragment) = url.split_once('#')?;
+	let bytes = fragment.as_bytes();
+	let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+	let mut index = 0;
+	while index < bytes.len() {
+		let hex = bytes
+			.get(index + 1..index + 3)
+			.and_then(|hex| std::str::from_utf8(hex).ok())
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok());
+		match (bytes[index], hex) {
+			(b'%', Some(byte)) => {
+				decoded.push(byte);
+				index += 3;
+			}
+			(byte, _) => {
+				decoded.push(byte);
+				index += 1;
+			}
+		}
+	}
+	Some(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+/// pseudo selector: `:target`
+fn pseudo_target(rules: &mut Vec<RuleItem>) {
+	let selector = ":target";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		vec![],
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele: &BoxDynElement, _| {
+				// the fragment of the document's url, such as `#top`
+				let fragment = match ele
+					.owner_document()
+					.and_then(|doc| doc.url().and_then(url_fragment))
+				{
+					Some(fragment) if !fragment.is_empty() => fragment,
+					_ => return false,
+				};
+				let is_name = |attr: &str| matches!(ele.get_attribute(attr), Some(IAttrValue::Value(value, _)) if value == fragment);
+				// the element with the id, or the `a` element with the name
+				is_name("id") || (ele.tag_name() == "A" && is_name("name"))
+			})),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
 // -----------jquery selectors----------
 
 /// pseudo selector: `:header`
@@ -637,6 +763,80 @@ fn pseudo_alias_submit(rules: &mut Vec<RuleItem>) {
 	rules.push(rule.into());
 }
 
+// get the value of a declaration in the inline style, the last declaration wins
+fn inline_style(ele: &BoxDynElement, property: &str) -> Option<String> {
+	ele
+		.parsed_attribute::<InlineStyle>("style")?
+		.get(property)
+		.map(|value| value.to_ascii_lowercase())
+}
+
+// check if the element itself is not rendered, so are its descendants
+pub(crate) fn is_not_rendered(ele: &BoxDynElement) -> bool {
+	NOT_RENDERED_TAGS.contains(&ele.tag_name())
+		|| ele.has_attribute("hidden")
+		|| inline_style(ele, "display").as_deref() == Some("none")
+}
+
+// check if the element is hidden, without the layout only the attributes and inline styles are checked
+fn is_hidden(ele: &BoxDynElement) -> bool {
+	if ele.tag_name() == "INPUT"
+		&& matches!(ele.get_attribute("type"), Some(IAttrValue::Value(ty, _)) if ty.trim().eq_ignore_ascii_case("hidden"))
+	{
+		return true;
+	}
+	// the `visibility` is inherited, the nearest declaration wins
+	let mut visibility: Option<String> = None;
+	let mut cur = Some(ele.cloned());
+	while let Some(node) = cur {
+		if !matches!(node.node_type(), INodeType::Element) {
+			break;
+		}
+		if is_not_rendered(&node) {
+			return true;
+		}
+		if visibility.is_none() {
+			visibility = inline_style(&node, "visibility");
+		}
+		cur = node.parent();
+	}
+	matches!(visibility.as_deref(), Some("hidden") | Some("collapse"))
+}
+
+/// pseudo selector: `:hidden`
+fn pseudo_hidden(rules: &mut Vec<RuleItem>) {
+	let selector = ":hidden";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		vec![],
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele: &BoxDynElement, _| is_hidden(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:visible`
+fn pseudo_visible(rules: &mut Vec<RuleItem>) {
+	let selector = ":visible";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		vec![],
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele: &BoxDynElement, _| !is_hidden(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
 pub fn init(rules: &mut Vec<RuleItem>) {
 	pseudo_empty(rules);
 	// first-child, last-child
@@ -659,6 +859,13 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 	pseudo_not(rules);
 	// contains
 	pseudo_contains(rules);
+	// lang
+	pseudo_lang(rules);
+	// any-link, link
+	pseudo_alias_any_link(rules);
+	pseudo_alias_link(rules);
+	// target
+	pseudo_target(rules);
 	// ---- jquery selectors -----
 	// :header alias
 	pseudo_alias_header(rules);
@@ -666,4 +873,7 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 	pseudo_alias_input(rules);
 	// :submit alias
 	pseudo_alias_submit(rules);
+	// :hidden, :visible
+	pseudo_hidden(rules);
+	pseudo_visible(rules);
 }
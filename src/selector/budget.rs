@@ -0,0 +1,80 @@
+use crate::error::Error;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+thread_local! {
+	static BUDGET: RefCell<Option<BudgetState>> = const { RefCell::new(None) };
+}
+
+/// the execution budget of the queries run by `with_budget`, such as the queries of the untrusted selectors
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct QueryBudget {
+	// the max count of the elements checked by the matchers, 0 means no limit
+	pub max_visits: usize,
+	// the max time of the queries, `None` means no limit
+	pub max_duration: Option<Duration>,
+}
+
+// the budget of the running queries in the current thread
+struct BudgetState {
+	budget: QueryBudget,
+	visits: usize,
+	start: Option<Instant>,
+	// the reason once the budget is exceeded
+	exceeded: Option<String>,
+}
+
+// count the elements checked by a matcher, return false once the budget is exceeded, then the matchers
+// match nothing so the query ends quickly
+pub(crate) fn visit(count: usize) -> bool {
+	BUDGET.with(|state| {
+		let mut state = state.borrow_mut();
+		let state = match state.as_mut() {
+			Some(state) => state,
+			None => return true,
+		};
+		if state.exceeded.is_some() {
+			return false;
+		}
+		state.visits += count;
+		let max_visits = state.budget.max_visits;
+		if max_visits > 0 && state.visits > max_visits {
+			state.exceeded = Some(format!("visited more than {} elements", max_visits));
+		} else if let (Some(start), Some(max_duration)) = (state.start, state.budget.max_duration) {
+			if start.elapsed() > max_duration {
+				state.exceeded = Some(format!("took more than {}ms", max_duration.as_millis()));
+			}
+		}
+		state.exceeded.is_none()
+	})
+}
+
+// the budget of the running queries is exceeded
+pub(crate) fn is_exceeded() -> bool {
+	BUDGET.with(|state| {
+		state
+			.borrow()
+			.as_ref()
+			.is_some_and(|state| state.exceeded.is_some())
+	})
+}
+
+/// pub fn `with_budget`
+/// run the queries in the handle with the budget, the queries are aborted and `Error::QueryBudgetExceeded`
+/// is returned once they check more elements or take more time than the budget
+pub fn with_budget<T>(budget: &QueryBudget, handle: impl FnOnce() -> T) -> Result<T, Error> {
+	let state = BudgetState {
+		budget: *budget,
+		visits: 0,
+		start: budget.max_duration.map(|_| Instant::now()),
+		exceeded: None,
+	};
+	// the outer budget is restored after the nested one
+	let outer = BUDGET.with(|cur| cur.borrow_mut().replace(state));
+	let result = handle();
+	let state = BUDGET.with(|cur| std::mem::replace(&mut *cur.borrow_mut(), outer));
+	match state.and_then(|state| state.exceeded) {
+		Some(reason) => Err(Error::QueryBudgetExceeded { reason }),
+		None => Ok(result),
+	}
+}
@@ -0,0 +1,90 @@
+/// the estimated worst-case cost of a selector, the services running the user-supplied selectors can
+/// reject or deprioritize the expensive ones before running them
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct SelectorComplexity {
+	// the max count of the combinators in a complex selector, the nested selectors' are added
+	pub combinator_depth: usize,
+	// the max levels of the nested selectors, such as 2 for `:not(:not(p))`
+	pub nesting_depth: usize,
+	// the count of the nested selectors, such as the selectors in `:not()`
+	pub nested_selectors: usize,
+	// the count of the compound selectors with only the universal selector, they match any element
+	pub universals: usize,
+	// the estimated cost, each compound selector costs 1 and the universal one costs 2, the nested
+	// selectors are added into the cost of their compound selector, and each descendant or general
+	// sibling combinator doubles the cost of the complex selector, the costs of the list are summed
+	pub score: u64,
+}
+
+// collect the complexity while parsing the selector
+#[derive(Default)]
+pub(crate) struct ComplexityBuilder {
+	complexity: SelectorComplexity,
+	// the complex selector being parsed
+	combinators: usize,
+	wide_combinators: u32,
+	cost: u64,
+	// the compound selector being parsed
+	is_universal: bool,
+	nested_combinators: usize,
+}
+
+impl ComplexityBuilder {
+	// a combinator, the empty one is the descendant combinator
+	pub(crate) fn combinator(&mut self, op: &str) {
+		self.end_compound();
+		if op == "," {
+			self.end_complex();
+			return;
+		}
+		self.combinators += 1;
+		if op.is_empty() || op == "~" {
+			self.wide_combinators += 1;
+		}
+	}
+	// a simple selector, the new item starts a compound selector
+	pub(crate) fn simple(&mut self, is_universal: bool, is_new_item: bool) {
+		if is_new_item {
+			self.end_compound();
+			self.cost += 1;
+			self.is_universal = is_universal;
+		} else {
+			self.is_universal = false;
+		}
+	}
+	// a nested selector in the current simple selector
+	pub(crate) fn nested(&mut self, nested: &SelectorComplexity) {
+		let complexity = &mut self.complexity;
+		complexity.nested_selectors += 1 + nested.nested_selectors;
+		complexity.nesting_depth = complexity.nesting_depth.max(nested.nesting_depth + 1);
+		complexity.universals += nested.universals;
+		self.nested_combinators = self.nested_combinators.max(nested.combinator_depth);
+		self.cost = self.cost.saturating_add(nested.score);
+	}
+	fn end_compound(&mut self) {
+		if self.is_universal {
+			self.complexity.universals += 1;
+			self.cost += 1;
+			self.is_universal = false;
+		}
+	}
+	fn end_complex(&mut self) {
+		let complexity = &mut self.complexity;
+		complexity.combinator_depth = complexity
+			.combinator_depth
+			.max(self.combinators + self.nested_combinators);
+		let factor = 1u64.checked_shl(self.wide_combinators).unwrap_or(u64::MAX);
+		complexity.score = complexity
+			.score
+			.saturating_add(self.cost.saturating_mul(factor));
+		self.combinators = 0;
+		self.wide_combinators = 0;
+		self.cost = 0;
+		self.nested_combinators = 0;
+	}
+	pub(crate) fn finish(mut self) -> SelectorComplexity {
+		self.end_compound();
+		self.end_complex();
+		self.complexity
+	}
+}
@@ -0,0 +1,135 @@
+use super::{Selector, SelectorSegment};
+use crate::error::Error;
+use crate::interface::{BoxDynElement, ClassList, Elements, FilterType, IAttrValue};
+use std::collections::{BTreeSet, HashMap};
+
+// the key of the rightmost compound selector, the elements without the key can't match
+enum BucketKey {
+	Id(String),
+	Class(String),
+	Tag(String),
+}
+
+/// a list of selectors to match one element against at once, such as the rules of a stylesheet.
+/// the selectors are bucketed by the id, class or tag of their rightmost compound selector,
+/// so only the selectors may match the element are checked
+#[derive(Default)]
+pub struct SelectorList {
+	selectors: Vec<Selector>,
+	ids: HashMap<String, Vec<usize>>,
+	classes: HashMap<String, Vec<usize>>,
+	tags: HashMap<String, Vec<usize>>,
+	// the selectors without a key, always checked
+	others: Vec<usize>,
+}
+
+impl SelectorList {
+	/// pub fn `new`
+	/// parse the selectors, the index of each selector is its position in the list
+	pub fn new<I, S>(selectors: I) -> Result<Self, Error>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let mut list = SelectorList::default();
+		for (index, selector) in selectors.into_iter().enumerate() {
+			let selector = selector.as_ref();
+			let chars: Vec<char> = selector.chars().collect();
+			// the filter methods don't use lookup
+			let parsed = Selector::from_str(selector, false)?;
+			let mut keys = Vec::with_capacity(parsed.process.len());
+			for process in &parsed.process {
+				match process
+					.query
+					.last()
+					.and_then(|rules| bucket_key(rules, &chars))
+				{
+					Some(key) => keys.push(key),
+					None => {
+						// one of the groups may match any element
+						keys.clear();
+						list.others.push(index);
+						break;
+					}
+				}
+			}
+			for key in keys {
+				let (bucket, name) = match key {
+					BucketKey::Id(name) => (&mut list.ids, name),
+					BucketKey::Class(name) => (&mut list.classes, name),
+					BucketKey::Tag(name) => (&mut list.tags, name),
+				};
+				let indexs = bucket.entry(name).or_default();
+				if indexs.last() != Some(&index) {
+					indexs.push(index);
+				}
+			}
+			list.selectors.push(parsed);
+		}
+		Ok(list)
+	}
+	/// pub fn `len`
+	pub fn len(&self) -> usize {
+		self.selectors.len()
+	}
+	/// pub fn `is_empty`
+	pub fn is_empty(&self) -> bool {
+		self.selectors.is_empty()
+	}
+	/// pub fn `match_element`
+	/// get the indexs of the selectors matching the element, in ascending order
+	pub fn match_element(&self, ele: &BoxDynElement) -> Vec<usize> {
+		let mut candidates: BTreeSet<usize> = self.others.iter().copied().collect();
+		// the tag names of the foreign elements may be not uppercase, such as `svg` in the browsers
+		if let Some(indexs) = self.tags.get(&ele.tag_name().to_ascii_uppercase()) {
+			candidates.extend(indexs);
+		}
+		if let Some(IAttrValue::Value(id, _)) = ele.get_attribute("id") {
+			if let Some(indexs) = self.ids.get(&id) {
+				candidates.extend(indexs);
+			}
+		}
+		if let Some(IAttrValue::Value(class_list, _)) = ele.get_attribute("class") {
+			for class_name in ClassList::split(&class_list) {
+				if let Some(indexs) = self.classes.get(class_name) {
+					candidates.extend(indexs);
+				}
+			}
+		}
+		let eles = Elements::with_node(ele);
+		candidates
+			.into_iter()
+			.filter(|&index| {
+				eles
+					.filter_type_handle(&self.selectors[index], &FilterType::Is)
+					.1
+			})
+			.collect()
+	}
+}
+
+// get the key of the compound selector, prefer the id, then the class, then the tag
+fn bucket_key(rules: &[SelectorSegment], chars: &[char]) -> Option<BucketKey> {
+	let mut key: Option<BucketKey> = None;
+	for (matcher, _) in rules {
+		let (start, end) = matcher.span;
+		let text: String = chars[start..end].iter().collect();
+		// the escaped identities are kept as written, skip them
+		if text.contains('\\') {
+			continue;
+		}
+		let first = match text.chars().next() {
+			Some(first) => first,
+			None => continue,
+		};
+		if first == '#' {
+			return Some(BucketKey::Id(String::from(&text[1..])));
+		}
+		if first == '.' {
+			key = Some(BucketKey::Class(String::from(&text[1..])));
+		} else if (first.is_ascii_alphabetic() || first == '_') && key.is_none() {
+			key = Some(BucketKey::Tag(text.to_ascii_uppercase()));
+		}
+	}
+	key
+}
@@ -1,22 +1,75 @@
+mod budget;
+mod complexity;
+mod list;
 pub mod pattern;
+mod rewrite;
 pub mod rule;
+pub mod trace;
+mod warning;
+pub(crate) use budget::{is_exceeded, visit};
+pub use budget::{with_budget, QueryBudget};
+pub use complexity::SelectorComplexity;
+pub use list::SelectorList;
+pub use rewrite::{SimpleSelector, SimpleSelectorKind};
+pub use warning::{SelectorWarning, SelectorWarningKind};
 
-use crate::{constants::NAME_SELECTOR_ALL, error::Error};
+use crate::{
+	constants::{
+		MAX_NESTED_DEPTH, MAX_SELECTOR_LENGTH, NAME_SELECTOR_ALL, NAME_SELECTOR_ATTR,
+		PRIORITY_PSEUDO_SELECTOR,
+	},
+	error::Error,
+	rules::Registry,
+};
+use complexity::ComplexityBuilder;
 use lazy_static::lazy_static;
-use pattern::{exec, Matched};
-use rule::{Rule, RULES};
+use pattern::{balanced_len, Matched};
+use rule::{unknown_pseudo_handle, Rule, UnknownPseudo, RULES};
 use std::{
 	str::FromStr,
 	sync::{Arc, Mutex},
 };
+use warning::NON_STANDARD_PSEUDO;
 
 use self::{pattern::BoxDynPattern, rule::Matcher};
 
 lazy_static! {
-	static ref SPLITTER: Mutex<Vec<BoxDynPattern>> =
-		Mutex::new(Rule::get_queues(r##"{regexp#(\s*[>,~+]\s*|\s+)#}"##));
+	static ref SPLITTER: Mutex<Vec<BoxDynPattern>> = Mutex::new(Rule::get_queues("{splitter}"));
 	static ref ALL_RULE: Mutex<Option<Arc<Rule>>> = Mutex::new(None);
+	static ref LIMITS: Mutex<SelectorLimits> = Mutex::new(SelectorLimits::default());
+}
+// the pseudo elements also allowed with one colon
+const LEGACY_PSEUDO_ELEMENTS: [&str; 4] = ["before", "after", "first-line", "first-letter"];
+
+/// the limits of parsing the selectors, a selector over the limits is invalid
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SelectorLimits {
+	// the max levels of the nested selectors, such as `:not(:not(...))`
+	pub max_depth: usize,
+	// the max count of the chars
+	pub max_length: usize,
+}
+
+impl Default for SelectorLimits {
+	fn default() -> Self {
+		SelectorLimits {
+			max_depth: MAX_NESTED_DEPTH,
+			max_length: MAX_SELECTOR_LENGTH,
+		}
+	}
 }
+
+/// pub fn `set_limits`
+/// set the limits of parsing the selectors, the limits are global
+pub fn set_limits(limits: SelectorLimits) {
+	*LIMITS.lock().unwrap() = limits;
+}
+
+/// pub fn `limits`
+pub fn limits() -> SelectorLimits {
+	*LIMITS.lock().unwrap()
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Combinator {
 	// descendants
@@ -79,6 +132,7 @@ pub struct QueryProcess {
 #[derive(Default, Debug)]
 pub struct Selector {
 	pub process: Vec<QueryProcess>,
+	complexity: SelectorComplexity,
 }
 
 type SelectorGroupsItem = Vec<Vec<SelectorSegment>>;
@@ -87,9 +141,86 @@ impl Selector {
 	pub fn new() -> Self {
 		Selector {
 			process: Vec::with_capacity(1),
+			..Default::default()
 		}
 	}
+	/// pub fn `complexity`
+	/// the estimated worst-case cost of the selector, the selector built from a segment has the default one
+	pub fn complexity(&self) -> SelectorComplexity {
+		self.complexity
+	}
 	pub fn from_str(context: &str, use_lookup: bool) -> Result<Self, Error> {
+		Selector::from_str_with_warnings(context, use_lookup, &mut Vec::new())
+	}
+	/// pub fn `from_str_with_warnings`
+	/// parse the selector and push the warnings into the sink, such as the redundant `*` and the non-standard syntax
+	pub fn from_str_with_warnings(
+		context: &str,
+		use_lookup: bool,
+		warnings: &mut Vec<SelectorWarning>,
+	) -> Result<Self, Error> {
+		let rules = RULES.lock().unwrap();
+		Selector::parse_with_rules(context, use_lookup, &rules, warnings)
+	}
+	/// pub fn `from_str_with_rules`
+	/// parse the selector with the rules of the registry instead of the global rules
+	pub fn from_str_with_rules(
+		context: &str,
+		use_lookup: bool,
+		registry: &Registry,
+	) -> Result<Self, Error> {
+		Selector::parse_with_rules(context, use_lookup, registry.rules(), &mut Vec::new())
+	}
+	// check the length, then parse the selector with the rules, the nested errors are reported with the selector
+	fn parse_with_rules(
+		context: &str,
+		use_lookup: bool,
+		rules: &[(&str, Arc<Rule>)],
+		warnings: &mut Vec<SelectorWarning>,
+	) -> Result<Self, Error> {
+		let limits = limits();
+		if context.chars().count() > limits.max_length {
+			return Err(Error::InvalidSelector {
+				context: String::from(context),
+				reason: format!("The selector is longer than {} chars", limits.max_length),
+			});
+		}
+		let splitter = SPLITTER.lock().unwrap();
+		Selector::parse(
+			context,
+			use_lookup,
+			rules,
+			&splitter,
+			(0, limits.max_depth),
+			warnings,
+		)
+		.map_err(|err| match err {
+			// the error of a nested selector
+			Error::InvalidSelector {
+				context: nested,
+				reason,
+			} if nested != context => Error::InvalidSelector {
+				context: String::from(context),
+				reason: format!("{} in the nested selector '{}'", reason, nested),
+			},
+			err => err,
+		})
+	}
+	// parse the selector with the locked rules, the nested selectors are parsed with a deeper depth
+	fn parse(
+		context: &str,
+		use_lookup: bool,
+		rules: &[(&str, Arc<Rule>)],
+		splitter: &[BoxDynPattern],
+		(depth, max_depth): (usize, usize),
+		warnings: &mut Vec<SelectorWarning>,
+	) -> Result<Self, Error> {
+		if depth > max_depth {
+			return Err(Error::InvalidSelector {
+				context: String::from(context),
+				reason: format!("The selectors are nested more than {} levels", max_depth),
+			});
+		}
 		let chars: Vec<char> = context.chars().collect();
 		let total_len = chars.len();
 		let mut selector = Selector::new();
@@ -99,13 +230,14 @@ impl Selector {
 			let mut prev_in = PrevInSelector::Begin;
 			let mut last_in = prev_in;
 			let mut groups: SelectorGroups = Vec::new();
-			let splitter = SPLITTER.lock().unwrap();
-			let rules = RULES.lock().unwrap();
+			let mut complexity = ComplexityBuilder::default();
+			// the span of the universal selector at the beginning of the compound selector
+			let mut universal: Option<(usize, usize)> = None;
 			Selector::add_group(&mut groups);
 			while index < total_len {
 				let next_chars = &chars[index..];
 				// first check if combinator
-				if let Some((matched, len, _)) = Rule::exec_queues(&splitter, next_chars) {
+				if let Some((matched, len, _)) = Rule::exec_queues(splitter, next_chars) {
 					let op = matched[0].chars.iter().collect::<String>();
 					let op = op.trim();
 					if prev_in == PrevInSelector::Splitter {
@@ -121,6 +253,7 @@ impl Selector {
 					}
 					// find the match
 					index += len;
+					complexity.combinator(op);
 					// set combinator
 					if op == "," {
 						if prev_in != PrevInSelector::Selector {
@@ -154,33 +287,83 @@ impl Selector {
 					last_in = prev_in;
 				}
 				let mut finded = false;
-				for (_, r) in rules.iter() {
-					if let Some((mut matched, len, queue_num)) = r.exec(next_chars) {
+				let start = index;
+				for (name, r) in rules.iter() {
+					if let Some((matched, len, queue_num)) = r.exec(next_chars) {
 						// find the rule
 						index += len;
 						let queues = &r.queues;
 						if queue_num == queues.len() {
-							// push to selector
-							Selector::add_group_item(&mut groups, (r.make(&matched), comb), is_new_item);
-							finded = true;
-						} else if queues[queue_num].is_nested() {
-							// nested selector
-							let (len, nested_matched) = Selector::parse_until(
-								&chars[index..],
-								&queues[queue_num + 1..],
-								&rules,
-								&splitter,
-								0,
+							// check the nested selectors, the spans of their warnings are moved into the selector
+							let mut offset = start;
+							for (queue, item) in queues.iter().zip(&matched) {
+								if queue.is_nested() {
+									let nested: String = item.chars.iter().collect();
+									let from = warnings.len();
+									let nested_selector = Selector::parse(
+										&nested,
+										use_lookup,
+										rules,
+										splitter,
+										(depth + 1, max_depth),
+										warnings,
+									)?;
+									complexity.nested(&nested_selector.complexity);
+									for warning in &mut warnings[from..] {
+										warning.span = (warning.span.0 + offset, warning.span.1 + offset);
+									}
+								}
+								offset += item.chars.len() + item.ignore_chars.unwrap_or(0);
+							}
+							let span = (start, index);
+							Selector::check_universal(
+								&mut universal,
+								*name == NAME_SELECTOR_ALL,
+								is_new_item,
+								span,
+								warnings,
 							);
-							index += len;
-							matched.extend(nested_matched);
-							Selector::add_group_item(&mut groups, (r.make(&matched), comb), is_new_item);
+							warnings.extend(Selector::non_standard(name, &matched, span));
+							complexity.simple(*name == NAME_SELECTOR_ALL, is_new_item);
+							// push to selector
+							let matcher = r.make(&matched).with_span(start, index);
+							Selector::add_group_item(&mut groups, (matcher, comb), is_new_item);
 							finded = true;
 						}
 						break;
 					}
 				}
 				if !finded {
+					// the pseudo elements can't be matched, give a clear reason
+					if let Some(name) = Selector::pseudo_element(next_chars) {
+						return Err(Error::InvalidSelector {
+							context: String::from(context),
+							reason: format!(
+								"Pseudo-element '{}' at index {} is not supported in matching",
+								name, index
+							),
+						});
+					}
+					// let the handle decide the unregistered pseudo class
+					if let Some((matcher, len, is_ignored)) = Selector::unknown_pseudo(next_chars) {
+						index += len;
+						let span = (start, index);
+						Selector::check_universal(&mut universal, false, is_new_item, span, warnings);
+						if is_ignored {
+							warnings.push(SelectorWarning::new(
+								SelectorWarningKind::IgnoredPseudo,
+								span,
+								format!(
+									"The pseudo class '{}' is ignored and matches nothing",
+									chars[start..index].iter().collect::<String>()
+								),
+							));
+						}
+						complexity.simple(false, is_new_item);
+						let matcher = matcher.with_span(start, index);
+						Selector::add_group_item(&mut groups, (matcher, comb), is_new_item);
+						continue;
+					}
 					// no splitter, no selector rule
 					return Err(Error::InvalidSelector {
 						context: String::from(context),
@@ -200,10 +383,125 @@ impl Selector {
 			}
 			// optimize groups to query process
 			selector.optimize(groups, use_lookup);
+			selector.complexity = complexity.finish();
 		}
 
 		Ok(selector)
 	}
+	// get the pseudo element such as '::before', or the legacy syntax with one colon such as ':before'
+	fn pseudo_element(chars: &[char]) -> Option<String> {
+		let colons = chars.iter().take(2).take_while(|&&ch| ch == ':').count();
+		if colons == 0 {
+			return None;
+		}
+		let name: String = chars[colons..]
+			.iter()
+			.take_while(|ch| ch.is_ascii_alphanumeric() || **ch == '-' || **ch == '_')
+			.collect();
+		if name.is_empty() || (colons == 1 && !LEGACY_PSEUDO_ELEMENTS.contains(&name.as_str())) {
+			return None;
+		}
+		Some(format!("{}{}", ":".repeat(colons), name))
+	}
+	// the universal selector is redundant when chained with other selectors in the compound selector
+	fn check_universal(
+		universal: &mut Option<(usize, usize)>,
+		is_universal: bool,
+		is_new_item: bool,
+		span: (usize, usize),
+		warnings: &mut Vec<SelectorWarning>,
+	) {
+		if is_new_item {
+			*universal = None;
+		}
+		let redundant = if is_universal {
+			if is_new_item {
+				*universal = Some(span);
+				None
+			} else {
+				Some(span)
+			}
+		} else if !is_new_item {
+			universal.take()
+		} else {
+			None
+		};
+		if let Some(span) = redundant {
+			warnings.push(SelectorWarning::new(
+				SelectorWarningKind::RedundantUniversal,
+				span,
+				String::from("The universal selector '*' chained with other selectors is redundant"),
+			));
+		}
+	}
+	// the warning of the non-standard rules, such as the jquery extensions
+	fn non_standard(
+		name: &str,
+		matched: &[Matched],
+		span: (usize, usize),
+	) -> Option<SelectorWarning> {
+		let message = if NON_STANDARD_PSEUDO.contains(&name) {
+			format!("The pseudo class '{}' is a jquery extension", name)
+		} else if name == NAME_SELECTOR_ATTR
+			&& matched.iter().any(|item| {
+				item.name == "attr_value" && item.data.get("op").and_then(|op| op.as_str()) == Some("!")
+			}) {
+			String::from("The attribute operator '!=' is a jquery extension")
+		} else {
+			return None;
+		};
+		Some(SelectorWarning::new(
+			SelectorWarningKind::NonStandard,
+			span,
+			message,
+		))
+	}
+	// parse the unregistered pseudo class such as ':hover' or ':lang(en)', get the matcher from the handle,
+	// and whether the pseudo class is ignored
+	fn unknown_pseudo(chars: &[char]) -> Option<(Matcher, usize, bool)> {
+		let handle = unknown_pseudo_handle()?;
+		if chars.first() != Some(&':') {
+			return None;
+		}
+		let name_len = chars[1..]
+			.iter()
+			.take_while(|ch| ch.is_ascii_alphanumeric() || **ch == '-' || **ch == '_')
+			.count();
+		if name_len == 0 {
+			return None;
+		}
+		let name: String = chars[1..=name_len].iter().collect();
+		let mut len = name_len + 1;
+		let mut params: Option<String> = None;
+		if chars.get(len) == Some(&'(') {
+			// find the matched ')', skip the quoted strings
+			let end = len + 1 + balanced_len(&chars[len + 1..])?;
+			if chars.get(end) != Some(&')') {
+				return None;
+			}
+			params = Some(
+				chars[len + 1..end]
+					.iter()
+					.collect::<String>()
+					.trim()
+					.to_string(),
+			);
+			len = end + 1;
+		}
+		let (matcher, is_ignored) = match handle(&name, params.as_deref()) {
+			UnknownPseudo::Error => return None,
+			UnknownPseudo::Ignore => (
+				Matcher {
+					one_handle: Some(Box::new(|_, _| false)),
+					priority: PRIORITY_PSEUDO_SELECTOR,
+					..Default::default()
+				},
+				true,
+			),
+			UnknownPseudo::Matcher(matcher) => (matcher, false),
+		};
+		Some((matcher, len, is_ignored))
+	}
 	// add a selector group, splitted by ','
 	fn add_group(groups: &mut SelectorGroups) {
 		groups.push(Vec::with_capacity(2));
@@ -226,23 +524,20 @@ impl Selector {
 			let mut max_index: usize = 0;
 			let mut max_priority: u32 = 0;
 			for (index, r) in group.iter_mut().enumerate() {
-				let mut total_priority = 0;
 				if r.len() > 1 {
 					let chain_comb = r[0].1;
 					r.sort_by(|a, b| b.0.priority.partial_cmp(&a.0.priority).unwrap());
-					let mut now_first = &mut r[0];
+					let now_first = &mut r[0];
 					if now_first.1 != chain_comb {
 						now_first.1 = chain_comb;
-						total_priority += now_first.0.priority;
 						for n in &mut r[1..] {
 							n.1 = Combinator::Chain;
-							total_priority += n.0.priority;
 						}
 						continue;
 					}
 				}
 				if use_lookup {
-					total_priority = r.iter().map(|p| p.0.priority).sum();
+					let total_priority: u32 = r.iter().map(|p| p.0.priority).sum();
 					if total_priority > max_priority {
 						max_priority = total_priority;
 						max_index = index;
@@ -313,71 +608,9 @@ impl Selector {
 		};
 		Selector {
 			process: vec![process],
+			..Default::default()
 		}
 	}
-	// parse until
-	pub fn parse_until(
-		chars: &[char],
-		until: &[BoxDynPattern],
-		rules: &[(&str, Arc<Rule>)],
-		splitter: &[BoxDynPattern],
-		level: usize,
-	) -> (usize, Vec<Matched>) {
-		let mut index = 0;
-		let total = chars.len();
-		let mut matched: Vec<Matched> = Vec::with_capacity(until.len() + 1);
-		while index < total {
-			let next_chars = &chars[index..];
-			if let Some((_, len, _)) = Rule::exec_queues(splitter, next_chars) {
-				index += len;
-				continue;
-			}
-			let mut finded = false;
-			for (_, r) in rules.iter() {
-				if let Some((_, len, queue_num)) = r.exec(next_chars) {
-					let queues = &r.queues;
-					// find the rule
-					index += len;
-					if queue_num == queues.len() {
-						// push to selector
-						finded = true;
-					} else {
-						let (nest_count, _) = Selector::parse_until(
-							&chars[index..],
-							&queues[queue_num + 1..],
-							rules,
-							splitter,
-							level + 1,
-						);
-						index += nest_count;
-					}
-					break;
-				}
-			}
-			if !finded {
-				if level == 0 {
-					matched.push(Matched {
-						chars: chars[0..index].iter().copied().collect(),
-						name: "selector",
-						..Default::default()
-					});
-				}
-				if !until.is_empty() {
-					let (util_matched, count, queue_num, _) = exec(until, &chars[index..]);
-					if queue_num != until.len() {
-						panic!("nested selector parse error");
-					} else {
-						index += count;
-						if level == 0 {
-							matched.extend(util_matched);
-						}
-					}
-				}
-				break;
-			}
-		}
-		(index, matched)
-	}
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -5,18 +5,25 @@
 * class: .{identity}
 * attribute: [{identity}{rule##"(^|*~$)?=('")"##}]
 */
-use crate::utils::{
-	chars_to_int, divide_isize, is_char_available_in_key, to_static_str, RoundType,
-};
+use super::rule::Rule;
+#[cfg(feature = "regex")]
+use crate::utils::to_static_str;
+use crate::utils::{chars_to_int, divide_isize, is_char_available_in_key, RoundType};
 use lazy_static::lazy_static;
+#[cfg(feature = "regex")]
 use regex::Regex;
+use std::borrow::Cow;
 use std::sync::{Arc, Mutex};
-use std::{collections::HashMap, fmt::Debug, usize};
+use std::{collections::HashMap, fmt, fmt::Debug};
 
-pub type FromParamsFn = Box<dyn Fn(&str, &str) -> Result<BoxDynPattern, String> + Send + 'static>;
+pub type FromParamsFn =
+	Box<dyn Fn(&str, &str) -> Result<BoxDynPattern, String> + Send + Sync + 'static>;
+#[cfg(feature = "regex")]
 lazy_static! {
 	static ref REGEXS: Mutex<HashMap<&'static str, Arc<Regex>>> = Mutex::new(HashMap::new());
-	static ref PATTERNS: Mutex<HashMap<&'static str, FromParamsFn>> = Mutex::new(HashMap::new());
+}
+lazy_static! {
+	static ref PATTERNS: Mutex<HashMap<&'static str, Arc<FromParamsFn>>> = Mutex::new(HashMap::new());
 }
 
 pub type BoxDynPattern = Box<dyn Pattern>;
@@ -25,7 +32,77 @@ fn no_implemented(name: &str) -> ! {
 	panic!("No supported Pattern type '{}' found", name);
 }
 
-pub type MatchedData = HashMap<&'static str, &'static str>;
+/// the typed value of the matched data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchedValue {
+	// the numbers, such as the `n` and `index` of the `nth`
+	Int(isize),
+	// the identifiers and the strings
+	Str(String),
+	// the flags
+	Bool(bool),
+}
+
+impl MatchedValue {
+	/// pub fn `as_int`
+	pub fn as_int(&self) -> Option<isize> {
+		match self {
+			MatchedValue::Int(value) => Some(*value),
+			_ => None,
+		}
+	}
+	/// pub fn `as_str`
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			MatchedValue::Str(value) => Some(value),
+			_ => None,
+		}
+	}
+	/// pub fn `as_bool`
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			MatchedValue::Bool(value) => Some(*value),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for MatchedValue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MatchedValue::Int(value) => write!(f, "{}", value),
+			MatchedValue::Str(value) => f.write_str(value),
+			MatchedValue::Bool(value) => write!(f, "{}", value),
+		}
+	}
+}
+
+impl From<isize> for MatchedValue {
+	fn from(value: isize) -> Self {
+		MatchedValue::Int(value)
+	}
+}
+
+impl From<String> for MatchedValue {
+	fn from(value: String) -> Self {
+		MatchedValue::Str(value)
+	}
+}
+
+impl From<&str> for MatchedValue {
+	fn from(value: &str) -> Self {
+		MatchedValue::Str(String::from(value))
+	}
+}
+
+impl From<bool> for MatchedValue {
+	fn from(value: bool) -> Self {
+		MatchedValue::Bool(value)
+	}
+}
+
+// the keys of the patterns are static, the keys flattened by the combinators are built
+pub type MatchedData = HashMap<Cow<'static, str>, MatchedValue>;
 #[derive(Debug, Default, Clone)]
 pub struct Matched {
 	pub chars: Vec<char>,
@@ -51,7 +128,7 @@ pub trait Pattern: Send + Sync + Debug {
 
 impl Pattern for char {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
-		let ch = chars[0];
+		let ch = *chars.first()?;
 		if *self == ch {
 			return Some(Matched {
 				chars: vec![ch],
@@ -92,6 +169,86 @@ impl Pattern for Vec<char> {
 	}
 }
 
+// the length of an integer without leading zeros
+fn number_len(chars: &[char]) -> usize {
+	match chars.first() {
+		Some('0') => 1,
+		Some('1'..='9') => chars.iter().take_while(|ch| ch.is_ascii_digit()).count(),
+		_ => 0,
+	}
+}
+
+// the length of the leading whitespaces
+fn spaces_len(chars: &[char]) -> usize {
+	chars.iter().take_while(|ch| ch.is_whitespace()).count()
+}
+
+// the length of the quoted string at the beginning, include the quotes, the backslash escapes the next char
+fn quoted_len(chars: &[char]) -> Option<usize> {
+	let quote = *chars.first().filter(|ch| **ch == '\'' || **ch == '"')?;
+	let mut index = 1;
+	while index < chars.len() {
+		match chars[index] {
+			'\\' => index += 2,
+			ch if ch == quote => return Some(index + 1),
+			_ => index += 1,
+		}
+	}
+	None
+}
+
+// unescape the css escapes, such as `\'`, `\\` and the hex code point `\26 `
+fn unescape(chars: &[char]) -> String {
+	let mut result = String::with_capacity(chars.len());
+	let mut index = 0;
+	while index < chars.len() {
+		let ch = chars[index];
+		index += 1;
+		if ch != '\\' {
+			result.push(ch);
+			continue;
+		}
+		let hex_len = chars[index..]
+			.iter()
+			.take(6)
+			.take_while(|ch| ch.is_ascii_hexdigit())
+			.count();
+		if hex_len > 0 {
+			let code = chars[index..index + hex_len]
+				.iter()
+				.fold(0, |code, ch| code * 16 + ch.to_digit(16).unwrap_or(0));
+			// the null and the invalid code points are replaced
+			result.push(
+				char::from_u32(code)
+					.filter(|ch| *ch != '\0')
+					.unwrap_or('\u{FFFD}'),
+			);
+			index += hex_len;
+			// a whitespace after the hex digits ends the escape
+			if chars.get(index).is_some_and(|ch| ch.is_ascii_whitespace()) {
+				index += 1;
+			}
+		} else if let Some(&next) = chars.get(index) {
+			// the escaped newline is removed
+			if next != '\n' {
+				result.push(next);
+			}
+			index += 1;
+		}
+	}
+	result
+}
+
+// the quoted or the unquoted value at the beginning, return the value and the matched length,
+// the quoted value is unescaped
+fn scan_value(chars: &[char], is_unquoted_end: fn(&char) -> bool) -> (String, usize) {
+	if let Some(len) = quoted_len(chars) {
+		return (unescape(&chars[1..len - 1]), len);
+	}
+	let len = chars.iter().take_while(|ch| !is_unquoted_end(ch)).count();
+	(chars[..len].iter().collect(), len)
+}
+
 /// Identity
 #[derive(Debug, Default)]
 pub struct Identity(bool);
@@ -99,7 +256,8 @@ pub struct Identity(bool);
 impl Pattern for Identity {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
 		let mut result: Vec<char> = Vec::with_capacity(5);
-		let first = chars[0];
+		// the empty chars are not an identity
+		let first = chars.first().copied().unwrap_or_default();
 		let name: &str = "identity";
 		if !(first.is_ascii_alphabetic() || first == '_') {
 			if self.0 {
@@ -157,8 +315,18 @@ pub struct AttrKey;
 impl Pattern for AttrKey {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
 		let mut result = Vec::with_capacity(5);
-		for ch in chars {
-			if is_char_available_in_key(ch) {
+		// the namespace prefix, such as `xlink|href`, `*|href` or `|href`
+		let is_ns_separator = |index: usize| {
+			chars.get(index) == Some(&'|') && chars.get(index + 1).is_some_and(is_char_available_in_key)
+		};
+		let mut has_ns = false;
+		for (index, ch) in chars.iter().enumerate() {
+			if index == 0 && *ch == '*' && is_ns_separator(1) {
+				result.push(*ch);
+			} else if !has_ns && is_ns_separator(index) {
+				has_ns = true;
+				result.push(*ch);
+			} else if is_char_available_in_key(ch) {
 				result.push(*ch);
 			} else {
 				break;
@@ -175,7 +343,7 @@ impl Pattern for AttrKey {
 	}
 	// from_params
 	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
-		check_params_return(&[s, p], || Box::new(AttrKey::default()))
+		check_params_return(&[s, p], || Box::new(AttrKey))
 	}
 }
 /// Spaces
@@ -207,7 +375,7 @@ impl Pattern for Spaces {
 			return Err(format!("Spaces not support param '{}'", p));
 		}
 		if !s.trim().is_empty() {
-			let rule: [BoxDynPattern; 3] = [Box::new('('), Box::new(Index::default()), Box::new(')')];
+			let rule: [BoxDynPattern; 3] = [Box::new('('), Box::new(Index), Box::new(')')];
 			let chars: Vec<char> = s.chars().collect();
 			let (result, _, _, match_all) = exec(&rule, &chars);
 			if !match_all {
@@ -225,9 +393,9 @@ pub struct Index;
 
 impl Pattern for Index {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
-		let first = chars[0];
+		let first = *chars.first()?;
 		let mut result = Vec::with_capacity(2);
-		let numbers = '0'..'9';
+		let numbers = '0'..='9';
 		if numbers.contains(&first) {
 			result.push(first);
 			if first != '0' {
@@ -246,7 +414,7 @@ impl Pattern for Index {
 		None
 	}
 	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
-		check_params_return(&[s, p], || Box::new(Index::default()))
+		check_params_return(&[s, p], || Box::new(Index))
 	}
 }
 
@@ -257,39 +425,56 @@ pub struct Nth;
 
 impl Pattern for Nth {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
-		let rule: RegExp = RegExp {
-			cache: true,
-			context: r#"^(?:([-+])?([0-9]|[1-9]\d+)?n(?:\s*([+-])\s*([0-9]|[1-9]\d+))?|([-+])?([0-9]|[1-9]\d+))"#,
-		};
 		let mut data = HashMap::with_capacity(2);
 		let mut matched_chars: Vec<char> = Vec::new();
-		if let Some(v) = Pattern::matched(&rule, chars) {
-			let rule_data = v.data;
-			// when the group index 6,
-			let only_index = rule_data.get("6").is_some();
-			let index_keys = if only_index { ("6", "5") } else { ("4", "3") };
-			// set index
-			if let Some(index) = Nth::get_number(&rule_data, index_keys, None) {
-				data.insert("index", index);
+		// the optional sign and the number before `n`, or the index
+		let mut index = 0;
+		let sign = match chars.first() {
+			Some(&ch) if ch == '-' || ch == '+' => {
+				index += 1;
+				Some(ch)
 			}
-			// also has `n`
-			if !only_index {
-				if let Some(n) = Nth::get_number(&rule_data, ("2", "1"), Some("1")) {
-					data.insert("n", n);
+			_ => None,
+		};
+		let number = &chars[index..index + number_len(&chars[index..])];
+		index += number.len();
+		if chars.get(index) == Some(&'n') {
+			index += 1;
+			let n = if number.is_empty() {
+				&['1'][..]
+			} else {
+				number
+			};
+			data.insert("n".into(), Nth::get_number(sign, n).into());
+			// the optional offset, such as ` + 1`
+			let mut next = index + spaces_len(&chars[index..]);
+			if let Some(&op) = chars.get(next).filter(|ch| **ch == '-' || **ch == '+') {
+				next += 1;
+				next += spaces_len(&chars[next..]);
+				let offset_len = number_len(&chars[next..]);
+				if offset_len > 0 {
+					data.insert(
+						"index".into(),
+						Nth::get_number(Some(op), &chars[next..next + offset_len]).into(),
+					);
+					index = next + offset_len;
 				}
 			}
-			matched_chars = v.chars;
+			matched_chars = chars[..index].to_vec();
+		} else if !number.is_empty() {
+			data.insert("index".into(), Nth::get_number(sign, number).into());
+			matched_chars = chars[..index].to_vec();
 		} else {
 			// maybe 'even' or 'odd'
 			let even = vec!['e', 'v', 'e', 'n'];
 			let odd = vec!['o', 'd', 'd'];
 			if Pattern::matched(&even, chars).is_some() {
-				data.insert("n", "2");
-				data.insert("index", "0");
+				data.insert("n".into(), MatchedValue::Int(2));
+				data.insert("index".into(), MatchedValue::Int(0));
 				matched_chars = even;
 			} else if Pattern::matched(&odd, chars).is_some() {
-				data.insert("n", "2");
-				data.insert("index", "1");
+				data.insert("n".into(), MatchedValue::Int(2));
+				data.insert("index".into(), MatchedValue::Int(1));
 				matched_chars = odd;
 			}
 		}
@@ -305,32 +490,29 @@ impl Pattern for Nth {
 	}
 	// from params to pattern
 	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
-		check_params_return(&[s, p], || Box::new(Nth::default()))
+		check_params_return(&[s, p], || Box::new(Nth))
 	}
 }
 
 impl Nth {
-	fn get_number(data: &MatchedData, keys: (&str, &str), def: Option<&str>) -> Option<&'static str> {
-		const MINUS: &str = "-";
-		if let Some(&idx) = data.get(keys.0).or_else(|| def.as_ref()) {
-			let mut index = String::from(idx);
-			if let Some(&op) = data.get(keys.1) {
-				if op == MINUS {
-					index = String::from(op) + &index;
-				}
-			}
-			return Some(to_static_str(index));
+	// the number with the sign, the too large numbers are saturated
+	fn get_number(sign: Option<char>, number: &[char]) -> isize {
+		let value = number.iter().fold(0isize, |value, ch| {
+			value
+				.saturating_mul(10)
+				.saturating_add(ch.to_digit(10).unwrap_or(0) as isize)
+		});
+		if sign == Some('-') {
+			-value
+		} else {
+			value
 		}
-		None
 	}
 	// get indexs allowed
-	pub fn get_allowed_indexs(n: Option<&str>, index: Option<&str>, total: usize) -> Vec<usize> {
+	pub fn get_allowed_indexs(n: Option<isize>, index: Option<isize>, total: usize) -> Vec<usize> {
 		// has n
 		if let Some(n) = n {
-			let n = n.parse::<isize>().unwrap();
-			let index = index
-				.map(|index| index.parse::<isize>().unwrap())
-				.unwrap_or(0);
+			let index = index.unwrap_or(0);
 			// n == 0
 			if n == 0 {
 				if index > 0 {
@@ -374,7 +556,7 @@ impl Nth {
 			}
 			let start = start_loop as usize;
 			let end = end_loop as usize;
-			let mut allow_indexs = Vec::with_capacity((end - start + 1) as usize);
+			let mut allow_indexs = Vec::with_capacity(end - start + 1);
 			for i in start..=end {
 				let cur_index = (i as isize * n + index) as usize;
 				if cur_index < 1 {
@@ -383,38 +565,359 @@ impl Nth {
 				// last index need -1 for real list index
 				allow_indexs.push(cur_index - 1);
 			}
+			// the negative step counts down, the handles need the indexs in ascending order
+			if n < 0 {
+				allow_indexs.reverse();
+			}
 			return allow_indexs;
 		}
 		// only index
-		let index = index
-			.expect("Nth must have 'index' value when 'n' is not setted.")
-			.parse::<isize>()
-			.expect("Nth's index is not a correct number");
+		let index = index.expect("Nth must have 'index' value when 'n' is not setted.");
 		if index <= 0 || index > (total as isize) {
 			return vec![];
 		}
-		return vec![(index - 1) as usize];
+		vec![(index - 1) as usize]
+	}
+}
+
+/// AttrValue
+/// the optional operator and value of the attribute selector, such as `^="value"`
+#[derive(Debug, Default)]
+pub struct AttrValue;
+
+impl Pattern for AttrValue {
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		let mut data = HashMap::with_capacity(2);
+		let mut index = 0;
+		let op = match chars.first() {
+			Some(&ch) if "*^$~|!".contains(ch) => {
+				index += 1;
+				ch.to_string()
+			}
+			_ => String::new(),
+		};
+		let mut len = 0;
+		if chars.get(index) == Some(&'=') {
+			index += 1;
+			index += spaces_len(&chars[index..]);
+			let (value, value_len) = scan_value(&chars[index..], |ch| {
+				ch.is_whitespace() || "]'\"<>/=`".contains(*ch)
+			});
+			if value_len > 0 {
+				data.insert("op".into(), op.into());
+				data.insert("value".into(), value.into());
+				len = index + value_len;
+			}
+		}
+		// the value is optional
+		Some(Matched {
+			chars: chars[..len].to_vec(),
+			name: "attr_value",
+			data,
+			ignore_chars: None,
+		})
+	}
+	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
+		check_params_return(&[s, p], || Box::new(AttrValue))
+	}
+}
+
+/// QuotedString
+/// a single or double quoted string with the css escapes, the `value` in the data is unescaped
+#[derive(Debug, Default)]
+pub struct QuotedString;
+
+impl Pattern for QuotedString {
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		let len = quoted_len(chars)?;
+		let mut data = HashMap::with_capacity(1);
+		data.insert("value".into(), unescape(&chars[1..len - 1]).into());
+		Some(Matched {
+			chars: chars[..len].to_vec(),
+			name: "quoted",
+			data,
+			ignore_chars: None,
+		})
+	}
+	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
+		check_params_return(&[s, p], || Box::new(QuotedString))
+	}
+}
+
+/// StringValue
+/// a quoted string unescaped as `QuotedString`, or the unquoted text until the whitespaces or the `)`, maybe empty
+#[derive(Debug, Default)]
+pub struct StringValue;
+
+impl Pattern for StringValue {
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		let (value, len) = scan_value(chars, |ch| ch.is_whitespace() || "'\"<>/=`)".contains(*ch));
+		let mut data = HashMap::with_capacity(1);
+		data.insert("value".into(), value.into());
+		Some(Matched {
+			chars: chars[..len].to_vec(),
+			name: "string",
+			data,
+			ignore_chars: None,
+		})
+	}
+	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
+		check_params_return(&[s, p], || Box::new(StringValue))
+	}
+}
+
+/// Splitter
+/// the combinator between the selectors with the spaces around, or only the spaces
+#[derive(Debug, Default)]
+pub struct Splitter;
+
+impl Pattern for Splitter {
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		let mut len = spaces_len(chars);
+		if chars.get(len).is_some_and(|ch| ">,~+".contains(*ch)) {
+			len += 1;
+			len += spaces_len(&chars[len..]);
+		} else if len == 0 {
+			return None;
+		}
+		Some(Matched {
+			chars: chars[..len].to_vec(),
+			name: "splitter",
+			..Default::default()
+		})
+	}
+	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
+		check_params_return(&[s, p], || Box::new(Splitter))
+	}
+}
+
+// match all the patterns in order, return the matched length and the matches
+fn exec_sequence(queues: &[BoxDynPattern], chars: &[char]) -> Option<(usize, Vec<Matched>)> {
+	let (matches, len, matched_num, _) = exec(queues, chars);
+	if matched_num == queues.len() {
+		Some((len, matches))
+	} else {
+		None
+	}
+}
+
+// flatten the data of the named matches into the combinator's data, the key is the name or `name.key`,
+// the keys of the combinator's own data start with `#` so they never conflict with the names,
+// the later matches of a name are suffixed with the order, such as `identity[1]`
+fn collect_data(
+	matches: &[Matched],
+	data: &mut MatchedData,
+	counts: &mut HashMap<&'static str, usize>,
+) {
+	for Matched {
+		name,
+		chars,
+		data: matched_data,
+		..
+	} in matches
+	{
+		if name.is_empty() {
+			continue;
+		}
+		let count = counts.entry(name).or_insert(0);
+		let prefix = if *count == 0 {
+			String::from(*name)
+		} else {
+			format!("{}[{}]", name, count)
+		};
+		*count += 1;
+		if matched_data.is_empty() {
+			data.insert(prefix.into(), MatchedValue::Str(chars.iter().collect()));
+		} else {
+			for (key, value) in matched_data {
+				data.insert(format!("{}.{}", prefix, key).into(), value.clone());
+			}
+		}
+	}
+}
+
+// parse the raw params into the patterns
+fn to_queues(name: &str, p: &str) -> Result<Vec<BoxDynPattern>, String> {
+	if p.is_empty() {
+		return Err(format!(
+			"The Pattern type '{}' must have the patterns in the raw params",
+			name
+		));
+	}
+	Ok(Rule::get_queues(p))
+}
+
+/// Optional
+/// the patterns are matched all or none, `{optional#{spaces}{index}#}`,
+/// the nested combinators need more `#` around, such as `{optional##{alt#a|b#}##}`,
+/// the data has the `#matched` flag
+#[derive(Debug)]
+pub struct Optional(Vec<BoxDynPattern>);
+
+impl Pattern for Optional {
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		let mut data = HashMap::new();
+		let matched = exec_sequence(&self.0, chars);
+		data.insert("#matched".into(), MatchedValue::Bool(matched.is_some()));
+		let len = match matched {
+			Some((len, matches)) => {
+				collect_data(&matches, &mut data, &mut HashMap::new());
+				len
+			}
+			None => 0,
+		};
+		Some(Matched {
+			chars: chars[..len].to_vec(),
+			name: "optional",
+			data,
+			ignore_chars: None,
+		})
+	}
+	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
+		if !s.is_empty() {
+			return Err(format!("Unrecognized params '{}'", s));
+		}
+		Ok(Box::new(Optional(to_queues("optional", p)?)))
+	}
+}
+
+/// Repeat
+/// the patterns are matched repeatedly, `{repeat(2)#..#}` for exactly 2 times,
+/// `{repeat(1,)#..#}` for at least once and `{repeat(0,3)#..#}` for at most 3 times,
+/// the data has the `#count` of the times
+#[derive(Debug)]
+pub struct Repeat(Vec<BoxDynPattern>, usize, Option<usize>);
+
+impl Pattern for Repeat {
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		let Repeat(queues, min, max) = self;
+		let mut data = HashMap::new();
+		let mut counts = HashMap::new();
+		let mut len = 0;
+		let mut count = 0;
+		while max.is_none_or(|max| count < max) {
+			match exec_sequence(queues, &chars[len..]) {
+				Some((cur_len, matches)) => {
+					collect_data(&matches, &mut data, &mut counts);
+					len += cur_len;
+					count += 1;
+					// an empty match would repeat forever
+					if cur_len == 0 {
+						break;
+					}
+				}
+				None => break,
+			}
+		}
+		if count < *min {
+			return None;
+		}
+		data.insert("#count".into(), MatchedValue::Int(count as isize));
+		Some(Matched {
+			chars: chars[..len].to_vec(),
+			name: "repeat",
+			data,
+			ignore_chars: None,
+		})
+	}
+	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
+		let wrong_times = || format!("Wrong times '{}' of Pattern type 'repeat'", s);
+		let times = s
+			.strip_prefix('(')
+			.and_then(|s| s.strip_suffix(')'))
+			.ok_or_else(wrong_times)?;
+		let to_number = |n: &str| n.trim().parse::<usize>().map_err(|_| wrong_times());
+		let (min, max) = match times.split_once(',') {
+			Some((min, max)) if max.trim().is_empty() => (to_number(min)?, None),
+			Some((min, max)) => (to_number(min)?, Some(to_number(max)?)),
+			None => {
+				let times = to_number(times)?;
+				(times, Some(times))
+			}
+		};
+		if max.is_some_and(|max| max < min || max == 0) {
+			return Err(wrong_times());
+		}
+		Ok(Box::new(Repeat(to_queues("repeat", p)?, min, max)))
+	}
+}
+
+/// Alt
+/// the first matched alternative, the alternatives are separated by `|`, a literal `|` is escaped as `\|`,
+/// `{alt#{identity}|{index}#}`, the data has the `#index` of the alternative
+#[derive(Debug)]
+pub struct Alt(Vec<Vec<BoxDynPattern>>);
+
+impl Pattern for Alt {
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		for (index, queues) in self.0.iter().enumerate() {
+			if let Some((len, matches)) = exec_sequence(queues, chars) {
+				let mut data = HashMap::new();
+				collect_data(&matches, &mut data, &mut HashMap::new());
+				data.insert("#index".into(), MatchedValue::Int(index as isize));
+				return Some(Matched {
+					chars: chars[..len].to_vec(),
+					name: "alt",
+					data,
+					ignore_chars: None,
+				});
+			}
+		}
+		None
+	}
+	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
+		if !s.is_empty() {
+			return Err(format!("Unrecognized params '{}'", s));
+		}
+		// split the alternatives out of the nested patterns
+		let mut alternatives = vec![String::new()];
+		let mut depth = 0;
+		let mut chars = p.chars().peekable();
+		while let Some(ch) = chars.next() {
+			match ch {
+				'\\' if depth == 0 && chars.peek() == Some(&'|') => {
+					alternatives.last_mut().unwrap().push('|');
+					chars.next();
+					continue;
+				}
+				'|' if depth == 0 => {
+					alternatives.push(String::new());
+					continue;
+				}
+				'{' => depth += 1,
+				'}' => depth -= 1,
+				_ => {}
+			}
+			alternatives.last_mut().unwrap().push(ch);
+		}
+		let alternatives = alternatives
+			.iter()
+			.map(|alternative| to_queues("alt", alternative))
+			.collect::<Result<Vec<_>, String>>()?;
+		Ok(Box::new(Alt(alternatives)))
 	}
 }
 
 /// RegExp
+#[cfg(feature = "regex")]
 #[derive(Debug)]
 pub struct RegExp<'a> {
 	pub cache: bool,
 	pub context: &'a str,
 }
 
+#[cfg(feature = "regex")]
 impl<'a> Pattern for RegExp<'a> {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
 		let Self { context, cache } = *self;
 		let content = chars.iter().collect::<String>();
 		let rule = RegExp::get_rule(context, cache);
-		if let Some(caps) = rule.captures(to_static_str(content)) {
-			let total_len = caps[0].len();
+		if let Some(caps) = rule.captures(&content) {
+			let total_len = caps[0].chars().count();
 			let mut data = HashMap::with_capacity(caps.len() - 1);
 			for (index, m) in caps.iter().skip(1).enumerate() {
 				if let Some(m) = m {
-					data.insert(to_static_str((index + 1).to_string()), m.as_str());
+					data.insert((index + 1).to_string().into(), m.as_str().into());
 				}
 			}
 			let result = chars[..total_len].to_vec();
@@ -443,6 +946,7 @@ impl<'a> Pattern for RegExp<'a> {
 	}
 }
 
+#[cfg(feature = "regex")]
 impl<'a> RegExp<'a> {
 	pub fn get_rule(context: &str, cache: bool) -> Arc<Regex> {
 		let wrong_regex = format!("Wrong regex context '{}'", context);
@@ -467,17 +971,59 @@ impl<'a> RegExp<'a> {
 	}
 }
 
+// the length of the content before the first unbalanced `)` or `]`, the quoted strings and the escaped chars are skipped,
+// none if the brackets are mismatched or a quote is not closed
+pub(crate) fn balanced_len(chars: &[char]) -> Option<usize> {
+	let mut closers: Vec<char> = Vec::new();
+	let mut index = 0;
+	while index < chars.len() {
+		match chars[index] {
+			'\\' => index += 1,
+			'\'' | '"' => {
+				index += quoted_len(&chars[index..])?;
+				continue;
+			}
+			'(' => closers.push(')'),
+			'[' => closers.push(']'),
+			ch @ (')' | ']') => match closers.pop() {
+				Some(closer) if closer == ch => {}
+				Some(_) => return None,
+				None => return Some(index),
+			},
+			_ => {}
+		}
+		index += 1;
+	}
+	if closers.is_empty() {
+		Some(chars.len())
+	} else {
+		None
+	}
+}
+
 /// Nested
+/// the selector in the parentheses, such as `:not({selector})`, the brackets must be balanced,
+/// it's parsed and checked with the outer selector, the rule's handle gets the selector string by the name `selector`
 #[derive(Debug, Default)]
 pub struct NestedSelector;
 
 impl Pattern for NestedSelector {
-	fn matched(&self, _chars: &[char]) -> Option<Matched> {
-		None
+	fn matched(&self, chars: &[char]) -> Option<Matched> {
+		let len = balanced_len(chars)?;
+		// the trailing spaces are left for the next pattern
+		let len = chars[..len]
+			.iter()
+			.rposition(|ch| !ch.is_whitespace())
+			.map(|index| index + 1)?;
+		Some(Matched {
+			chars: chars[..len].to_vec(),
+			name: "selector",
+			..Default::default()
+		})
 	}
 	// from params to pattern
 	fn from_params(s: &str, p: &str) -> Result<BoxDynPattern, String> {
-		check_params_return(&[s, p], || Box::new(NestedSelector::default()))
+		check_params_return(&[s, p], || Box::new(NestedSelector))
 	}
 	// set to be nested
 	fn is_nested(&self) -> bool {
@@ -490,7 +1036,7 @@ pub fn add_pattern(name: &'static str, from_handle: FromParamsFn) {
 	if patterns.get(name).is_some() {
 		panic!("The pattern '{}' is already exist.", name);
 	} else {
-		patterns.insert(name, from_handle);
+		patterns.insert(name, Arc::new(from_handle));
 	}
 }
 
@@ -501,16 +1047,25 @@ pub(crate) fn init() {
 	add_pattern("attr_key", Box::new(AttrKey::from_params));
 	add_pattern("index", Box::new(Index::from_params));
 	add_pattern("nth", Box::new(Nth::from_params));
+	add_pattern("attr_value", Box::new(AttrValue::from_params));
+	add_pattern("quoted", Box::new(QuotedString::from_params));
+	add_pattern("string", Box::new(StringValue::from_params));
+	add_pattern("splitter", Box::new(Splitter::from_params));
+	add_pattern("optional", Box::new(Optional::from_params));
+	add_pattern("repeat", Box::new(Repeat::from_params));
+	add_pattern("alt", Box::new(Alt::from_params));
+	#[cfg(feature = "regex")]
 	add_pattern("regexp", Box::new(RegExp::from_params));
 	add_pattern("selector", Box::new(NestedSelector::from_params));
 }
 
 pub fn to_pattern(name: &str, s: &str, p: &str) -> Result<BoxDynPattern, String> {
-	let patterns = PATTERNS.lock().unwrap();
-	if let Some(cb) = patterns.get(name) {
-		return cb(s, p);
+	// release the lock before calling, the combinators parse their nested patterns
+	let cb = PATTERNS.lock().unwrap().get(name).cloned();
+	match cb {
+		Some(cb) => cb(s, p),
+		None => no_implemented(name),
 	}
-	no_implemented(name);
 }
 
 pub fn exec(queues: &[BoxDynPattern], chars: &[char]) -> (Vec<Matched>, usize, usize, bool) {
@@ -0,0 +1,238 @@
+use super::Selector;
+use crate::css::split_top_level;
+use crate::error::Error;
+
+/// the kind of a simple selector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimpleSelectorKind {
+	Universal,
+	Type,
+	Id,
+	Class,
+	Attribute,
+	PseudoClass,
+}
+
+/// a simple selector in a compound selector, such as `div`, `.item` or `:not(.a)`
+#[derive(Debug, Clone, Copy)]
+pub struct SimpleSelector<'s> {
+	pub kind: SimpleSelectorKind,
+	// the selector text as written
+	pub text: &'s str,
+}
+
+impl<'s> SimpleSelector<'s> {
+	fn new(text: &'s str) -> Self {
+		use SimpleSelectorKind::*;
+		let kind = match text.chars().next() {
+			Some('*') => Universal,
+			Some('#') => Id,
+			Some('.') => Class,
+			Some('[') => Attribute,
+			Some(':') => PseudoClass,
+			_ => Type,
+		};
+		SimpleSelector { kind, text }
+	}
+	/// pub fn `name`
+	/// the name of the type, id, class or pseudo class, the escapes are kept
+	pub fn name(&self) -> &'s str {
+		use SimpleSelectorKind::*;
+		match self.kind {
+			Id | Class => &self.text[1..],
+			PseudoClass => {
+				let name = self.text.trim_start_matches(':');
+				&name[..name.find('(').unwrap_or(name.len())]
+			}
+			Universal | Type | Attribute => self.text,
+		}
+	}
+}
+
+// the chars of the pseudo class with a selector argument
+const NOT_START: &str = ":not(";
+
+impl Selector {
+	/// pub fn `map_simple_selectors`
+	/// rewrite each simple selector with the closure, `None` removes it, an emptied compound selector becomes `*`.
+	/// the arguments of `:not()` are mapped before the `:not()` itself
+	pub fn map_simple_selectors<F>(selector: &str, mut handle: F) -> Result<String, Error>
+	where
+		F: FnMut(SimpleSelector) -> Option<String>,
+	{
+		Selector::map_simple_selectors_with(selector, &mut handle)
+	}
+	// the recursive implementation of `map_simple_selectors`
+	fn map_simple_selectors_with(
+		selector: &str,
+		handle: &mut dyn FnMut(SimpleSelector) -> Option<String>,
+	) -> Result<String, Error> {
+		let chars: Vec<char> = selector.chars().collect();
+		let parsed = Selector::from_str(selector, false)?;
+		let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+		for process in &parsed.process {
+			for rules in &process.query {
+				let mut compound: Vec<(usize, usize, Option<String>)> = Vec::with_capacity(rules.len());
+				for (matcher, _) in rules {
+					let (start, end) = matcher.span;
+					let text: String = chars[start..end].iter().collect();
+					let text = match text
+						.strip_prefix(NOT_START)
+						.and_then(|argument| argument.strip_suffix(')'))
+					{
+						Some(argument) => format!(
+							"{}{})",
+							NOT_START,
+							Selector::map_simple_selectors_with(argument, handle)?
+						),
+						None => text,
+					};
+					compound.push((start, end, handle(SimpleSelector::new(&text))));
+				}
+				compound.sort_by_key(|(start, ..)| *start);
+				let is_emptied = compound
+					.iter()
+					.all(|(_, _, text)| text.as_deref().is_none_or(str::is_empty));
+				if is_emptied {
+					if let Some(first) = compound.first_mut() {
+						first.2 = Some(String::from("*"));
+					}
+				}
+				replacements.extend(
+					compound
+						.into_iter()
+						.map(|(start, end, text)| (start, end, text.unwrap_or_default())),
+				);
+			}
+		}
+		replacements.sort_by_key(|(start, ..)| *start);
+		let mut result = String::with_capacity(selector.len());
+		let mut index = 0;
+		for (start, end, text) in replacements {
+			result.extend(&chars[index..start]);
+			result.push_str(&text);
+			index = end;
+		}
+		result.extend(&chars[index..]);
+		Ok(result)
+	}
+	/// pub fn `prefix`
+	/// prefix each selector in the list with the scope as the ancestor, `.widget` makes `a, b` into `.widget a, .widget b`
+	pub fn prefix(selector: &str, scope: &str) -> Result<String, Error> {
+		Selector::from_str(selector, false)?;
+		let scope = scope.trim();
+		let result = split_top_level(selector, ',')
+			.into_iter()
+			.map(|group| format!("{} {}", scope, group.trim()))
+			.collect::<Vec<String>>()
+			.join(", ");
+		Selector::from_str(&result, false)?;
+		Ok(result)
+	}
+	/// pub fn `rename_classes`
+	/// rename the classes, include the classes in `:not()`
+	pub fn rename_classes<F>(selector: &str, mut rename: F) -> Result<String, Error>
+	where
+		F: FnMut(&str) -> String,
+	{
+		Selector::map_simple_selectors(selector, |simple| match simple.kind {
+			SimpleSelectorKind::Class => Some(format!(".{}", rename(simple.name()))),
+			_ => Some(String::from(simple.text)),
+		})
+	}
+	/// pub fn `strip_unsupported_pseudos`
+	/// remove the pseudo classes and pseudo elements can't be matched, such as `:hover` and `::before`,
+	/// an emptied compound selector becomes `*`
+	pub fn strip_unsupported_pseudos(selector: &str) -> Result<String, Error> {
+		let chars: Vec<char> = selector.chars().collect();
+		let total = chars.len();
+		let mut result = String::with_capacity(selector.len());
+		let mut index = 0;
+		let mut quote: Option<char> = None;
+		let mut bracket_depth = 0;
+		// whether the current compound selector has a simple selector
+		let mut has_simple = false;
+		while index < total {
+			let ch = chars[index];
+			if let Some(q) = quote {
+				result.push(ch);
+				if ch == '\\' && index + 1 < total {
+					result.push(chars[index + 1]);
+					index += 1;
+				} else if ch == q {
+					quote = None;
+				}
+				index += 1;
+				continue;
+			}
+			match ch {
+				'"' | '\'' => quote = Some(ch),
+				'[' => bracket_depth += 1,
+				']' => bracket_depth -= 1,
+				':' if bracket_depth == 0 => {
+					let end = pseudo_end(&chars, index);
+					let pseudo: String = chars[index..end].iter().collect();
+					let is_supported = Selector::from_str(&format!("*{}", pseudo), false).is_ok();
+					if is_supported {
+						result.push_str(&pseudo);
+						has_simple = true;
+					} else {
+						let is_compound_end = chars
+							.get(end)
+							.is_none_or(|ch| ch.is_whitespace() || matches!(ch, '>' | '+' | '~' | ','));
+						if is_compound_end && !has_simple {
+							result.push('*');
+							has_simple = true;
+						}
+					}
+					index = end;
+					continue;
+				}
+				ch if bracket_depth == 0 && (ch.is_whitespace() || matches!(ch, '>' | '+' | '~' | ',')) => {
+					has_simple = false;
+				}
+				_ => has_simple = true,
+			}
+			result.push(ch);
+			index += 1;
+		}
+		Selector::from_str(&result, false)?;
+		Ok(result)
+	}
+}
+
+// the end of the pseudo started at the index, include the arguments in the parentheses
+fn pseudo_end(chars: &[char], start: usize) -> usize {
+	let mut index = start;
+	while chars.get(index) == Some(&':') {
+		index += 1;
+	}
+	while let Some(ch) = chars.get(index) {
+		if ch.is_alphanumeric() || *ch == '-' || *ch == '_' {
+			index += 1;
+		} else {
+			break;
+		}
+	}
+	if chars.get(index) == Some(&'(') {
+		let mut depth = 0;
+		let mut quote: Option<char> = None;
+		while let Some(&ch) = chars.get(index) {
+			index += 1;
+			match (quote, ch) {
+				(Some(q), ch) if ch == q => quote = None,
+				(Some(_), _) => {}
+				(None, '"' | '\'') => quote = Some(ch),
+				(None, '(') => depth += 1,
+				(None, ')') => {
+					depth -= 1;
+					if depth == 0 {
+						break;
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+	index
+}
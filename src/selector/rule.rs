@@ -1,23 +1,34 @@
-use super::pattern::{self, exec, to_pattern, BoxDynPattern, Matched, Pattern};
+use super::pattern::{self, exec, to_pattern, BoxDynPattern, Matched, MatchedValue, Pattern};
 use crate::{constants::PRIORITY_PSEUDO_SELECTOR, interface::Elements};
-use crate::{
-	interface::BoxDynElement,
-	utils::{to_static_str, vec_char_to_clean_str},
-};
+use crate::{interface::BoxDynElement, utils::vec_char_to_clean_str};
 use lazy_static::lazy_static;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::{Arc, Mutex};
 lazy_static! {
 	pub static ref RULES: Mutex<Vec<(&'static str, Arc<Rule>)>> = Mutex::new(Vec::with_capacity(20));
+	static ref UNKNOWN_PSEUDO_HANDLE: Mutex<Option<Arc<UnknownPseudoHandle>>> = Mutex::new(None);
 }
 // matcher handles
-pub type MatchAllHandle = Box<dyn (for<'a, 'r> Fn(&'a Elements<'r>, Option<bool>) -> Elements<'r>)>;
+pub type MatchAllHandle = Box<dyn for<'a, 'r> Fn(&'a Elements<'r>, Option<bool>) -> Elements<'r>>;
 pub type MatchOneHandle = Box<dyn Fn(&BoxDynElement, Option<bool>) -> bool>;
 // matcher data
-pub type MatcherData = HashMap<SavedDataKey, &'static str>;
+pub type MatcherData = HashMap<SavedDataKey, MatchedValue>;
 // matcher factory
 pub type MatcherFactory = Box<dyn (Fn(MatcherData) -> Matcher) + Send + Sync>;
+// the handle of the unregistered pseudo classes, called with the name and the content in the parentheses
+pub type UnknownPseudoHandle = Box<dyn Fn(&str, Option<&str>) -> UnknownPseudo + Send + Sync>;
+
+/// the decision of an unregistered pseudo class such as `:hover`
+pub enum UnknownPseudo {
+	// the selector is invalid
+	Error,
+	// the pseudo class matches nothing
+	Ignore,
+	// match with the matcher
+	Matcher(Matcher),
+}
 
 #[derive(Default)]
 pub struct Matcher {
@@ -25,6 +36,8 @@ pub struct Matcher {
 	pub one_handle: Option<MatchOneHandle>,
 	pub priority: u32,
 	pub in_cache: bool,
+	// the chars range in the selector the matcher parsed from
+	pub span: (usize, usize),
 }
 
 impl fmt::Debug for Matcher {
@@ -41,8 +54,13 @@ impl fmt::Debug for Matcher {
 }
 
 impl Matcher {
+	// set the chars range in the selector
+	pub fn with_span(mut self, start: usize, end: usize) -> Self {
+		self.span = (start, end);
+		self
+	}
 	// apply all elements
-	pub fn apply<'a, 'r>(&self, eles: &'a Elements<'r>, use_cache: Option<bool>) -> Elements<'r> {
+	pub fn apply<'r>(&self, eles: &Elements<'r>, use_cache: Option<bool>) -> Elements<'r> {
 		if let Some(handle) = &self.all_handle {
 			return handle(eles, use_cache);
 		}
@@ -66,25 +84,50 @@ impl Matcher {
 	}
 }
 
+// count the elements checked by the matcher into the query budget, nothing is matched once it's exceeded
+fn budgeted(mut matcher: Matcher) -> Matcher {
+	if let Some(handle) = matcher.one_handle.take() {
+		matcher.one_handle = Some(Box::new(
+			move |ele: &BoxDynElement, use_cache: Option<bool>| super::visit(1) && handle(ele, use_cache),
+		));
+	}
+	if let Some(handle) = matcher.all_handle.take() {
+		matcher.all_handle = Some(Box::new(move |eles: &Elements, use_cache: Option<bool>| {
+			if super::visit(eles.length()) {
+				handle(eles, use_cache)
+			} else {
+				Elements::new()
+			}
+		}));
+	}
+	matcher
+}
+
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
-pub struct SavedDataKey(&'static str, usize, &'static str);
+pub struct SavedDataKey(&'static str, usize, Cow<'static, str>);
 pub type DataKey = (&'static str, usize);
 
 impl From<(&'static str,)> for SavedDataKey {
 	fn from(t: (&'static str,)) -> Self {
-		SavedDataKey(t.0, 0, "_")
+		SavedDataKey(t.0, 0, Cow::Borrowed("_"))
 	}
 }
 
 impl From<(&'static str, usize)> for SavedDataKey {
 	fn from(t: (&'static str, usize)) -> Self {
-		SavedDataKey(t.0, t.1, "_")
+		SavedDataKey(t.0, t.1, Cow::Borrowed("_"))
 	}
 }
 
 impl From<(&'static str, usize, &'static str)> for SavedDataKey {
 	fn from(t: (&'static str, usize, &'static str)) -> Self {
-		SavedDataKey(t.0, t.1, t.2)
+		SavedDataKey(t.0, t.1, Cow::Borrowed(t.2))
+	}
+}
+
+impl From<(&'static str, usize, String)> for SavedDataKey {
+	fn from(t: (&'static str, usize, String)) -> Self {
+		SavedDataKey(t.0, t.1, Cow::Owned(t.2))
 	}
 }
 
@@ -171,12 +214,7 @@ impl Rule {
 		let mut index: usize = 0;
 		for ch in content.chars() {
 			index += 1;
-			let is_prev_matched_finish = if is_matched_finish {
-				is_matched_finish = false;
-				true
-			} else {
-				false
-			};
+			let is_prev_matched_finish = std::mem::take(&mut is_matched_finish);
 			if store.is_wait_end {
 				if ch.is_ascii_whitespace() {
 					continue;
@@ -279,7 +317,7 @@ impl Rule {
 			if is_matched_finish {
 				match store.next() {
 					Ok(queue) => queues.push(queue),
-					Err(reason) => panic!(reason),
+					Err(reason) => panic!("{}", reason),
 				};
 			}
 			prev_char = ch;
@@ -312,7 +350,7 @@ impl Rule {
 		queues: &[BoxDynPattern],
 		chars: &[char],
 	) -> Option<(Vec<Matched>, usize, usize)> {
-		let (result, matched_len, matched_queue_item, _) = exec(&queues, chars);
+		let (result, matched_len, matched_queue_item, _) = exec(queues, chars);
 		if matched_len > 0 {
 			Some((result, matched_len, matched_queue_item))
 		} else {
@@ -323,7 +361,7 @@ impl Rule {
 	pub fn make(&self, data: &[Matched]) -> Matcher {
 		let handle = &self.handle;
 		let data = self.data(data);
-		let mut matcher = handle(data);
+		let mut matcher = budgeted(handle(data));
 		matcher.priority = self.priority;
 		matcher.in_cache = self.in_cache;
 		matcher
@@ -339,6 +377,7 @@ impl Rule {
 			// priority
 			priority: PRIORITY_PSEUDO_SELECTOR,
 			in_cache: false,
+			span: (0, 0),
 		}
 	}
 
@@ -360,14 +399,10 @@ impl Rule {
 					let count = hash_data.len();
 					if count == 0 {
 						let cur_key = (*name, *index);
-						result.insert(
-							cur_key.into(),
-							to_static_str(chars.iter().collect::<String>()),
-						);
+						result.insert(cur_key.into(), MatchedValue::Str(chars.iter().collect()));
 					} else {
-						for (&key, &val) in hash_data.iter() {
-							let cur_key = (*name, *index, key);
-							result.insert(cur_key.into(), val);
+						for (key, val) in hash_data.iter() {
+							result.insert(SavedDataKey(name, *index, key.clone()), val.clone());
 						}
 					}
 				}
@@ -380,9 +415,17 @@ impl Rule {
 		rule.queues = Rule::get_queues(context);
 		rule
 	}
-	// quick method to get param
-	pub fn param<T: Into<SavedDataKey>>(params: &MatcherData, v: T) -> Option<&'static str> {
-		params.get(&v.into()).copied()
+	// quick method to get the string param
+	pub fn param<T: Into<SavedDataKey>>(params: &MatcherData, v: T) -> Option<&str> {
+		params.get(&v.into()).and_then(MatchedValue::as_str)
+	}
+	// quick method to get the integer param
+	pub fn param_int<T: Into<SavedDataKey>>(params: &MatcherData, v: T) -> Option<isize> {
+		params.get(&v.into()).and_then(MatchedValue::as_int)
+	}
+	// quick method to get the flag param
+	pub fn param_bool<T: Into<SavedDataKey>>(params: &MatcherData, v: T) -> Option<bool> {
+		params.get(&v.into()).and_then(MatchedValue::as_bool)
 	}
 }
 
@@ -429,6 +472,18 @@ pub fn add_rules(rules: Vec<RuleItem>) {
 	}
 }
 
+/// pub fn `set_unknown_pseudo_handle`
+/// set the handle called when the selector meets an unregistered pseudo class, `None` restores the error.
+/// the handle is called while parsing, it should not parse selectors itself, use `Rule::make_alias` instead
+pub fn set_unknown_pseudo_handle(handle: Option<UnknownPseudoHandle>) {
+	*UNKNOWN_PSEUDO_HANDLE.lock().unwrap() = handle.map(Arc::new);
+}
+
+// get the handle of the unregistered pseudo classes
+pub(crate) fn unknown_pseudo_handle() -> Option<Arc<UnknownPseudoHandle>> {
+	UNKNOWN_PSEUDO_HANDLE.lock().unwrap().clone()
+}
+
 pub(crate) fn init() {
 	pattern::init();
 }
@@ -0,0 +1,75 @@
+use super::Combinator;
+use crate::interface::Elements;
+use std::fmt;
+use std::time::Duration;
+
+/// a step of the traced query, one rule applied to the elements found by the previous step
+pub struct TraceStep<'a> {
+	// the selector text of the rule, such as `.item` or `:nth-child(2n)`
+	pub rule: String,
+	// how the candidates are got from the previous step
+	pub combinator: Combinator,
+	// the elements checked by the rule
+	pub candidates: usize,
+	// the candidates the rule accepted
+	pub matched: Elements<'a>,
+	// the candidates the rule rejected
+	pub rejected: Elements<'a>,
+	pub elapsed: Duration,
+}
+
+/// the steps of a selector group split by `,`
+#[derive(Default)]
+pub struct TraceGroup<'a> {
+	pub steps: Vec<TraceStep<'a>>,
+}
+
+/// the report of a traced query
+#[derive(Default)]
+pub struct QueryTrace<'a> {
+	pub selector: String,
+	pub groups: Vec<TraceGroup<'a>>,
+	// the elements found by all the groups, in document order
+	pub result: Elements<'a>,
+	pub elapsed: Duration,
+}
+
+impl<'a> QueryTrace<'a> {
+	/// pub fn `empty_step`
+	/// the first step with no matched elements, the steps after it are not executed
+	pub fn empty_step(&self) -> Option<&TraceStep<'a>> {
+		self
+			.groups
+			.iter()
+			.flat_map(|group| group.steps.iter())
+			.find(|step| step.matched.is_empty())
+	}
+}
+
+impl<'a> fmt::Display for QueryTrace<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"query '{}' found {} elements in {:?}",
+			self.selector,
+			self.result.length(),
+			self.elapsed
+		)?;
+		for (index, group) in self.groups.iter().enumerate() {
+			writeln!(f, "group {}:", index + 1)?;
+			for step in &group.steps {
+				writeln!(
+					f,
+					"  {:?} '{}': {} candidates, {} matched, {} rejected in {:?}",
+					step.combinator,
+					step.rule,
+					step.candidates,
+					step.matched.length(),
+					step.rejected.length(),
+					step.elapsed
+				)?;
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+// the pseudo classes of the jquery extensions, not in the css standard
+pub(crate) const NON_STANDARD_PSEUDO: [&str; 6] = [
+	":contains",
+	":header",
+	":input",
+	":submit",
+	":hidden",
+	":visible",
+];
+
+/// the kinds of the warnings found while parsing the selectors
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SelectorWarningKind {
+	// the universal selector chained with other selectors, such as `*.item`
+	RedundantUniversal,
+	// the unregistered pseudo class ignored by the unknown pseudo handle, it matches nothing
+	IgnoredPseudo,
+	// the syntax not in the css standard, such as `:contains()` and the `!=` attribute operator
+	NonStandard,
+}
+
+impl SelectorWarningKind {
+	/// pub fn `name`
+	/// the name of the warning kind
+	pub fn name(&self) -> &'static str {
+		use SelectorWarningKind::*;
+		match self {
+			RedundantUniversal => "redundant-universal",
+			IgnoredPseudo => "ignored-pseudo",
+			NonStandard => "non-standard",
+		}
+	}
+}
+
+impl fmt::Display for SelectorWarningKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// a warning found while parsing, unlike the errors the selector is still valid
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SelectorWarning {
+	pub kind: SelectorWarningKind,
+	// the chars range of the selector text causing the warning
+	pub span: (usize, usize),
+	pub message: String,
+}
+
+impl SelectorWarning {
+	pub(crate) fn new(kind: SelectorWarningKind, span: (usize, usize), message: String) -> Self {
+		SelectorWarning {
+			kind,
+			span,
+			message,
+		}
+	}
+}
+
+impl fmt::Display for SelectorWarning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} at index {}: {}",
+			self.kind, self.span.0, self.message
+		)
+	}
+}
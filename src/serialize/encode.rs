@@ -0,0 +1,94 @@
+use crate::error::Error as IError;
+use crate::html::spec::RAW_TEXT_ELEMENTS;
+use encoding_rs::Encoding;
+
+// the end of the start tag at the start of the html, the `>` in the quoted attribute values is skipped
+fn start_tag_end(html: &str) -> usize {
+	let mut quote = None;
+	for (index, ch) in html.char_indices() {
+		match (quote, ch) {
+			(Some(open), ch) if ch == open => quote = None,
+			(Some(_), _) => {}
+			(None, '"' | '\'') => quote = Some(ch),
+			(None, '>') => return index + 1,
+			_ => {}
+		}
+	}
+	html.len()
+}
+
+// the raw text element of the start tag at the start of the html, such as `script`
+fn raw_text_element(html: &str) -> Option<&'static str> {
+	let rest = html.strip_prefix('<')?.as_bytes();
+	RAW_TEXT_ELEMENTS.iter().copied().find(|name| {
+		rest.len() > name.len()
+			&& rest[..name.len()].eq_ignore_ascii_case(name.as_bytes())
+			&& matches!(
+				rest[name.len()],
+				b'>' | b'/' | b' ' | b'\t' | b'\n' | b'\r' | b'\x0c'
+			)
+	})
+}
+
+// split the html into the parts, the raw parts such as the comments and the contents of `script`
+// have the names of their contexts, the character references are not decoded in them
+fn split_raw_parts(html: &str) -> Vec<(&str, Option<&'static str>)> {
+	let mut parts = Vec::new();
+	let (mut start, mut pos) = (0, 0);
+	while let Some(offset) = html[pos..].find('<') {
+		let tag_start = pos + offset;
+		let rest = &html[tag_start..];
+		let (raw_start, raw_end, context) = if rest.starts_with("<!--") {
+			let raw_start = tag_start + 4;
+			let raw_end = html[raw_start..]
+				.find("-->")
+				.map_or(html.len(), |end| raw_start + end);
+			(raw_start, raw_end, "comment")
+		} else if let Some(name) = raw_text_element(rest) {
+			let raw_start = tag_start + start_tag_end(rest);
+			// the tag names are ascii, the offsets of the lowercase copy are the same
+			let raw_end = html[raw_start..]
+				.to_ascii_lowercase()
+				.find(&format!("</{}", name))
+				.map_or(html.len(), |end| raw_start + end);
+			(raw_start, raw_end, name)
+		} else {
+			pos = tag_start + 1;
+			continue;
+		};
+		parts.push((&html[start..raw_start], None));
+		parts.push((&html[raw_start..raw_end], Some(context)));
+		start = raw_end;
+		pos = raw_end;
+	}
+	parts.push((&html[start..], None));
+	parts
+}
+
+/// pub fn `encode_html`
+/// encode the html in the charset such as `gbk` or `shift_jis`, the characters can't be represented in
+/// the charset are written as the numeric character references such as `&#8364;`. the references are not
+/// decoded in the comments and the raw texts such as `<script>` and `<style>`, so an error is returned if
+/// they have such characters. the charsets the browsers don't encode in, such as `utf-16`, are unsupported
+pub fn encode_html(html: &str, charset: &str) -> Result<Vec<u8>, IError> {
+	let unsupported = || IError::UnsupportedCharset {
+		charset: String::from(charset),
+	};
+	let encoding = Encoding::for_label(charset.trim().as_bytes()).ok_or_else(unsupported)?;
+	// the `utf-16` and the `replacement` encodings are written as `utf-8`, not the declared charset
+	if encoding.output_encoding() != encoding {
+		return Err(unsupported());
+	}
+	let mut result = Vec::with_capacity(html.len());
+	for (part, context) in split_raw_parts(html) {
+		let (bytes, _, has_unmappable) = encoding.encode(part);
+		if let (true, Some(context)) = (has_unmappable, context) {
+			return Err(IError::UnencodableRawText {
+				charset: String::from(charset),
+				context: String::from(context),
+			});
+		}
+		result.extend_from_slice(&bytes);
+	}
+	Ok(result)
+}
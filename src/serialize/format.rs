@@ -0,0 +1,39 @@
+use super::{write, SerializeOptions};
+use crate::interface::BoxDynNode;
+
+/// the options of `format`
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+	// the indent of each level, two spaces by default
+	pub indent: String,
+	// the maximum width of the start tag before its attributes are wrapped one per line, `0` never wraps
+	pub max_width: usize,
+}
+
+impl Default for FormatOptions {
+	fn default() -> Self {
+		FormatOptions {
+			indent: String::from("  "),
+			max_width: 80,
+		}
+	}
+}
+
+impl From<FormatOptions> for SerializeOptions {
+	fn from(options: FormatOptions) -> Self {
+		SerializeOptions {
+			collapse_whitespace: true,
+			indent: Some(options.indent),
+			max_width: options.max_width,
+			..Default::default()
+		}
+	}
+}
+
+/// pub fn `format`
+/// serialize the node with one block element per line, the inline contents are kept in one line
+/// with their whitespaces collapsed, the `pre`, `textarea`, `script` and `style` are kept as they are,
+/// format the result again gets the same html
+pub fn format(node: &BoxDynNode, options: FormatOptions) -> String {
+	write(node, &options.into())
+}
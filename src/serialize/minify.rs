@@ -0,0 +1,44 @@
+use super::{write, SerializeOptions};
+use crate::interface::BoxDynNode;
+
+/// the options of `minify`, all the passes are enabled by default
+#[derive(Debug, Clone)]
+pub struct MinifyOptions {
+	// remove the comments
+	pub remove_comments: bool,
+	// remove the whitespaces between the elements, except in `pre`, `textarea`, `script` and `style`
+	pub collapse_whitespace: bool,
+	// write the boolean attributes without values
+	pub collapse_boolean_attributes: bool,
+	// remove the attributes equal to their default values
+	pub remove_default_attributes: bool,
+}
+
+impl Default for MinifyOptions {
+	fn default() -> Self {
+		MinifyOptions {
+			remove_comments: true,
+			collapse_whitespace: true,
+			collapse_boolean_attributes: true,
+			remove_default_attributes: true,
+		}
+	}
+}
+
+impl From<MinifyOptions> for SerializeOptions {
+	fn from(options: MinifyOptions) -> Self {
+		SerializeOptions {
+			remove_comments: options.remove_comments,
+			collapse_whitespace: options.collapse_whitespace,
+			collapse_boolean_attributes: options.collapse_boolean_attributes,
+			remove_default_attributes: options.remove_default_attributes,
+			..Default::default()
+		}
+	}
+}
+
+/// pub fn `minify`
+/// serialize the node with the minify passes
+pub fn minify(node: &BoxDynNode, options: MinifyOptions) -> String {
+	write(node, &options.into())
+}
@@ -0,0 +1,485 @@
+use crate::error::Error as IError;
+use crate::html::spec::{
+	is_block_element, is_newline_dropped_element, is_raw_text_element, is_void_element,
+};
+use crate::interface::{is_html_element, BoxDynElement, BoxDynNode, IAttrValue, INodeType};
+// minify, remove the comments, whitespaces and redundant attributes
+mod minify;
+pub use minify::{minify, MinifyOptions};
+// format, indent the document line by line
+mod format;
+pub use format::{format, FormatOptions};
+// snapshot, the normalized html compared with the stored files
+mod snapshot;
+pub use snapshot::{
+	compare_snapshot, snapshot, snapshot_diff, SnapshotOptions, UPDATE_SNAPSHOTS_ENV,
+};
+// encode, write the html in the legacy charsets
+#[cfg(feature = "encoding")]
+mod encode;
+#[cfg(feature = "encoding")]
+pub use encode::encode_html;
+
+// the elements' whitespaces are significant
+const PRESERVE_TAGS: [&str; 4] = ["pre", "textarea", "script", "style"];
+// the attributes only care about existence
+const BOOLEAN_ATTRS: [&str; 23] = [
+	"allowfullscreen",
+	"async",
+	"autofocus",
+	"autoplay",
+	"checked",
+	"controls",
+	"default",
+	"defer",
+	"disabled",
+	"formnovalidate",
+	"ismap",
+	"itemscope",
+	"loop",
+	"multiple",
+	"muted",
+	"nomodule",
+	"novalidate",
+	"open",
+	"playsinline",
+	"readonly",
+	"required",
+	"reversed",
+	"selected",
+];
+// the tag, attribute and its default value
+const DEFAULT_ATTRS: [(&str, &str, &str); 10] = [
+	("script", "type", "text/javascript"),
+	("script", "language", "javascript"),
+	("style", "type", "text/css"),
+	("link", "type", "text/css"),
+	("style", "media", "all"),
+	("form", "method", "get"),
+	("form", "autocomplete", "on"),
+	("input", "type", "text"),
+	("button", "type", "submit"),
+	("area", "shape", "rect"),
+];
+
+/// the options of the serializer, the passes such as `minify` are built on it,
+/// the default options write the nodes as they are
+#[derive(Debug, Default, Clone)]
+pub struct SerializeOptions {
+	// remove the comments
+	pub remove_comments: bool,
+	// collapse the whitespaces of the texts and remove the whitespaces between the blocks
+	pub collapse_whitespace: bool,
+	// write the boolean attributes without values, `disabled="disabled"` as `disabled`
+	pub collapse_boolean_attributes: bool,
+	// remove the attributes equal to their default values, such as `type="text"` of `input`
+	pub remove_default_attributes: bool,
+	// write the attributes sorted by the lowercase names, the output is stable whatever the source order is
+	pub sort_attributes: bool,
+	// the indent of the lines, the nodes are written without line breaks if it's `None`
+	pub indent: Option<String>,
+	// the maximum width of the start tag before its attributes are wrapped, `0` never wraps
+	pub max_width: usize,
+	// the strict mode for the untrusted trees, `serialize` fails if a raw zone is met,
+	// see `INodeTrait::set_raw_zone`
+	pub deny_raw_zones: bool,
+}
+
+// the context inherited from the ancestors
+#[derive(Clone, Copy, Default)]
+struct Context {
+	// in `script`, `style` or a raw zone
+	raw: bool,
+	// in the elements keep the whitespaces
+	preserve: bool,
+	// write the children line by line
+	layout: bool,
+	// the depth of the lines
+	depth: usize,
+}
+
+/// pub fn `escape_text`
+/// escape the text content of the html
+pub fn escape_text(content: &str, result: &mut String) {
+	for ch in content.chars() {
+		match ch {
+			'&' => result.push_str("&amp;"),
+			'<' => result.push_str("&lt;"),
+			'>' => result.push_str("&gt;"),
+			'\u{00A0}' => result.push_str("&nbsp;"),
+			_ => result.push(ch),
+		}
+	}
+}
+
+/// pub fn `escape_attr_value`
+/// escape the attribute value in double quotes
+pub fn escape_attr_value(value: &str, result: &mut String) {
+	for ch in value.chars() {
+		match ch {
+			'&' => result.push_str("&amp;"),
+			'"' => result.push_str("&quot;"),
+			'\u{00A0}' => result.push_str("&nbsp;"),
+			_ => result.push(ch),
+		}
+	}
+}
+
+// collapse the ascii whitespaces into one space
+fn collapse_whitespace(content: &str, trim_start: bool, trim_end: bool) -> String {
+	let mut result = String::with_capacity(content.len());
+	let mut in_whitespace = false;
+	for ch in content.chars() {
+		if ch.is_ascii_whitespace() {
+			in_whitespace = true;
+			continue;
+		}
+		if in_whitespace && !(trim_start && result.is_empty()) {
+			result.push(' ');
+		}
+		in_whitespace = false;
+		result.push(ch);
+	}
+	if in_whitespace && !(trim_end || (trim_start && result.is_empty())) {
+		result.push(' ');
+	}
+	result
+}
+
+// the lowercase tag name
+fn lower_tag_name(ele: &BoxDynElement) -> String {
+	ele.tag_name().to_ascii_lowercase()
+}
+
+// the html element is a known block, the unknown elements are inline so the whitespaces beside them are kept
+fn is_block_tag(tag_name: &str, ele: &BoxDynElement) -> bool {
+	is_block_element(tag_name) && is_html_element(ele)
+}
+
+// check if the node is an element breaks the line, the texts beside it can be trimmed
+fn is_block(node: &BoxDynNode) -> bool {
+	// the nodes in the head are not rendered, such as the `script` and the comments
+	let is_in_head = || {
+		node
+			.parent()
+			.is_some_and(|parent| is_html_element(&parent) && lower_tag_name(&parent) == "head")
+	};
+	match node.node_type() {
+		INodeType::Element => match node.clone_node().typed().into_element() {
+			Some(ele) => is_block_tag(&lower_tag_name(&ele), &ele) || is_in_head(),
+			None => false,
+		},
+		INodeType::Comment => is_in_head(),
+		INodeType::HTMLDOCTYPE => true,
+		_ => false,
+	}
+}
+
+struct Serializer<'o> {
+	options: &'o SerializeOptions,
+	result: String,
+	// the name of the first raw zone met in the strict mode
+	denied: Option<String>,
+}
+
+impl<'o> Serializer<'o> {
+	fn node(&mut self, node: &BoxDynNode, context: Context) {
+		match node.node_type() {
+			INodeType::Document | INodeType::DocumentFragement => {
+				if let Some(ele) = node.clone_node().typed().into_element() {
+					if context.layout {
+						self.block_children(&ele, context);
+					} else {
+						self.children(&ele, true, context);
+					}
+				}
+			}
+			INodeType::Element => {
+				if let Some(ele) = node.clone_node().typed().into_element() {
+					self.element(&ele, context);
+				}
+			}
+			INodeType::Text => {
+				let context = self.raw_zone_context(node.is_raw_zone(), "#text", context);
+				self.text(node.text_content(), context);
+			}
+			INodeType::Comment if !self.options.remove_comments => {
+				self.result.push_str("<!--");
+				self.result.push_str(node.text_content());
+				self.result.push_str("-->");
+			}
+			// the backends without the doctype's name write the html5 doctype
+			INodeType::HTMLDOCTYPE => match node.doctype() {
+				Some(doctype) => self.result.push_str(&doctype.to_string()),
+				None => self.result.push_str("<!DOCTYPE html>"),
+			},
+			INodeType::XMLCDATA => {
+				self.result.push_str("<![CDATA[");
+				self.result.push_str(node.text_content());
+				self.result.push_str("]]>");
+			}
+			INodeType::ProcessingInstruction => {
+				self.result.push_str("<?");
+				self.result.push_str(&node.pi_target().unwrap_or_default());
+				let data = node.text_content();
+				if !data.is_empty() {
+					self.result.push(' ');
+					self.result.push_str(data);
+				}
+				self.result.push_str("?>");
+			}
+			_ => {}
+		}
+	}
+	// the texts in the raw zones are written without escaping, the raw zones met in the strict mode
+	// are escaped and the first one fails the serializing
+	fn raw_zone_context(&mut self, is_raw_zone: bool, name: &str, context: Context) -> Context {
+		if is_raw_zone && self.options.deny_raw_zones {
+			self.denied.get_or_insert_with(|| String::from(name));
+		}
+		Context {
+			raw: context.raw || (is_raw_zone && !self.options.deny_raw_zones),
+			..context
+		}
+	}
+	fn text(&mut self, content: &str, context: Context) {
+		if context.raw {
+			self.result.push_str(content);
+		} else {
+			escape_text(content, &mut self.result);
+		}
+	}
+	// the child nodes without the removed comments, the template's contents are serialized as its child nodes
+	fn child_nodes<'b>(&self, parent: &BoxDynElement) -> Vec<BoxDynNode<'b>> {
+		let remove_comments = self.options.remove_comments;
+		let mut child_nodes = parent.child_nodes();
+		if let Some(content) = parent.template_content() {
+			child_nodes.extend(content.child_nodes());
+		}
+		child_nodes
+			.retain(|child| !(remove_comments && matches!(child.node_type(), INodeType::Comment)));
+		child_nodes
+	}
+	fn children(&mut self, parent: &BoxDynElement, is_block_parent: bool, context: Context) {
+		let child_nodes = self.child_nodes(parent);
+		let child_nodes: Vec<&BoxDynNode> = child_nodes.iter().collect();
+		self.inline_nodes(&child_nodes, is_block_parent, context);
+	}
+	// write the nodes in one line, the texts at the edges are trimmed if `trim_edges` is true,
+	// the adjacent texts such as the ones around a removed comment are collapsed as one
+	fn inline_nodes(&mut self, child_nodes: &[&BoxDynNode], trim_edges: bool, context: Context) {
+		let is_collapse = self.options.collapse_whitespace && !context.preserve;
+		let is_text = |node: &BoxDynNode| matches!(node.node_type(), INodeType::Text);
+		let total = child_nodes.len();
+		let mut index = 0;
+		while index < total {
+			let child = child_nodes[index];
+			if !is_collapse || !is_text(child) {
+				self.node(child, context);
+				index += 1;
+				continue;
+			}
+			let is_raw_zone = child.is_raw_zone();
+			let mut content = String::from(child.text_content());
+			let mut next = index + 1;
+			while next < total
+				&& is_text(child_nodes[next])
+				&& child_nodes[next].is_raw_zone() == is_raw_zone
+			{
+				content.push_str(child_nodes[next].text_content());
+				next += 1;
+			}
+			let trim_start = match index {
+				0 => trim_edges,
+				_ => is_block(child_nodes[index - 1]),
+			};
+			let trim_end = match next {
+				next if next == total => trim_edges,
+				next => is_block(child_nodes[next]),
+			};
+			let content = collapse_whitespace(&content, trim_start, trim_end);
+			let context = self.raw_zone_context(is_raw_zone, "#text", context);
+			self.text(&content, context);
+			index = next;
+		}
+	}
+	// start a new line with the indent
+	fn new_line(&mut self, depth: usize) {
+		if !self.result.is_empty() {
+			self.result.push('\n');
+		}
+		if let Some(indent) = &self.options.indent {
+			for _ in 0..depth {
+				self.result.push_str(indent);
+			}
+		}
+	}
+	// write the children line by line, the block nodes own their lines and the inline nodes between them share one line
+	fn block_children(&mut self, parent: &BoxDynElement, context: Context) {
+		let child_nodes = self.child_nodes(parent);
+		let mut inlines: Vec<&BoxDynNode> = Vec::new();
+		for child in &child_nodes {
+			if is_block(child) {
+				self.inline_line(&inlines, context);
+				inlines.clear();
+				self.new_line(context.depth);
+				self.node(child, context);
+			} else {
+				inlines.push(child);
+			}
+		}
+		self.inline_line(&inlines, context);
+	}
+	// write the inline nodes in a new line, the line is dropped if nothing is written
+	fn inline_line(&mut self, inlines: &[&BoxDynNode], context: Context) {
+		if inlines.is_empty() {
+			return;
+		}
+		let start = self.result.len();
+		self.new_line(context.depth);
+		let content_start = self.result.len();
+		self.inline_nodes(
+			inlines,
+			true,
+			Context {
+				layout: false,
+				..context
+			},
+		);
+		if self.result.len() == content_start {
+			self.result.truncate(start);
+		}
+	}
+	// the attributes, each one with a leading space
+	fn attributes(&self, tag_name: &str, ele: &BoxDynElement) -> Vec<String> {
+		let options = self.options;
+		let mut result = Vec::new();
+		let mut attributes = ele.attributes();
+		if options.sort_attributes {
+			attributes.sort_by_cached_key(|(name, _)| name.to_ascii_lowercase());
+		}
+		for (name, value) in attributes {
+			let lower_name = name.to_ascii_lowercase();
+			let value = match value {
+				IAttrValue::Value(value, _) => Some(value),
+				IAttrValue::True => None,
+			};
+			if options.remove_default_attributes {
+				let current = value.as_deref().unwrap_or("");
+				let is_default = DEFAULT_ATTRS.iter().any(|(tag, attr, default_value)| {
+					*tag == tag_name && *attr == lower_name && current.eq_ignore_ascii_case(default_value)
+				});
+				if is_default {
+					continue;
+				}
+			}
+			let mut attr = format!(" {}", name);
+			// the boolean attributes are only collapsed if the values are empty or their names
+			let is_collapsed = options.collapse_boolean_attributes
+				&& BOOLEAN_ATTRS.contains(&lower_name.as_str())
+				&& value
+					.as_deref()
+					.is_none_or(|value| value.is_empty() || value.eq_ignore_ascii_case(&lower_name));
+			if let (Some(value), false) = (value, is_collapsed) {
+				attr.push_str("=\"");
+				escape_attr_value(&value, &mut attr);
+				attr.push('"');
+			}
+			result.push(attr);
+		}
+		result
+	}
+	fn start_tag(&mut self, tag_name: &str, ele: &BoxDynElement, context: Context) {
+		let attrs = self.attributes(tag_name, ele);
+		// the local name keeps the case of the svg and mathml elements
+		self.result.push('<');
+		self.result.push_str(ele.local_name());
+		let max_width = self.options.max_width;
+		let indent = self.options.indent.as_deref().unwrap_or("");
+		let width = indent.len() * context.depth
+			+ tag_name.len()
+			+ attrs.iter().map(|attr| attr.len()).sum::<usize>()
+			+ 2;
+		if context.layout && max_width > 0 && attrs.len() > 1 && width > max_width {
+			// one attribute per line, indented one more level than the tag
+			for attr in &attrs {
+				self.new_line(context.depth + 1);
+				self.result.push_str(&attr[1..]);
+			}
+		} else {
+			for attr in &attrs {
+				self.result.push_str(attr);
+			}
+		}
+		self.result.push('>');
+	}
+	fn element(&mut self, ele: &BoxDynElement, context: Context) {
+		let tag_name = lower_tag_name(ele);
+		self.start_tag(&tag_name, ele, context);
+		if is_void_element(&tag_name) {
+			return;
+		}
+		let is_preserve = PRESERVE_TAGS.contains(&tag_name.as_str());
+		let is_block_parent = is_block_tag(&tag_name, ele);
+		let has_block_child = ele.child_nodes().iter().any(is_block);
+		let child_context = self.raw_zone_context(
+			ele.is_raw_zone(),
+			&tag_name,
+			Context {
+				raw: context.raw || (is_raw_text_element(&tag_name) && is_html_element(ele)),
+				preserve: context.preserve || is_preserve,
+				layout: context.layout && !is_preserve && is_block_parent && has_block_child,
+				depth: context.depth + 1,
+			},
+		);
+		// the parser drops the first newline of the element, keep it by writing one more
+		if is_newline_dropped_element(&tag_name) {
+			if let Some(first) = ele.child_nodes_item(0) {
+				if matches!(first.node_type(), INodeType::Text) && first.text_content().starts_with('\n') {
+					self.result.push('\n');
+				}
+			}
+		}
+		if child_context.layout {
+			self.block_children(ele, child_context);
+			self.new_line(context.depth);
+		} else {
+			self.children(ele, is_block_parent, child_context);
+		}
+		self.result.push_str("</");
+		self.result.push_str(ele.local_name());
+		self.result.push('>');
+	}
+}
+
+fn run<'o>(node: &BoxDynNode, options: &'o SerializeOptions) -> Serializer<'o> {
+	let mut serializer = Serializer {
+		options,
+		result: String::with_capacity(256),
+		denied: None,
+	};
+	serializer.node(
+		node,
+		Context {
+			layout: options.indent.is_some(),
+			..Default::default()
+		},
+	);
+	serializer
+}
+
+// serialize the node by the options of the passes, they don't deny the raw zones so it never fails
+pub(super) fn write(node: &BoxDynNode, options: &SerializeOptions) -> String {
+	run(node, options).result
+}
+
+/// pub fn `serialize`
+/// serialize the node with the options, the document node writes its children,
+/// it fails if a raw zone is met and `deny_raw_zones` is set
+pub fn serialize(node: &BoxDynNode, options: &SerializeOptions) -> Result<String, IError> {
+	let serializer = run(node, options);
+	match serializer.denied {
+		Some(name) => Err(IError::RawZoneDenied { name }),
+		None => Ok(serializer.result),
+	}
+}
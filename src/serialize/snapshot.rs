@@ -0,0 +1,130 @@
+use super::{write, SerializeOptions};
+use crate::interface::BoxDynNode;
+use std::fs;
+use std::path::Path;
+
+/// the environment variable to rewrite the stored snapshots with the found html
+pub const UPDATE_SNAPSHOTS_ENV: &str = "MESDOC_UPDATE_SNAPSHOTS";
+
+/// the options of `snapshot`
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+	// the indent of each level, two spaces by default
+	pub indent: String,
+	// remove the comments, they are kept by default
+	pub remove_comments: bool,
+}
+
+impl Default for SnapshotOptions {
+	fn default() -> Self {
+		SnapshotOptions {
+			indent: String::from("  "),
+			remove_comments: false,
+		}
+	}
+}
+
+impl From<SnapshotOptions> for SerializeOptions {
+	fn from(options: SnapshotOptions) -> Self {
+		SerializeOptions {
+			remove_comments: options.remove_comments,
+			collapse_whitespace: true,
+			collapse_boolean_attributes: true,
+			sort_attributes: true,
+			indent: Some(options.indent),
+			..Default::default()
+		}
+	}
+}
+
+/// pub fn `snapshot`
+/// serialize the node with the normalized formatting for the snapshots, one block element per line,
+/// the attributes sorted by names and the whitespaces collapsed, the attributes are never wrapped
+pub fn snapshot(node: &BoxDynNode, options: SnapshotOptions) -> String {
+	write(node, &options.into())
+}
+
+// the lines without the carriage returns and the trailing empty lines
+fn lines(content: &str) -> Vec<&str> {
+	let mut lines: Vec<&str> = content
+		.lines()
+		.map(|line| line.trim_end_matches('\r'))
+		.collect();
+	while let Some(&"") = lines.last() {
+		lines.pop();
+	}
+	lines
+}
+
+/// pub fn `snapshot_diff`
+/// diff the snapshots line by line, `None` if they are the same,
+/// the missed lines start with `- `, the unexpected lines start with `+ `, the kept lines start with two spaces
+pub fn snapshot_diff(expected: &str, found: &str) -> Option<String> {
+	let expected = lines(expected);
+	let found = lines(found);
+	if expected == found {
+		return None;
+	}
+	// the lengths of the longest common lines from the indexes
+	let (total, found_total) = (expected.len(), found.len());
+	let mut common = vec![vec![0usize; found_total + 1]; total + 1];
+	for i in (0..total).rev() {
+		for j in (0..found_total).rev() {
+			common[i][j] = if expected[i] == found[j] {
+				common[i + 1][j + 1] + 1
+			} else {
+				common[i + 1][j].max(common[i][j + 1])
+			};
+		}
+	}
+	let mut result = Vec::with_capacity(total.max(found_total));
+	let (mut i, mut j) = (0, 0);
+	while i < total || j < found_total {
+		if i < total && j < found_total && expected[i] == found[j] {
+			result.push(format!("  {}", expected[i]));
+			i += 1;
+			j += 1;
+		} else if j == found_total || (i < total && common[i + 1][j] >= common[i][j + 1]) {
+			result.push(format!("- {}", expected[i]));
+			i += 1;
+		} else {
+			result.push(format!("+ {}", found[j]));
+			j += 1;
+		}
+	}
+	Some(result.join("\n"))
+}
+
+/// pub fn `compare_snapshot`
+/// compare the found html with the snapshot stored in the file, the file is written if it doesn't exist
+/// or the environment variable `MESDOC_UPDATE_SNAPSHOTS` is set, the error shows the diff of the lines
+pub fn compare_snapshot<P: AsRef<Path>>(path: P, found: &str) -> Result<(), String> {
+	let path = path.as_ref();
+	let is_update = std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some();
+	if is_update || !path.exists() {
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir).map_err(|e| {
+				format!(
+					"Can't create the snapshot directory '{}': {}",
+					dir.display(),
+					e
+				)
+			})?;
+		}
+		let mut content = String::from(found);
+		content.push('\n');
+		return fs::write(path, content)
+			.map_err(|e| format!("Can't write the snapshot '{}': {}", path.display(), e));
+	}
+	let expected = fs::read_to_string(path)
+		.map_err(|e| format!("Can't read the snapshot '{}': {}", path.display(), e))?;
+	match snapshot_diff(&expected, found) {
+		None => Ok(()),
+		Some(diff) => Err(format!(
+			"The snapshot '{}' is not matched, set `{}` to update it\n{}",
+			path.display(),
+			UPDATE_SNAPSHOTS_ENV,
+			diff
+		)),
+	}
+}
@@ -0,0 +1,169 @@
+//! a logic-less template layer, the elements are filled by the attributes:
+//! `data-bind="path"` sets the text, `data-bind-{attr}="path"` sets the attribute,
+//! `data-each="path"` repeats the element for each item of the array
+use crate::interface::{BoxDynElement, IAttrValue, InsertPosition};
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+
+const ATTR_BIND: &str = "data-bind";
+const ATTR_BIND_PREFIX: &str = "data-bind-";
+const ATTR_EACH: &str = "data-each";
+// the path of the current item
+const PATH_THIS: &str = ".";
+// the path of the current item's index
+const PATH_INDEX: &str = "$index";
+
+/// the data of the template
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+	data: Value,
+}
+
+impl Context {
+	/// pub fn `new`
+	/// serialize the data as the context
+	pub fn new<T: Serialize>(data: &T) -> Result<Self, serde_json::Error> {
+		Ok(Context {
+			data: serde_json::to_value(data)?,
+		})
+	}
+	/// pub fn `get_ref`
+	pub fn get_ref(&self) -> &Value {
+		&self.data
+	}
+}
+
+impl From<Value> for Context {
+	fn from(data: Value) -> Self {
+		Context { data }
+	}
+}
+
+// the values of the current and the outer `data-each`
+struct Scope<'s> {
+	value: &'s Value,
+	index: Option<usize>,
+	parent: Option<&'s Scope<'s>>,
+}
+
+impl<'s> Scope<'s> {
+	// find the value, the first key is looked up from the inner scope to the outer
+	fn lookup(&self, path: &str) -> Option<Value> {
+		let path = path.trim();
+		if path == PATH_THIS {
+			return Some(self.value.clone());
+		}
+		if path == PATH_INDEX {
+			let mut scope = Some(self);
+			while let Some(cur) = scope {
+				if let Some(index) = cur.index {
+					return Some(Value::from(index));
+				}
+				scope = cur.parent;
+			}
+			return None;
+		}
+		let mut keys = path.split('.');
+		let first = keys.next()?;
+		let mut scope = Some(self);
+		let mut value = None;
+		while let Some(cur) = scope {
+			if let Some(found) = cur.value.get(first) {
+				value = Some(found);
+				break;
+			}
+			scope = cur.parent;
+		}
+		let mut value = value?;
+		for key in keys {
+			value = match value {
+				Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+				_ => value.get(key)?,
+			};
+		}
+		Some(value.clone())
+	}
+}
+
+// the text of the value, the arrays and objects are written as json
+fn value_to_string(value: &Value) -> String {
+	match value {
+		Value::Null => String::new(),
+		Value::String(content) => content.clone(),
+		_ => value.to_string(),
+	}
+}
+
+// repeat the template element for each item, the template is removed
+fn render_each(ele: &BoxDynElement, items: &Value, scope: &Scope) -> Result<(), Box<dyn Error>> {
+	let items = match items {
+		Value::Array(items) => items.as_slice(),
+		Value::Null | Value::Bool(false) => &[],
+		item => std::slice::from_ref(item),
+	};
+	let mut template = ele.cloned();
+	for (index, item) in items.iter().enumerate() {
+		let mut copy = ele.clone_deep()?;
+		copy.remove_attribute(ATTR_EACH);
+		template.insert_adjacent(&InsertPosition::BeforeBegin, &copy);
+		let item_scope = Scope {
+			value: item,
+			index: Some(index),
+			parent: Some(scope),
+		};
+		render_element(&mut copy, &item_scope)?;
+	}
+	if let Some(mut parent) = ele.parent() {
+		parent.remove_child(template);
+	}
+	Ok(())
+}
+
+// fill the element and its descendants
+fn render_element(ele: &mut BoxDynElement, scope: &Scope) -> Result<(), Box<dyn Error>> {
+	if let Some(IAttrValue::Value(path, _)) = ele.get_attribute(ATTR_EACH) {
+		if let Some(items) = scope.lookup(&path) {
+			render_each(ele, &items, scope)?;
+		}
+		return Ok(());
+	}
+	for (name, value) in ele.attributes() {
+		let path = match value {
+			IAttrValue::Value(path, _) => path,
+			IAttrValue::True => continue,
+		};
+		let attr_name = match name.to_ascii_lowercase().strip_prefix(ATTR_BIND_PREFIX) {
+			Some(attr_name) if !attr_name.is_empty() => String::from(attr_name),
+			_ => continue,
+		};
+		match scope.lookup(&path) {
+			Some(Value::Bool(true)) => ele.set_attribute(&attr_name, None),
+			Some(Value::Bool(false)) | Some(Value::Null) => ele.remove_attribute(&attr_name),
+			Some(value) => ele.set_attribute(&attr_name, Some(&value_to_string(&value))),
+			None => {}
+		}
+	}
+	if let Some(IAttrValue::Value(path, _)) = ele.get_attribute(ATTR_BIND) {
+		if let Some(value) = scope.lookup(&path) {
+			ele.set_text(&value_to_string(&value));
+			return Ok(());
+		}
+	}
+	// the `data-each` removes and inserts the children, so the children are got before rendering
+	for mut child in ele.children().get_ref().iter().map(|child| child.cloned()) {
+		render_element(&mut child, scope)?;
+	}
+	Ok(())
+}
+
+/// pub fn `render`
+/// fill the element and its descendants with the context, `data-each` needs the backend supports `clone_deep`
+pub fn render(ele: &mut BoxDynElement, context: &Context) -> Result<(), Box<dyn Error>> {
+	let scope = Scope {
+		value: &context.data,
+		index: None,
+		parent: None,
+	};
+	render_element(ele, &scope)
+}
@@ -1,9 +1,68 @@
+use std::cell::OnceCell;
 use std::error::Error;
 
 pub fn to_static_str(content: String) -> &'static str {
 	Box::leak(content.into_boxed_str())
 }
 
+/// the kinds of the strings returned by reference from a handle, each one has its own slot
+// some kinds are only used by the backends behind the features
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+pub(crate) enum StrKind {
+	Html,
+	InnerHtml,
+	Text,
+	TagName,
+	LocalName,
+	Namespace,
+	Url,
+}
+
+const STR_KINDS: usize = 7;
+
+// the strings of a slot, only compared with the latest one
+#[derive(Default)]
+struct StrSlot(OnceCell<(Box<str>, Box<StrSlot>)>);
+
+impl StrSlot {
+	fn keep(&self, content: String) -> &str {
+		let mut slot = self;
+		while let Some((kept, next)) = slot.0.get() {
+			if next.0.get().is_none() && **kept == *content {
+				return kept;
+			}
+			slot = next;
+		}
+		let (kept, _) = slot
+			.0
+			.get_or_init(|| (content.into_boxed_str(), Box::default()));
+		kept
+	}
+}
+
+// the strings returned by reference from a handle, kept until the handle is dropped or changed by `&mut self`,
+// a slot only keeps a new string when the value differs from its latest one, the repeated calls don't grow it
+#[derive(Default)]
+pub(crate) struct StrCache([StrSlot; STR_KINDS]);
+
+impl StrCache {
+	pub(crate) fn keep(&self, kind: StrKind, content: String) -> &str {
+		self.0[kind as usize].keep(content)
+	}
+	// the borrows are ended by `&mut self`, free the strings
+	pub(crate) fn clear(&mut self) {
+		*self = StrCache::default();
+	}
+}
+
+// the cloned handle has its own strings
+impl Clone for StrCache {
+	fn clone(&self) -> Self {
+		StrCache::default()
+	}
+}
+
 pub fn vec_char_to_clean_str(v: &mut Vec<char>) -> &'static str {
 	to_static_str(v.drain(..).collect::<String>())
 }
@@ -134,18 +193,206 @@ pub fn retain_by_index<T>(v: &mut Vec<T>, indexs: &[usize]) {
 	}
 }
 
-pub fn get_class_list(v: &str) -> Vec<&str> {
-	let v = v.trim();
-	if v.is_empty() {
-		vec![]
+/// pub fn `split_attr_name`
+/// split the qualified attribute name into the prefix and the local name, `xlink:href` into (Some("xlink"), "href")
+pub fn split_attr_name(name: &str) -> (Option<&str>, &str) {
+	match name.split_once(':') {
+		Some((prefix, local_name)) if !prefix.is_empty() && !local_name.is_empty() => {
+			(Some(prefix), local_name)
+		}
+		_ => (None, name),
+	}
+}
+
+// the parts of an url: scheme, authority, path, query and fragment
+struct UrlParts<'a> {
+	scheme: Option<&'a str>,
+	authority: Option<&'a str>,
+	path: &'a str,
+	query: Option<&'a str>,
+	fragment: Option<&'a str>,
+}
+
+impl<'a> UrlParts<'a> {
+	// https://www.rfc-editor.org/rfc/rfc3986#appendix-B
+	fn parse(url: &'a str) -> Self {
+		let mut rest = url;
+		let mut fragment = None;
+		if let Some((before, after)) = rest.split_once('#') {
+			rest = before;
+			fragment = Some(after);
+		}
+		let mut query = None;
+		if let Some((before, after)) = rest.split_once('?') {
+			rest = before;
+			query = Some(after);
+		}
+		let mut scheme = None;
+		if let Some(index) = rest.find(':') {
+			let name = &rest[..index];
+			let is_scheme = name.starts_with(|ch: char| ch.is_ascii_alphabetic())
+				&& name
+					.chars()
+					.all(|ch| ch.is_ascii_alphanumeric() || ch == '+' || ch == '-' || ch == '.');
+			if is_scheme && !name.contains('/') {
+				scheme = Some(name);
+				rest = &rest[index + 1..];
+			}
+		}
+		let mut authority = None;
+		if let Some(after) = rest.strip_prefix("//") {
+			let end = after.find('/').unwrap_or(after.len());
+			authority = Some(&after[..end]);
+			rest = &after[end..];
+		}
+		UrlParts {
+			scheme,
+			authority,
+			path: rest,
+			query,
+			fragment,
+		}
+	}
+}
+
+// https://www.rfc-editor.org/rfc/rfc3986#section-5.2.4
+fn remove_dot_segments(path: &str) -> String {
+	let segments: Vec<&str> = path.split('/').collect();
+	let total = segments.len();
+	let mut result: Vec<&str> = Vec::with_capacity(total);
+	for (index, segment) in segments.into_iter().enumerate() {
+		let is_last = index == total - 1;
+		match segment {
+			"." | ".." => {
+				// keep the empty segment before the leading slash
+				if segment == ".." && !(result.len() == 1 && result[0].is_empty()) {
+					result.pop();
+				}
+				if is_last {
+					result.push("");
+				}
+			}
+			_ => result.push(segment),
+		}
+	}
+	result.join("/")
+}
+
+/// pub fn `resolve_url`
+/// resolve the reference against the absolute base url, the same as `new URL(reference, base)` without normalization,
+/// return `None` if the reference is relative and the base is not absolute
+/// https://www.rfc-editor.org/rfc/rfc3986#section-5.2.2
+pub fn resolve_url(base: &str, reference: &str) -> Option<String> {
+	// the tabs and newlines are removed in the urls
+	let reference: String = reference
+		.trim()
+		.chars()
+		.filter(|ch| !matches!(ch, '\t' | '\n' | '\r'))
+		.collect();
+	let reference = UrlParts::parse(&reference);
+	let base = UrlParts::parse(base.trim());
+	let (scheme, authority, path, query);
+	if let Some(ref_scheme) = reference.scheme {
+		scheme = ref_scheme;
+		authority = reference.authority;
+		path = remove_dot_segments(reference.path);
+		query = reference.query;
 	} else {
-		v.split_ascii_whitespace().collect::<Vec<&str>>()
+		scheme = base.scheme?;
+		if reference.authority.is_some() {
+			authority = reference.authority;
+			path = remove_dot_segments(reference.path);
+			query = reference.query;
+		} else {
+			authority = base.authority;
+			if reference.path.is_empty() {
+				path = String::from(base.path);
+				query = reference.query.or(base.query);
+			} else {
+				if reference.path.starts_with('/') {
+					path = remove_dot_segments(reference.path);
+				} else {
+					// merge the paths
+					let merged = if base.authority.is_some() && base.path.is_empty() {
+						format!("/{}", reference.path)
+					} else {
+						let dir = base
+							.path
+							.rfind('/')
+							.map_or("", |index| &base.path[..=index]);
+						format!("{}{}", dir, reference.path)
+					};
+					path = remove_dot_segments(&merged);
+				}
+				query = reference.query;
+			}
+		}
 	}
+	let mut result = format!("{}:", scheme);
+	if let Some(authority) = authority {
+		result.push_str("//");
+		result.push_str(authority);
+	}
+	result.push_str(&path);
+	if let Some(query) = query {
+		result.push('?');
+		result.push_str(query);
+	}
+	if let Some(fragment) = reference.fragment {
+		result.push('#');
+		result.push_str(fragment);
+	}
+	Some(result)
 }
 
 #[cfg(test)]
 mod test {
-	use super::{divide_isize, RoundType};
+	use super::{divide_isize, resolve_url, RoundType};
+	#[test]
+	fn test_resolve_url() {
+		// https://www.rfc-editor.org/rfc/rfc3986#section-5.4
+		let base = "http://a/b/c/d;p?q";
+		let cases = [
+			("g:h", "g:h"),
+			("g", "http://a/b/c/g"),
+			("./g", "http://a/b/c/g"),
+			("g/", "http://a/b/c/g/"),
+			("/g", "http://a/g"),
+			("//g", "http://g"),
+			("?y", "http://a/b/c/d;p?y"),
+			("g?y", "http://a/b/c/g?y"),
+			("#s", "http://a/b/c/d;p?q#s"),
+			("g#s", "http://a/b/c/g#s"),
+			(";x", "http://a/b/c/;x"),
+			("", "http://a/b/c/d;p?q"),
+			(".", "http://a/b/c/"),
+			("./", "http://a/b/c/"),
+			("..", "http://a/b/"),
+			("../g", "http://a/b/g"),
+			("../..", "http://a/"),
+			("../../../g", "http://a/g"),
+			("/./g", "http://a/g"),
+			("g.", "http://a/b/c/g."),
+			("g/../h", "http://a/b/c/h"),
+			("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+			("g?y/./x", "http://a/b/c/g?y/./x"),
+			(" g\n ", "http://a/b/c/g"),
+		];
+		for (reference, expected) in cases.iter() {
+			assert_eq!(
+				resolve_url(base, reference).as_deref(),
+				Some(*expected),
+				"{}",
+				reference
+			);
+		}
+		assert_eq!(resolve_url("http://a", "b").as_deref(), Some("http://a/b"));
+		assert_eq!(resolve_url("", "b"), None);
+		assert_eq!(
+			resolve_url("", "https://a/b/../c").as_deref(),
+			Some("https://a/c")
+		);
+	}
 	#[test]
 	fn test_divide_isize() {
 		// round
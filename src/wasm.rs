@@ -0,0 +1,511 @@
+use crate::error::Error as IError;
+use crate::interface::{
+	BoxDynElement, BoxDynNode, BoxDynText, DocumentType, Elements, IAttrValue, IDocumentTrait,
+	IElementTrait, IEnumTyped, INodeTrait, INodeType, ITextTrait, IUncareNodeTrait, InsertPosition,
+	MaybeDoc, MaybeElement, Texts,
+};
+use crate::utils::{StrCache, StrKind};
+use std::any::Any;
+use std::cell::{Cell, OnceCell};
+use std::error::Error;
+use wasm_bindgen::{JsCast, JsValue};
+
+// the property of the dom node keeps its uuid, so the handles of the same node get the same uuid
+const UUID_PROPERTY: &str = "__mesdocUuid";
+
+thread_local! {
+	static NEXT_UUID: Cell<u64> = const { Cell::new(1) };
+}
+
+/// a node of the live browser dom
+#[derive(Clone)]
+pub struct Node {
+	node: web_sys::Node,
+	uuid: OnceCell<String>,
+	// the strings returned by reference, freed with the handle
+	strings: StrCache,
+}
+
+/// the live browser document
+#[derive(Clone)]
+pub struct Document {
+	doc: web_sys::Document,
+	strings: StrCache,
+}
+
+impl Document {
+	/// pub fn `new`
+	/// wrap a `web_sys::Document`
+	pub fn new(doc: web_sys::Document) -> Self {
+		Document {
+			doc,
+			strings: StrCache::default(),
+		}
+	}
+	/// pub fn `current`
+	/// the document of the current window
+	pub fn current() -> Option<Self> {
+		web_sys::window()
+			.and_then(|window| window.document())
+			.map(Document::new)
+	}
+	/// pub fn `elements`
+	/// the document node as `Elements`, use `find` to query the live dom
+	pub fn elements<'b>(&self) -> Elements<'b> {
+		let root = Node::new(self.doc.clone().into());
+		Elements::with_node(&(Box::new(root) as BoxDynElement))
+	}
+}
+
+impl Node {
+	/// pub fn `new`
+	/// wrap a `web_sys::Node`
+	pub fn new(node: web_sys::Node) -> Self {
+		Node {
+			node,
+			uuid: OnceCell::new(),
+			strings: StrCache::default(),
+		}
+	}
+	/// pub fn `from_element`
+	/// wrap a `web_sys::Element` as `Elements`
+	pub fn from_element<'b>(ele: web_sys::Element) -> Elements<'b> {
+		let node = Node::new(ele.into());
+		Elements::with_node(&(Box::new(node) as BoxDynElement))
+	}
+	/// pub fn `get_ref`
+	/// get the `web_sys::Node`
+	pub fn get_ref(&self) -> &web_sys::Node {
+		&self.node
+	}
+	// the element, none for the document or character data
+	fn element(&self) -> Option<&web_sys::Element> {
+		self.node.dyn_ref::<web_sys::Element>()
+	}
+	// the document element of a document node
+	fn document_element(&self) -> Option<web_sys::Element> {
+		self
+			.node
+			.dyn_ref::<web_sys::Document>()
+			.and_then(|doc| doc.document_element())
+	}
+	// the html of a document fragment, such as the template contents, serialized by a copy in a holder
+	fn fragment_html(&self) -> Option<String> {
+		self.node.dyn_ref::<web_sys::DocumentFragment>()?;
+		let holder = self.node.owner_document()?.create_element("div").ok()?;
+		let copy = self.node.clone_node_with_deep(true).ok()?;
+		holder.append_child(&copy).ok()?;
+		Some(holder.inner_html())
+	}
+	// the uuid kept in the node's property, a new one is assigned if the node has none
+	fn stable_uuid(&self) -> String {
+		let key = JsValue::from_str(UUID_PROPERTY);
+		if let Some(uuid) = js_sys::Reflect::get(&self.node, &key)
+			.ok()
+			.and_then(|uuid| uuid.as_string())
+		{
+			return uuid;
+		}
+		let uuid = NEXT_UUID.with(|next| {
+			let uuid = next.get();
+			next.set(uuid + 1);
+			format!("wasm-{}", uuid)
+		});
+		// the property can't be set on a frozen node, the uuid is still stable for this handle
+		let _ = js_sys::Reflect::set(&self.node, &key, &JsValue::from_str(&uuid));
+		uuid
+	}
+	// the dom exceptions are reported to the document's error handle
+	fn trigger_dom_error(&self, method: &str, target: &str) {
+		if let Some(doc) = self.owner_document() {
+			doc.trigger_error(Box::new(IError::InvalidTraitMethodCall {
+				method: String::from(method),
+				message: format!("The dom throws an exception with '{}'.", target),
+			}));
+		}
+	}
+}
+
+// get the `web_sys::Node` of an element created by this backend
+fn to_web_node(ele: &BoxDynElement) -> Option<web_sys::Node> {
+	ele
+		.cloned()
+		.to_node()
+		.downcast::<Node>()
+		.ok()
+		.map(|node| node.node)
+}
+
+// get the `web_sys::Node` of a node created by this backend
+fn to_web_child(node: &BoxDynNode) -> Option<web_sys::Node> {
+	node
+		.clone_node()
+		.to_node()
+		.downcast::<Node>()
+		.ok()
+		.map(|node| node.node)
+}
+
+impl IDocumentTrait for Document {
+	fn get_element_by_id<'b>(&self, id: &str) -> Option<BoxDynElement<'b>> {
+		self
+			.doc
+			.get_element_by_id(id)
+			.map(|ele| Box::new(Node::new(ele.into())) as BoxDynElement)
+	}
+	// the serialized live dom, the original source is not kept by the browser
+	fn source_code(&self) -> &str {
+		match self.doc.document_element() {
+			Some(ele) => self.strings.keep(StrKind::Html, ele.outer_html()),
+			None => "",
+		}
+	}
+	fn get_root_node<'b>(&self) -> BoxDynNode<'b> {
+		Box::new(Node::new(self.doc.clone().into()))
+	}
+	fn deep_clone<'b>(&self) -> Result<Box<dyn IDocumentTrait + 'b>, Box<dyn Error>> {
+		let doc = self
+			.doc
+			.clone_node_with_deep(true)
+			.ok()
+			.and_then(|node| node.dyn_into::<web_sys::Document>().ok())
+			.ok_or("The document can't be cloned.")?;
+		Ok(Box::new(Document::new(doc)))
+	}
+}
+
+impl INodeTrait for Node {
+	fn to_node(self: Box<Self>) -> Box<dyn Any> {
+		self
+	}
+	fn clone_node<'b>(&self) -> BoxDynNode<'b> {
+		Box::new(self.clone())
+	}
+	fn typed<'b>(self: Box<Self>) -> IEnumTyped<'b> {
+		match self.node.node_type() {
+			web_sys::Node::ELEMENT_NODE
+			| web_sys::Node::DOCUMENT_NODE
+			| web_sys::Node::DOCUMENT_FRAGMENT_NODE => IEnumTyped::Element(self),
+			web_sys::Node::TEXT_NODE | web_sys::Node::CDATA_SECTION_NODE => IEnumTyped::Text(self),
+			_ => IEnumTyped::UncareNode(self),
+		}
+	}
+	fn node_type(&self) -> INodeType {
+		match self.node.node_type() {
+			web_sys::Node::ELEMENT_NODE => INodeType::Element,
+			web_sys::Node::TEXT_NODE => INodeType::Text,
+			web_sys::Node::CDATA_SECTION_NODE => INodeType::XMLCDATA,
+			web_sys::Node::PROCESSING_INSTRUCTION_NODE => INodeType::ProcessingInstruction,
+			web_sys::Node::COMMENT_NODE => INodeType::Comment,
+			web_sys::Node::DOCUMENT_NODE => INodeType::Document,
+			web_sys::Node::DOCUMENT_TYPE_NODE => INodeType::HTMLDOCTYPE,
+			web_sys::Node::DOCUMENT_FRAGMENT_NODE => INodeType::DocumentFragement,
+			_ => INodeType::Other,
+		}
+	}
+	fn parent<'b>(&self) -> MaybeElement<'b> {
+		self
+			.node
+			.parent_node()
+			.map(|parent| Box::new(Node::new(parent)) as BoxDynElement)
+	}
+	// the dom nodes have no ids, the uuid is assigned when it's first asked and kept in the node
+	fn uuid(&self) -> Option<&str> {
+		Some(self.uuid.get_or_init(|| self.stable_uuid()))
+	}
+	fn owner_document(&self) -> MaybeDoc<'_> {
+		match self.node.dyn_ref::<web_sys::Document>() {
+			Some(doc) => Some(Box::new(Document::new(doc.clone()))),
+			None => self
+				.node
+				.owner_document()
+				.map(|doc| Box::new(Document::new(doc)) as Box<dyn IDocumentTrait>),
+		}
+	}
+	fn doctype(&self) -> Option<DocumentType> {
+		self
+			.node
+			.dyn_ref::<web_sys::DocumentType>()
+			.map(|doctype| DocumentType {
+				name: doctype.name(),
+				public_id: doctype.public_id(),
+				system_id: doctype.system_id(),
+			})
+	}
+	fn pi_target(&self) -> Option<String> {
+		self
+			.node
+			.dyn_ref::<web_sys::ProcessingInstruction>()
+			.map(|pi| pi.target())
+	}
+	fn text_content(&self) -> &str {
+		let content = match self.document_element() {
+			Some(ele) => ele.text_content(),
+			None => self.node.text_content(),
+		};
+		self
+			.strings
+			.keep(StrKind::Text, content.unwrap_or_default())
+	}
+	fn set_text(&mut self, content: &str) {
+		match self.document_element() {
+			Some(ele) => ele.set_text_content(Some(content)),
+			None => self.node.set_text_content(Some(content)),
+		}
+	}
+	fn set_html(&mut self, content: &str) {
+		if let Some(ele) = self.element() {
+			ele.set_inner_html(content);
+		} else if let Some(ele) = self.document_element() {
+			ele.set_inner_html(content);
+		} else {
+			self.node.set_text_content(Some(content));
+		}
+	}
+	fn index(&self) -> usize {
+		if let Some(parent) = self.node.parent_node() {
+			let child_nodes = parent.child_nodes();
+			for index in 0..child_nodes.length() {
+				if let Some(child) = child_nodes.item(index) {
+					if child.is_same_node(Some(&self.node)) {
+						return index as usize;
+					}
+				}
+			}
+		}
+		0
+	}
+}
+
+impl IElementTrait for Node {
+	fn is(&self, ele: &BoxDynElement) -> bool {
+		match to_web_node(ele) {
+			Some(node) => self.node.is_same_node(Some(&node)),
+			None => false,
+		}
+	}
+	// the html tag names are uppercase, the same as the other backends
+	fn tag_name(&self) -> &str {
+		match self.element() {
+			Some(ele) => self.strings.keep(StrKind::TagName, ele.tag_name()),
+			None => "",
+		}
+	}
+	fn local_name(&self) -> &str {
+		match self.element() {
+			Some(ele) => self.strings.keep(StrKind::LocalName, ele.local_name()),
+			None => "",
+		}
+	}
+	fn namespace_uri(&self) -> Option<&str> {
+		let namespace = self.element()?.namespace_uri()?;
+		Some(self.strings.keep(StrKind::Namespace, namespace))
+	}
+	fn child_nodes_length(&self) -> usize {
+		self.node.child_nodes().length() as usize
+	}
+	fn child_nodes_item<'b>(&self, index: usize) -> Option<BoxDynNode<'b>> {
+		self
+			.node
+			.child_nodes()
+			.item(index as u32)
+			.map(|node| Box::new(Node::new(node)) as BoxDynNode)
+	}
+	// the live dom can't tell `attr` from `attr=""`, the attributes always have values
+	fn get_attribute(&self, name: &str) -> Option<IAttrValue> {
+		self
+			.element()
+			.and_then(|ele| ele.get_attribute(name))
+			.map(|value| IAttrValue::Value(value, Some('"')))
+	}
+	fn set_attribute(&mut self, name: &str, value: Option<&str>) {
+		if let Some(ele) = self.element() {
+			let result = ele.set_attribute(name, value.unwrap_or(""));
+			if result.is_err() {
+				self.trigger_dom_error("set_attribute", name);
+			}
+		}
+	}
+	fn remove_attribute(&mut self, name: &str) {
+		if let Some(ele) = self.element() {
+			let result = ele.remove_attribute(name);
+			if result.is_err() {
+				self.trigger_dom_error("remove_attribute", name);
+			}
+		}
+	}
+	fn has_attribute(&self, name: &str) -> bool {
+		match self.element() {
+			Some(ele) => ele.has_attribute(name),
+			None => false,
+		}
+	}
+	fn inner_html(&self) -> &str {
+		match self.element() {
+			Some(ele) => self.strings.keep(StrKind::InnerHtml, ele.inner_html()),
+			None => match self.document_element() {
+				Some(ele) => self.strings.keep(StrKind::InnerHtml, ele.outer_html()),
+				None => match self.fragment_html() {
+					Some(html) => self.strings.keep(StrKind::InnerHtml, html),
+					None => "",
+				},
+			},
+		}
+	}
+	fn outer_html(&self) -> &str {
+		match self.element() {
+			Some(ele) => self.strings.keep(StrKind::Html, ele.outer_html()),
+			None => self.inner_html(),
+		}
+	}
+	fn insert_adjacent(&mut self, position: &InsertPosition, ele: &BoxDynElement) {
+		let node = match to_web_node(ele) {
+			Some(node) => node,
+			None => return,
+		};
+		let result = match position {
+			InsertPosition::BeforeBegin => match self.node.parent_node() {
+				Some(parent) => parent.insert_before(&node, Some(&self.node)),
+				None => return,
+			},
+			InsertPosition::AfterBegin => self
+				.node
+				.insert_before(&node, self.node.first_child().as_ref()),
+			InsertPosition::BeforeEnd => self.node.append_child(&node),
+			InsertPosition::AfterEnd => match self.node.parent_node() {
+				Some(parent) => parent.insert_before(&node, self.node.next_sibling().as_ref()),
+				None => return,
+			},
+		};
+		if result.is_err() {
+			self.trigger_dom_error("insert_adjacent", position.action());
+		}
+	}
+	fn template_content<'b>(&self) -> Option<BoxDynElement<'b>> {
+		let template = self.node.dyn_ref::<web_sys::HtmlTemplateElement>()?;
+		Some(Box::new(Node::new(template.content().into())))
+	}
+	fn clone_deep<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		match self.node.clone_node_with_deep(true) {
+			Ok(node) => Ok(Box::new(Node::new(node))),
+			Err(_) => Err(Box::new(IError::InvalidTraitMethodCall {
+				method: "clone_deep".into(),
+				message: "The node can't be cloned.".into(),
+			})),
+		}
+	}
+	fn clone_shallow<'b>(&self) -> Result<BoxDynElement<'b>, Box<dyn Error>> {
+		match self.node.clone_node_with_deep(false) {
+			Ok(node) => Ok(Box::new(Node::new(node))),
+			Err(_) => Err(Box::new(IError::InvalidTraitMethodCall {
+				method: "clone_shallow".into(),
+				message: "The node can't be cloned.".into(),
+			})),
+		}
+	}
+	fn remove_child(&mut self, ele: BoxDynElement) {
+		if let Some(node) = to_web_node(&ele) {
+			if self.node.remove_child(&node).is_err() {
+				self.trigger_dom_error("remove_child", ele.tag_name());
+			}
+		}
+	}
+	fn insert_child(&mut self, index: usize, node: &BoxDynNode) -> Result<(), Box<dyn Error>> {
+		let node = to_web_child(node).ok_or("The node is not a browser node.")?;
+		let child = self.node.child_nodes().item(index as u32);
+		self
+			.node
+			.insert_before(&node, child.as_ref())
+			.map_err(|_| "The node can't be inserted.")?;
+		Ok(())
+	}
+	fn texts<'b>(&self, limit_depth: u32) -> Option<Texts<'b>> {
+		let mut result = Texts::with_capacity(5);
+		// `limit_depth` 0 means no limit, 1 means only the child text nodes
+		fn loop_handle(node: &web_sys::Node, depth: u32, limit_depth: u32, result: &mut Texts) {
+			let child_nodes = node.child_nodes();
+			for index in 0..child_nodes.length() {
+				if let Some(child) = child_nodes.item(index) {
+					match child.node_type() {
+						web_sys::Node::TEXT_NODE | web_sys::Node::CDATA_SECTION_NODE => {
+							result
+								.get_mut_ref()
+								.push(Box::new(Node::new(child)) as BoxDynText);
+						}
+						web_sys::Node::ELEMENT_NODE if limit_depth == 0 || depth < limit_depth => {
+							loop_handle(&child, depth + 1, limit_depth, result);
+						}
+						_ => {}
+					}
+				}
+			}
+		}
+		loop_handle(&self.node, 1, limit_depth, &mut result);
+		Some(result)
+	}
+	fn into_text<'b>(self: Box<Self>) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		Ok(self)
+	}
+}
+
+impl ITextTrait for Node {
+	fn remove(self: Box<Self>) {
+		if let Some(parent) = self.node.parent_node() {
+			// the text node is removed from the parent node, ignore the exception
+			let _ = parent.remove_child(&self.node);
+		}
+	}
+	fn append_text(&mut self, content: &str) {
+		let text = self.node.text_content().unwrap_or_default();
+		self
+			.node
+			.set_text_content(Some(&format!("{}{}", text, content)));
+	}
+	fn prepend_text(&mut self, content: &str) {
+		let text = self.node.text_content().unwrap_or_default();
+		self
+			.node
+			.set_text_content(Some(&format!("{}{}", content, text)));
+	}
+	fn split_text<'b>(&mut self, offset: usize) -> Result<BoxDynText<'b>, Box<dyn Error>> {
+		let content = self.node.text_content().unwrap_or_default();
+		if !content.is_char_boundary(offset) {
+			return Err(format!("The offset {} is not a char boundary.", offset).into());
+		}
+		// the dom counts the offset in utf-16 code units
+		let offset = content[..offset].encode_utf16().count() as u32;
+		let text = self
+			.node
+			.dyn_ref::<web_sys::Text>()
+			.ok_or("The node is not a text node.")?
+			.split_text(offset)
+			.map_err(|_| "The text can't be split.")?;
+		Ok(Box::new(Node::new(text.into())))
+	}
+	fn wrap(&mut self, wrapper: &BoxDynElement) -> Result<(), Box<dyn Error>> {
+		let wrapper = to_web_node(wrapper).ok_or("The wrapper is not a browser node.")?;
+		if let Some(parent) = self.node.parent_node() {
+			parent
+				.insert_before(&wrapper, Some(&self.node))
+				.map_err(|_| "The wrapper can't be inserted.")?;
+		}
+		wrapper
+			.append_child(&self.node)
+			.map_err(|_| "The text can't be moved into the wrapper.")?;
+		Ok(())
+	}
+}
+
+impl IUncareNodeTrait for Node {
+	fn remove(self: Box<Self>) -> Result<(), Box<dyn Error>> {
+		if let Some(parent) = self.node.parent_node() {
+			parent
+				.remove_child(&self.node)
+				.map_err(|_| IError::InvalidTraitMethodCall {
+					method: "remove".into(),
+					message: "The node can't be removed from the parent.".into(),
+				})?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,99 @@
+mod common;
+use mesdoc::assert::{check_attr, check_selector, check_text, Expected};
+use mesdoc::interface::{Elements, IDocumentTrait};
+use mesdoc::{assert_attr, assert_selector, assert_text};
+
+const HTML: &str = concat!(
+	"<html><body><h1 class=\"title main\">Welcome home</h1>",
+	"<ul><li><a href=\"/a\">a</a></li><li><a href=\"/b\" hidden>b</a></li></ul></body></html>"
+);
+
+#[test]
+fn test_assert_macros() {
+	mesdoc::init();
+	let doc = common::parse(HTML);
+	assert_selector!(doc, "ul > li");
+	assert_selector!(doc, "ul > li", count = 2);
+	assert_selector!(doc, "ol", count = 0);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let title = root.find("h1").get(0).unwrap().cloned();
+	assert_text!(title, "Welcome home");
+	assert_text!(title, contains "Welcome");
+	assert_attr!(title, "class");
+	assert_attr!(title, "class", "title main");
+	assert_attr!(title, "class", contains "main");
+	// the elements
+	let links = root.find("a");
+	assert_text!(links, "ab");
+	assert_attr!(links, "href", contains "/");
+	assert_selector!(root.find("ul"), "a[hidden]", count = 1);
+}
+
+#[test]
+fn test_assert_messages() {
+	mesdoc::init();
+	let doc = common::parse(HTML);
+	assert_eq!(
+		check_selector(&doc, "ul > li", Some(3)).unwrap_err(),
+		concat!(
+			"The selector 'ul > li' matched 2 elements\n- 3 elements\n+ 2 elements\n",
+			"  <li><a href=\"/a\">a</a></li>\n  <li><a href=\"/b\" hidden>b</a></li>"
+		)
+	);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let title = root.find("h1");
+	assert_eq!(
+		check_text(&title, Expected::Contains("Hello")).unwrap_err(),
+		concat!(
+			"The text is not matched\n- contains \"Hello\"\n+ \"Welcome home\"\n",
+			"  <h1 class=\"title main\">Welcome home</h1>"
+		)
+	);
+	// the first element not matched is shown
+	assert_eq!(
+		check_attr(&root.find("a"), "hidden", None).unwrap_err(),
+		"The attribute 'hidden' is not matched\n- has the attribute\n+ no attribute\n  <a href=\"/a\">a</a>"
+	);
+	assert!(check_attr(&Elements::new(), "href", None).is_err());
+}
+
+#[test]
+#[should_panic(expected = "The selector 'ol' matched 0 elements")]
+fn test_assert_selector_panic() {
+	mesdoc::init();
+	let doc = common::parse(HTML);
+	assert_selector!(doc, "ol");
+}
+
+#[test]
+fn test_assert_snapshot() {
+	use mesdoc::assert::check_snapshot;
+	use mesdoc::assert_snapshot;
+	mesdoc::init();
+	let dir = std::env::temp_dir().join(format!("mesdoc-snapshots-{}", std::process::id()));
+	let path = dir.join("list.html");
+	let doc = common::parse(HTML);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let list = root.find("ul");
+	// the missing snapshot is written
+	assert_snapshot!(list, &path);
+	assert_eq!(
+		std::fs::read_to_string(&path).unwrap(),
+		"<ul>\n  <li><a href=\"/a\">a</a></li>\n  <li><a hidden href=\"/b\">b</a></li>\n</ul>\n"
+	);
+	assert_snapshot!(list, &path);
+	// the changed html
+	let changed = common::parse(&HTML.replace("/b", "/c"));
+	let changed_root = Elements::with_nodes(vec![changed
+		.get_root_node()
+		.typed()
+		.into_element()
+		.unwrap()]);
+	let message = check_snapshot(&changed_root.find("ul"), &path, Default::default()).unwrap_err();
+	assert!(message.contains("list.html' is not matched"));
+	assert!(message.ends_with(concat!(
+		"  <ul>\n    <li><a href=\"/a\">a</a></li>\n",
+		"-   <li><a hidden href=\"/b\">b</a></li>\n+   <li><a hidden href=\"/c\">b</a></li>\n  </ul>"
+	)));
+	std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,139 @@
+mod common;
+use mesdoc::interface::{
+	Elements, IDocumentTrait, InlineStyle, Srcset, SrcsetCandidate, SrcsetDescriptor,
+};
+
+fn candidate(url: &str, descriptor: SrcsetDescriptor) -> SrcsetCandidate {
+	SrcsetCandidate {
+		url: String::from(url),
+		descriptor,
+	}
+}
+
+#[test]
+fn test_parse_srcset() {
+	let srcset: Srcset = concat!(
+		" a.png, b.png 2x,c.png 480w 320h , d.png,, e.png 1x 2x, f.png 0w, g.png 3h, ",
+		"data:image/png;base64,AAA= 1.5x"
+	)
+	.parse()
+	.unwrap();
+	assert_eq!(
+		srcset.candidates,
+		vec![
+			candidate("a.png", SrcsetDescriptor::None),
+			candidate("b.png", SrcsetDescriptor::Density(2.0)),
+			candidate("c.png", SrcsetDescriptor::Width(480)),
+			candidate("d.png", SrcsetDescriptor::None),
+			candidate("data:image/png;base64,AAA=", SrcsetDescriptor::Density(1.5)),
+		]
+	);
+	// the descriptors with parentheses
+	let srcset: Srcset = "a.png 1x (x, y), b.png 2x".parse().unwrap();
+	assert_eq!(
+		srcset.candidates,
+		vec![candidate("b.png", SrcsetDescriptor::Density(2.0))]
+	);
+	assert!("".parse::<Srcset>().unwrap().candidates.is_empty());
+}
+
+#[test]
+fn test_parsed_attribute() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><img srcset=\"a.png 1x, b.png 2x\" ",
+		"style=\"color: red !important; COLOR: blue; display : none; display: block\" hidden>",
+		"</body></html>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let img = root.find("img").get(0).unwrap().cloned();
+	let srcset = img.parsed_attribute::<Srcset>("srcset").unwrap();
+	assert_eq!(srcset.candidates.len(), 2);
+	assert_eq!(srcset.candidates[1].url, "b.png");
+	let style = img.parsed_attribute::<InlineStyle>("style").unwrap();
+	assert_eq!(style.declarations.len(), 4);
+	assert_eq!(style.get("color"), Some("red"));
+	assert_eq!(style.get("display"), Some("block"));
+	assert_eq!(style.get("margin"), None);
+	// the attribute without value is parsed from an empty string
+	assert!(img.parsed_attribute::<Srcset>("hidden").is_some());
+	assert!(img.parsed_attribute::<u32>("hidden").is_none());
+	assert!(img.parsed_attribute::<Srcset>("sizes").is_none());
+}
+
+#[test]
+fn test_attribute_shorthands() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div><img class=\"lazy\" data-src=\"a.png\" alt=\"a\">",
+		"<img class=\"lazy\" data-src=\"b.png\" src=\"loading.gif\">",
+		"<img class=\"lazy\" src=\"c.png\"></div><p id=\"from\" title=\"t\" lang=\"en\" hidden></p>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let mut images = root.find("img.lazy");
+	// the existing `src` is overwritten, the image without `data-src` is kept
+	images
+		.rename_attr("data-src", "src")
+		.toggle_attr("hidden", true);
+	let srcs: Vec<String> = images
+		.get_ref()
+		.iter()
+		.map(|ele| ele.get_attribute("src").unwrap().to_string())
+		.collect();
+	assert_eq!(srcs, vec!["a.png", "b.png", "c.png"]);
+	assert_eq!(root.find("[data-src]").length(), 0);
+	assert_eq!(root.find("img[hidden]").length(), 3);
+	let mut first = images.get(0).unwrap().cloned();
+	assert!(!first.rename_attribute("data-src", "src"));
+	assert!(!first.toggle_attribute("hidden", false));
+	assert!(!first.has_attribute("hidden"));
+	assert!(first.toggle_attribute("hidden", true));
+	// keep the value of the existing attribute
+	first.set_attribute("hidden", Some("until-found"));
+	first.toggle_attribute("hidden", true);
+	assert!(first.get_attribute("hidden").unwrap().is_str("until-found"));
+	// copy the attributes
+	let from = root.find("#from").get(0).unwrap().cloned();
+	images.copy_attr_from(&from, &["title", "lang", "missing"]);
+	assert_eq!(root.find("img[title=t][lang=en]").length(), 3);
+	let mut div = root.find("div").get(0).unwrap().cloned();
+	div.copy_attributes_from(&from, &[]);
+	assert_eq!(div.attribute_names(), vec!["id", "title", "lang", "hidden"]);
+}
+
+#[test]
+fn test_update_attr() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<ul><li><a href=\"/a\">a</a></li><li><a href=\"https://b.com/b\">b</a></li>",
+		"<li><a>c</a></li><li><a href=\"javascript:void(0)\">d</a></li></ul>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let mut calls = Vec::new();
+	root.find("a").update_attr("href", |old| {
+		calls.push(old.map(String::from));
+		match old {
+			Some(href) if href.starts_with("javascript:") => None,
+			Some(href) if href.starts_with('/') => Some(format!("https://site.com{}", href)),
+			Some(href) => Some(String::from(href)),
+			None => Some(String::from("#")),
+		}
+	});
+	assert_eq!(calls.len(), 4);
+	assert_eq!(calls[2], None);
+	let hrefs: Vec<Option<String>> = root
+		.find("a")
+		.get_ref()
+		.iter()
+		.map(|ele| ele.get_attribute("href").map(|value| value.to_string()))
+		.collect();
+	assert_eq!(
+		hrefs,
+		vec![
+			Some(String::from("https://site.com/a")),
+			Some(String::from("https://b.com/b")),
+			Some(String::from("#")),
+			None
+		]
+	);
+}
@@ -0,0 +1,42 @@
+mod common;
+use mesdoc::batch::{process, Source};
+use mesdoc::error::Error;
+use mesdoc::interface::{Elements, IDocumentTrait};
+
+#[test]
+fn test_batch_process() {
+	let path = std::env::temp_dir().join("mesdoc_batch_test.html");
+	std::fs::write(&path, "<ul><li>a</li><li>b</li></ul>").unwrap();
+	let mut sources = vec![Source::Path(path.clone())];
+	for count in 0..20 {
+		sources.push(Source::Html("<li></li>".repeat(count)));
+	}
+	sources.push(Source::Path(path.with_extension("missing")));
+	sources.push(Source::Html(String::from("<p>error</p>")));
+	let results = process(&sources, common::parse, |doc| {
+		let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+		if !root.find("p").is_empty() {
+			return Err("The paragraph is not allowed.".into());
+		}
+		Ok(root.find("li").length())
+	});
+	std::fs::remove_file(&path).unwrap();
+	assert_eq!(results.len(), sources.len());
+	// the results keep the order of the sources
+	assert_eq!(*results[0].as_ref().unwrap(), 2);
+	for count in 0..20 {
+		assert_eq!(*results[count + 1].as_ref().unwrap(), count);
+	}
+	// the errors are collected per document
+	assert!(matches!(
+		results[21],
+		Err(Error::BatchFailed { index: 21, .. })
+	));
+	match &results[22] {
+		Err(Error::BatchFailed { index, message }) => {
+			assert_eq!(*index, 22);
+			assert_eq!(message, "The paragraph is not allowed.");
+		}
+		_ => panic!("expect a batch error"),
+	}
+}
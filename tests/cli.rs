@@ -0,0 +1,61 @@
+#![cfg(feature = "arena")]
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const HTML: &str = "<html><head><title>T</title></head><body><ul><li class=\"a\"><a href=\"/x\">One \"1\"</a></li><li><a href=\"/y\" hidden>Two</a></li></ul></body></html>";
+
+// run the cli with the html from stdin, return the exit code and the stdout
+fn gotdom(args: &[&str], html: &str) -> (i32, String) {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_gotdom"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::null())
+		.spawn()
+		.unwrap();
+	// the cli may exit before reading the stdin
+	let _ = child.stdin.take().unwrap().write_all(html.as_bytes());
+	let output = child.wait_with_output().unwrap();
+	(
+		output.status.code().unwrap_or(-1),
+		String::from_utf8(output.stdout).unwrap(),
+	)
+}
+
+#[test]
+fn test_cli() {
+	assert_eq!(
+		gotdom(&["a"], HTML),
+		(
+			0,
+			String::from("<a href=\"/x\">One \"1\"</a>\n<a href=\"/y\" hidden=\"\">Two</a>\n")
+		)
+	);
+	// the selectors are applied in turn
+	assert_eq!(gotdom(&["li.a", "a", "--text"], HTML).1, "One \"1\"\n");
+	assert_eq!(gotdom(&["a", "-o", "text", "-1"], HTML).1, "One \"1\"\n");
+	assert_eq!(gotdom(&["a", "-a", "href"], HTML).1, "/x\n/y\n");
+	// the elements without the attribute are skipped
+	assert_eq!(gotdom(&["a", "-a", "hidden"], HTML).1, "\n");
+	assert_eq!(gotdom(&["p"], HTML), (0, String::new()));
+	assert_eq!(
+		gotdom(&["ul", "--pretty"], HTML).1,
+		"<ul>\n  <li class=\"a\"><a href=\"/x\">One \"1\"</a></li>\n  <li><a href=\"/y\" hidden=\"\">Two</a></li>\n</ul>\n"
+	);
+	assert_eq!(
+		gotdom(&["a", "-o", "json", "-a", "href"], HTML).1,
+		"[\"/x\",\"/y\"]\n"
+	);
+	assert_eq!(
+		gotdom(&["a", "-o", "json", "-1"], HTML).1,
+		"[{\"tag\":\"a\",\"attributes\":{\"href\":\"/x\"},\"text\":\"One \\\"1\\\"\",\"html\":\"<a href=\\\"/x\\\">One \\\"1\\\"</a>\"}]\n"
+	);
+	assert_eq!(
+		gotdom(&["title", "-o", "json", "-p"], HTML).1,
+		"[\n  {\n    \"tag\": \"title\",\n    \"attributes\": {},\n    \"text\": \"T\",\n    \"html\": \"<title>T</title>\"\n  }\n]\n"
+	);
+	// the unknown options and formats fail
+	assert_eq!(gotdom(&["--bogus"], HTML).0, 2);
+	assert_eq!(gotdom(&["-o", "xml"], HTML).0, 2);
+	assert_eq!(gotdom(&["-f", "/no/such/file.html"], "").0, 1);
+}
@@ -0,0 +1,119 @@
+// a tiny html parser for the arena backend, only for testing the adapter kit
+use mesdoc::adapter::{AttrMap, NodeId};
+pub use mesdoc::dom::Document;
+use mesdoc::dom::{NodeKind, Tree};
+use mesdoc::html::spec::{is_text_only_element, is_void_element};
+
+pub fn parse(html: &str) -> Document {
+	let mut tree = Tree::new(parse_into);
+	let root = tree.root();
+	parse_into(&mut tree, root, html);
+	Document::from(tree)
+}
+
+// parse the html nodes into the parent, only enough for the test fixtures
+fn parse_into(tree: &mut Tree, parent: NodeId, html: &str) {
+	let mut stack = vec![parent];
+	let mut rest = html;
+	while !rest.is_empty() {
+		// the child nodes of the template are its contents
+		let cur = *stack.last().unwrap();
+		let cur = tree.template_content(cur).unwrap_or(cur);
+		if let Some(comment) = rest.strip_prefix("<!--") {
+			let end = comment.find("-->").unwrap_or(comment.len());
+			let id = tree.create(NodeKind::Comment, &comment[..end]);
+			tree.append(cur, id).unwrap();
+			rest = comment.get(end + 3..).unwrap_or("");
+		} else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+			let end = cdata.find("]]>").unwrap_or(cdata.len());
+			let id = tree.create(NodeKind::CData, &cdata[..end]);
+			tree.append(cur, id).unwrap();
+			rest = cdata.get(end + 3..).unwrap_or("");
+		} else if let Some(pi) = rest.strip_prefix("<?") {
+			let end = pi.find("?>").unwrap_or(pi.len());
+			let (target, data) = pi[..end].split_once(' ').unwrap_or((&pi[..end], ""));
+			let id = tree.create(
+				NodeKind::ProcessingInstruction(String::from(target)),
+				data.trim(),
+			);
+			tree.append(cur, id).unwrap();
+			rest = pi.get(end + 2..).unwrap_or("");
+		} else if let Some(doctype) = rest.strip_prefix("<!") {
+			let end = doctype.find('>').unwrap_or(doctype.len());
+			let id = tree.create(NodeKind::Doctype, &doctype[..end]);
+			tree.append(cur, id).unwrap();
+			rest = doctype.get(end + 1..).unwrap_or("");
+		} else if let Some(close) = rest.strip_prefix("</") {
+			let end = close.find('>').unwrap_or(close.len());
+			let name = close[..end].trim().to_ascii_lowercase();
+			if let Some(pos) = stack
+				.iter()
+				.rposition(|id| *tree.kind(*id) == NodeKind::Element(name.clone()))
+			{
+				stack.truncate(pos.max(1));
+			}
+			rest = close.get(end + 1..).unwrap_or("");
+		} else if rest.starts_with('<') && rest[1..].starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+			let end = rest.find('>').unwrap_or(rest.len());
+			let (name, attrs) = parse_tag(&rest[1..end]);
+			rest = rest.get(end + 1..).unwrap_or("");
+			if name == "pre" || name == "textarea" {
+				rest = rest.strip_prefix('\n').unwrap_or(rest);
+			}
+			let id = tree.create(NodeKind::Element(name.clone()), "");
+			*tree.attrs_mut(id) = attrs;
+			tree.append(cur, id).unwrap();
+			if is_text_only_element(&name) {
+				let close = format!("</{}", name);
+				let end = rest.find(&close).unwrap_or(rest.len());
+				if end > 0 {
+					let text = tree.create(NodeKind::Text, &rest[..end]);
+					tree.append(id, text).unwrap();
+				}
+				rest = &rest[end..];
+			} else if !is_void_element(&name) {
+				if name == "template" {
+					tree.ensure_template_content(id);
+				}
+				stack.push(id);
+			}
+		} else {
+			let end = rest[1..].find('<').map(|end| end + 1).unwrap_or(rest.len());
+			let content = rest[..end]
+				.replace("&lt;", "<")
+				.replace("&gt;", ">")
+				.replace("&amp;", "&");
+			let id = tree.create(NodeKind::Text, &content);
+			tree.append(cur, id).unwrap();
+			rest = &rest[end..];
+		}
+	}
+}
+// parse the tag name and the attributes, the values must be quoted
+fn parse_tag(content: &str) -> (String, AttrMap) {
+	let content = content.trim_end_matches('/');
+	let name_end = content.find(char::is_whitespace).unwrap_or(content.len());
+	let mut attrs = Vec::new();
+	let mut rest = content[name_end..].trim_start();
+	while !rest.is_empty() {
+		let key_end = rest
+			.find(|ch: char| ch == '=' || ch.is_whitespace())
+			.unwrap_or(rest.len());
+		let key = rest[..key_end].to_ascii_lowercase();
+		rest = rest[key_end..].trim_start();
+		if let Some(value) = rest.strip_prefix('=') {
+			let value = value.trim_start();
+			let quote = value.chars().next().unwrap();
+			let end = value[1..].find(quote).unwrap() + 1;
+			let decoded = value[1..end].replace("&quot;", "\"").replace("&amp;", "&");
+			attrs.push((key, Some(decoded)));
+			rest = value[end + 1..].trim_start();
+		} else {
+			attrs.push((key, None));
+		}
+	}
+	(
+		content[..name_end].to_ascii_lowercase(),
+		attrs.into_iter().collect(),
+	)
+}
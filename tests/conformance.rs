@@ -0,0 +1,46 @@
+mod common;
+mesdoc::conformance_tests!(common::parse);
+
+// the arena backend keeps the source order of the attributes
+#[test]
+fn test_attribute_order() {
+	use mesdoc::interface::{Elements, IDocumentTrait};
+	mesdoc::init();
+	let doc =
+		common::parse("<html><body><a title=\"t\" href=\"/\" class=\"c\" hidden>a</a></body></html>");
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let link = Elements::with_nodes(vec![root])
+		.find("a")
+		.get(0)
+		.unwrap()
+		.cloned();
+	assert_eq!(
+		link.attribute_names(),
+		vec!["title", "href", "class", "hidden"]
+	);
+}
+
+// the relative `<base href>` is resolved against the document url
+#[test]
+fn test_relative_base_url() {
+	use mesdoc::interface::{Elements, IDocumentTrait};
+	mesdoc::init();
+	let mut doc = common::parse(
+		"<html><head><base href=\"sub/\"></head><body><a href=\"a.html\">a</a></body></html>",
+	);
+	doc.set_url("https://example.com/b/c.html").unwrap();
+	assert_eq!(
+		doc.base_url().as_deref(),
+		Some("https://example.com/b/sub/")
+	);
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let link = Elements::with_nodes(vec![root])
+		.find("a")
+		.get(0)
+		.unwrap()
+		.cloned();
+	assert_eq!(
+		link.absolute_attr("href").as_deref(),
+		Some("https://example.com/b/sub/a.html")
+	);
+}
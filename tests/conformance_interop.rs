@@ -0,0 +1,31 @@
+#[cfg(feature = "rcdom")]
+mod rcdom {
+	use html5ever::tendril::TendrilSink;
+	use markup5ever_rcdom::RcDom;
+	use mesdoc::interop::rcdom::Document;
+	fn parse(html: &str) -> Document {
+		let dom = html5ever::parse_document(RcDom::default(), Default::default()).one(html);
+		Document::from(dom)
+	}
+	mesdoc::conformance_tests!(parse);
+}
+
+#[cfg(feature = "kuchiki")]
+mod kuchiki {
+	use kuchiki::traits::TendrilSink;
+	use mesdoc::interop::kuchiki::Document;
+	fn parse(html: &str) -> Document {
+		Document::from(kuchiki::parse_html().one(html))
+	}
+	// kuchiki stores the attributes in a map sorted by the names
+	mesdoc::conformance_tests!(parse, unordered_attributes);
+}
+
+#[cfg(feature = "arena")]
+mod arena {
+	use mesdoc::dom::Document;
+	fn parse(html: &str) -> Document {
+		mesdoc::dom::parse(html)
+	}
+	mesdoc::conformance_tests!(parse);
+}
@@ -0,0 +1,219 @@
+mod common;
+use mesdoc::css::{
+	matches_media, parse_declarations, scope_attr, scope_css, scope_selector, Declaration,
+	Specificity, Stylesheet, Viewport,
+};
+use mesdoc::interface::{Elements, IDocumentTrait};
+
+#[test]
+fn test_parse_stylesheet() {
+	mesdoc::init();
+	let stylesheet = Stylesheet::parse(concat!(
+		"@charset \"utf-8\";\n/* comment { } */\n",
+		"p, .a { color: red; margin : 0 !IMPORTANT; }\n",
+		"@media (max-width: 600px) { p { color: blue } }\n",
+		"a[title=\"}\"] { content: \"a;b\"; --Main-Color: #fff }\n",
+		"div { }"
+	));
+	let rules: Vec<(&str, usize)> = stylesheet
+		.rules()
+		.iter()
+		.map(|rule| (rule.selector.as_str(), rule.declarations.len()))
+		.collect();
+	assert_eq!(rules, vec![("p, .a", 2), ("a[title=\"}\"]", 2), ("div", 0)]);
+	assert_eq!(
+		stylesheet.rules()[1].declarations[1],
+		Declaration {
+			name: String::from("--Main-Color"),
+			value: String::from("#fff"),
+			important: false
+		}
+	);
+	let declarations =
+		parse_declarations("COLOR: Red; ; invalid; background: url(a;b.png) !important");
+	let declarations: Vec<(&str, &str, bool)> = declarations
+		.iter()
+		.map(|decl| (decl.name.as_str(), decl.value.as_str(), decl.important))
+		.collect();
+	assert_eq!(
+		declarations,
+		vec![
+			("color", "Red", false),
+			("background", "url(a;b.png)", true)
+		]
+	);
+}
+
+#[test]
+fn test_specificity() {
+	let cases = [
+		("*", Specificity(0, 0, 0)),
+		("li", Specificity(0, 0, 1)),
+		("ul li::before", Specificity(0, 0, 3)),
+		("ul > li.item:first-child", Specificity(0, 2, 2)),
+		("#nav a[href]:hover", Specificity(1, 2, 1)),
+		(":not(#a, .b) p", Specificity(1, 0, 1)),
+		(":where(#a) p", Specificity(0, 0, 1)),
+	];
+	for (selector, specificity) in cases {
+		assert_eq!(Specificity::of(selector), specificity, "{}", selector);
+	}
+}
+
+#[test]
+fn test_matched_css_rules() {
+	mesdoc::init();
+	let doc = common::parse(
+		"<html><body><div id=\"main\"><p class=\"note\">a</p><p>b</p></div></body></html>",
+	);
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	let stylesheet = Stylesheet::parse(concat!(
+		"#main .note { color: red }\n",
+		"p { color: blue }\n",
+		"p:hover { color: green }\n",
+		"div p, .note { color: gray }\n",
+		"p { margin: 0 }"
+	));
+	let matched: Vec<Vec<(usize, Specificity)>> = root
+		.find("p")
+		.matched_css_rules(&stylesheet)
+		.iter()
+		.map(|rules| {
+			rules
+				.iter()
+				.map(|matched| (matched.index, matched.specificity))
+				.collect()
+		})
+		.collect();
+	assert_eq!(
+		matched,
+		vec![
+			vec![
+				(1, Specificity(0, 0, 1)),
+				(4, Specificity(0, 0, 1)),
+				(3, Specificity(0, 1, 0)),
+				(0, Specificity(1, 1, 0))
+			],
+			vec![
+				(1, Specificity(0, 0, 1)),
+				(4, Specificity(0, 0, 1)),
+				(3, Specificity(0, 0, 2))
+			]
+		]
+	);
+}
+
+#[test]
+fn test_scope_styles() {
+	mesdoc::init();
+	let attr = scope_attr("card");
+	assert!(attr.starts_with("data-s-") && attr.len() == 15);
+	assert_eq!(attr, scope_attr("card"));
+	assert_ne!(attr, scope_attr("list"));
+	assert_eq!(
+		scope_selector(".a .b::before, a:hover > b:after, p:not(.x, .y)", "data-s"),
+		".a .b[data-s]::before, a:hover > b[data-s]:after, p:not(.x, .y)[data-s]"
+	);
+	assert_eq!(
+		scope_css(
+			"/* c */ @import \"x.css\"; .a{color:red} @media (min-width: 1px) { .b { color: blue } } @keyframes k { from { top: 0 } }",
+			"data-s"
+		),
+		concat!(
+			"@import \"x.css\";\n",
+			".a[data-s] {color:red}\n",
+			"@media (min-width: 1px) {\n.b[data-s] { color: blue }\n}\n",
+			"@keyframes k { from { top: 0 } }\n"
+		)
+	);
+	// the markup and the stylesheet are scoped together
+	let doc =
+		common::parse("<div class=\"card\"><style>p { margin: 0 }</style><p>a</p></div><p>b</p>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let css = root
+		.find(".card")
+		.scope_styles(&attr, ".card p { color: red }");
+	let stylesheet = Stylesheet::parse(&css);
+	let matched: Vec<usize> = root
+		.find("p")
+		.matched_css_rules(&stylesheet)
+		.iter()
+		.map(|rules| rules.len())
+		.collect();
+	assert_eq!(matched, vec![1, 0]);
+	assert_eq!(root.find(&format!("[{}]", attr)).length(), 3);
+	assert_eq!(
+		root.find("style").text(),
+		format!("p[{}] {{ margin: 0 }}\n", attr)
+	);
+}
+
+#[test]
+fn test_computed_style() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><head><style>.box { color: Red; display: flex } p { font-weight: bold !important }",
+		" .box span { visibility: hidden }</style></head><body>",
+		"<div class=\"box\" style=\"color: blue\"><p style=\"font-weight: normal\">a<span>b</span></p>",
+		"<em style=\"color: inherit; display: initial\">c</em><b>d</b></div>",
+		"<ul hidden><li>e</li></ul></body></html>"
+	));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	let style = |selector: &str, property: &str| -> Option<String> {
+		root.find(selector).get(0).unwrap().computed_style(property)
+	};
+	// the inline style wins the rules
+	assert_eq!(style(".box", "color").as_deref(), Some("blue"));
+	assert_eq!(style(".box", "display").as_deref(), Some("flex"));
+	// the inherited properties
+	assert_eq!(style("span", "color").as_deref(), Some("blue"));
+	assert_eq!(style("span", "visibility").as_deref(), Some("hidden"));
+	assert_eq!(style("p", "visibility").as_deref(), Some("visible"));
+	// the important declarations win the inline style
+	assert_eq!(style("p", "font-weight").as_deref(), Some("bold"));
+	assert_eq!(style("em", "color").as_deref(), Some("blue"));
+	assert_eq!(style("em", "font-style").as_deref(), Some("italic"));
+	assert_eq!(style("em", "display").as_deref(), Some("inline"));
+	assert_eq!(style("b", "font-weight").as_deref(), Some("bold"));
+	// the user agent styles
+	assert_eq!(style("p", "display").as_deref(), Some("block"));
+	assert_eq!(style("ul", "display").as_deref(), Some("none"));
+	assert_eq!(style("li", "display").as_deref(), Some("list-item"));
+	assert_eq!(style("head", "display").as_deref(), Some("none"));
+	assert_eq!(
+		style("body", "background-color").as_deref(),
+		Some("transparent")
+	);
+	// the unsupported properties
+	assert_eq!(style("p", "margin"), None);
+}
+
+#[test]
+fn test_matches_media() {
+	let viewport = Viewport {
+		width: 800.0,
+		height: 600.0,
+		device_pixel_ratio: 2.0,
+	};
+	let matches = |media: &str| matches_media(media, &viewport);
+	assert!(matches(""));
+	assert!(matches("all"));
+	assert!(matches("screen and (min-width: 600px)"));
+	assert!(matches("(MIN-WIDTH: 50em) and (max-width: 800px)"));
+	assert!(!matches("(min-width: 801px)"));
+	assert!(!matches("(width >= 800px) and (height < 100vh)"));
+	assert!(matches("(width >= 800px) and (height <= 100vh)"));
+	assert!(matches("(orientation: landscape)"));
+	assert!(matches("(min-resolution: 192dpi)"));
+	assert!(matches("(-webkit-min-device-pixel-ratio: 2)"));
+	assert!(!matches("(max-resolution: 1.5dppx)"));
+	assert!(!matches("print"));
+	assert!(matches("print, (max-width: 1000px)"));
+	assert!(matches("not print"));
+	assert!(!matches("not all and (min-width: 600px)"));
+	// the unknown features never match
+	assert!(!matches("(hover: hover)"));
+	assert!(matches("only screen and (width)"));
+}
@@ -0,0 +1,728 @@
+mod common;
+use mesdoc::css::Viewport;
+use mesdoc::extract::{microdata, rdfa, FormData, FormValue, Item};
+#[cfg(feature = "serde")]
+use mesdoc::extract::{ExtractSchema, FieldValue, Schema};
+use mesdoc::interface::{Elements, IDocumentTrait, SrcsetDescriptor};
+#[cfg(feature = "serde")]
+use serde_json::json;
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, Debug, PartialEq)]
+struct Product {
+	name: String,
+	price: Option<String>,
+	url: String,
+	tags: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl ExtractSchema for Product {
+	fn schema() -> Schema {
+		Schema::new(".product")
+			.field("name", "h2", FieldValue::Text)
+			.field("price", ".price", FieldValue::Text)
+			.field("url", "a", FieldValue::Attr(String::from("href")))
+			.list("tags", ".tag", FieldValue::Text)
+	}
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_extract_schema() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div id=\"list\"><div class=\"product\"><h2> Apple </h2><span class=\"price\">$1</span>",
+		"<a href=\"/apple\">more</a><i class=\"tag\">fruit</i><i class=\"tag\">red</i></div>",
+		"<div class=\"product\" data-id=\"2\"><h2>Pear</h2><a href=\"/pear\">more</a></div></div>"
+	));
+	let products = doc.extract::<Product>().unwrap();
+	assert_eq!(
+		products,
+		vec![
+			Product {
+				name: String::from("Apple"),
+				price: Some(String::from("$1")),
+				url: String::from("/apple"),
+				tags: vec![String::from("fruit"), String::from("red")],
+			},
+			Product {
+				name: String::from("Pear"),
+				price: None,
+				url: String::from("/pear"),
+				tags: vec![],
+			},
+		]
+	);
+	// the scope of the elements, the empty selector takes the record element
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let schema = Schema::new("")
+		.field("id", "", FieldValue::Attr(String::from("data-id")))
+		.field("html", "h2", FieldValue::Html)
+		.list("links", "a", FieldValue::OuterHtml);
+	assert_eq!(
+		schema.extract_values(&root.find(".product")),
+		vec![
+			json!({"id": null, "html": " Apple ", "links": ["<a href=\"/apple\">more</a>"]}),
+			json!({"id": "2", "html": "Pear", "links": ["<a href=\"/pear\">more</a>"]}),
+		]
+	);
+	// the missing required field fails to deserialize
+	let result = Schema::new("h2")
+		.field("name", "b", FieldValue::Text)
+		.extract::<Product>(&root);
+	assert!(result.is_err());
+	assert_eq!(root.find("#list").extract::<Product>().unwrap().len(), 2);
+}
+
+#[test]
+#[cfg(feature = "url")]
+fn test_pagination() {
+	mesdoc::init();
+	let page = |pagination: &mesdoc::extract::Pagination| {
+		pagination
+			.pages
+			.iter()
+			.map(|(number, url)| format!("{}:{}", number, url))
+			.collect::<Vec<_>>()
+	};
+	let doc = common::parse(concat!(
+		"<html><head><link rel=\"canonical\" href=\"/list\"><link rel=\"next\" href=\"?page=3\"></head>",
+		"<body><a href=\"/top\">1</a><ul class=\"pages\"><li><a href=\"?page=1\">1</a></li>",
+		"<li><a href=\"?page=3\">3</a></li><li><a href=\"?page=2\">2</a></li>",
+		"<li><a class=\"page-prev\" href=\"?page=1\">Back</a></li></ul></body></html>"
+	));
+	let pagination = mesdoc::extract::pagination(&doc, "https://example.com/list?page=2").unwrap();
+	assert_eq!(
+		pagination.next.as_ref().unwrap().as_str(),
+		"https://example.com/list?page=3"
+	);
+	assert_eq!(
+		pagination.prev.as_ref().unwrap().as_str(),
+		"https://example.com/list?page=1"
+	);
+	assert_eq!(
+		pagination.canonical.as_ref().unwrap().as_str(),
+		"https://example.com/list"
+	);
+	assert_eq!(
+		page(&pagination),
+		vec![
+			"1:https://example.com/list?page=1",
+			"2:https://example.com/list?page=2",
+			"3:https://example.com/list?page=3"
+		]
+	);
+	// the aria labels and the texts
+	let doc = common::parse(concat!(
+		"<div><a href=\"/a\">First</a><a aria-label=\"Next page\" href=\"/b\">Go</a>",
+		"<a href=\"/c\"> Prev </a><a href=\"/d\">9</a></div>"
+	));
+	let pagination = mesdoc::extract::pagination(&doc, "https://example.com/").unwrap();
+	assert_eq!(
+		pagination.next.as_ref().unwrap().as_str(),
+		"https://example.com/b"
+	);
+	assert_eq!(
+		pagination.prev.as_ref().unwrap().as_str(),
+		"https://example.com/c"
+	);
+	assert!(pagination.canonical.is_none());
+	assert!(page(&pagination).is_empty());
+}
+
+#[test]
+fn test_extract_images() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><head><base href=\"https://example.com/a/\"></head><body>",
+		"<img src=\"b.png\" srcset=\"b-1x.png 1x, /b-2x.png 2x\" sizes=\"50vw\" alt=\"B\" width=\"300\" height=\"200px\">",
+		"<div><img src=\"data:image/gif;base64,R0lGOD\" data-src=\"c.png\" data-srcset=\"c-480.png 480w\" loading=\"LAZY\"></div>",
+		"<img></body></html>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let images = root.find("body").extract_images();
+	assert_eq!(images.len(), 3);
+	let image = &images[0];
+	assert_eq!(image.src.as_deref(), Some("https://example.com/a/b.png"));
+	assert_eq!(image.src_attr, Some("src"));
+	let candidates: Vec<_> = image
+		.srcset
+		.candidates
+		.iter()
+		.map(|candidate| (candidate.url.as_str(), candidate.descriptor))
+		.collect();
+	assert_eq!(
+		candidates,
+		vec![
+			(
+				"https://example.com/a/b-1x.png",
+				SrcsetDescriptor::Density(1.0)
+			),
+			(
+				"https://example.com/b-2x.png",
+				SrcsetDescriptor::Density(2.0)
+			)
+		]
+	);
+	assert_eq!(image.sizes.as_deref(), Some("50vw"));
+	assert_eq!(image.alt.as_deref(), Some("B"));
+	assert_eq!((image.width, image.height), (Some(300), Some(200)));
+	assert!(!image.is_lazy);
+	// the lazy-load attributes win over the placeholder
+	let image = &images[1];
+	assert_eq!(image.src.as_deref(), Some("https://example.com/a/c.png"));
+	assert_eq!(image.src_attr, Some("data-src"));
+	assert_eq!(
+		image.srcset.candidates[0].descriptor,
+		SrcsetDescriptor::Width(480)
+	);
+	assert_eq!(image.loading.as_deref(), Some("lazy"));
+	assert!(image.is_lazy);
+	let image = &images[2];
+	assert!(image.src.is_none() && image.srcset.candidates.is_empty() && image.alt.is_none());
+	// the img elements in the set
+	assert_eq!(root.find("img").extract_images().len(), 3);
+}
+
+#[test]
+fn test_best_source() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><head><base href=\"https://example.com/\"></head><body><picture>",
+		"<source media=\"(min-width: 1000px)\" type=\"image/jxl\" srcset=\"wide.jxl\">",
+		"<source media=\"(min-width: 1000px)\" srcset=\"wide-1x.png 1x, wide-2x.png 2x\">",
+		"<source media=\"(max-width: 600px)\" sizes=\"(max-width: 400px) 100vw, 50vw\" srcset=\"s-200.png 200w, s-400.png 400w, s-800.png 800w\">",
+		"<img src=\"fallback.png\"></picture>",
+		"<img src=\"a.png\" srcset=\"a-2x.png 2x\"><img src=\"b.png\" srcset=\"b-small.png 300w, b-large.png 1200w\" sizes=\"50vw\">",
+		"</body></html>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let images = root.find("img").extract_images();
+	let picture = &images[0];
+	assert_eq!(picture.sources.len(), 3);
+	assert_eq!(picture.sources[0].mime_type.as_deref(), Some("image/jxl"));
+	let best = |image: &mesdoc::extract::Image, width: f32, ratio: f32| {
+		let viewport = Viewport {
+			width,
+			height: 800.0,
+			device_pixel_ratio: ratio,
+		};
+		image
+			.best_source(&viewport)
+			.unwrap()
+			.replace("https://example.com/", "")
+	};
+	// the unsupported type is skipped
+	assert_eq!(best(picture, 1280.0, 1.0), "wide-1x.png");
+	assert_eq!(best(picture, 1280.0, 2.0), "wide-2x.png");
+	assert_eq!(best(picture, 1280.0, 3.0), "wide-2x.png");
+	// the sizes of the matched media condition
+	assert_eq!(best(picture, 375.0, 1.0), "s-400.png");
+	assert_eq!(best(picture, 500.0, 1.0), "s-400.png");
+	assert_eq!(best(picture, 375.0, 2.0), "s-800.png");
+	assert_eq!(best(picture, 800.0, 2.0), "fallback.png");
+	// the src is the 1x candidate
+	assert_eq!(best(&images[1], 1280.0, 1.0), "a.png");
+	assert_eq!(best(&images[1], 1280.0, 1.5), "a-2x.png");
+	assert_eq!(best(&images[2], 500.0, 1.0), "b-small.png");
+	assert_eq!(best(&images[2], 1280.0, 1.0), "b-large.png");
+}
+
+#[test]
+fn test_outline() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><h1>Site</h1><nav><h2>Menu</h2></nav>",
+		"<main><article><h1>Post</h1><h2>Intro</h2><h3>Detail</h3><section><h3>Deep</h3></section>",
+		"<h2>Usage</h2><blockquote><h1>Quoted</h1></blockquote>",
+		"<hgroup><h3>Subtitle</h3><h2> Grouped </h2></hgroup></article></main>",
+		"<h2>Footer</h2></body></html>"
+	));
+	fn tree(items: &[mesdoc::extract::OutlineItem]) -> String {
+		items
+			.iter()
+			.map(|item| {
+				if item.children.is_empty() {
+					format!("{}{}", item.level, item.text)
+				} else {
+					format!("{}{}[{}]", item.level, item.text, tree(&item.children))
+				}
+			})
+			.collect::<Vec<_>>()
+			.join(",")
+	}
+	let outline = doc.outline();
+	assert_eq!(
+		tree(&outline),
+		"1Site[2Menu,1Post[2Intro[3Detail,3Deep],2Usage,2Grouped],2Footer]"
+	);
+	let post = &outline[0].children[1];
+	assert_eq!(post.element.tag_name(), "H1");
+	assert_eq!(post.section.as_ref().unwrap().tag_name(), "ARTICLE");
+	assert!(outline[0].section.is_none());
+	assert!(outline[0].children[2].section.is_none());
+	// the outline of the elements
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let items = mesdoc::extract::outline(&root.find("article"));
+	assert_eq!(tree(&items), "1Post[2Intro[3Detail,3Deep],2Usage,2Grouped]");
+}
+
+#[test]
+fn test_article() {
+	mesdoc::init();
+	let doc = common::parse(include_str!("fixtures/article.html"));
+	let article = mesdoc::extract::article(&doc).unwrap();
+	// the positive class wins over the parent, the comments and the links are left out
+	assert_eq!(article.content.tag_name(), "ARTICLE");
+	assert_eq!(article.title.as_deref(), Some("The Arena Backend"));
+	assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+	assert!(article.text().contains("generational arena"));
+	assert!(!article.text().contains("Great post"));
+	assert!(article
+		.html()
+		.starts_with("\n<h1>Inside the arena backend</h1>"));
+	assert!(article.score > 0.0);
+	// the title falls back to the heading, the byline to the author elements
+	let doc = common::parse(include_str!("fixtures/article_byline.html"));
+	let article = mesdoc::extract::article(&doc).unwrap();
+	assert_eq!(article.content.tag_name(), "DIV");
+	assert!(article.content.get_attribute("class").is_none());
+	assert_eq!(article.title.as_deref(), Some("Parsing the fragments"));
+	assert_eq!(article.byline.as_deref(), Some("By John Smith"));
+	// the paragraphs are too short to be scored
+	let doc = common::parse("<html><body><div><p>Too short.</p></div></body></html>");
+	assert!(mesdoc::extract::article(&doc).is_none());
+	assert!(mesdoc::extract::article(&common::parse("<p>No body</p>")).is_none());
+}
+
+#[test]
+fn test_extract_table() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div><table><thead><tr><th colspan=\"2\">Name</th><th>Note</th></tr>",
+		"<tr><th>First</th><th>Last</th><th>Note</th></tr></thead><tbody>",
+		"<tr><td rowspan=\"2\">Ann</td><td>Lee</td><td>say \"hi\", ok</td></tr>",
+		"<tr><td>Ray</td><td>line1\n  line2</td></tr>",
+		"<tr><td colspan=\"2\">  Both   names </td><td rowspan=\"3\">tail</td></tr></tbody>",
+		"<tfoot><tr><td>f1</td><td>f2</td></tr></tfoot></table>",
+		"<table><tr><th>A</th><th>B</th><th>C</th></tr>",
+		"<tr><td>1</td><td>2</td><td rowspan=\"2\">3</td></tr><tr><td>4</td></tr>",
+		"<tr><td rowspan=\"2\">x</td><td>y</td></tr><tr></tr></table>",
+		"<table><tr><td colspan=\"0\">a</td><td colspan=\"x\">b</td></tr></table></div>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let tables = root.find("table, div").extract_table();
+	assert_eq!(tables.len(), 3);
+	let rows = |rows: &[&[&str]]| -> Vec<Vec<String>> {
+		rows
+			.iter()
+			.map(|row| row.iter().map(|cell| String::from(*cell)).collect())
+			.collect()
+	};
+	// the last row of `thead` is the headers, the spanned cells are repeated, the footer is the last row
+	assert_eq!(tables[0].headers, vec!["First", "Last", "Note"]);
+	assert_eq!(
+		tables[0].rows,
+		rows(&[
+			&["Ann", "Lee", "say \"hi\", ok"],
+			&["Ann", "Ray", "line1 line2"],
+			&["Both names", "Both names", "tail"],
+			&["f1", "f2", "tail"],
+		])
+	);
+	// the leading row of `th` is the headers, the holes before the spanned cells are kept
+	assert_eq!(tables[1].headers, vec!["A", "B", "C"]);
+	assert_eq!(
+		tables[1].rows,
+		rows(&[&["1", "2", "3"], &["4", "", "3"], &["x", "y"], &["x"]])
+	);
+	let records = tables[1].records();
+	assert_eq!(records[1]["B"], "");
+	assert_eq!(records[3].len(), 1);
+	assert_eq!(records[3]["A"], "x");
+	// no headers, the invalid spans are 1
+	assert!(tables[2].headers.is_empty());
+	assert_eq!(tables[2].rows, rows(&[&["a", "b"]]));
+	#[cfg(feature = "csv")]
+	{
+		let mut csv = Vec::new();
+		tables[0].write_csv(&mut csv).unwrap();
+		assert_eq!(
+			String::from_utf8(csv).unwrap(),
+			concat!(
+				"First,Last,Note\n",
+				"Ann,Lee,\"say \"\"hi\"\", ok\"\n",
+				"Ann,Ray,line1 line2\n",
+				"Both names,Both names,tail\n",
+				"f1,f2,tail\n"
+			)
+		);
+		// the rows have their own lengths, the table without headers writes only the rows
+		let mut csv = Vec::new();
+		tables[1].write_csv(&mut csv).unwrap();
+		assert!(String::from_utf8(csv).unwrap().ends_with("4,,3\nx,y\nx\n"));
+		let mut csv = Vec::new();
+		tables[2].write_csv(&mut csv).unwrap();
+		assert_eq!(String::from_utf8(csv).unwrap(), "a,b\n");
+	}
+}
+
+#[test]
+fn test_metadata() {
+	mesdoc::init();
+	let doc = common::parse(include_str!("fixtures/metadata.html"));
+	let meta = doc.metadata();
+	assert_eq!(meta.title.as_deref(), Some("Mesdoc Release Notes"));
+	assert_eq!(
+		meta.description.as_deref(),
+		Some("The notes of the new release.")
+	);
+	// the first canonical link wins, `rel` is a token list
+	assert_eq!(meta.canonical.as_deref(), Some("https://example.com/notes"));
+	// the open graph properties keep the source order and the duplicates, the ones out of the head are skipped
+	assert_eq!(
+		meta.open_graph,
+		vec![
+			(String::from("og:title"), String::from("Release Notes")),
+			(
+				String::from("og:image"),
+				String::from("https://example.com/a.png")
+			),
+			(
+				String::from("og:image"),
+				String::from("https://example.com/b.png")
+			),
+			(String::from("og:type"), String::from("article")),
+		]
+	);
+	assert_eq!(meta.og("IMAGE"), Some("https://example.com/a.png"));
+	assert_eq!(meta.og("description"), None);
+	// the twitter cards use `name` or `property`, the prefix is case-insensitive
+	assert_eq!(meta.twitter.len(), 2);
+	assert_eq!(meta.twitter_card("card"), Some("summary_large_image"));
+	assert_eq!(meta.twitter_card("site"), Some("@mesdoc"));
+	// the other named metas, the empty content is kept
+	assert_eq!(meta.meta("keywords"), Some("html, dom, parser"));
+	assert_eq!(meta.meta("robots"), Some(""));
+	assert_eq!(meta.meta("charset"), None);
+	// the json-ld blocks in the whole document, the blank ones are skipped
+	assert_eq!(meta.json_ld.len(), 3);
+	assert!(meta.json_ld[0].starts_with("{\"@context\""));
+	assert_eq!(meta.json_ld[2], "{ invalid json");
+	#[cfg(feature = "serde")]
+	{
+		let values = meta.json_ld_values();
+		assert_eq!(values.len(), 2);
+		assert_eq!(values[0]["headline"], "Release Notes");
+		assert_eq!(values[1]["name"], "Jane");
+	}
+	// the document without the head
+	let meta = common::parse("<p>text</p>").metadata();
+	assert!(meta.title.is_none() && meta.canonical.is_none() && meta.open_graph.is_empty());
+}
+
+#[test]
+fn test_serialize_form() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div><form id=\"f\"><input name=\"q\" value=\"a b&amp;c\"><input name=\"empty\"><input name=\"\" value=\"x\">",
+		"<input type=\"checkbox\" name=\"c1\" checked><input type=\"checkbox\" name=\"c2\" value=\"x\">",
+		"<input type=\"radio\" name=\"r\" value=\"1\" checked><input type=\"submit\" name=\"go\" value=\"Go\">",
+		"<input name=\"dis\" value=\"1\" disabled><input name=\"other\" form=\"g\" value=\"no\">",
+		"<fieldset disabled><legend><input name=\"in_legend\" value=\"L\"></legend><input name=\"in_set\" value=\"S\"></fieldset>",
+		"<input type=\"file\" name=\"upload\"><input type=\"hidden\" name=\"_charset_\">",
+		"<textarea name=\"t\">\nline1\nline2\rline3\r\n</textarea>",
+		"<select name=\"single\"><option selected>one</option><option value=\"2\" selected>two</option></select>",
+		"<select name=\"multi\" multiple><option selected value=\"a\">A</option><option>B</option>",
+		"<option selected disabled>C</option><option selected>D</option></select>",
+		"<select name=\"default\"><option disabled>x</option><option>  first   choice </option></select>",
+		"<select name=\"listbox\" size=\"3\"><option>y</option></select>",
+		"<select name=\"off\"><option>z</option><option selected disabled>w</option></select>",
+		"<input name=\"ünï\" value=\"é\"></form><input name=\"outside\" form=\"f\" value=\"o\"></div>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let data = root.find("form").serialize_form();
+	let text = |name: &str, value: &str| (String::from(name), FormValue::Text(String::from(value)));
+	assert_eq!(
+		data.entries,
+		vec![
+			text("q", "a b&c"),
+			text("empty", ""),
+			text("c1", "on"),
+			text("r", "1"),
+			text("in_legend", "L"),
+			(String::from("upload"), FormValue::File(String::new())),
+			text("_charset_", "UTF-8"),
+			// the newlines of the textarea are CRLF
+			text("t", "line1\r\nline2\r\nline3\r\n"),
+			// only the last selected option of a single select
+			text("single", "2"),
+			text("multi", "a"),
+			text("multi", "D"),
+			text("default", "first choice"),
+			text("ünï", "é"),
+			text("outside", "o"),
+		]
+	);
+	assert_eq!(
+		data.to_urlencoded(),
+		concat!(
+			"q=a+b%26c&empty=&c1=on&r=1&in_legend=L&upload=&_charset_=UTF-8&t=line1%0D%0Aline2%0D%0Aline3%0D%0A",
+			"&single=2&multi=a&multi=D&default=first+choice&%C3%BCn%C3%AF=%C3%A9&outside=o"
+		)
+	);
+	// the controls out of the forms are serialized in the order of the set
+	let data = root
+		.find("select[name='single'], input[name='c2']")
+		.serialize_form();
+	assert_eq!(data.to_urlencoded(), "single=2");
+	// the names and the filenames are escaped in the headers
+	let data = FormData {
+		entries: vec![
+			text("a\"b", "x\r\ny"),
+			(
+				String::from("file"),
+				FormValue::File(String::from("my\nfile.txt")),
+			),
+		],
+	};
+	assert_eq!(
+		data.to_multipart("B"),
+		concat!(
+			"--B\r\nContent-Disposition: form-data; name=\"a%22b\"\r\n\r\nx\r\ny\r\n",
+			"--B\r\nContent-Disposition: form-data; name=\"file\"; filename=\"my%0Afile.txt\"\r\n",
+			"Content-Type: application/octet-stream\r\n\r\n\r\n--B--\r\n"
+		)
+	);
+	assert_eq!(FormData::default().to_multipart("B"), "--B--\r\n");
+}
+
+#[test]
+fn test_microdata() {
+	mesdoc::init();
+	let doc = common::parse(include_str!("fixtures/microdata.html"));
+	let items = microdata(&doc);
+	// the nested items with `itemprop` are not at the top level
+	assert_eq!(items.len(), 2);
+	let product = &items[0];
+	assert!(
+		product.is_type("https://schema.org/Product") && product.is_type("https://schema.org/Thing")
+	);
+	assert_eq!(product.id.as_deref(), Some("urn:sku:42"));
+	let text = |item: &Item, name: &str| {
+		item
+			.get(name)
+			.and_then(|value| value.as_text())
+			.map(String::from)
+	};
+	// the names share the value, the values come from the attributes by the tag names
+	assert_eq!(text(product, "name").as_deref(), Some("The Widget"));
+	assert_eq!(
+		text(product, "alternateName").as_deref(),
+		Some("The Widget")
+	);
+	assert_eq!(text(product, "image").as_deref(), Some("widget.png"));
+	assert_eq!(
+		text(product, "url").as_deref(),
+		Some("https://example.com/widget")
+	);
+	assert_eq!(text(product, "releaseDate").as_deref(), Some("2024-05-01"));
+	assert_eq!(text(product, "sku").as_deref(), Some("42"));
+	// the properties of the nested item are its own
+	let offer = product
+		.get("offers")
+		.and_then(|value| value.as_item())
+		.unwrap();
+	assert!(offer.is_type("https://schema.org/Offer"));
+	assert_eq!(text(offer, "price").as_deref(), Some("9.99"));
+	assert_eq!(
+		text(offer, "availability").as_deref(),
+		Some("https://schema.org/InStock")
+	);
+	assert_eq!(text(offer, "priceCurrency").as_deref(), Some("USD"));
+	assert!(product.get("price").is_none());
+	// the item referencing itself is not nested in itself
+	let author = product
+		.get("author")
+		.and_then(|value| value.as_item())
+		.unwrap();
+	assert_eq!(author.properties.len(), 1);
+	assert_eq!(text(author, "name").as_deref(), Some("Jane"));
+	// the `itemref` elements are crawled after the children, the missing ids are ignored
+	let names: Vec<&str> = product
+		.properties
+		.iter()
+		.map(|(name, _)| name.as_str())
+		.collect();
+	assert_eq!(
+		names,
+		vec![
+			"name",
+			"alternateName",
+			"image",
+			"url",
+			"releaseDate",
+			"sku",
+			"offers",
+			"author",
+			"brand"
+		]
+	);
+	assert_eq!(text(product, "brand").as_deref(), Some("ACME"));
+	assert!(items[1].is_type("https://schema.org/Event"));
+	assert_eq!(text(&items[1], "name").as_deref(), Some("Launch"));
+}
+
+#[test]
+fn test_rdfa() {
+	mesdoc::init();
+	let doc = common::parse(include_str!("fixtures/microdata.html"));
+	let items = rdfa(&doc);
+	assert_eq!(items.len(), 2);
+	let person = &items[0];
+	assert_eq!(person.types, vec!["https://schema.org/Person"]);
+	assert_eq!(person.id.as_deref(), Some("#jane"));
+	let text = |item: &Item, name: &str| {
+		item
+			.get(name)
+			.and_then(|value| value.as_text())
+			.map(String::from)
+	};
+	// the terms are expanded by the `vocab`, the values come from `content`, `href` or `datetime`
+	assert_eq!(
+		text(person, "https://schema.org/name").as_deref(),
+		Some("Jane Doe")
+	);
+	assert_eq!(
+		text(person, "https://schema.org/url").as_deref(),
+		Some("https://jane.example")
+	);
+	assert_eq!(
+		text(person, "https://schema.org/birthDate").as_deref(),
+		Some("1990-01-01")
+	);
+	let address = person
+		.get("https://schema.org/address")
+		.and_then(|value| value.as_item())
+		.unwrap();
+	assert_eq!(address.types, vec!["https://schema.org/PostalAddress"]);
+	assert_eq!(address.id.as_deref(), Some("#home"));
+	assert_eq!(
+		text(address, "https://schema.org/addressLocality").as_deref(),
+		Some("Springfield")
+	);
+	assert!(person.get("https://schema.org/streetAddress").is_none());
+	// the prefixed names are kept, the empty `vocab` resets the vocabulary
+	assert_eq!(text(person, "og:title").as_deref(), Some("Prefixed"));
+	assert_eq!(text(person, "foaf:name").as_deref(), Some("Prefixed"));
+	assert_eq!(text(person, "plain").as_deref(), Some("kept"));
+	// the `vocab` of the element expands its own `typeof`, the property out of any item is ignored
+	assert_eq!(items[1].types, vec!["http://example.org/Event"]);
+	assert_eq!(
+		text(&items[1], "http://example.org/name").as_deref(),
+		Some("Party")
+	);
+	assert!(items.iter().all(|item| item
+		.properties
+		.iter()
+		.all(|(name, _)| !name.ends_with("orphan"))));
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn test_links() {
+	use mesdoc::extract::{absolutize_links, base_url, links};
+	mesdoc::init();
+	let doc = common::parse(include_str!("fixtures/links.html"));
+	let page = "https://example.com/a/b.html";
+	// the `<base href>` is resolved against the page url
+	assert_eq!(
+		base_url(&doc, page).unwrap().as_str(),
+		"https://example.com/docs/v1/"
+	);
+	assert_eq!(
+		base_url(&common::parse("<p>no base</p>"), page)
+			.unwrap()
+			.as_str(),
+		page
+	);
+	assert!(base_url(&doc, "/relative").is_err());
+	assert!(links(&doc, "/relative").is_err());
+	let found = links(&doc, page).unwrap();
+	let resolved: Vec<(&str, &str, Option<&str>)> = found
+		.iter()
+		.map(|link| {
+			(
+				link.attr_name,
+				link.raw.as_str(),
+				link.url.as_ref().map(|url| url.as_str()),
+			)
+		})
+		.collect();
+	let base = "https://example.com/docs/v1/";
+	let url = |path: &str| format!("{}{}", base, path);
+	let (style, page_url, fragment, a, a_1x, c, s, frame, map) = (
+		url("style.css"),
+		url("page.html"),
+		url("#top"),
+		url("a.png"),
+		url("a-1x.png"),
+		url("c.png"),
+		url("s.webp"),
+		url("frame.html"),
+		url("map.html"),
+	);
+	// the empty urls are skipped, the srcset candidates are the links of one attribute
+	assert_eq!(
+		resolved,
+		vec![
+			("href", "style.css", Some(style.as_str())),
+			("href", "page.html", Some(page_url.as_str())),
+			(
+				"href",
+				"../up.html",
+				Some("https://example.com/docs/up.html")
+			),
+			("href", "/root", Some("https://example.com/root")),
+			("href", "https://other.com/x", Some("https://other.com/x")),
+			("href", "#top", Some(fragment.as_str())),
+			("href", "http://[bad", None),
+			("src", "a.png", Some(a.as_str())),
+			("srcset", "a-1x.png", Some(a_1x.as_str())),
+			("srcset", "/b-2x.png", Some("https://example.com/b-2x.png")),
+			("srcset", "c.png", Some(c.as_str())),
+			("srcset", "s.webp", Some(s.as_str())),
+			(
+				"srcset",
+				"https://cdn.com/t.webp",
+				Some("https://cdn.com/t.webp")
+			),
+			("src", "//cdn.com/app.js", Some("https://cdn.com/app.js")),
+			("src", "frame.html", Some(frame.as_str())),
+			("href", "map.html", Some(map.as_str())),
+		]
+	);
+	assert_eq!(found[0].element.tag_name(), "LINK");
+	// the absolute and the invalid urls are kept, each srcset is rewritten once with its descriptors
+	assert_eq!(absolutize_links(&doc, page).unwrap(), 11);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	assert_eq!(
+		root.find("img").attr("srcset").unwrap().to_string(),
+		format!("{} 1x, https://example.com/b-2x.png 2x, {}", a_1x, c)
+	);
+	assert_eq!(
+		root.find("source").attr("srcset").unwrap().to_string(),
+		format!("{} 100w, https://cdn.com/t.webp 200w", s)
+	);
+	assert_eq!(
+		root.find("a").eq(2).attr("href").unwrap().to_string(),
+		"https://example.com/root"
+	);
+	assert_eq!(
+		root.find("a").eq(6).attr("href").unwrap().to_string(),
+		"http://[bad"
+	);
+	// the urls are absolute now, so nothing is rewritten again
+	assert_eq!(absolutize_links(&doc, page).unwrap(), 0);
+}
@@ -0,0 +1,105 @@
+mod common;
+use mesdoc::interface::IDocumentTrait;
+use mesdoc::lint::LintRule;
+
+#[test]
+fn test_lint() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><p>text<div id=\"a\">block</div></p><li>item</li>",
+		"<ul><li id=\"a\">ok</li></ul><blink id=\"a\">old</blink><my-widget></my-widget>",
+		"<svg><circle></circle></svg></body></html>"
+	));
+	let issues = doc.lint();
+	let found: Vec<(LintRule, String)> = issues
+		.iter()
+		.map(|issue| (issue.rule, issue.node.tag_name().to_string()))
+		.collect();
+	assert_eq!(
+		found,
+		vec![
+			(LintRule::BlockInParagraph, String::from("DIV")),
+			(LintRule::ListItemOutsideList, String::from("LI")),
+			(LintRule::DuplicateId, String::from("LI")),
+			(LintRule::DuplicateId, String::from("BLINK")),
+			(LintRule::UnknownElement, String::from("BLINK")),
+		]
+	);
+	assert_eq!(
+		issues[0].error.to_string(),
+		"Invalid structure 'block-in-paragraph': The '<div>' element can't be in a '<p>' element."
+	);
+	assert!(
+		common::parse("<html><head></head><body><p>ok</p></body></html>")
+			.lint()
+			.is_empty()
+	);
+	// the empty ids are not duplicated, the elements in `svg` and `math` are not unknown
+	let doc = common::parse(concat!(
+		"<div id=\"\"><span id=\"\"></span><i id=\" \"></i><i id=\" \"></i></div><svg><g><blink></blink></g></svg>",
+		"<math><mi></mi></math><blink></blink>"
+	));
+	let found: Vec<(LintRule, String)> = doc
+		.lint()
+		.iter()
+		.map(|issue| (issue.rule, issue.node.tag_name().to_string()))
+		.collect();
+	assert_eq!(
+		found,
+		vec![(LintRule::UnknownElement, String::from("BLINK"))]
+	);
+	// the root in a foreign element
+	let root = doc.find("g").get(0).unwrap().cloned();
+	assert!(mesdoc::lint::lint(&root).is_empty());
+}
+
+#[test]
+fn test_duplicate_ids() {
+	use mesdoc::interface::Elements;
+	mesdoc::init();
+	let mut doc = common::parse(concat!(
+		"<html><body><div id=\"a\">1</div><p id=\"b\">2</p><span id=\"a\">3</span>",
+		"<i id=\"a-1\">4</i><b id=\"b\">5</b><em id=\"a\">6</em><u id=\"c\">7</u></body></html>"
+	));
+	let groups: Vec<(String, String)> = doc
+		.duplicate_ids()
+		.iter()
+		.map(|(id, eles)| (id.clone(), String::from(eles.text())))
+		.collect();
+	assert_eq!(
+		groups,
+		vec![
+			(String::from("a"), String::from("136")),
+			(String::from("b"), String::from("25"))
+		]
+	);
+	// the `#id` selector finds the first element only
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	assert_eq!(root.find("#a").length(), 1);
+	// the first element keeps the id, the used id `a-1` is skipped
+	assert_eq!(
+		doc.uniquify_ids(""),
+		vec![
+			(String::from("a"), String::from("a-2")),
+			(String::from("b"), String::from("b-1")),
+			(String::from("a"), String::from("a-3")),
+		]
+	);
+	assert!(doc.duplicate_ids().is_empty());
+	assert_eq!(root.find("#a-3").text(), "6");
+	assert_eq!(root.find("#b-1").text(), "5");
+	assert!(doc.uniquify_ids("dup-").is_empty());
+	let mut doc = common::parse("<div id=\"x\"></div><div id=\"x\"></div>");
+	assert_eq!(
+		doc.uniquify_ids("dup-"),
+		vec![(String::from("x"), String::from("dup-x-1"))]
+	);
+	// the empty and whitespace-only ids are not duplicated ids
+	let mut doc = common::parse("<p id=\"\"></p><p id=\"\"></p><p id=\" \"></p><p id=\" \"></p>");
+	assert!(doc.duplicate_ids().is_empty());
+	assert!(doc.uniquify_ids("x-").is_empty());
+	assert_eq!(
+		doc.source_code(),
+		"<p id=\"\"></p><p id=\"\"></p><p id=\" \"></p><p id=\" \"></p>"
+	);
+}
@@ -0,0 +1,263 @@
+mod common;
+use mesdoc::interface::{
+	BoxDynNode, Elements, IDocumentTrait, InsertPosition, MutationKind, MutationRecord, Range,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// describe the record as a string
+fn describe(record: &MutationRecord) -> String {
+	let name = |node: &BoxDynNode| match node.clone_node().typed().into_element() {
+		Some(ele) if !ele.tag_name().is_empty() => ele.tag_name().to_ascii_lowercase(),
+		_ => format!("{:?}", node.text_content()),
+	};
+	match &record.kind {
+		MutationKind::Attribute {
+			name: attr,
+			old_value,
+			..
+		} => format!(
+			"attr {} {}: {:?}",
+			name(&record.target),
+			attr,
+			old_value.as_ref().map(|value| value.to_list().join(" "))
+		),
+		MutationKind::ChildInserted { index } => format!(
+			"insert {} into {} at {}",
+			name(record.node.as_ref().unwrap()),
+			name(&record.target),
+			index
+		),
+		MutationKind::ChildRemoved { index } => format!(
+			"remove {} from {} at {}",
+			name(record.node.as_ref().unwrap()),
+			name(&record.target),
+			index
+		),
+		MutationKind::Text { old_value } => {
+			format!("text {:?}: {:?}", record.target.text_content(), old_value)
+		}
+		MutationKind::RawZone { old_value } => {
+			format!("raw zone {}: {}", name(&record.target), old_value)
+		}
+	}
+}
+
+#[test]
+fn test_on_mutation() {
+	mesdoc::init();
+	let mut doc =
+		common::parse("<html><body><div id=\"main\" class=\"a\"><p>one</p>two</div></body></html>");
+	let records = Rc::new(RefCell::new(Vec::new()));
+	let log = records.clone();
+	doc
+		.on_mutation(Box::new(move |record| {
+			log.borrow_mut().push(describe(record))
+		}))
+		.unwrap();
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let mut main = root.find("#main").get(0).unwrap().cloned();
+	main.set_attribute("class", Some("b"));
+	main.set_attribute("title", None);
+	main.remove_attribute("title");
+	// nothing is changed
+	main.remove_attribute("lang");
+	let mut p = root.find("p").get(0).unwrap().cloned();
+	p.set_html("<b>x</b>y");
+	let b = root.find("b").get(0).unwrap().cloned();
+	main.insert_adjacent(&InsertPosition::AfterBegin, &b);
+	let mut text = main
+		.child_nodes_item(2)
+		.unwrap()
+		.typed()
+		.into_text()
+		.unwrap();
+	text.append_text("!");
+	text.split_text(1).unwrap();
+	main.remove_child(b);
+	assert_eq!(
+		*records.borrow(),
+		vec![
+			"attr div class: Some(\"a\")",
+			"attr div title: None",
+			"attr div title: Some(\"\")",
+			"remove \"one\" from p at 0",
+			"insert b into p at 0",
+			"insert \"y\" into p at 1",
+			"remove b from p at 0",
+			"insert b into div at 0",
+			"text \"two!\": \"two\"",
+			"text \"t\": \"two!\"",
+			"insert \"wo!\" into div at 3",
+			"remove b from div at 0",
+		]
+	);
+	// the mutations made by the range are notified too
+	records.borrow_mut().clear();
+	let p = root.find("p").get(0).unwrap().clone_node();
+	Range::select_node(&p).unwrap().delete_contents().unwrap();
+	assert_eq!(*records.borrow(), vec!["remove p from div at 0"]);
+}
+
+#[test]
+fn test_transaction() {
+	mesdoc::init();
+	let html = "<html><body><div id=\"main\" class=\"a\"><p>one</p>two<!--c--></div></body></html>";
+	let mut doc = common::parse(html);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let main = root.find("#main").get(0).unwrap().cloned();
+	// the failed transaction is rolled back
+	let result = doc.transaction(&mut |tx| {
+		let mut main = main.cloned();
+		main.set_attribute("class", Some("b"));
+		main.set_attribute("hidden", None);
+		main.remove_attribute("id");
+		let mut p = main.children().get(0).unwrap().cloned();
+		p.set_html("<b>x</b>y");
+		let mut text = main
+			.child_nodes_item(1)
+			.unwrap()
+			.typed()
+			.into_text()
+			.unwrap();
+		text.append_text("!");
+		text.split_text(1)?;
+		let b = p.children().get(0).unwrap().cloned();
+		main.insert_adjacent(&InsertPosition::AfterBegin, &b);
+		let mut range = Range::select_node_contents(&main.clone_node());
+		range.extract_contents()?;
+		assert!(!tx.is_empty());
+		assert_eq!(main.inner_html(), "");
+		Err("The template is invalid.".into())
+	});
+	assert!(result.is_err());
+	// the removed attribute is restored at its index
+	assert_eq!(doc.source_code(), html);
+	// the successful transaction is kept
+	doc
+		.transaction(&mut |_| {
+			main.cloned().set_attribute("class", Some("b"));
+			Ok(())
+		})
+		.unwrap();
+	assert_eq!(main.get_attribute("class").unwrap().to_list(), vec!["b"]);
+	// rollback manually
+	doc
+		.transaction(&mut |tx| {
+			let mut main = main.cloned();
+			main.set_text("text");
+			tx.rollback()?;
+			assert!(tx.is_empty());
+			main.set_attribute("title", Some("t"));
+			Ok(())
+		})
+		.unwrap();
+	assert_eq!(
+		main.outer_html(),
+		"<div id=\"main\" class=\"b\" title=\"t\"><p>one</p>two<!--c--></div>"
+	);
+}
+
+#[test]
+fn test_insert_into_descendant() {
+	mesdoc::init();
+	let html = "<html><body><div id=\"main\"><p><b>one</b></p></div></body></html>";
+	let doc = common::parse(html);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let main = root.find("#main").get(0).unwrap().cloned();
+	let mut b = root.find("b").get(0).unwrap().cloned();
+	// the ancestor can't be moved into its descendants, the tree is unchanged
+	b.insert_adjacent(&InsertPosition::BeforeEnd, &main);
+	b.insert_adjacent(&InsertPosition::AfterEnd, &main);
+	b.insert_adjacent(&InsertPosition::BeforeEnd, &b.cloned());
+	assert!(b.insert_child(0, &main.clone_node()).is_err());
+	root.find("b").append(&mut root.find("p"));
+	assert_eq!(doc.source_code(), html);
+	// the move mode still moves the other elements
+	root.find("body").append(&mut root.find("p"));
+	assert_eq!(
+		root.find("body").html(),
+		"<div id=\"main\"></div><p><b>one</b></p>"
+	);
+}
+
+#[test]
+fn test_surround_contents() {
+	mesdoc::init();
+	let doc =
+		common::parse("<html><body><div id=\"main\">one <b>two</b> three</div><i></i></body></html>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let main = root.find("#main").get(0).unwrap().cloned();
+	let b = root.find("b").get(0).unwrap().cloned();
+	let one = main.child_nodes_item(0).unwrap();
+	let three = main.child_nodes_item(2).unwrap();
+	// the texts are split at the boundaries, the nodes between are moved into the wrapper
+	let mut range = Range::new(&one, 2, &three, 3).unwrap();
+	let wrapper = root.find("i").get(0).unwrap().cloned();
+	range.surround_contents(&wrapper).unwrap();
+	assert_eq!(main.inner_html(), "on<i>e <b>two</b> th</i>ree");
+	let wrapped = main.children().get(0).unwrap().cloned();
+	assert!(wrapped.is(&wrapper));
+	assert!(wrapped.children().get(0).unwrap().is(&b));
+	// the split texts keep their heads
+	assert!(one.parent().unwrap().is(&main));
+	assert_eq!(one.text_content(), "on");
+	assert!(three.parent().unwrap().is(&wrapper));
+	assert_eq!(three.text_content(), " th");
+	assert_eq!(range.start_offset(), 1);
+	assert_eq!(range.end_offset(), 2);
+}
+
+#[test]
+fn test_raw_zone_mutation() {
+	mesdoc::init();
+	let html = "<html><body><div id=\"main\">one</div></body></html>";
+	let mut doc = common::parse(html);
+	let records = Rc::new(RefCell::new(Vec::new()));
+	let log = records.clone();
+	doc
+		.on_mutation(Box::new(move |record| {
+			log.borrow_mut().push(describe(record))
+		}))
+		.unwrap();
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	root.find("#main").set_raw_html("<b>two</b>");
+	assert_eq!(
+		*records.borrow(),
+		vec![
+			"raw zone div: false",
+			"remove \"one\" from div at 0",
+			"insert \"<b>two</b>\" into div at 0",
+		]
+	);
+	// the raw zone is unmarked by the rollback
+	let main = root.find("#main").get(0).unwrap().cloned();
+	let result = doc.transaction(&mut |_| {
+		main.cloned().set_raw_zone(false)?;
+		Err("The markup is untrusted.".into())
+	});
+	assert!(result.is_err());
+	assert!(main.is_raw_zone());
+	doc
+		.transaction(&mut |_| {
+			let mut main = main.cloned();
+			main.set_raw_zone(false)?;
+			main.set_raw_zone(true)?;
+			Err("The markup is untrusted.".into())
+		})
+		.unwrap_err();
+	assert!(main.is_raw_zone());
+	doc
+		.transaction(&mut |tx| {
+			main.cloned().set_text("three");
+			tx.rollback()?;
+			main.cloned().set_raw_zone(false)?;
+			Ok(())
+		})
+		.unwrap();
+	assert!(!main.is_raw_zone());
+	assert_eq!(
+		main.outer_html(),
+		"<div id=\"main\">&lt;b&gt;two&lt;/b&gt;</div>"
+	);
+}
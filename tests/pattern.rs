@@ -0,0 +1,19 @@
+use mesdoc::selector::pattern::{Index, Pattern};
+
+// the chars matched by the index pattern
+fn matched_index(content: &str) -> Option<String> {
+	let chars: Vec<char> = content.chars().collect();
+	Index
+		.matched(&chars)
+		.map(|matched| matched.chars.iter().collect())
+}
+
+#[test]
+fn test_index_pattern() {
+	assert_eq!(matched_index("0").as_deref(), Some("0"));
+	assert_eq!(matched_index("12").as_deref(), Some("12"));
+	// the digit `9` is an index too
+	assert_eq!(matched_index("9").as_deref(), Some("9"));
+	assert_eq!(matched_index("19").as_deref(), Some("19"));
+	assert_eq!(matched_index("a"), None);
+}
@@ -0,0 +1,104 @@
+use mesdoc::error::Error;
+use mesdoc::rewrite::Rewriter;
+
+#[test]
+fn test_rewrite_attributes() {
+	mesdoc::init();
+	let html = concat!(
+		"<!DOCTYPE html><html><body><!-- <img data-src=\"no\"> -->",
+		"<img data-src=\"/a.png\" alt='a > b'><img src=\"/b.png\">",
+		"<p>keep <b class=x>the</b> text</p></body></html>"
+	);
+	let mut rewriter = Rewriter::new()
+		.on("img[data-src]", |ele| {
+			let src = ele.get_attribute("data-src").map(|value| value.to_string());
+			ele.set_attribute("src", src.as_deref());
+			ele.remove_attribute("data-src");
+		})
+		.on("p > b", |ele| {
+			ele.set_attribute("title", Some("\"bold\" & more"))
+		});
+	assert_eq!(
+		rewriter.rewrite(html).unwrap(),
+		concat!(
+			"<!DOCTYPE html><html><body><!-- <img data-src=\"no\"> -->",
+			"<img alt=\"a > b\" src=\"/a.png\"><img src=\"/b.png\">",
+			"<p>keep <b class=\"x\" title=\"&quot;bold&quot; &amp; more\">the</b> text</p></body></html>"
+		)
+	);
+	// the chunks split the tags and the chars
+	let mut output = String::new();
+	for chunk in ["<p>caf", "é <img da", "ta-src=\"/c.png\"", "/> <", "/p>"] {
+		output.push_str(&rewriter.write(chunk).unwrap());
+	}
+	output.push_str(&rewriter.end().unwrap());
+	assert_eq!(output, "<p>café <img src=\"/c.png\" /> </p>");
+}
+
+#[test]
+fn test_rewrite_structure() {
+	mesdoc::init();
+	let mut count = 0;
+	let mut rewriter = Rewriter::new()
+		.on("ul > li:nth-child(2n)", |ele| {
+			ele.set_attribute("class", Some("even"))
+		})
+		.on("li + li", |_| count += 1)
+		.remove("script, .ad");
+	let output = rewriter
+		.rewrite(concat!(
+			"<ul><li>1<li>2<li>3</li><li>4</ul>",
+			"<div class=\"ad\"><p>ad<script>var a = \"<div>\";</script></div>",
+			"<script>document.write(\"</p>\")</script><p>end"
+		))
+		.unwrap();
+	drop(rewriter);
+	assert_eq!(
+		output,
+		"<ul><li>1<li class=\"even\">2<li>3</li><li class=\"even\">4</ul><p>end"
+	);
+	assert_eq!(count, 3);
+	// the invalid selector
+	match Rewriter::new().on("li[", |_| {}).rewrite("<li>") {
+		Err(Error::InvalidSelector { .. }) => {}
+		_ => panic!("expect an invalid selector error"),
+	}
+}
+
+#[test]
+fn test_rewrite_to() {
+	mesdoc::init();
+	let html = format!("<div>{}</div>", "<a href=\"/x\">链接</a>".repeat(2000));
+	let mut output: Vec<u8> = Vec::new();
+	Rewriter::new()
+		.on("a", |ele| ele.set_attribute("rel", Some("nofollow")))
+		.rewrite_to(html.as_bytes(), &mut output)
+		.unwrap();
+	assert_eq!(
+		String::from_utf8(output).unwrap(),
+		format!(
+			"<div>{}</div>",
+			"<a href=\"/x\" rel=\"nofollow\">链接</a>".repeat(2000)
+		)
+	);
+}
+
+#[test]
+fn test_rewrite_foreign_content() {
+	mesdoc::init();
+	// the svg `style` and `title` contain elements, the svg names keep their case
+	let html = concat!(
+		"<svg><title><tspan>x</tspan></title><lineargradient id=g></lineargradient></svg>",
+		"<title><tspan>raw</tspan></title>"
+	);
+	let mut rewriter = Rewriter::new()
+		.on("svg tspan", |ele| ele.set_attribute("class", Some("t")))
+		.on("linearGradient", |ele| ele.set_attribute("id", Some("h")));
+	assert_eq!(
+		rewriter.rewrite(html).unwrap(),
+		concat!(
+			"<svg><title><tspan class=\"t\">x</tspan></title><linearGradient id=\"h\"></lineargradient></svg>",
+			"<title><tspan>raw</tspan></title>"
+		)
+	);
+}
@@ -0,0 +1,80 @@
+// the rules are global, so the tests are kept in their own binary
+mod common;
+use mesdoc::interface::{BoxDynElement, Elements, IDocumentTrait};
+use mesdoc::rules::{disable, override_rule, Registry};
+use mesdoc::selector::{
+	rule::{Matcher, MatcherData, Rule, RuleDefItem, RuleItem},
+	Selector,
+};
+
+// the rule matches the prefix of the attribute value
+fn prefix_rule(name: &'static str, context: &'static str, attr: &'static str) -> RuleItem {
+	RuleDefItem(
+		name,
+		context,
+		1000,
+		vec![("identity", 0)],
+		Box::new(move |data: MatcherData| {
+			let prefix = Rule::param(&data, "identity").unwrap().to_string();
+			Matcher {
+				one_handle: Some(Box::new(move |ele: &BoxDynElement, _| {
+					ele
+						.get_attribute(attr)
+						.is_some_and(|value| value.to_string().starts_with(&prefix))
+				})),
+				..Default::default()
+			}
+		}),
+	)
+	.into()
+}
+
+#[test]
+fn test_disable_and_override_rules() {
+	mesdoc::init();
+	let doc = common::parse("<div><p class=\"ab\" id=\"xy\">hello</p><p>world</p></div>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	assert_eq!(root.find("p:contains(world)").length(), 1);
+	// the registry owned by the caller doesn't change the global rules
+	let mut registry = Registry::new();
+	assert!(registry.disable(":contains"));
+	assert!(root
+		.find_with_rules("p:contains(world)", &registry)
+		.is_err());
+	assert_eq!(root.find("p:contains(world)").length(), 1);
+	assert!(!registry.disable("all"));
+	// the overridden rule keeps the registered name, not the item's name
+	assert!(registry.override_rule("id", prefix_rule("prefix", "#{identity}", "id")));
+	assert!(!registry.disable("prefix"));
+	assert_eq!(root.find_with_rules("#x", &registry).unwrap().length(), 1);
+	assert_eq!(root.find("#x").length(), 0);
+	assert!(registry.disable("id"));
+	assert!(root.find_with_rules("#x", &registry).is_err());
+	// disable the rule
+	assert!(disable(":contains"));
+	assert!(!disable(":contains"));
+	assert!("p:contains(world)".parse::<Selector>().is_err());
+	assert_eq!(root.find("p.ab").length(), 1);
+	assert_eq!(root.find("p.a").length(), 0);
+	// the `all` rule is required
+	assert!(!disable("all"));
+	assert_eq!(root.find("div > *").length(), 2);
+	// override the rule, `.name` matches the prefix of the class names
+	assert!(override_rule(
+		"class",
+		prefix_rule("class", ".{identity}", "class")
+	));
+	assert_eq!(root.find("p.a").length(), 1);
+	assert_eq!(root.find("p.b").length(), 0);
+	assert!(!override_rule(
+		":contains",
+		RuleDefItem(
+			":contains",
+			":contains",
+			10,
+			vec![],
+			Box::new(|_| Matcher::default())
+		)
+		.into()
+	));
+}
@@ -0,0 +1,963 @@
+mod common;
+use mesdoc::{
+	error::Error,
+	interface::{Elements, IDocumentTrait, SelectorStrategy},
+	selector::{
+		pattern::{to_pattern, MatchedValue},
+		rule::{add_rules, Matcher, MatcherData, Rule, RuleDefItem},
+		Selector, SelectorComplexity, SelectorList, SelectorWarningKind, SimpleSelectorKind,
+	},
+};
+
+// get the reason of the invalid selector
+fn reason(selector: &str) -> String {
+	match selector.parse::<Selector>() {
+		Err(Error::InvalidSelector { reason, .. }) => reason,
+		other => panic!("expect an invalid selector error, but got {:?}", other),
+	}
+}
+
+#[test]
+fn test_pseudo_element() {
+	mesdoc::init();
+	assert_eq!(
+		reason("p::before"),
+		"Pseudo-element '::before' at index 1 is not supported in matching"
+	);
+	assert_eq!(
+		reason("div > a:first-child::first-line"),
+		"Pseudo-element '::first-line' at index 19 is not supported in matching"
+	);
+	// the legacy syntax with one colon
+	assert_eq!(
+		reason("p:after"),
+		"Pseudo-element ':after' at index 1 is not supported in matching"
+	);
+	// the unknown pseudo classes are not pseudo elements
+	assert!(reason("p:unknown").starts_with("Unrecognized selector"));
+}
+
+#[test]
+fn test_visibility_pseudos() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><head><title>t</title></head><body>",
+		"<p id=\"a\">a</p><p id=\"b\" hidden>b</p>",
+		"<div style=\"color: red; display : None !important\"><p id=\"c\">c</p></div>",
+		"<div style=\"visibility:hidden\"><p id=\"d\">d</p><p id=\"e\" style=\"visibility: visible\">e</p></div>",
+		"<input id=\"f\" type=\"hidden\"><input id=\"g\" type=\"text\">",
+		"</body></html>"
+	));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	let ids = |selector: &str| -> Vec<String> {
+		root
+			.find(selector)
+			.get_ref()
+			.iter()
+			.filter_map(|ele| ele.get_attribute("id").map(|id| id.to_string()))
+			.collect()
+	};
+	assert_eq!(ids("p:hidden, input:hidden"), vec!["b", "c", "d", "f"]);
+	assert_eq!(ids("p:visible, input:visible"), vec!["a", "e", "g"]);
+	assert_eq!(root.find("head:hidden, title:hidden").length(), 2);
+	assert!(root.find("body").is(":visible"));
+}
+
+#[test]
+fn test_link_pseudos() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><a id=\"a\" href=\"/a\">a</a><a id=\"b\">b</a>",
+		"<map><area id=\"c\" href=\"/c\"><area id=\"d\"></map>",
+		"<link id=\"e\" href=\"/e.css\"></body></html>"
+	));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	for selector in [":any-link", ":link", "body :any-link"] {
+		let ids: Vec<String> = root
+			.find(selector)
+			.get_ref()
+			.iter()
+			.filter_map(|ele| ele.get_attribute("id").map(|id| id.to_string()))
+			.collect();
+		assert_eq!(ids, vec!["a", "c"]);
+	}
+	assert!(!root.find("#b").is(":link"));
+}
+
+#[test]
+fn test_target_pseudo() {
+	mesdoc::init();
+	let mut doc = common::parse(concat!(
+		"<html><body><h2 id=\"intro\">intro</h2><a name=\"more\">more</a>",
+		"<p id=\"caf\u{e9}\">caf\u{e9}</p></body></html>"
+	));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	// no url, no target
+	assert!(root.find(":target").is_empty());
+	let target_text = |doc: &mut common::Document, url: &str| -> String {
+		doc.set_url(url).unwrap();
+		root.find(":target").text().to_string()
+	};
+	assert_eq!(target_text(&mut doc, "https://a.com/#intro"), "intro");
+	assert_eq!(target_text(&mut doc, "https://a.com/page#more"), "more");
+	assert_eq!(
+		target_text(&mut doc, "https://a.com/#caf%C3%A9"),
+		"caf\u{e9}"
+	);
+	assert_eq!(target_text(&mut doc, "https://a.com/#"), "");
+	assert_eq!(target_text(&mut doc, "https://a.com/#none"), "");
+	doc.set_url("https://a.com/#intro").unwrap();
+	assert!(root.find("h2").is(":target"));
+	assert_eq!(root.find("body > :not(:target)").length(), 2);
+}
+
+#[test]
+fn test_trace() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><ul><li class=\"a\">1</li><li>2</li><li class=\"a\">3</li></ul>",
+		"<ol><li class=\"a\">4</li></ol></body></html>"
+	));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	let trace = root.trace("ul > li.a, p").unwrap();
+	assert_eq!(trace.result.text(), "13");
+	assert_eq!(trace.groups.len(), 2);
+	let steps: Vec<(&str, usize, usize, usize)> = trace.groups[0]
+		.steps
+		.iter()
+		.map(|step| {
+			(
+				step.rule.as_str(),
+				step.candidates,
+				step.matched.length(),
+				step.rejected.length(),
+			)
+		})
+		.collect();
+	// the descendants: html, body, ul, li * 3, ol, li
+	assert_eq!(
+		steps,
+		vec![("ul", 8, 1, 7), (".a", 3, 2, 1), ("li", 2, 2, 0)]
+	);
+	assert_eq!(trace.groups[0].steps[1].rejected.text(), "2");
+	// the group without result stops at the empty step
+	assert_eq!(trace.empty_step().map(|step| step.rule.as_str()), Some("p"));
+	assert!(trace
+		.to_string()
+		.contains("Children '.a': 3 candidates, 2 matched, 1 rejected"));
+	assert!(root.trace("ul >").is_err());
+}
+
+#[test]
+fn test_selector_list() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><div id=\"main\" class=\"box wide\"><p class=\"note\">a</p><p>b</p></div>",
+		"<span class=\"note\">c</span></body></html>"
+	));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	let selectors = [
+		"p",
+		".note",
+		"#main",
+		"div.box > p:first-child",
+		"*",
+		":not(p)",
+		"span, #main",
+		"div .note",
+		"P.note",
+	];
+	let list = SelectorList::new(selectors).unwrap();
+	assert_eq!(list.len(), selectors.len());
+	// same as checking the selectors one by one
+	for ele in root.find("*").get_ref() {
+		let expected: Vec<usize> = selectors
+			.iter()
+			.enumerate()
+			.filter(|(_, selector)| Elements::with_nodes(vec![ele.cloned()]).is(selector))
+			.map(|(index, _)| index)
+			.collect();
+		assert_eq!(list.match_element(ele), expected);
+	}
+	let first_p = root.find("p").get(0).unwrap().cloned();
+	assert_eq!(list.match_element(&first_p), vec![0, 1, 3, 4, 7, 8]);
+	assert!(SelectorList::new(["p", "p >"]).is_err());
+}
+
+#[test]
+fn test_rewrite_selectors() {
+	mesdoc::init();
+	assert_eq!(
+		Selector::prefix("a.b, ul > li", ".widget").unwrap(),
+		".widget a.b, .widget ul > li"
+	);
+	assert_eq!(
+		Selector::rename_classes("div.a > p.b:not(.a), [class='a']", |name| format!(
+			"x-{}",
+			name
+		))
+		.unwrap(),
+		"div.x-a > p.x-b:not(.x-a), [class='a']"
+	);
+	// remove the ids, the emptied compound selector becomes `*`
+	let kinds = std::cell::RefCell::new(Vec::new());
+	let mapped = Selector::map_simple_selectors("#main > p:first-child, #side", |simple| {
+		kinds
+			.borrow_mut()
+			.push((simple.kind, simple.name().to_string()));
+		match simple.kind {
+			SimpleSelectorKind::Id => None,
+			_ => Some(simple.text.to_uppercase()),
+		}
+	})
+	.unwrap();
+	assert_eq!(mapped, "* > P:FIRST-CHILD, *");
+	assert_eq!(
+		kinds.into_inner(),
+		vec![
+			(SimpleSelectorKind::Id, String::from("main")),
+			(SimpleSelectorKind::Type, String::from("p")),
+			(SimpleSelectorKind::PseudoClass, String::from("first-child")),
+			(SimpleSelectorKind::Id, String::from("side")),
+		]
+	);
+	assert_eq!(
+		Selector::strip_unsupported_pseudos(
+			"a:hover, li:first-child::before, :focus > [title=':hover']"
+		)
+		.unwrap(),
+		"a, li:first-child, * > [title=':hover']"
+	);
+	assert!(Selector::prefix("a >", ".widget").is_err());
+}
+
+#[test]
+fn test_retain_and_drain() {
+	mesdoc::init();
+	let doc = common::parse("<ul><li id=\"a\"></li><li id=\"b\" class=\"x\"></li><li id=\"c\"></li><li id=\"d\" class=\"x\"></li><li id=\"e\"></li></ul>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let ids = |eles: &Elements| -> Vec<String> {
+		eles.map(|_, ele| ele.get_attribute("id").unwrap().to_string())
+	};
+	let mut items = root.find("li");
+	let capacity = items.get_ref().capacity();
+	items.retain(|index, ele| index != 0 && !ele.has_attribute("class"));
+	assert_eq!(ids(&items), vec!["c", "e"]);
+	// the buffer is reused
+	assert_eq!(items.get_ref().capacity(), capacity);
+	items.truncate(1);
+	assert_eq!(ids(&items), vec!["c"]);
+	let mut items = root.find("li");
+	assert_eq!(ids(&items.drain(1..=2)), vec!["b", "c"]);
+	assert_eq!(ids(&items), vec!["a", "d", "e"]);
+	assert_eq!(ids(&items.drain(2..10)), vec!["e"]);
+	assert!(items.drain(5..).is_empty());
+	assert_eq!(ids(&items.drain(..)), vec!["a", "d"]);
+	assert!(items.is_empty());
+}
+
+#[test]
+fn test_node_identity() {
+	mesdoc::init();
+	let doc = common::parse("<ul><li id=\"a\"></li><li id=\"b\"></li><li id=\"c\"></li></ul>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let items = root.find("li");
+	let second = root.find("#b").get(0).unwrap().cloned();
+	assert!(items.get(1).unwrap() == &second);
+	assert!(items.get(0).unwrap() != &second);
+	assert_eq!(items.position_of(&second), Some(1));
+	assert_eq!(root.find("ul").position_of(&second), None);
+	assert!(items.eq_node(&root.find("ul > li")));
+	assert!(!items.eq_node(&items.eq(1)));
+	let reversed = Elements::with_nodes(
+		items
+			.get_ref()
+			.iter()
+			.rev()
+			.map(|ele| ele.cloned())
+			.collect(),
+	);
+	assert!(!items.eq_node(&reversed));
+}
+
+#[test]
+fn test_builtin_patterns() {
+	mesdoc::init();
+	let doc = common::parse(&format!(
+		"<div><p title=\"a b\" lang=\"en-US\"><b>bold</b></p>{}</div>",
+		"<p>c</p>".repeat(11)
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let count = |selector: &str| root.find(selector).length();
+	// the numbers with more than one digit
+	assert_eq!(count("p:nth-child(10)"), 1);
+	assert_eq!(count("p:nth-child(2n+10)"), 2);
+	assert_eq!(count("p:nth-child( -n + 3 )"), 3);
+	assert_eq!(count("p:nth-child(12n)"), 1);
+	assert_eq!(count("p:nth-child(odd)"), 6);
+	// the attribute values
+	assert_eq!(count("p[title='a b']"), 1);
+	assert_eq!(count("p[title = \"a b\" ]"), 1);
+	assert_eq!(count("p[lang|=en]"), 1);
+	assert_eq!(count("p[title~=b]"), 1);
+	assert_eq!(count("p[title='']"), 0);
+	assert_eq!(count("p[title='a\\20 b']"), 1);
+	assert_eq!(count("p[title=\"\\61 \\ b\"]"), 1);
+	// the quoted and the unquoted text
+	assert_eq!(count("p:contains(bold)"), 1);
+	assert_eq!(count("p:contains( \"bo\" )"), 1);
+	assert_eq!(count("p:contains()"), 12);
+	assert_eq!(count("p:contains('\\'bold')"), 0);
+	assert_eq!(count("p:contains(\"b\\6F ld\")"), 1);
+	// the languages
+	assert_eq!(count("p:lang(en)"), 1);
+	assert_eq!(count("p:lang( 'EN-us' )"), 1);
+	assert_eq!(count("p:lang(e)"), 0);
+	assert_eq!(count("div:lang(en)"), 0);
+	assert_eq!(count("b:lang(en-us)"), 1);
+	// the combinators with the spaces around
+	assert_eq!(count("div > p"), 12);
+	assert_eq!(count("p:first-child~p"), 11);
+}
+
+#[test]
+fn test_pattern_combinators() {
+	mesdoc::init();
+	let matched = |name: &str, s: &str, p: &str, content: &str| {
+		let chars: Vec<char> = content.chars().collect();
+		to_pattern(name, s, p)
+			.unwrap()
+			.matched(&chars)
+			.map(|matched| (matched.chars.iter().collect::<String>(), matched.data))
+	};
+	// quoted
+	let (chars, data) = matched("quoted", "", "", "'a\\'b\\\n'c").unwrap();
+	assert_eq!(
+		(chars.as_str(), data["value"].as_str()),
+		("'a\\'b\\\n'", Some("a'b"))
+	);
+	assert!(matched("quoted", "", "", "'a").is_none());
+	// optional
+	let (chars, data) = matched("optional", "", "{spaces}+{index}", " +12px").unwrap();
+	assert_eq!(
+		(chars.as_str(), data["index"].as_str()),
+		(" +12", Some("12"))
+	);
+	assert_eq!(data["#matched"], MatchedValue::Bool(true));
+	let (chars, data) = matched("optional", "", "+{index}", "-1").unwrap();
+	assert_eq!(
+		(chars.as_str(), data["#matched"].as_bool()),
+		("", Some(false))
+	);
+	// nth
+	let (_, data) = matched("nth", "", "", "-2n + 13").unwrap();
+	assert_eq!(
+		(data["n"].as_int(), data["index"].as_int()),
+		(Some(-2), Some(13))
+	);
+	let (_, data) = matched("nth", "", "", "odd").unwrap();
+	assert_eq!(
+		(data["n"].to_string(), data["index"].to_string()),
+		("2".into(), "1".into())
+	);
+	// repeat
+	let (chars, data) = matched("repeat", "(1,)", ",{identity}", ",a,b,c;").unwrap();
+	assert_eq!(chars, ",a,b,c");
+	assert_eq!(
+		(
+			data["#count"].as_int(),
+			data["identity"].as_str(),
+			data["identity[2]"].as_str()
+		),
+		(Some(3), Some("a"), Some("c"))
+	);
+	assert_eq!(
+		matched("repeat", "(0,2)", ",{identity}", ",a,b,c")
+			.unwrap()
+			.0,
+		",a,b"
+	);
+	assert!(matched("repeat", "(2)", ",{identity}", ",a").is_none());
+	assert!(to_pattern("repeat", "(3,1)", "a").is_err());
+	// alt
+	let (chars, data) = matched("alt", "", "a\\|b|{index}px", "12px").unwrap();
+	assert_eq!(
+		(
+			chars.as_str(),
+			data["#index"].as_int(),
+			data["index"].as_str()
+		),
+		("12px", Some(1), Some("12"))
+	);
+	assert_eq!(
+		matched("alt", "", "a\\|b|{index}px", "a|b").unwrap().0,
+		"a|b"
+	);
+	assert!(matched("alt", "", "a|b", "c").is_none());
+	// a rule with the nested combinators, `:attrs(any title, lang)`
+	add_rules(vec![RuleDefItem(
+		":attrs",
+		":attrs({spaces}{optional##{alt#all|any#}{spaces(1)}##}{attr_key}{repeat(0,)#{spaces},{spaces}{attr_key}#}{spaces})",
+		10,
+		vec![("optional", 0), ("attr_key", 0), ("repeat", 0)],
+		Box::new(|data: MatcherData| {
+			let is_any = Rule::param_int(&data, ("optional", 0, "alt.#index")) == Some(1);
+			let mut names = vec![Rule::param(&data, "attr_key").unwrap().to_string()];
+			let count = Rule::param_int(&data, ("repeat", 0, "#count")).unwrap();
+			for index in 0..count {
+				let key = if index == 0 {
+					String::from("attr_key")
+				} else {
+					format!("attr_key[{}]", index)
+				};
+				names.push(Rule::param(&data, ("repeat", 0, key)).unwrap().to_string());
+			}
+			Matcher {
+				one_handle: Some(Box::new(move |ele, _| {
+					if is_any {
+						names.iter().any(|name| ele.has_attribute(name))
+					} else {
+						names.iter().all(|name| ele.has_attribute(name))
+					}
+				})),
+				..Default::default()
+			}
+		}),
+	)
+	.into()]);
+	let doc = common::parse("<p title=\"a\" lang=\"en\"></p><p title=\"b\"></p><p id=\"c\"></p>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	assert_eq!(root.find("p:attrs(title, lang)").length(), 1);
+	assert_eq!(root.find("p:attrs( all title,lang )").length(), 1);
+	assert_eq!(root.find("p:attrs(any title , id)").length(), 3);
+	assert_eq!(root.find("p:attrs(title)").length(), 2);
+}
+
+#[test]
+fn test_nested_selector() {
+	mesdoc::init();
+	let doc = common::parse("<div><p title=\"a)b\">a</p><p class=\"x\">b</p><span>c</span></div>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let count = |selector: &str| root.find(selector).length();
+	assert_eq!(count("div > :not(p)"), 1);
+	assert_eq!(count("div > :not( p.x , span )"), 1);
+	assert_eq!(count("div > :not(:not(p))"), 2);
+	// the brackets in the quoted strings are skipped
+	assert_eq!(count("p:not([title='a)b'])"), 1);
+	assert_eq!(count("p:not([title=\"a)b\"], :nth-child(2))"), 0);
+	// the nested selectors are checked
+	let reason = |selector: &str| match selector.parse::<Selector>() {
+		Err(Error::InvalidSelector { reason, context }) => {
+			assert_eq!(context, selector);
+			reason
+		}
+		other => panic!("expect an invalid selector error, but got {:?}", other),
+	};
+	assert!(reason("p:not(p[)").starts_with("Unrecognized selector"));
+	assert!(reason("p:not()").starts_with("Unrecognized selector"));
+	assert!(reason("p:not(:unknown)").ends_with("in the nested selector ':unknown'"));
+	assert!(reason("p:not(p").starts_with("Unrecognized selector"));
+	// the depth is limited
+	let nested = |depth: usize| format!("{}p{}", ":not(".repeat(depth), ")".repeat(depth));
+	assert!(nested(32).parse::<Selector>().is_ok());
+	assert!(reason(&nested(33)).starts_with("The selectors are nested more than 32 levels"));
+}
+
+#[test]
+fn test_selector_warnings() {
+	mesdoc::init();
+	let warnings = |selector: &str| {
+		let mut warnings = Vec::new();
+		Selector::from_str_with_warnings(selector, true, &mut warnings).unwrap();
+		warnings
+			.iter()
+			.map(|warning| (warning.kind, warning.span))
+			.collect::<Vec<_>>()
+	};
+	assert!(warnings("div > p.a, *").is_empty());
+	assert!(warnings("* > *").is_empty());
+	// the redundant universal selectors
+	assert_eq!(
+		warnings("*.a, p *[title]"),
+		vec![
+			(SelectorWarningKind::RedundantUniversal, (0, 1)),
+			(SelectorWarningKind::RedundantUniversal, (7, 8))
+		]
+	);
+	assert_eq!(
+		warnings("p*"),
+		vec![(SelectorWarningKind::RedundantUniversal, (1, 2))]
+	);
+	// the non-standard syntax, the spans of the nested selectors are in the whole selector
+	assert_eq!(
+		warnings("a[href!='#']:not(p:contains(a))"),
+		vec![
+			(SelectorWarningKind::NonStandard, (1, 12)),
+			(SelectorWarningKind::NonStandard, (18, 30))
+		]
+	);
+	let mut sink = Vec::new();
+	Selector::from_str_with_warnings(":header", true, &mut sink).unwrap();
+	assert_eq!(
+		sink[0].to_string(),
+		"non-standard at index 0: The pseudo class ':header' is a jquery extension"
+	);
+	// the errors are still returned
+	assert!(Selector::from_str_with_warnings("*.a[", true, &mut Vec::new()).is_err());
+}
+
+#[test]
+fn test_selector_complexity() {
+	mesdoc::init();
+	let complexity = |selector: &str| Selector::from_str(selector, false).unwrap().complexity();
+	let score = |selector: &str| complexity(selector).score;
+	assert_eq!(
+		complexity("p"),
+		SelectorComplexity {
+			score: 1,
+			..Default::default()
+		}
+	);
+	assert_eq!(score("div p"), 4);
+	assert_eq!(score("*.a"), 1);
+	assert_eq!(score("ul > li, a ~ b"), 6);
+	assert_eq!(
+		complexity("* *"),
+		SelectorComplexity {
+			combinator_depth: 1,
+			universals: 2,
+			score: 8,
+			..Default::default()
+		}
+	);
+	assert_eq!(
+		complexity(":not(:not(div p)) b"),
+		SelectorComplexity {
+			combinator_depth: 2,
+			nesting_depth: 2,
+			nested_selectors: 2,
+			universals: 0,
+			score: 14,
+		}
+	);
+	// the pathological selectors cost much more
+	assert!(score("* * * * * * * * a") > score("#main .item > a") * 100);
+}
+
+#[test]
+fn test_query_selector() {
+	mesdoc::init();
+	let doc = common::parse(
+		"<div id=\"main\"><a href=\"#a\">a</a><p><a href=\"#b\">b</a><a>c</a></p></div><a href=\"#d\">d</a>",
+	);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let main = root.find("#main").get(0).unwrap().cloned();
+	// only the descendants in document order
+	assert_eq!(main.query_selector_all("a[href]").text(), "ab");
+	assert_eq!(main.query_selector("p a").unwrap().text_content(), "b");
+	assert!(main.query_selector("div").is_none());
+	assert!(main.query_selector_all("#main a").length() == 3);
+	// the invalid selector matches nothing
+	assert!(main.query_selector("a[").is_none());
+}
+
+#[test]
+fn test_document_from_element() {
+	mesdoc::init();
+	let doc = common::parse("<div id=\"main\"><p><b>x</b></p></div><a href=\"#d\">d</a>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let bold = root.find("b").get(0).unwrap().cloned();
+	// the queries from the deep element are matched in the whole document
+	assert_eq!(bold.document().find("a[href]").text(), "d");
+	assert_eq!(bold.document().find("#main > p").length(), 1);
+	assert_eq!(bold.owner_document().unwrap().find("div b").length(), 1);
+	assert_eq!(doc.find("a").length(), 1);
+}
+
+#[test]
+fn test_find_in_nested_scopes() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div class=\"o\"><p>1</p><div class=\"i\"><p>2</p><span><p>3</p></span></div><p>4</p></div>",
+		"<p>5</p>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let texts = |eles: &Elements| {
+		eles
+			.get_ref()
+			.iter()
+			.map(|ele| ele.text_content().to_string())
+			.collect::<Vec<_>>()
+	};
+	let outer = root.find(".o").get(0).unwrap().cloned();
+	let inner = root.find(".i").get(0).unwrap().cloned();
+	// the scopes are ancestors of each other, in any order and repeated
+	let scopes = Elements::with_nodes(vec![inner.cloned(), outer.cloned(), inner.cloned()]);
+	assert_eq!(texts(&scopes.find("p")), vec!["1", "2", "3", "4"]);
+	assert_eq!(texts(&scopes.find("span p, div p")), vec!["2", "3"]);
+	// the selector is matched in the descendants of the scope
+	let inner_scope = Elements::with_nodes(vec![inner.cloned()]);
+	assert!(inner_scope.find(".o p").is_empty());
+	assert_eq!(texts(&inner_scope.find("span p")), vec!["3"]);
+	// the first found element
+	assert_eq!(texts(&scopes.find_first("p")), vec!["1"]);
+	assert_eq!(
+		texts(&scopes.find_first("span > p, p:last-child")),
+		vec!["3"]
+	);
+	assert_eq!(texts(&scopes.find_first("div p")), vec!["2"]);
+	assert_eq!(texts(&inner_scope.find_first("p ~ span p")), vec!["3"]);
+	assert!(inner_scope.find_first(".o p").is_empty());
+	assert!(scopes.find_first("a").is_empty());
+	// the last child in a part of the siblings
+	assert_eq!(texts(&root.find("p:last-child")), vec!["3", "4", "5"]);
+	assert_eq!(texts(&root.find("p:nth-last-child(3)")), vec!["1"]);
+	for selector in ["p", "div p", "span p, p:first-child", "p + div p", "* > p"] {
+		assert_eq!(
+			texts(&scopes.find_first(selector)),
+			texts(&scopes.find(selector).first()),
+			"find_first('{}')",
+			selector
+		);
+	}
+}
+
+#[test]
+fn test_has_and_has_in() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div class=\"o\"><p>1</p><div class=\"i\"><span><p class=\"x\">2</p></span></div></div>",
+		"<div class=\"e\"></div>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let divs = root.find("div");
+	let classes = |eles: Elements| {
+		eles
+			.get_ref()
+			.iter()
+			.map(|ele| ele.get_attribute("class").unwrap().to_string())
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(classes(divs.has("p")), vec!["o", "i"]);
+	assert_eq!(classes(divs.has("div")), vec!["o"]);
+	assert_eq!(classes(divs.has("span > .x, a")), vec!["o", "i"]);
+	// the descendants are matched in the whole document
+	assert_eq!(classes(divs.has(".o p")), vec!["o", "i"]);
+	assert!(divs.has("a").is_empty());
+	assert!(divs.has("p[").is_empty());
+	// has the elements
+	assert_eq!(classes(divs.has_in(&root.find(".x"))), vec!["o", "i"]);
+	assert_eq!(classes(divs.has_in(&root.find(".i"))), vec!["o"]);
+	// the element itself is not its descendant
+	assert!(divs.has_in(&root.find(".o")).is_empty());
+	assert!(divs.has_in(&Elements::new()).is_empty());
+}
+
+#[test]
+fn test_count_and_exists() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<ul><li class=\"a\">1</li><li>2<ul><li class=\"a\">3</li><li>4</li></ul></li></ul>",
+		"<p>5</p>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let lists = root.find("ul");
+	for selector in [
+		"li",
+		".a",
+		"li:first-child, p",
+		"ul li",
+		"li > ul > li",
+		"li ~ li",
+		"p",
+	] {
+		assert_eq!(
+			lists.count(selector),
+			lists.find(selector).length(),
+			"count('{}')",
+			selector
+		);
+		assert_eq!(
+			lists.exists(selector),
+			!lists.find(selector).is_empty(),
+			"exists('{}')",
+			selector
+		);
+	}
+	assert_eq!(root.count("li.a"), 2);
+	assert!(root.exists("ul ul .a"));
+	assert!(!root.exists("ol"));
+	// the invalid selector counts nothing
+	assert_eq!(root.count("li["), 0);
+	assert!(!Elements::new().exists("li"));
+}
+
+#[test]
+fn test_find_like() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div class=\"card featured\" id=\"c1\"><h2> First   post </h2><i></i>",
+		"<a href=\"/posts/1\">Read</a></div>",
+		"<div class=\"card\" id=\"c2\"><h2>Second post</h2><a href=\"/posts/2\">Read</a></div>",
+		"<div class=\"card\" id=\"c3\"><a href=\"/posts/3\">Read</a><h2>Third post</h2></div>",
+		"<div id=\"c4\"><h2>Fourth post</h2><a href=\"/posts/4\">Read</a></div>"
+	));
+	let matches = doc
+		.find_like("<div class=\"card\"><h2>{title}</h2><a href=\"/posts/{id}\">Read</a></div>")
+		.unwrap();
+	let found: Vec<(String, String, String)> = matches
+		.iter()
+		.map(|item| {
+			(
+				item.element.get_attribute("id").unwrap().to_string(),
+				item.captures["title"].clone(),
+				item.captures["id"].clone(),
+			)
+		})
+		.collect();
+	// the children are matched in order, the classes are contained
+	assert_eq!(
+		found,
+		vec![
+			(
+				String::from("c1"),
+				String::from("First post"),
+				String::from("1")
+			),
+			(
+				String::from("c2"),
+				String::from("Second post"),
+				String::from("2")
+			),
+		]
+	);
+	// the wildcards
+	assert_eq!(doc.find_like("<* id=\"c*\"><a>*</a></*>").unwrap().len(), 4);
+	assert_eq!(
+		doc.find_like("<div><h2>* post</h2></div>").unwrap().len(),
+		4
+	);
+	assert_eq!(
+		doc
+			.find_like("<div><h2>Fifth post</h2></div>")
+			.unwrap()
+			.len(),
+		0
+	);
+	// the example must have one root element
+	for example in ["<h2></h2><a></a>", "text", ""] {
+		match mesdoc::extract::find_like(&Elements::new(), example) {
+			Err(Error::InvalidPattern { .. }) => {}
+			_ => panic!("expect an invalid pattern error: {:?}", example),
+		}
+	}
+}
+
+#[test]
+fn test_build_selector() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<div id=\"main\"><ul><li class=\"item\">1</li><li class=\"item active\">2</li>",
+		"<li class=\"item\">3</li></ul></div>",
+		"<div><p id=\"1st\">a</p><p>b</p><span>c</span></div>"
+	));
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let cases = [
+		("#main", "#main"),
+		(".active", "li.active"),
+		("li:last-child", "li:nth-child(3)"),
+		("p:first-child", "[id=\"1st\"]"),
+		("span", "span"),
+		("p:nth-child(2)", "p:nth-child(2)"),
+	];
+	for (selector, expected) in cases {
+		let ele = root.find(selector).get(0).unwrap().cloned();
+		let built = ele.build_selector(SelectorStrategy::Shortest);
+		assert_eq!(built, expected, "build_selector of '{}'", selector);
+		// the built selectors match only the element
+		let found = root.find(&built);
+		assert_eq!(found.length(), 1);
+		assert!(found.get(0).unwrap().is(&ele));
+	}
+	// the path doesn't use the ids and the classes
+	let ele = root.find(".active").get(0).unwrap().cloned();
+	let path = ele.build_selector(SelectorStrategy::Path);
+	assert_eq!(path, "div:nth-child(1) > ul:nth-child(1) > li:nth-child(2)");
+	assert!(root.find(&path).get(0).unwrap().is(&ele));
+}
+
+#[test]
+fn test_detached_subtree() {
+	mesdoc::init();
+	let doc = common::parse("<div id=\"main\"><p id=\"x\" class=\"a\"><b>x</b></p></div>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let main = root.find("#main").get(0).unwrap().cloned();
+	assert!(main.is_connected());
+	// the copy is not in the document, the id is found in the copy rather than the document
+	let mut copy = main.clone_deep().unwrap();
+	assert!(!copy.is_connected());
+	copy.set_attribute("class", Some("copy"));
+	let copies = Elements::with_nodes(vec![copy.cloned()]);
+	let found = copies.find("#x");
+	assert_eq!(found.length(), 1);
+	assert!(!found.get(0).unwrap().is_connected());
+	assert!(!found.get(0).unwrap().is(root.find("#x").get(0).unwrap()));
+	assert_eq!(copies.find("#main").length(), 0);
+	assert_eq!(copies.find("#x > b").text(), "x");
+	assert_eq!(copies.find("p#x.a").length(), 1);
+	assert_eq!(copies.filter("#main").length(), 1);
+	assert_eq!(copies.find("#y").length(), 0);
+	// the removed element is detached too
+	let mut main = main;
+	let p = root.find("#x").get(0).unwrap().cloned();
+	main.remove_child(p.cloned());
+	assert!(!p.is_connected());
+	assert_eq!(Elements::with_nodes(vec![p.cloned()]).find("b").length(), 1);
+	assert_eq!(
+		Elements::with_nodes(vec![p.cloned()]).filter("#x").length(),
+		1
+	);
+	assert_eq!(root.find("#x").length(), 0);
+}
+
+#[test]
+fn test_traversal_filter_expression() {
+	mesdoc::init();
+	let doc = common::parse(
+		"<div id=\"box\"><p class=\"a\"></p><span></span><p class=\"b\"></p></div><section><p class=\"c\"></p></section>",
+	);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let box_ele = root.find("#box");
+	// the filter expression matches against the whole document, as jquery's
+	assert_eq!(box_ele.children("div p").length(), 2);
+	assert_eq!(box_ele.children("div > .b").length(), 1);
+	assert_eq!(box_ele.children(" ").length(), 3);
+	let first = root.find(".a");
+	assert_eq!(first.siblings("p").length(), 1);
+	assert_eq!(first.siblings("").length(), 2);
+	assert_eq!(first.next("span").length(), 1);
+	assert_eq!(first.next("p").length(), 0);
+	assert_eq!(root.find(".c").parents("section").length(), 1);
+	assert_eq!(root.find(".c").parents("body section").length(), 0);
+	assert_eq!(root.find("span").prev(".a").length(), 1);
+}
+
+#[test]
+fn test_group_and_partition() {
+	mesdoc::init();
+	let doc = common::parse(
+		"<table><tr data-kind=\"a\"></tr><tr data-kind=\"b\" class=\"on\"></tr><tr data-kind=\"a\" class=\"on\"></tr><tr></tr></table>",
+	);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let rows = root.find("tr");
+	let groups = rows.group_by(|ele| {
+		ele
+			.get_attribute("data-kind")
+			.map(|value| value.to_string())
+	});
+	let keys: Vec<_> = groups.iter().map(|(key, _)| key.clone()).collect();
+	assert_eq!(
+		keys,
+		vec![Some(String::from("a")), Some(String::from("b")), None]
+	);
+	assert_eq!(groups[0].1.length(), 2);
+	assert!(groups[0].1.get(1).unwrap() == rows.get(2).unwrap());
+	let (on, off) = rows.partition(".on");
+	assert_eq!(on.length(), 2);
+	assert_eq!(off.length(), 2);
+	assert!(off.eq_node(&rows.not(".on")));
+	let (all, none) = rows.partition("tr");
+	assert_eq!((all.length(), none.length()), (4, 0));
+}
+
+#[test]
+fn test_common_ancestor() {
+	mesdoc::init();
+	let doc = common::parse(
+		"<div id=\"main\"><ul id=\"list\"><li><a></a></li><li><a></a></li></ul><p></p></div>",
+	);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let ancestor = root.find("a").common_ancestor();
+	assert_eq!(
+		ancestor.attr("id").map(|id| id.to_string()),
+		Some(String::from("list"))
+	);
+	let ancestor = root.find("li, p").common_ancestor();
+	assert_eq!(
+		ancestor.attr("id").map(|id| id.to_string()),
+		Some(String::from("main"))
+	);
+	// an element contains itself
+	let ancestor = root.find("#list, li").common_ancestor();
+	assert_eq!(
+		ancestor.attr("id").map(|id| id.to_string()),
+		Some(String::from("list"))
+	);
+	assert!(root.find("p").common_ancestor().eq_node(&root.find("p")));
+	assert!(root.find("span").common_ancestor().is_empty());
+	let other = common::parse("<p></p>");
+	let other_root =
+		Elements::with_nodes(vec![other.get_root_node().typed().into_element().unwrap()]);
+	assert!(root
+		.find("p")
+		.add(other_root.find("p"))
+		.common_ancestor()
+		.is_empty());
+}
+
+#[test]
+fn test_repeating_items() {
+	mesdoc::init();
+	let doc = common::parse(
+		r#"<div id="page"><nav><a>Home</a><a>About</a></nav><ul id="list"><li class="card"><h2>One</h2><p>1</p></li><li class="card"><h2>Two</h2><p>2</p><p>3</p></li><li class="card featured"><h2>Three</h2></li><li class="card"><h2>Four</h2><p>4</p></li><script></script><script></script></ul><br><br></div>"#,
+	);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let groups = mesdoc::extract::repeating_items(&root.find("#page"));
+	// the cards with the same children shapes are grouped, the featured card is not
+	let top = &groups[0];
+	assert_eq!(
+		top.container.get_attribute("id").unwrap().to_string(),
+		"list"
+	);
+	assert_eq!(top.items.length(), 3);
+	assert_eq!(top.score, 3 + 7);
+	assert!(top.items.eq_node(&root.find("li.card:not(.featured)")));
+	// the links of the nav and the paragraphs in the second card
+	assert_eq!(groups.len(), 3);
+	assert_eq!(groups[1].items.length(), 2);
+	assert_eq!(groups[1].container.tag_name(), "NAV");
+	assert_eq!(groups[2].items.text(), "23");
+	assert!(groups.iter().all(|group| !group.items.is("script, br")));
+}
+
+#[test]
+fn test_negative_nth_in_document_order() {
+	mesdoc::init();
+	let doc = common::parse("<ul><li>1</li><li>2</li><li>3</li><li>4</li><li>5</li></ul>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let texts = |eles: Elements| {
+		eles
+			.get_ref()
+			.iter()
+			.map(|ele| ele.text_content().to_string())
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(texts(root.find("li:nth-child(-n+3)")), ["1", "2", "3"]);
+	assert_eq!(texts(root.find("li:nth-child(-2n+5)")), ["1", "3", "5"]);
+	assert_eq!(texts(root.find("li:nth-last-child(-n+2)")), ["4", "5"]);
+	assert_eq!(
+		texts(root.find("li").filter(":nth-child(-n+3)")),
+		["1", "2", "3"]
+	);
+	assert_eq!(
+		texts(root.find("li:nth-child(2) ~ li:nth-child(-n+4)")),
+		["3", "4"]
+	);
+	assert_eq!(texts(root.find_first("li:nth-child(-n+3)")), ["1"]);
+	assert_eq!(root.count("li:nth-child(-2n+5)"), 3);
+}
@@ -0,0 +1,80 @@
+// the limits are global, so the tests are kept in their own binary
+use mesdoc::error::Error;
+mod common;
+use mesdoc::interface::{Elements, IDocumentTrait};
+use mesdoc::selector::{limits, set_limits, with_budget, QueryBudget, Selector, SelectorLimits};
+use std::time::Duration;
+
+#[test]
+fn test_selector_limits() {
+	mesdoc::init();
+	let reason = |selector: &str| match selector.parse::<Selector>() {
+		Err(Error::InvalidSelector { reason, .. }) => reason,
+		other => panic!("expect an invalid selector error, but got {:?}", other),
+	};
+	let nested = |depth: usize| format!("{}p{}", ":not(".repeat(depth), ")".repeat(depth));
+	// the unbalanced brackets are invalid
+	assert!("p[title".parse::<Selector>().is_err());
+	assert!(":not(:not(p)".parse::<Selector>().is_err());
+	// the default limits
+	assert_eq!(limits(), SelectorLimits::default());
+	assert!(nested(32).parse::<Selector>().is_ok());
+	assert!(reason(&nested(600)).starts_with("The selectors are nested more than 32 levels"));
+	let long = format!("p{}", ".a".repeat(2048));
+	assert!(reason(&long).starts_with("The selector is longer than 4096 chars"));
+	// the custom limits
+	set_limits(SelectorLimits {
+		max_depth: 2,
+		max_length: 8192,
+	});
+	assert!(nested(2).parse::<Selector>().is_ok());
+	assert!(reason(&nested(3)).starts_with("The selectors are nested more than 2 levels"));
+	assert!(long.parse::<Selector>().is_ok());
+	set_limits(SelectorLimits::default());
+	assert!(nested(3).parse::<Selector>().is_ok());
+}
+
+#[test]
+fn test_query_budget() {
+	mesdoc::init();
+	let html = format!("<div>{}</div>", "<p><b><i>x</i></b></p>".repeat(100));
+	let doc = common::parse(&html);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let selector = "* :not(b) *";
+	let expected = root.find(selector).length();
+	assert_eq!(expected, 200);
+	// the generous budget gets the same elements
+	let budget = QueryBudget {
+		max_visits: 1_000_000,
+		max_duration: Some(Duration::from_secs(60)),
+	};
+	assert_eq!(
+		root.find_with_budget(selector, &budget).unwrap().length(),
+		expected
+	);
+	// the query is aborted once it checks too many elements
+	let budget = QueryBudget {
+		max_visits: 500,
+		..Default::default()
+	};
+	match root.find_with_budget(selector, &budget) {
+		Err(Error::QueryBudgetExceeded { reason }) => {
+			assert_eq!(reason, "visited more than 500 elements")
+		}
+		other => panic!(
+			"expect the budget exceeded, but got {:?}",
+			other.map(|eles| eles.length())
+		),
+	}
+	assert!(matches!(
+		root.find_with_budget("p[", &budget),
+		Err(Error::InvalidSelector { .. })
+	));
+	// any queries can be budgeted, the budget ends with the handle
+	let result = with_budget(&budget, || {
+		(root.count("p"), root.find("* :not(p) i").length())
+	});
+	assert!(result.is_err());
+	assert_eq!(root.find(selector).length(), expected);
+	assert_eq!(with_budget(&budget, || root.count("p")).unwrap(), 100);
+}
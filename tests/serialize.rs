@@ -0,0 +1,508 @@
+mod common;
+use mesdoc::dom::parse_with_source;
+use mesdoc::error::Error as IError;
+use mesdoc::html::spec;
+use mesdoc::interface::{Elements, IDocumentTrait};
+use mesdoc::serialize::{serialize, MinifyOptions, SerializeOptions};
+
+#[test]
+fn test_minify() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<!DOCTYPE html>\n<html>\n<head>\n  <!-- meta -->\n  <script type=\"text/javascript\">\n  var a = 1 < 2;\n  </script>\n</head>\n",
+		"<body>\n  <p>  hello   <b>big</b>  world  </p>\n  <pre>  keep\n  this </pre>\n",
+		"  <input type=\"text\" disabled=\"disabled\" value=\"a &amp; &quot;b&quot;\">\n",
+		"  <textarea>  a\n b </textarea>\n</body>\n</html>"
+	));
+	assert_eq!(
+		doc.minify(Default::default()),
+		concat!(
+			"<!DOCTYPE html><html><head><script>\n  var a = 1 < 2;\n  </script></head>",
+			"<body><p>hello <b>big</b> world</p><pre>  keep\n  this </pre>",
+			"<input disabled value=\"a &amp; &quot;b&quot;\"> <textarea>  a\n b </textarea></body></html>"
+		)
+	);
+	// the whitespaces beside the unknown and the other inline elements are rendered
+	let inline_doc = common::parse(
+		"<p>See <del>old</del> <ins>new</ins> and <svg></svg> x <textarea>t</textarea> y <my-tag>z</my-tag> </p>",
+	);
+	assert_eq!(
+		inline_doc.minify(Default::default()),
+		"<p>See <del>old</del> <ins>new</ins> and <svg></svg> x <textarea>t</textarea> y <my-tag>z</my-tag></p>"
+	);
+	// only the boolean attributes with empty values or their names are collapsed
+	let attrs_doc = common::parse(
+		"<div hidden=\"until-found\"><input checked=\"checked\" disabled=\"\" selected=\"no\"></div>",
+	);
+	assert_eq!(
+		attrs_doc.minify(Default::default()),
+		"<div hidden=\"until-found\"><input checked disabled selected=\"no\"></div>"
+	);
+	// the texts around the removed comments are collapsed as one
+	let comment_doc = common::parse("<p>a <!-- c --> b <!-- d --><b>c</b></p>");
+	assert_eq!(
+		comment_doc.minify(Default::default()),
+		"<p>a b <b>c</b></p>"
+	);
+	// keep the comments
+	let options = MinifyOptions {
+		remove_comments: false,
+		..Default::default()
+	};
+	assert!(doc.minify(options).contains("<head><!-- meta --><script>"));
+	// the default serializer options keep the source
+	let root = doc.get_root_node();
+	assert_eq!(
+		serialize(&root, &SerializeOptions::default()).unwrap(),
+		doc.source_code()
+	);
+}
+
+#[test]
+fn test_format() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<!DOCTYPE html><html><head><title>Format</title><style>a { color: red; }</style></head>",
+		"<body><!-- nav --><nav class=\"top-navigation main-navigation\" id=\"navigation\" data-role=\"menu\">",
+		"<a href=\"/\">home</a> <a href=\"/about\">about</a></nav>",
+		"<div>  hello <b>big</b>   world<p>para</p>tail</div>",
+		"<pre>\n\n keep   this\n</pre><ul><li>a</li><li>b <i>c</i></li></ul></body></html>"
+	));
+	let formatted = doc.format(Default::default());
+	assert_eq!(
+		formatted,
+		concat!(
+			"<!DOCTYPE html>\n<html>\n  <head>\n    <title>Format</title>\n    <style>a { color: red; }</style>\n  </head>\n",
+			"  <body>\n    <!-- nav -->\n    <nav\n      class=\"top-navigation main-navigation\"\n      id=\"navigation\"\n",
+			"      data-role=\"menu\"><a href=\"/\">home</a> <a href=\"/about\">about</a></nav>\n",
+			"    <div>\n      hello <b>big</b> world\n      <p>para</p>\n      tail\n    </div>\n",
+			"    <pre>\n\n keep   this\n</pre>\n    <ul>\n      <li>a</li>\n      <li>b <i>c</i></li>\n    </ul>\n",
+			"  </body>\n</html>"
+		)
+	);
+	// format again gets the same result
+	assert_eq!(
+		common::parse(&formatted).format(Default::default()),
+		formatted
+	);
+	// the indent and the width are configurable
+	let options = mesdoc::serialize::FormatOptions {
+		indent: String::from("\t"),
+		max_width: 0,
+	};
+	let formatted = doc.format(options.clone());
+	assert!(formatted.contains("\n\t<body>\n\t\t<!-- nav -->\n\t\t<nav class="));
+	assert_eq!(common::parse(&formatted).format(options), formatted);
+}
+
+#[test]
+fn test_serialize_xml_nodes() {
+	use mesdoc::interface::{DocumentType, INodeType};
+	mesdoc::init();
+	let html = concat!(
+		"<?xml-stylesheet href=\"a.xsl\"?>",
+		"<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd\">",
+		"<html><body><svg><![CDATA[a < b]]></svg></body></html>"
+	);
+	let doc = common::parse(html);
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let child_nodes = root.child_nodes();
+	assert!(matches!(
+		child_nodes[0].node_type(),
+		INodeType::ProcessingInstruction
+	));
+	assert_eq!(
+		child_nodes[0].pi_target().as_deref(),
+		Some("xml-stylesheet")
+	);
+	assert_eq!(child_nodes[0].text_content(), "href=\"a.xsl\"");
+	assert_eq!(
+		child_nodes[1].doctype(),
+		Some(DocumentType {
+			name: String::from("html"),
+			public_id: String::from("-//W3C//DTD XHTML 1.0 Strict//EN"),
+			system_id: String::from("http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd"),
+		})
+	);
+	// the nodes are kept on round-trip
+	let root = doc.get_root_node();
+	assert_eq!(
+		serialize(&root, &SerializeOptions::default()).unwrap(),
+		html
+	);
+	assert_eq!(
+		DocumentType::parse("<!doctype html SYSTEM 'about:legacy-compat'>")
+			.unwrap()
+			.to_string(),
+		"<!DOCTYPE html SYSTEM \"about:legacy-compat\">"
+	);
+	assert_eq!(DocumentType::parse("<!-- a -->"), None);
+}
+
+#[test]
+fn test_snapshot() {
+	use mesdoc::serialize::{snapshot, snapshot_diff};
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><nav id=\"top\" class=\"menu\"  data-role=\"nav\">",
+		"<a   href=\"/\">home</a>\n  <a href=\"/about\" itemscope=\"itemscope\">about</a></nav></body></html>"
+	));
+	let same = common::parse(concat!(
+		"<html><body><nav data-role=\"nav\" class=\"menu\" id=\"top\">",
+		"<a href=\"/\">home</a> <a itemscope href=\"/about\">about</a></nav></body></html>"
+	));
+	let found = snapshot(&doc.get_root_node(), Default::default());
+	assert_eq!(
+		found,
+		concat!(
+			"<html>\n  <body>\n",
+			"    <nav class=\"menu\" data-role=\"nav\" id=\"top\"><a href=\"/\">home</a> <a href=\"/about\" itemscope>about</a></nav>\n",
+			"  </body>\n</html>"
+		)
+	);
+	assert_eq!(snapshot(&same.get_root_node(), Default::default()), found);
+	// the diff of the lines
+	assert_eq!(snapshot_diff(&format!("{}\r\n\n", found), &found), None);
+	assert_eq!(
+		snapshot_diff(
+			"<ul>\n  <li>a</li>\n  <li>b</li>\n</ul>",
+			"<ul>\n  <li>a</li>\n  <li>c</li>\n</ul>"
+		)
+		.as_deref(),
+		Some("  <ul>\n    <li>a</li>\n-   <li>b</li>\n+   <li>c</li>\n  </ul>")
+	);
+}
+
+#[test]
+fn test_content_hash() {
+	use mesdoc::interface::{Elements, HashOptions};
+	mesdoc::init();
+	let hash_of = |html: &str, options: &HashOptions| -> u64 {
+		let doc = common::parse(html);
+		let root = doc.get_root_node().typed().into_element().unwrap();
+		Elements::with_nodes(vec![root])
+			.find("div")
+			.get(0)
+			.unwrap()
+			.content_hash(options)
+	};
+	let options = HashOptions::default();
+	let base = hash_of(
+		"<div class=\"a\" id=\"x\"><p>hello  world</p><!-- c --></div>",
+		&options,
+	);
+	// the attribute order and the case of the names don't matter
+	assert_eq!(
+		hash_of(
+			"<DIV ID=\"x\" class=\"a\"><p>hello  world</p><!-- c --></DIV>",
+			&options
+		),
+		base
+	);
+	assert_ne!(
+		hash_of(
+			"<div class=\"a\" id=\"y\"><p>hello  world</p><!-- c --></div>",
+			&options
+		),
+		base
+	);
+	assert_ne!(
+		hash_of(
+			"<div class=\"a\" id=\"x\"><p>hello world</p><!-- c --></div>",
+			&options
+		),
+		base
+	);
+	assert_ne!(
+		hash_of(
+			"<div class=\"a\" id=\"x\"><p>hello  world</p></div>",
+			&options
+		),
+		base
+	);
+	// the texts can't be confused with the tags
+	assert_ne!(
+		hash_of("<div><p>a</p>b</div>", &options),
+		hash_of("<div><p>ab</p></div>", &options)
+	);
+	let options = HashOptions {
+		ignore_attributes: vec![String::from("ID")],
+		ignore_whitespace: true,
+		ignore_comments: true,
+	};
+	assert_eq!(
+		hash_of(
+			"<div class=\"a\" id=\"x\"><p>hello  world</p><!-- c --></div>",
+			&options
+		),
+		hash_of(
+			"<div id=\"y\" class=\"a\">\n  <p> hello\nworld </p>\n</div>",
+			&options
+		)
+	);
+	// the hash is stable
+	assert_eq!(
+		hash_of("<div></div>", &Default::default()),
+		0xebc4_a505_c453_9c07
+	);
+}
+
+#[test]
+fn test_preserving_source() {
+	mesdoc::init();
+	let html = concat!(
+		"<!doctype html>\n<HTML lang=en>\n<body>\n  <!-- nav -->\n",
+		"  <ul class='nav'  id=list>\n    <li>One &amp; two\n    <li><a href=/a>A</a><br/>\n  </ul>\n",
+		"  <p id=\"intro\">Hello   <b>world</b></p>\n",
+		"  <script>if (a < b) { run(); }</script>\n</body>\n</HTML>\n"
+	);
+	let doc = parse_with_source(html);
+	// nothing changed
+	assert_eq!(doc.to_html_preserving_source(), html);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	assert_eq!(root.find("#list > li").length(), 2);
+	assert_eq!(root.find("li").eq(0).text(), "One & two\n    ");
+	// only the start tag of the changed element is serialized
+	root.find("a").set_attr("href", Some("/b"));
+	assert_eq!(
+		doc.to_html_preserving_source(),
+		html.replace("<a href=/a>", "<a href=\"/b\">")
+	);
+	// the changed children are serialized, the start tag is kept
+	root.find("#intro").set_text("Bye");
+	root.find("script").set_text("run();");
+	let expected = html
+		.replace("<a href=/a>", "<a href=\"/b\">")
+		.replace("Hello   <b>world</b>", "Bye")
+		.replace("if (a < b) { run(); }", "run();");
+	assert_eq!(doc.to_html_preserving_source(), expected);
+	// the inserted and the removed nodes
+	root.find("li").eq(0).remove();
+	root.find("#intro").set_html("<i>new</i> text");
+	let expected = expected
+		.replace("<li>One &amp; two\n    ", "")
+		.replace(">Bye<", "><i>new</i> text<");
+	assert_eq!(doc.to_html_preserving_source(), expected);
+}
+
+#[test]
+fn test_template_html() {
+	mesdoc::init();
+	let html = "<div><template id=\"card\"><p class=\"title\">Title</p></template></div>";
+	for doc in [common::parse(html), parse_with_source(html)] {
+		let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+		// the contents are serialized but not matched
+		assert_eq!(root.find(".title").length(), 0);
+		assert_eq!(root.find("template").outer_html(), &html[5..html.len() - 6]);
+		assert_eq!(
+			serialize(&doc.get_root_node(), &SerializeOptions::default()).unwrap(),
+			html
+		);
+		root.find("template").set_html("<b>new</b>");
+		assert_eq!(root.find("template").html(), "<b>new</b>");
+		assert_eq!(root.find("template").template_content().length(), 1);
+		assert_eq!(root.find("b").length(), 0);
+	}
+	// the source of the contents is kept if they are not changed
+	let source = concat!(
+		"<body>\n<template id=card>\n  <p class=title>Title</p>\n</template>\n",
+		"<p id=x>x</p>\n</body>"
+	);
+	let doc = parse_with_source(source);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	root.find("#x").set_text("y");
+	assert_eq!(
+		doc.to_html_preserving_source(),
+		source.replace(">x<", ">y<")
+	);
+	let mut title = root.find("template").template_content().find(".title");
+	title.set_attr("class", Some("heading"));
+	assert_eq!(
+		doc.to_html_preserving_source(),
+		source
+			.replace(">x<", ">y<")
+			.replace("<p class=title>", "<p class=\"heading\">")
+	);
+}
+
+#[test]
+fn test_foreign_content_source() {
+	mesdoc::init();
+	let html = "<svg><lineargradient id=g></lineargradient><style><a>x</a></style></svg><style><a>y</a></style>";
+	let doc = parse_with_source(html);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	// the svg style is not a raw text element
+	assert_eq!(root.find("svg style > a").length(), 1);
+	assert_eq!(root.find("a").length(), 1);
+	assert_eq!(root.find("linearGradient").length(), 1);
+	assert_eq!(
+		doc.source_code(),
+		"<svg><linearGradient id=\"g\"></linearGradient><style><a>x</a></style></svg><style><a>y</a></style>"
+	);
+	assert_eq!(doc.to_html_preserving_source(), html);
+}
+
+#[test]
+fn test_html_spec() {
+	mesdoc::init();
+	assert!(spec::is_void_element("BR"));
+	assert!(!spec::is_void_element("div"));
+	assert!(spec::is_raw_text_element("xmp"));
+	assert!(spec::is_escapable_raw_text_element("Title"));
+	assert!(spec::is_text_only_element("textarea"));
+	assert!(spec::is_implied_end("li", "LI"));
+	assert!(spec::is_implied_end("p", "div"));
+	assert!(!spec::is_implied_end("p", "span"));
+	let html = "<xmp><b>a &amp; b</b></xmp><p>x<div>y</div><br>";
+	let doc = parse_with_source(html);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	// the tags in the raw text are not parsed, the `p` is closed by the `div`
+	assert_eq!(root.find("b").length(), 0);
+	assert_eq!(root.find("p > div").length(), 0);
+	assert_eq!(root.find("br:empty").length(), 1);
+	assert_eq!(
+		serialize(&doc.get_root_node(), &SerializeOptions::default()).unwrap(),
+		"<xmp><b>a &amp; b</b></xmp><p>x</p><div>y</div><br>"
+	);
+}
+
+#[test]
+fn test_reparse_preserving_source() {
+	mesdoc::init();
+	let source = "<div id=main>\n  <p class=a>x</p>\n  <ul><li>1</li></ul>\n</div>";
+	let doc = parse_with_source(source);
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let list = root.find("ul").get(0).unwrap().cloned();
+	let mut para = root.find("p").get(0).unwrap().cloned();
+	let inserted = para.reparse("<p class=b>y<b>z</b></p><br>").unwrap();
+	assert_eq!(inserted.length(), 2);
+	assert!(root.find("ul").get(0).unwrap().is(&list));
+	assert_eq!(
+		doc.to_html_preserving_source(),
+		"<div id=main>\n  <p class=\"b\">y<b>z</b></p><br>\n  <ul><li>1</li></ul>\n</div>"
+	);
+}
+
+#[test]
+#[cfg(feature = "encoding")]
+fn test_encode_html() {
+	use mesdoc::error::Error;
+	use mesdoc::serialize::encode_html;
+	mesdoc::init();
+	assert_eq!(
+		encode_html("中文 €", "gbk").unwrap(),
+		b"\xd6\xd0\xce\xc4 \x80"
+	);
+	// the unmappable characters are numeric character references
+	assert_eq!(
+		encode_html("<p title=\"日本\">€ ✓</p>", "Shift_JIS").unwrap(),
+		b"<p title=\"\x93\xfa\x96{\">&#8364; &#10003;</p>"
+	);
+	// the references are not decoded in the raw texts and the comments
+	assert_eq!(
+		encode_html(
+			"<script title='>'>var a = '日本';</script><p>✓</p>",
+			"shift_jis"
+		)
+		.unwrap(),
+		b"<script title='>'>var a = '\x93\xfa\x96{';</script><p>&#10003;</p>"
+	);
+	for html in [
+		"<script>var a = '✓';</script>",
+		"<STYLE media=\"all\">p::after { content: '€'; }</STYLE>",
+		"<p>a</p><!-- ✓ -->",
+	] {
+		assert!(matches!(
+			encode_html(html, "shift_jis"),
+			Err(Error::UnencodableRawText { .. })
+		));
+	}
+	assert_eq!(
+		encode_html("<scripts>✓</scripts>", "shift_jis").unwrap(),
+		b"<scripts>&#10003;</scripts>"
+	);
+	// the html in `utf-16` would be written as `utf-8`
+	for charset in ["unknown", "utf-16le", "UTF-16"] {
+		assert!(matches!(
+			encode_html("", charset),
+			Err(Error::UnsupportedCharset { charset: name }) if name == charset
+		));
+	}
+	// the document is encoded in the declared charset
+	let doc = parse_with_source(
+		"<html><head><meta charset=\"gbk\"></head><body><p>价格 €5 ✓</p></body></html>",
+	);
+	let bytes = doc.encode_html().unwrap();
+	let expected = b"<p>\xbc\xdb\xb8\xf1 \x805 &#10003;</p>";
+	assert!(bytes
+		.windows(expected.len())
+		.any(|window| window == expected));
+	let doc = parse_with_source("<p>✓</p>");
+	assert!(String::from_utf8(doc.encode_html().unwrap())
+		.unwrap()
+		.contains("<p>✓</p>"));
+}
+
+#[test]
+fn test_raw_zones() {
+	mesdoc::init();
+	let doc = common::parse("<div><p id=\"a\"></p><p id=\"b\">1 &lt; 2</p></div>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let markup = "<b>{{ name }}</b> & co";
+	root.find("#a").set_raw_html(markup);
+	assert_eq!(root.find("#a").text(), markup);
+	assert!(root.find("#a").get(0).unwrap().is_raw_zone());
+	let node = doc.get_root_node();
+	let html = serialize(&node, &Default::default()).unwrap();
+	assert_eq!(
+		html,
+		"<div><p id=\"a\"><b>{{ name }}</b> & co</p><p id=\"b\">1 &lt; 2</p></div>"
+	);
+	assert_eq!(root.find("div").html(), &html[5..html.len() - 6]);
+	// the strict mode refuses the raw zones
+	let strict = SerializeOptions {
+		deny_raw_zones: true,
+		..Default::default()
+	};
+	assert!(matches!(
+		serialize(&node, &strict),
+		Err(IError::RawZoneDenied { name }) if name == "p"
+	));
+	assert!(doc.set_deny_raw_zones(true).is_err());
+	// a text node can be a raw zone, the copies keep the mark
+	let mut text = root.find("#b").get(0).unwrap().child_nodes_item(0).unwrap();
+	text.set_raw_zone(true).unwrap();
+	let copy = root.find("#b").get(0).unwrap().clone_deep().unwrap();
+	assert_eq!(copy.outer_html(), "<p id=\"b\">1 < 2</p>");
+	text.set_raw_zone(false).unwrap();
+	assert!(serialize(&node, &Default::default())
+		.unwrap()
+		.ends_with("<p id=\"b\">1 &lt; 2</p></div>"));
+	// the strict tree can't have the raw zones, so its html is always escaped, the detached copy is freed
+	drop(copy);
+	root
+		.find("#a")
+		.get(0)
+		.unwrap()
+		.cloned()
+		.set_raw_zone(false)
+		.unwrap();
+	doc.set_deny_raw_zones(true).unwrap();
+	assert!(text.set_raw_zone(true).is_err());
+	root.find("#b").set_raw_html(markup);
+	assert_eq!(root.find("#b").text(), "1 < 2");
+	let trusted = common::parse("<p id=\"c\"></p>");
+	let trusted = Elements::with_nodes(vec![trusted
+		.get_root_node()
+		.typed()
+		.into_element()
+		.unwrap()]);
+	trusted.find("#c").set_raw_html(markup);
+	root.find("div").append(&mut trusted.find("#c"));
+	assert_eq!(trusted.find("#c").length(), 1);
+	assert_eq!(
+		root.find("div").html(),
+		"<p id=\"a\">&lt;b&gt;{{ name }}&lt;/b&gt; &amp; co</p><p id=\"b\">1 &lt; 2</p>"
+	);
+	assert!(serialize(&node, &strict).is_ok());
+}
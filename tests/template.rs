@@ -0,0 +1,53 @@
+#![cfg(feature = "serde")]
+mod common;
+use mesdoc::interface::{Elements, IDocumentTrait};
+use mesdoc::template::Context;
+use serde_json::json;
+
+#[test]
+fn test_render() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><div id=\"app\"><h1 data-bind=\"title\">Title</h1>",
+		"<a data-bind-href=\"user.url\" data-bind=\"user.name\">name</a>",
+		"<input type=\"checkbox\" data-bind-checked=\"user.admin\" checked>",
+		"<ul><li data-each=\"items\" data-bind-class=\"kind\"><span data-bind=\"$index\"></span>:",
+		"<b data-bind=\"name\"></b>@<i data-bind=\"user.name\"></i>",
+		"<em data-each=\"tags\" data-bind=\".\"></em></li></ul>",
+		"<p data-each=\"empty\">none</p><p data-bind=\"missing\">keep</p></div></body></html>"
+	));
+	let context = Context::from(json!({
+		"title": "Hello <world>",
+		"user": { "name": "Ann", "url": "/ann", "admin": false },
+		"items": [
+			{ "name": "one", "kind": "a", "tags": ["x", "y"] },
+			{ "name": "two", "kind": 2, "tags": [] }
+		],
+		"empty": []
+	}));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let mut app = Elements::with_nodes(vec![root]).find("#app");
+	app.render(&context);
+	assert_eq!(
+		app.html(),
+		concat!(
+			"<h1 data-bind=\"title\">Hello &lt;world&gt;</h1>",
+			"<a data-bind-href=\"user.url\" data-bind=\"user.name\" href=\"/ann\">Ann</a>",
+			"<input type=\"checkbox\" data-bind-checked=\"user.admin\">",
+			"<ul><li data-bind-class=\"kind\" class=\"a\"><span data-bind=\"$index\">0</span>:",
+			"<b data-bind=\"name\">one</b>@<i data-bind=\"user.name\">Ann</i>",
+			"<em data-bind=\".\">x</em><em data-bind=\".\">y</em></li>",
+			"<li data-bind-class=\"kind\" class=\"2\"><span data-bind=\"$index\">1</span>:",
+			"<b data-bind=\"name\">two</b>@<i data-bind=\"user.name\">Ann</i></li></ul>",
+			"<p data-bind=\"missing\">keep</p>"
+		)
+	);
+	// the context can be any serializable data
+	#[derive(serde::Serialize)]
+	struct Data {
+		title: &'static str,
+	}
+	let context = Context::new(&Data { title: "Again" }).unwrap();
+	app.find("h1").render(&context);
+	assert_eq!(app.find("h1").text(), "Again");
+}
@@ -0,0 +1,304 @@
+mod common;
+use mesdoc::interface::{Elements, IDocumentTrait, TextsOptions};
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+fn body<'a>(doc: &common::Document) -> Elements<'a> {
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	Elements::with_nodes(vec![root]).find("body")
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn test_find_and_replace_text() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><p>call 555-1234 or <b>555-9876</b></p>",
+		"<p>none</p><div>fax 555-0000</div></body></html>"
+	));
+	let body = body(&doc);
+	let regex = Regex::new(r"\d{3}-(\d{4})").unwrap();
+	let matches = body.find_text(&regex);
+	let found: Vec<(&str, usize, usize)> = matches
+		.iter()
+		.map(|m| (m.text.as_str(), m.start, m.end))
+		.collect();
+	assert_eq!(
+		found,
+		vec![("555-1234", 5, 13), ("555-9876", 0, 8), ("555-0000", 4, 12)]
+	);
+	assert_eq!(matches[1].node.text_content(), "555-9876");
+	// the nested elements in the set don't repeat the matches
+	assert_eq!(body.find("p, b").find_text(&regex).len(), 2);
+	body.find("p").replace_text(&regex, "xxx-$1");
+	assert_eq!(
+		body.html(),
+		"<p>call xxx-1234 or <b>xxx-9876</b></p><p>none</p><div>fax 555-0000</div>"
+	);
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn test_wrap_text() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><head><template><mark class=\"hl\"></mark></template></head>",
+		"<body><p>hello world, hello</p><p>say hello<script>hello</script></p></body></html>"
+	));
+	let mut body = body(&doc);
+	let wrapper = body.document().unwrap().head().unwrap().childrens();
+	// the wrapper is in the contents of the template
+	let wrapper = wrapper
+		.filter("template")
+		.template_content()
+		.children("mark");
+	// split the first text node into three adjacent text nodes
+	let mut first = body.find("p").texts(1).get_mut_ref().remove(0);
+	let mut rest = first.split_text(3).unwrap();
+	rest.split_text(4).unwrap();
+	assert_eq!(body.find("p").get(0).unwrap().child_nodes_length(), 3);
+	// the first match spans the first and the second text nodes
+	let marks = body.wrap_text(&Regex::new("el+").unwrap(), &wrapper);
+	assert_eq!(marks.length(), 3);
+	assert_eq!(
+		body.html(),
+		concat!(
+			"<p>h<mark class=\"hl\">ell</mark>o world, h<mark class=\"hl\">ell</mark>o</p>",
+			"<p>say h<mark class=\"hl\">ell</mark>o<script>hello</script></p>"
+		)
+	);
+	// the text nodes are not merged
+	assert_eq!(body.find("p").get(0).unwrap().child_nodes_length(), 6);
+	// the copies in the nested elements are in document order
+	let doc = common::parse("<html><body><p>x1 <b>x2 <i>x3</i></b> x4</p><u></u></body></html>");
+	let nested = self::body(&doc);
+	let wrapper = nested.find("u");
+	let wrapped = nested
+		.children("p")
+		.wrap_text(&Regex::new("x\\d").unwrap(), &wrapper);
+	let texts: Vec<String> = wrapped
+		.get_ref()
+		.iter()
+		.map(|ele| ele.text_content().to_string())
+		.collect();
+	assert_eq!(texts, vec!["x1", "x2", "x3", "x4"]);
+	assert_eq!(
+		nested.children("p").html(),
+		"<u>x1</u> <b><u>x2</u> <i><u>x3</u></i></b> <u>x4</u>"
+	);
+}
+
+#[test]
+fn test_texts_options() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><div>a <p>b<b>c</b></p>\n<script>var d;</script>",
+		"<textarea>e</textarea></div></body></html>"
+	));
+	let div = body(&doc).children("div");
+	let contents = |options: &TextsOptions| -> Vec<String> {
+		div
+			.texts_with(options)
+			.get_ref()
+			.iter()
+			.map(|text| text.text_content().to_string())
+			.collect()
+	};
+	assert_eq!(
+		contents(&Default::default()),
+		vec!["a ", "b", "c", "\n", "var d;", "e"]
+	);
+	// keep the compatible `texts(limit_depth)`
+	assert_eq!(div.texts(0).length(), 6);
+	assert_eq!(div.texts(1).length(), 2);
+	assert_eq!(
+		contents(&TextsOptions {
+			limit_depth: 2,
+			..Default::default()
+		}),
+		vec!["a ", "b", "\n", "var d;", "e"]
+	);
+	assert_eq!(
+		contents(&TextsOptions {
+			include_content_tags: false,
+			skip_whitespace: true,
+			..Default::default()
+		}),
+		vec!["a ", "b", "c"]
+	);
+}
+
+#[test]
+fn test_plural_accessors() {
+	mesdoc::init();
+	let doc = common::parse(
+		"<html><body><ul><li>a<b>b</b></li><li class=\"c\">c</li><li></li></ul></body></html>",
+	);
+	let items = body(&doc).find("li");
+	assert_eq!(items.htmls(), vec!["a<b>b</b>", "c", ""]);
+	assert_eq!(
+		items.outer_htmls(),
+		vec!["<li>a<b>b</b></li>", "<li class=\"c\">c</li>", "<li></li>"]
+	);
+	assert_eq!(items.text_contents(), vec!["ab", "c", ""]);
+	assert!(items.find("p").htmls().is_empty());
+}
+
+#[test]
+fn test_script_contents() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><head><style>p > a { color: red; }</style></head><body>",
+		"<script>if (a < b && c) { run(); }</script><template><p>x</p></template>",
+		"<p>not a script</p></body></html>"
+	));
+	let root = body(&doc).parent("");
+	assert_eq!(
+		root.script_contents(),
+		vec![
+			"p > a { color: red; }",
+			"if (a < b && c) { run(); }",
+			"<p>x</p>"
+		]
+	);
+	assert_eq!(
+		root.find("script").script_content().as_deref(),
+		Some("if (a < b && c) { run(); }")
+	);
+	assert_eq!(root.find("p").script_content(), None);
+	// the contents are not escaped, the other elements are skipped
+	root
+		.find("script, p")
+		.set_script_content("let ok = 1 < 2 && 3 > 2;");
+	assert_eq!(
+		root.find("script").outer_html(),
+		"<script>let ok = 1 < 2 && 3 > 2;</script>"
+	);
+	assert_eq!(root.find("body > p").text(), "not a script");
+	root.find("template").set_script_content("<b>y</b>");
+	assert_eq!(root.find("template").script_contents(), vec!["<b>y</b>"]);
+}
+
+#[test]
+fn test_text_options() {
+	use mesdoc::interface::TextOptions;
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><div>\n  <h1> Title\n  here </h1>\n  <p>first <b>bold</b>\tline<br>second</p>",
+		"<script>var a;</script><p hidden>secret</p><p style=\"display: none\">none</p>",
+		"<ul><li> a </li><li>b</li></ul>  </div></body></html>"
+	));
+	let div = body(&doc).children("div");
+	// the default options get the same text
+	assert_eq!(div.text_with(&Default::default()), div.text());
+	let options = TextOptions {
+		trim: true,
+		collapse_whitespace: true,
+		..Default::default()
+	};
+	assert_eq!(
+		div.text_with(&options),
+		"Title here first bold linesecondvar a;secretnone a b"
+	);
+	let options = TextOptions {
+		collapse_whitespace: true,
+		block_separator: Some(String::from("\n")),
+		include_hidden: false,
+		..Default::default()
+	};
+	assert_eq!(
+		div.text_with(&options),
+		"Title here\nfirst bold line\nsecond\na\nb"
+	);
+	// each element is a block
+	let items = div.find("li");
+	assert_eq!(items.text_with(&options), "a\nb");
+	assert_eq!(items.text_contents_with(&options), vec!["a", "b"]);
+	let options = TextOptions {
+		trim: true,
+		block_separator: Some(String::from(" | ")),
+		..Default::default()
+	};
+	assert_eq!(
+		div.find("h1, ul").text_with(&options),
+		"Title\n  here | a | b"
+	);
+	// the hidden element itself
+	let script = div.find("script").get(0).unwrap().cloned();
+	assert_eq!(
+		script.text_with(&TextOptions {
+			include_hidden: false,
+			..Default::default()
+		}),
+		""
+	);
+}
+
+#[test]
+fn test_text_blocks() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html lang=\"en\"><body><article><h1>Title</h1>intro <b>text</b>",
+		"<p lang=\"fr\">Bonjour\n  le <i>monde</i><br>encore</p><script>var a;</script>",
+		"<ul><li>one</li><li hidden>two</li></ul> outro </article></body></html>"
+	));
+	let article = body(&doc).children("article");
+	let blocks: Vec<(String, String, Option<String>)> = article
+		.text_blocks()
+		.into_iter()
+		.map(|block| {
+			(
+				block.text,
+				block.element.tag_name().to_ascii_lowercase(),
+				block.lang,
+			)
+		})
+		.collect();
+	let en = Some(String::from("en"));
+	let fr = Some(String::from("fr"));
+	assert_eq!(
+		blocks,
+		vec![
+			(String::from("Title"), String::from("h1"), en.clone()),
+			(
+				String::from("intro text"),
+				String::from("article"),
+				en.clone()
+			),
+			(
+				String::from("Bonjour le monde"),
+				String::from("p"),
+				fr.clone()
+			),
+			(String::from("encore"), String::from("p"), fr),
+			(String::from("one"), String::from("li"), en.clone()),
+			(String::from("outro"), String::from("article"), en),
+		]
+	);
+	assert_eq!(body(&doc).find(":lang(fr) i").length(), 1);
+}
+
+#[test]
+fn test_text_stats() {
+	use mesdoc::interface::TextStats;
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><div><h1>Big title</h1><p>Read <a href=\"/\">the do<b>cs</b></a> now.</p>",
+		"<script>var a = 1;</script><p hidden>secret words</p>",
+		"<ul><li>one</li><li>two three</li></ul>tail</div></body></html>"
+	));
+	let div = body(&doc).children("div");
+	let stats = div.text_stats();
+	assert_eq!(
+		stats,
+		TextStats {
+			characters: 38,
+			words: 10,
+			paragraphs: 5,
+			link_characters: 7,
+		}
+	);
+	assert!((stats.link_text_ratio() - 7.0 / 38.0).abs() < f64::EPSILON);
+	assert_eq!(body(&doc).find("script").text_stats(), Default::default());
+	assert_eq!(TextStats::default().link_text_ratio(), 0.0);
+}
@@ -0,0 +1,52 @@
+// the handle is global, so the tests are kept in their own binary
+mod common;
+use mesdoc::interface::{BoxDynElement, Elements, IDocumentTrait};
+use mesdoc::selector::{
+	rule::{set_unknown_pseudo_handle, Matcher, UnknownPseudo},
+	Selector, SelectorWarningKind,
+};
+
+#[test]
+fn test_unknown_pseudo_handle() {
+	mesdoc::init();
+	let doc = common::parse(concat!(
+		"<html><body><a id=\"a\" lang=\"en\">a</a><a id=\"b\" lang=\"fr\">b</a>",
+		"<a id=\"c\">c</a></body></html>"
+	));
+	let root = doc.get_root_node().typed().into_element().unwrap();
+	let root = Elements::with_nodes(vec![root]);
+	// the unregistered pseudo classes are invalid by default
+	assert!("a:hover".parse::<Selector>().is_err());
+	set_unknown_pseudo_handle(Some(Box::new(|name, params| match (name, params) {
+		("hover" | "focus", None) => UnknownPseudo::Ignore,
+		("locale", Some(lang)) => {
+			let lang = String::from(lang.trim_matches('"'));
+			UnknownPseudo::Matcher(Matcher {
+				one_handle: Some(Box::new(move |ele: &BoxDynElement, _| {
+					ele.get_attribute("lang").map(|value| value.to_string()) == Some(lang.clone())
+				})),
+				..Default::default()
+			})
+		}
+		_ => UnknownPseudo::Error,
+	})));
+	assert!(root.find("a:hover").is_empty());
+	assert_eq!(root.find("body > :focus, #c").length(), 1);
+	assert_eq!(root.find("a:locale(fr)").text(), "b");
+	assert_eq!(root.find(":locale(\"en\") , a:locale( fr )").text(), "ab");
+	assert!("a:active".parse::<Selector>().is_err());
+	assert!("a:locale(en".parse::<Selector>().is_err());
+	// the ignored pseudo classes are warned
+	let mut warnings = Vec::new();
+	Selector::from_str_with_warnings("a:hover, a:locale(en)", true, &mut warnings).unwrap();
+	assert_eq!(
+		warnings
+			.iter()
+			.map(|warning| (warning.kind, warning.span))
+			.collect::<Vec<_>>(),
+		vec![(SelectorWarningKind::IgnoredPseudo, (1, 7))]
+	);
+	// restore the error
+	set_unknown_pseudo_handle(None);
+	assert!("a:hover".parse::<Selector>().is_err());
+}
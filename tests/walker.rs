@@ -0,0 +1,121 @@
+mod common;
+use mesdoc::error::Error;
+use mesdoc::interface::{
+	BoxDynNode, BreadthFirst, DepthFirst, Elements, IDocumentTrait, INodeType, NodeFilterResult,
+	NodePath, TreeWalker, WhatToShow,
+};
+
+// the tag name of the element or the text content of the other nodes
+fn name_of(node: &BoxDynNode) -> String {
+	match node.clone_node().typed().into_element() {
+		Some(ele) if matches!(node.node_type(), INodeType::Element) => {
+			ele.tag_name().to_ascii_lowercase()
+		}
+		_ => String::from(node.text_content()),
+	}
+}
+
+fn names<'a>(nodes: impl Iterator<Item = BoxDynNode<'a>>) -> Vec<String> {
+	nodes.map(|node| name_of(&node)).collect()
+}
+
+const HTML: &str =
+	"<div><p>a<b>b</b></p><!--c--><ul><li>d</li><li class=\"skip\">e<i>f</i></li></ul></div>";
+
+#[test]
+fn test_tree_walker() {
+	mesdoc::init();
+	let doc = common::parse(HTML);
+	let root = doc.get_root_node();
+	// the elements in document order
+	let walker = TreeWalker::new(&root, WhatToShow::ELEMENT);
+	assert_eq!(names(walker), vec!["div", "p", "b", "ul", "li", "li", "i"]);
+	// the texts and comments
+	let walker = TreeWalker::new(&root, WhatToShow::TEXT | WhatToShow::COMMENT);
+	assert_eq!(names(walker), vec!["a", "b", "c", "d", "e", "f"]);
+	// skip the element but keep its descendants, reject the `b` and its text
+	let walker =
+		TreeWalker::new(&root, WhatToShow::ELEMENT | WhatToShow::TEXT).with_filter(Box::new(
+			|node: &BoxDynNode| match node.clone_node().typed().into_element() {
+				Some(ele) if ele.has_attribute("class") => NodeFilterResult::Skip,
+				Some(ele) if ele.tag_name() == "B" => NodeFilterResult::Reject,
+				_ => NodeFilterResult::Accept,
+			},
+		));
+	assert_eq!(
+		names(walker),
+		vec!["div", "p", "a", "ul", "li", "d", "e", "i", "f"]
+	);
+	// move by the methods
+	let mut walker = TreeWalker::new(&root, WhatToShow::ELEMENT);
+	assert_eq!(name_of(&walker.first_child().unwrap()), "div");
+	assert_eq!(name_of(&walker.first_child().unwrap()), "p");
+	assert!(walker.previous_sibling().is_none());
+	assert_eq!(name_of(&walker.next_sibling().unwrap()), "ul");
+	assert_eq!(name_of(&walker.last_child().unwrap()), "li");
+	assert_eq!(name_of(&walker.previous_node().unwrap()), "li");
+	assert_eq!(name_of(&walker.previous_node().unwrap()), "ul");
+	assert_eq!(name_of(&walker.previous_node().unwrap()), "b");
+	assert_eq!(name_of(&walker.parent_node().unwrap()), "p");
+	assert_eq!(name_of(&walker.parent_node().unwrap()), "div");
+	// the root is not an element
+	assert!(walker.parent_node().is_none());
+	assert_eq!(name_of(walker.current_node()), "div");
+}
+
+#[test]
+fn test_traversal_iterators() {
+	mesdoc::init();
+	let doc = common::parse(HTML);
+	let root = doc.get_root_node();
+	let div = DepthFirst::new(&root).nth(1).unwrap();
+	assert_eq!(
+		names(DepthFirst::new(&div)),
+		vec!["div", "p", "a", "b", "b", "c", "ul", "li", "d", "li", "e", "i", "f"]
+	);
+	assert_eq!(
+		names(BreadthFirst::new(&div)),
+		vec!["div", "p", "c", "ul", "a", "b", "li", "li", "b", "d", "e", "i", "f"]
+	);
+}
+
+#[test]
+fn test_node_path() {
+	mesdoc::init();
+	let doc = common::parse("<div><p>a</p>text<p>b<b>c</b></p></div><ul><li>d</li></ul>");
+	let root = Elements::with_nodes(vec![doc.get_root_node().typed().into_element().unwrap()]);
+	let bold = root.find("b").get(0).unwrap().cloned();
+	let path = bold.node_path();
+	// the texts are not counted in the indexes
+	assert_eq!(path.to_string(), "div[0]/p[1]/b[0]");
+	assert_eq!(path.breadcrumb(" > "), "div > p > b");
+	// the saved path is resolved again
+	let parsed: NodePath = path.to_string().parse().unwrap();
+	assert_eq!(parsed, path);
+	assert!(doc.from_path(&parsed).unwrap().is(&bold));
+	let item = root.find("li").get(0).unwrap().cloned();
+	assert!(doc.from_path(&item.node_path()).unwrap().is(&item));
+	// the empty path is the root
+	assert_eq!(
+		doc
+			.from_path(&"".parse().unwrap())
+			.unwrap()
+			.children()
+			.length(),
+		2
+	);
+	// the changed structures are not resolved
+	for changed in ["div[0]/p[5]", "div[0]/span[0]", "ul[0]/li[0]/b[0]"] {
+		assert!(
+			doc.from_path(&changed.parse().unwrap()).is_none(),
+			"{}",
+			changed
+		);
+	}
+	for invalid in ["div", "div[x]", "[0]", "div[0]//p[1]"] {
+		match invalid.parse::<NodePath>() {
+			Err(Error::InvalidNodePath { .. }) => {}
+			other => panic!("expect an invalid node path: {:?}, {:?}", invalid, other),
+		}
+	}
+}
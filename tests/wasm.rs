@@ -0,0 +1,64 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use mesdoc::interface::IDocumentTrait;
+use mesdoc::wasm::{Document, Node};
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// a new detached element of the current document
+fn create(html: &str) -> web_sys::Element {
+	let doc = web_sys::window().unwrap().document().unwrap();
+	let ele = doc.create_element("div").unwrap();
+	ele.set_inner_html(html);
+	ele
+}
+
+#[wasm_bindgen_test]
+fn test_live_dom() {
+	mesdoc::init();
+	let root = Node::from_element(create(
+		"<p id=\"a\" class=\"b\">one <b>two</b></p><svg></svg>",
+	));
+	let p = root.find("#a");
+	assert_eq!(p.length(), 1);
+	let ele = p.get(0).unwrap();
+	assert_eq!(ele.tag_name(), "P");
+	assert_eq!(ele.local_name(), "p");
+	assert_eq!(ele.text_content(), "one two");
+	assert_eq!(ele.inner_html(), "one <b>two</b>");
+	assert_eq!(
+		ele.outer_html(),
+		"<p id=\"a\" class=\"b\">one <b>two</b></p>"
+	);
+	assert_eq!(
+		root.find("svg").get(0).unwrap().namespace_uri(),
+		Some("http://www.w3.org/2000/svg")
+	);
+	// the strings are kept by the handle, calling again returns the same one
+	assert!(std::ptr::eq(ele.outer_html(), ele.outer_html()));
+	let mut p = p;
+	p.set_attr("title", Some("t"));
+	assert_eq!(p.attr("title").unwrap().to_string(), "t");
+}
+
+#[wasm_bindgen_test]
+fn test_stable_uuid() {
+	mesdoc::init();
+	let root = Node::from_element(create("<p></p><p></p>"));
+	let first = root.find("p").get(0).unwrap().uuid().map(String::from);
+	let again = root.find("p").get(0).unwrap().uuid().map(String::from);
+	let second = root.find("p").get(1).unwrap().uuid().map(String::from);
+	assert!(first.is_some());
+	assert_eq!(first, again);
+	assert_ne!(first, second);
+	// the uuids work with the set operations of the elements
+	assert_eq!(root.find("p").add(root.find("p:first-child")).length(), 2);
+}
+
+#[wasm_bindgen_test]
+fn test_current_document() {
+	mesdoc::init();
+	let doc = Document::current().unwrap();
+	assert!(doc.source_code().starts_with("<html"));
+	assert!(doc.get_root_node().uuid().is_some());
+}